@@ -0,0 +1,702 @@
+//! 簡易版家計簿アプリ
+//!
+//! このアプリは、家計簿の登録と集計を行うためのシンプルなCLIアプリです。
+//! サブコマンドを指定すると対応する機能を直接実行し、指定しない場合はメニューから実行したい内容を選択します。
+//! メニューは、`q`を入力するまで繰り返し表示されます。
+//!
+//! #### 例
+//!
+//! ```
+//! cargo run
+//! ```
+//!
+//! サブコマンドの一覧は`--help`で確認できます。
+//!
+//! ```
+//! cargo run -- --help
+//! cargo run -- register
+//! ```
+//!
+//! `summarize`サブコマンドは、表示オプションをフラグで受け取り、対話入力なしで集計結果を表示します。
+//!
+//! ```
+//! cargo run -- summarize --cumulative --fixed-costs
+//! ```
+//!
+//! 集計の対象に複数のデータファイルを指定する場合は、`--files`オプションを付けます。
+//!
+//! ```
+//! cargo run -- summarize --files store/2022.json store/2023.json
+//! ```
+//!
+//! `--timing`オプションを付けると、読み込みや集計の各段階の処理時間を表示します。
+//!
+//! ```
+//! cargo run --release -- summarize --timing --files store/sample.json
+//! ```
+//!
+//! `--store`オプションを付けると、家計簿データの保存先を変更できます（設定ファイルの`store`より優先します）。
+//! `sqlite://`で始まるパスを指定すると、JSONファイルの代わりにSQLiteのデータベースに保存します。
+//!
+//! ```
+//! cargo run -- --store sqlite://store/kakeibo.db summarize
+//! ```
+//!
+//! `--timeout`オプションを付けると、対話プロンプトで指定した秒数のあいだ入力が無い場合に入力を打ち切ります。
+//! スクリプトから実行するときに、入力待ちで止まらないようにできます（設定ファイルの`prompt.timeout_secs`より優先します）。
+//!
+//! ```
+//! cargo run -- --timeout 30 < answers.txt
+//! ```
+//!
+//! `digest`サブコマンドは、先週分の支出のダイジェストを表示して終了します。
+//! 以前の`--digest`オプションも引き続き使用できます。
+//!
+//! ```
+//! cargo run -- digest
+//! ```
+//!
+//! `today`サブコマンドは、今日の支出合計と明細を表示して終了します。
+//!
+//! ```
+//! cargo run -- today
+//! ```
+
+use std::process;
+use clap::{Parser, Subcommand};
+use kakeibo_app::config::Config;
+use kakeibo_app::error::{KakeiboError, Result};
+use kakeibo_app::services;
+use kakeibo_app::services::list::{ListFilter, SortKey};
+use kakeibo_app::services::summarize::{SummarizeOptions, SummaryFormat};
+
+const FILE_PATH: &str = "store/data.json";
+const CONFIG_PATH: &str = "store/config.json";
+const SCHEMA_PATH: &str = "store/data.schema.json";
+const CLOSED_PATH: &str = "store/closed.json";
+const DEVICE_PATH: &str = "store/device.json";
+const PAYEES_PATH: &str = "store/payees.json";
+const RECURRING_PATH: &str = "store/recurring.json";
+const ACCOUNTS_PATH: &str = "store/accounts.json";
+const BUDGET_PATH: &str = "store/budget.json";
+const TRACKERS_PATH: &str = "store/trackers.json";
+const ACCOUNT_CODES_PATH: &str = "store/account_codes.json";
+const BANKS_DIR: &str = "store/banks";
+const QIF_CATEGORIES_PATH: &str = "store/qif_categories.json";
+
+/// コマンドライン引数を表す構造体
+#[derive(Parser)]
+#[command(version, about = "簡易版家計簿アプリ")]
+struct Cli {
+    /// 集計の対象にするデータファイル
+    #[arg(long, global = true, num_args = 1..)]
+    files: Vec<String>,
+
+    /// 読み込みや集計の各段階の処理時間を表示する
+    #[arg(long, global = true)]
+    timing: bool,
+
+    /// 家計簿データの保存先（例: sqlite://store/kakeibo.db）
+    #[arg(long, global = true)]
+    store: Option<String>,
+
+    /// 対話プロンプトで入力を待つ秒数（超えた場合は入力を打ち切る）
+    #[arg(long, global = true)]
+    timeout: Option<u64>,
+
+    /// `digest`サブコマンドと同じ（互換性のために残している）
+    #[arg(long, hide = true)]
+    digest: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// サブコマンドを表す列挙型
+#[derive(Subcommand)]
+enum Command {
+    /// 収支を登録する
+    Register,
+    /// 月ごとの収支を集計する
+    Summarize {
+        /// 登録の無い月も0円として表示する
+        #[arg(long)]
+        fill_empty_months: bool,
+        /// 累積収支も表示する
+        #[arg(long)]
+        cumulative: bool,
+        /// 固定費・変動費の内訳も表示する
+        #[arg(long)]
+        fixed_costs: bool,
+        /// 月ごとのカテゴリ別の内訳も表示する（サブカテゴリはカテゴリにまとめる）
+        #[arg(long)]
+        categories: bool,
+        /// 月ごとのカテゴリ別の内訳をサブカテゴリに分けて表示する
+        #[arg(long)]
+        detail: bool,
+        /// 指定した月（yyyy-mm）だけを集計する（その月の項目だけを読み込む）
+        #[arg(long, conflicts_with_all = ["from", "to"])]
+        month: Option<String>,
+        /// 指定した日（yyyy-mm-dd）以降の項目だけを集計する
+        #[arg(long)]
+        from: Option<String>,
+        /// 指定した日（yyyy-mm-dd）以前の項目だけを集計する
+        #[arg(long)]
+        to: Option<String>,
+        /// 月ごとではなくISO週（月曜日始まり）ごとに集計する
+        #[arg(long)]
+        weekly: bool,
+        /// 設定の物価指数で換算した実質値も表示する
+        #[arg(long)]
+        real: bool,
+        /// 表の代わりに、収入・支出・収支を端末の幅に合わせた横棒グラフで表示する
+        #[arg(long)]
+        chart: bool,
+        /// 出力形式（table・csv・tsv）。csv・tsvでは月・収入・支出・収支を区切り文字で区切って出力する
+        #[arg(long, conflicts_with_all = ["weekly", "chart", "real"])]
+        format: Option<String>,
+    },
+    /// 曜日別の支出を分析する
+    Analyze,
+    /// イベント別に集計する
+    Events,
+    /// 条件に一致する項目を一括削除する（IDを指定した場合はその項目を削除する）
+    Delete {
+        /// 削除する項目のID（`list`サブコマンドで確認できる）
+        id: Option<u64>,
+        /// 確認せずに削除する
+        #[arg(long, requires = "id")]
+        force: bool,
+        /// 一括削除の条件式（例: `category=趣味 and date<2022-01-01`）。省略した場合は対話的に尋ねる
+        #[arg(long, conflicts_with = "id")]
+        filter: Option<String>,
+    },
+    /// データファイルのJSONスキーマを出力する
+    Schema,
+    /// カテゴリを付け替える
+    Migrate,
+    /// サンプルデータを生成する
+    Generate,
+    /// 項目をピン留めする
+    Pin,
+    /// 項目を検索する
+    Search,
+    /// 月を締める・再オープンする
+    Close,
+    /// アーカイブをエクスポート・インポートする
+    Archive,
+    /// 他の端末のジャーナルを統合する
+    Journal,
+    /// 未分類の支出を検出する
+    Uncategorized,
+    /// 先週分の支出のダイジェストを表示する
+    Digest,
+    /// 今日の支出を表示する
+    Today,
+    /// 試算表・勘定元帳を表示する
+    Ledger,
+    /// 仕訳を会計ソフトの勘定科目コード付きでCSVに出力する
+    Accounting,
+    /// 自由文（例: 5/2 スタバ 680円）から支出を登録する
+    Smart,
+    /// 登録した項目を一覧表示する
+    List {
+        /// 対象の月（yyyy-mm）
+        #[arg(long)]
+        month: Option<String>,
+        /// カテゴリ名（例: 食費）
+        #[arg(long)]
+        category: Option<String>,
+        /// 品目名に含まれる文字列
+        #[arg(long)]
+        name: Option<String>,
+        /// 並び順（date, price, name, category, idをカンマ区切りで指定。先頭に-を付けると降順）
+        #[arg(long, default_value = "date", allow_hyphen_values = true)]
+        sort: String,
+    },
+    /// 貼り付けた表形式のテキストから支出をまとめて登録する
+    Paste {
+        /// 確認せずに登録する
+        #[arg(long)]
+        yes: bool,
+    },
+    /// 日ごとの支出額をヒートマップで表示する
+    Heatmap {
+        /// 対象の年（省略時はデータの中で最新の年）
+        year: Option<i32>,
+        /// 端末ではなく、指定したHTMLファイルに出力する
+        #[arg(long)]
+        html: Option<String>,
+    },
+    /// 1年分の総括をまとめた年次レポートをPDFで出力する
+    YearEnd {
+        /// 対象の年（省略時はデータの中で最新の年）
+        year: Option<i32>,
+        /// 保存先（省略時はkakeibo_<年>_report.pdf）
+        #[arg(long, short)]
+        output: Option<String>,
+    },
+    /// 支払先マスタを管理する（追加・別名・表記ゆれの統合）
+    Payee {
+        /// 支払先別の支出ランキングだけを表示する
+        #[arg(long)]
+        ranking: bool,
+    },
+    /// 定期取引のルールを管理する（追加・削除・期日を迎えた分と口座の利息・手数料の登録）
+    Recurring {
+        #[command(subcommand)]
+        action: Option<RecurringAction>,
+    },
+    /// 支出カテゴリごとの月の予算を設定する
+    Budget {
+        #[command(subcommand)]
+        action: Option<BudgetAction>,
+    },
+    /// 監査用に、指定した日までのデータをハッシュ値付きの読み取り専用スナップショットに書き出す
+    Snapshot {
+        #[command(subcommand)]
+        action: Option<SnapshotAction>,
+    },
+    /// 年ごとの支出カテゴリのランキングと、前年からの順位の変動を表示する
+    Ranking,
+    /// すべての項目を日付順に並べ、残高の推移を表示する（開始残高は設定の`opening_balance`）
+    Balance,
+    /// 任意の2つの期間の収支をカテゴリ別に比較する
+    Compare {
+        /// 期間A（例: 2023-01..2023-06、yyyy-mm-dd..yyyy-mm-ddも可）
+        #[arg(long)]
+        a: String,
+        /// 期間B（例: 2024-01..2024-06）
+        #[arg(long)]
+        b: String,
+    },
+    /// 月ごとの支出・収入の平均・中央値・最小・最大・標準偏差を表示する
+    Stats,
+    /// 期間内の支出を金額の大きい順に上位だけ表示する
+    Top {
+        /// 表示する件数
+        #[arg(default_value_t = services::list::DEFAULT_TOP_COUNT)]
+        count: usize,
+        /// 指定した日（yyyy-mm-dd）以降の支出だけを対象にする
+        #[arg(long)]
+        from: Option<String>,
+        /// 指定した日（yyyy-mm-dd）以前の支出だけを対象にする
+        #[arg(long)]
+        to: Option<String>,
+    },
+    /// 集計結果などを表計算ソフト向けのファイルに書き出す
+    Export {
+        #[command(subcommand)]
+        action: Option<ExportAction>,
+    },
+    /// 月ごとの支出を必要・浪費・投資の分類別に振り返る
+    Review,
+    /// 子ども費・ペット費など、タグ（イベント名）ごとの支出の累計を開始日から積み上げて表示する
+    Tracker {
+        #[command(subcommand)]
+        action: Option<TrackerAction>,
+    },
+    /// 他のアプリや表計算ソフトで管理していた取引をファイルから取り込む
+    Import {
+        #[command(subcommand)]
+        action: Option<ImportAction>,
+    },
+    /// 為替レートや株価などの相場を取得する（取得先は設定の`quote_sources`）
+    Quote {
+        /// 銘柄（例: USD）
+        symbol: String,
+        /// 相場の日付（yyyy-mm-dd、省略時は今日）
+        #[arg(long)]
+        date: Option<String>,
+        /// 既定通貨に換算する数量
+        #[arg(long)]
+        amount: Option<f64>,
+    },
+    /// 指定したフォルダを監視し、置かれたCSV・JSONファイルを自動で取り込む（Ctrl+Cで終了）
+    Watch {
+        /// 監視するフォルダ
+        dir: String,
+    },
+}
+
+/// `recurring`サブコマンドの操作を表す列挙型
+#[derive(Subcommand)]
+enum RecurringAction {
+    /// 期日を迎えた定期取引と口座の利息・手数料を登録する（登録済みの月の分は登録しない）
+    Apply,
+}
+
+/// `budget`サブコマンドの操作を表す列挙型
+#[derive(Subcommand)]
+enum BudgetAction {
+    /// 月ごとの予算と実績を表示する
+    Report,
+    /// 今月の経過率と予算の消化率を比べるメーターを表示する
+    Pace,
+}
+
+/// `tracker`サブコマンドの操作を表す列挙型
+#[derive(Subcommand)]
+enum TrackerAction {
+    /// タグごとの累計を表示する
+    Report,
+}
+
+/// `import`サブコマンドの操作を表す列挙型
+#[derive(Subcommand)]
+enum ImportAction {
+    /// CSVファイルの取引を取り込む
+    Csv {
+        /// 取り込むCSVファイル
+        file: String,
+        /// 列の並び（例: date,name,amount,category。省略時は設定の`import_columns`）
+        #[arg(long)]
+        columns: Option<String>,
+        /// 1行目を見出しとして読み飛ばす
+        #[arg(long)]
+        header: bool,
+    },
+    /// OFX/QFXファイルの取引を、カテゴリ「その他」の項目として取り込む（未分類チェックで要分類として表示される）
+    Ofx {
+        /// 取り込むOFX/QFXファイル
+        file: String,
+    },
+    /// QIFファイルの取引を、カテゴリの読み替え表（store/qif_categories.json）でカテゴリを読み替えて取り込む
+    Qif {
+        /// 取り込むQIFファイル
+        file: String,
+    },
+    /// 銀行の明細のCSVファイルを、プロファイル（store/banks/<プロファイル名>.toml）の形式で取り込む
+    Bank {
+        /// 取り込む明細のCSVファイル
+        file: String,
+        /// プロファイル名（例: smbc）
+        #[arg(long)]
+        profile: String,
+    },
+}
+
+/// `snapshot`サブコマンドの操作を表す列挙型
+#[derive(Subcommand)]
+enum SnapshotAction {
+    /// 指定した日までのデータをスナップショットに書き出す
+    Export {
+        /// 基準日（yyyy-mm-dd、省略時は今日）
+        #[arg(long)]
+        as_of: Option<String>,
+        /// 保存先（省略時は kakeibo_snapshot_<基準日>.json）
+        #[arg(long, short)]
+        output: Option<String>,
+    },
+    /// スナップショットが書き出した後に変更されていないかを検証する
+    Verify {
+        /// スナップショットのパス
+        path: String,
+    },
+}
+
+/// `export`サブコマンドの操作を表す列挙型
+#[derive(Subcommand)]
+enum ExportAction {
+    /// 月ごとの集計結果（月・収入・支出・収支）を書き出す
+    Summary {
+        /// 形式（csv）
+        #[arg(long, default_value = "csv")]
+        format: String,
+        /// 文字コード（utf8・utf8-bom・shift_jis）
+        #[arg(long, default_value = "utf8")]
+        encoding: String,
+        /// 保存先
+        #[arg(long, default_value = services::export::DEFAULT_SUMMARY_PATH)]
+        out: String,
+        /// カテゴリ別の合計の列も書き出す
+        #[arg(long)]
+        categories: bool,
+    },
+    /// すべての項目（ID・日付・品目・カテゴリ・符号付きの金額）を書き出す
+    Data {
+        /// 形式（csv）
+        #[arg(long, default_value = "csv")]
+        format: String,
+        /// 文字コード（utf8・utf8-bom・shift_jis）
+        #[arg(long, default_value = "utf8")]
+        encoding: String,
+        /// 保存先
+        #[arg(long, default_value = services::export::DEFAULT_DATA_PATH)]
+        out: String,
+    },
+    /// 月ごとの表とグラフをまとめたレポートを書き出す
+    Report {
+        /// 形式（html）
+        #[arg(long, default_value = "html")]
+        format: String,
+        /// 保存先
+        #[arg(long, default_value = services::export::DEFAULT_REPORT_PATH)]
+        out: String,
+    },
+}
+
+/// main関数
+///
+/// アプリのエントリーポイントです。
+/// サブコマンドの実行中にエラーが発生した場合は、メッセージを標準エラー出力に表示して終了します。
+/// 終了コードは、データが無い場合は3、それ以外のエラーは1です。
+/// メニューから実行した機能のエラーは、メッセージを表示してメニューに戻ります。
+///
+/// #### 例
+///
+/// ```
+/// cargo run
+/// ```
+fn main() {
+    if let Err(error) = run() {
+        print_error(&error);
+        process::exit(error.get_exit_code());
+    }
+}
+
+/// コマンドライン引数を解析し、対応する機能を実行する
+///
+/// サブコマンドが指定されていればその機能を実行し、指定されていなければメニューを表示します。
+fn run() -> Result<()> {
+    let cli = Cli::parse();
+    let config = Config::load_or_default(CONFIG_PATH)?;
+    let mut prompt = config.prompt.clone();
+    if cli.timeout.is_some() {
+        prompt.timeout_secs = cli.timeout;
+    }
+    services::prompt::configure(prompt);
+    if cli.timing {
+        services::timing::enable();
+    }
+    let store = cli.store.clone().or_else(|| config.store.clone());
+    let file_path = store.as_deref().unwrap_or(FILE_PATH);
+    let summary_files: Vec<&str> = if cli.files.is_empty() {
+        vec![file_path]
+    } else {
+        cli.files.iter().map(|file| file.as_str()).collect()
+    };
+
+    let command = match cli.command {
+        Some(command) => command,
+        None if cli.digest => Command::Digest,
+        None => return run_menu(file_path, &summary_files, &config),
+    };
+    match command {
+        Command::Register => services::register::run(file_path, CLOSED_PATH, DEVICE_PATH, PAYEES_PATH, &config),
+        Command::Summarize { fill_empty_months, cumulative, fixed_costs, categories, detail, month, from, to, weekly, real, chart, format } => {
+            let month = month.as_deref().map(services::list::parse_month).transpose()?;
+            let from = from.as_deref().map(services::summarize::parse_date).transpose()?;
+            let to = to.as_deref().map(services::summarize::parse_date).transpose()?;
+            let format = format.as_deref().map(SummaryFormat::parse).transpose()?.unwrap_or_default();
+            let options = SummarizeOptions {
+                fill_empty_months,
+                show_cumulative: cumulative,
+                show_fixed_costs: fixed_costs,
+                show_categories: categories || detail,
+                detail,
+                month,
+                from,
+                to,
+                weekly,
+                show_real: real,
+                chart,
+                format,
+            };
+            services::summarize::run_with_options(&summary_files, &options, &config)
+        },
+        Command::Analyze => services::analyze::run(file_path, &config),
+        Command::Events => services::summarize::run_by_event(file_path, &config),
+        Command::Delete { id: Some(id), force, .. } => services::delete::run_by_id(file_path, CLOSED_PATH, id, force, &config),
+        Command::Delete { id: None, filter, .. } => services::delete::run(file_path, CLOSED_PATH, filter.as_deref(), &config),
+        Command::Schema => write_json_schema(),
+        Command::Migrate => services::migrate::run(file_path, CLOSED_PATH, &config),
+        Command::Generate => services::generate::run(),
+        Command::Pin => services::pin::run(file_path, &config),
+        Command::Search => services::search::run(file_path, CLOSED_PATH, &config),
+        Command::Close => services::close::run(file_path, CLOSED_PATH, &config),
+        Command::Archive => services::archive::run(&archive_paths(file_path)),
+        Command::Journal => services::journal::run(file_path, CLOSED_PATH),
+        Command::Uncategorized => services::analyze::run_uncategorized(file_path, &config),
+        Command::Digest => services::digest::run(file_path, &config),
+        Command::Today => services::today::run(file_path, &config),
+        Command::Ledger => services::ledger::run(file_path, &config),
+        Command::Accounting => services::accounting::run(file_path, ACCOUNT_CODES_PATH),
+        Command::Smart => services::register::run_smart(file_path, CLOSED_PATH, DEVICE_PATH, PAYEES_PATH, &config),
+        Command::List { month, category, name, sort } => {
+            let filter = ListFilter::new(month.as_deref(), category.as_deref(), name.as_deref())?;
+            services::list::run(file_path, &filter, &SortKey::parse_keys(&sort)?, &config)
+        },
+        Command::Paste { yes } => services::paste::run(file_path, CLOSED_PATH, DEVICE_PATH, yes, &config),
+        Command::Heatmap { year, html } => services::heatmap::run_with_options(file_path, year, html.as_deref(), &config),
+        Command::YearEnd { year, output } => services::report::run_with_options(file_path, year, output.as_deref(), &config),
+        Command::Payee { ranking: true } => services::payee::run_ranking(file_path, PAYEES_PATH, &config),
+        Command::Payee { ranking: false } => services::payee::run(file_path, PAYEES_PATH, &config),
+        Command::Recurring { action: Some(RecurringAction::Apply) } => services::recurring::run_apply(&recurring_paths(file_path), &config),
+        Command::Recurring { action: None } => services::recurring::run(&recurring_paths(file_path), &config),
+        Command::Budget { action: Some(BudgetAction::Report) } => services::budget::run_report(file_path, BUDGET_PATH, &config),
+        Command::Budget { action: Some(BudgetAction::Pace) } => services::budget::run_pace(file_path, BUDGET_PATH, &config),
+        Command::Budget { action: None } => services::budget::run(BUDGET_PATH, &config),
+        Command::Snapshot { action: Some(SnapshotAction::Export { as_of, output }) } => {
+            services::snapshot::run_export(file_path, as_of.as_deref(), output.as_deref())
+        },
+        Command::Snapshot { action: Some(SnapshotAction::Verify { path }) } => services::snapshot::run_verify(&path),
+        Command::Snapshot { action: None } => services::snapshot::run(file_path),
+        Command::Ranking => services::analyze::run_ranking(file_path, &config),
+        Command::Balance => services::summarize::run_running_balance(&summary_files, &config),
+        Command::Compare { a, b } => services::compare::run(file_path, &a, &b, &config),
+        Command::Stats => services::stats::run(file_path, &config),
+        Command::Review => services::review::run(file_path, &config),
+        Command::Tracker { action: Some(TrackerAction::Report) } => services::tracker::run_report(file_path, TRACKERS_PATH, &config),
+        Command::Import { action: Some(ImportAction::Csv { file, columns, header }) } => {
+            services::import::run_csv(file_path, CLOSED_PATH, DEVICE_PATH, &file, columns.as_deref(), header, &config)
+        },
+        Command::Import { action: Some(ImportAction::Bank { file, profile }) } => {
+            services::import::run_bank(file_path, CLOSED_PATH, DEVICE_PATH, BANKS_DIR, &profile, &file, &config)
+        },
+        Command::Import { action: Some(ImportAction::Ofx { file }) } => {
+            services::import::run_ofx(file_path, CLOSED_PATH, DEVICE_PATH, &file, &config)
+        },
+        Command::Import { action: Some(ImportAction::Qif { file }) } => {
+            services::import::run_qif(file_path, CLOSED_PATH, DEVICE_PATH, QIF_CATEGORIES_PATH, &file, &config)
+        },
+        Command::Import { action: None } => services::import::run(file_path, CLOSED_PATH, DEVICE_PATH, BANKS_DIR, QIF_CATEGORIES_PATH, &config),
+        Command::Tracker { action: None } => services::tracker::run(file_path, TRACKERS_PATH, &config),
+        Command::Top { count, from, to } => {
+            let from = from.as_deref().map(services::summarize::parse_date).transpose()?;
+            let to = to.as_deref().map(services::summarize::parse_date).transpose()?;
+            services::list::run_top(file_path, count, from, to, &config)
+        },
+        Command::Export { action: Some(ExportAction::Summary { format, encoding, out, categories }) } => {
+            let format = services::export::ExportFormat::parse(&format)?;
+            let encoding = services::export::CsvEncoding::parse(&encoding)?;
+            services::export::run_summary(&summary_files, format, encoding, &out, categories, &config)
+        },
+        Command::Export { action: Some(ExportAction::Data { format, encoding, out }) } => {
+            let format = services::export::ExportFormat::parse(&format)?;
+            let encoding = services::export::CsvEncoding::parse(&encoding)?;
+            services::export::run_data(&summary_files, format, encoding, &out, &config)
+        },
+        Command::Export { action: Some(ExportAction::Report { format, out }) } => {
+            let format = services::export::ExportFormat::parse(&format)?;
+            services::export::run_report(&summary_files, format, &out, &config)
+        },
+        Command::Export { action: None } => services::export::run(&summary_files, &config),
+        Command::Quote { symbol, date, amount } => services::quote::run(&symbol, date.as_deref(), amount, &config),
+        Command::Watch { dir } => services::watch::run(&dir, file_path, CLOSED_PATH, DEVICE_PATH, &config),
+    }
+}
+
+/// メニューを繰り返し表示し、ユーザーが選択した機能を実行する
+///
+/// サブコマンドが指定されなかった場合に呼び出します。集計は、表示オプションを対話入力で尋ねます。
+/// 各機能の実行後はメニューに戻り、`q`が入力されるか入力が終わるまで繰り返します。
+/// 機能の実行中に入力ミスなどのエラーが発生した場合は、メッセージを表示してメニューに戻ります。
+fn run_menu(file_path: &str, summary_files: &[&str], config: &Config) -> Result<()> {
+    loop {
+        let input = services::prompt::ask("実行したい内容を入力してください (0:登録, 1:集計, 2:曜日別分析, 3:イベント別集計, 4:一括削除, 5:スキーマ出力, 6:カテゴリ付け替え, 7:サンプルデータ生成, 8:ピン留め, 9:検索, 10:月次締め, 11:アーカイブ, 12:ジャーナル統合, 13:未分類チェック, 14:複式簿記, 15:会計ソフト出力, 16:スマート入力, 17:一覧表示, 18:貼り付け登録, 19:ヒートマップ, 20:年次レポート, 21:支払先管理, 22:定期取引, 23:予算, 24:予算実績, 25:監査用スナップショット, 26:カテゴリ順位の推移, 27:残高推移, 28:期間比較, 29:統計, 30:高額支出, 31:相場, 32:エクスポート, 33:振り返り, 34:累計トラッカー, 35:消費ペース, 36:取り込み, q:終了)")?;
+        // 空文字列は入力の終わり（EOF）を表す
+        if input.is_empty() || input.trim() == "q" {
+            println!("終了します");
+            return Ok(());
+        }
+        if input.trim().is_empty() {
+            continue;
+        }
+
+        match run_service(input.trim(), file_path, summary_files, config) {
+            // タイムアウトした後は入力できないため、メニューに戻らずに終了する
+            Err(error) if error.is_timeout() => return Err(error),
+            Err(error) => print_error(&error),
+            Ok(()) => {},
+        }
+        println!();
+    }
+}
+
+/// メニューで入力された番号に対応する機能を実行する
+fn run_service(input: &str, file_path: &str, summary_files: &[&str], config: &Config) -> Result<()> {
+    let service_type: u8 = input
+                            .parse()
+                            .map_err(|_| KakeiboError::invalid_input("数値で入力してください"))?;
+
+    // 入力値のバリデーション
+    services::validate::InputValidator::validate_service_type(service_type)?;
+
+    match service_type {
+        0 => services::register::run(file_path, CLOSED_PATH, DEVICE_PATH, PAYEES_PATH, config),
+        1 => services::summarize::run(summary_files, config),
+        2 => services::analyze::run(file_path, config),
+        3 => services::summarize::run_by_event(file_path, config),
+        4 => services::delete::run(file_path, CLOSED_PATH, None, config),
+        5 => write_json_schema(),
+        6 => services::migrate::run(file_path, CLOSED_PATH, config),
+        7 => services::generate::run(),
+        8 => services::pin::run(file_path, config),
+        9 => services::search::run(file_path, CLOSED_PATH, config),
+        10 => services::close::run(file_path, CLOSED_PATH, config),
+        11 => services::archive::run(&archive_paths(file_path)),
+        12 => services::journal::run(file_path, CLOSED_PATH),
+        13 => services::analyze::run_uncategorized(file_path, config),
+        14 => services::ledger::run(file_path, config),
+        15 => services::accounting::run(file_path, ACCOUNT_CODES_PATH),
+        16 => services::register::run_smart(file_path, CLOSED_PATH, DEVICE_PATH, PAYEES_PATH, config),
+        17 => services::list::run_interactive(file_path, config),
+        18 => services::paste::run(file_path, CLOSED_PATH, DEVICE_PATH, false, config),
+        19 => services::heatmap::run(file_path, config),
+        20 => services::report::run(file_path, config),
+        21 => services::payee::run(file_path, PAYEES_PATH, config),
+        22 => services::recurring::run(&recurring_paths(file_path), config),
+        23 => services::budget::run(BUDGET_PATH, config),
+        24 => services::budget::run_report(file_path, BUDGET_PATH, config),
+        25 => services::snapshot::run(file_path),
+        26 => services::analyze::run_ranking(file_path, config),
+        27 => services::summarize::run_running_balance(summary_files, config),
+        28 => services::compare::run_interactive(file_path, config),
+        29 => services::stats::run(file_path, config),
+        30 => services::list::run_top_interactive(file_path, config),
+        31 => services::quote::run_interactive(config),
+        32 => services::export::run(summary_files, config),
+        33 => services::review::run(file_path, config),
+        34 => services::tracker::run(file_path, TRACKERS_PATH, config),
+        35 => services::budget::run_pace(file_path, BUDGET_PATH, config),
+        _ => services::import::run(file_path, CLOSED_PATH, DEVICE_PATH, BANKS_DIR, QIF_CATEGORIES_PATH, config),
+    }
+}
+
+/// エラーのメッセージを標準エラー出力に表示する
+///
+/// データが無い場合は、エラーではなく登録を促す案内として表示します。
+fn print_error(error: &KakeiboError) {
+    match error {
+        KakeiboError::NoData => eprintln!("{}", error),
+        _ => eprintln!("エラー: {}", error),
+    }
+}
+
+/// JSONスキーマを出力する
+fn write_json_schema() -> Result<()> {
+    services::io::write_json_schema(SCHEMA_PATH)?;
+    println!("JSONスキーマを{}に出力しました", SCHEMA_PATH);
+    Ok(())
+}
+
+/// アーカイブ内のファイルの保存先を取得する
+fn archive_paths(file_path: &str) -> services::archive::ArchivePaths<'_> {
+    services::archive::ArchivePaths {
+        file_path,
+        config_path: CONFIG_PATH,
+        closed_path: CLOSED_PATH,
+    }
+}
+
+/// 定期取引で読み書きするファイルの保存先を取得する
+fn recurring_paths(file_path: &str) -> services::recurring::RecurringPaths<'_> {
+    services::recurring::RecurringPaths {
+        file_path,
+        recurring_path: RECURRING_PATH,
+        accounts_path: ACCOUNTS_PATH,
+        closed_path: CLOSED_PATH,
+        device_path: DEVICE_PATH,
+    }
+}
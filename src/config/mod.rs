@@ -0,0 +1,494 @@
+//! アプリ設定
+//!
+//! このモジュールは、JSONファイル`store/config.json`から読み込むアプリの設定を定義します。
+//! 設定ファイルが存在しない場合は、既定値（日本円・桁区切りなし・月曜始まり）が使われます。
+//!
+//! #### 設定ファイルの例
+//!
+//! ```json
+//! {
+//!   "currency": "JPY",
+//!   "thousands_separator": ",",
+//!   "week_start": "Mon",
+//!   "show_category_emoji": true,
+//!   "category_emojis": { "食費": "🍜", "収入:その他": "🪙" },
+//!   "double_entry": false,
+//!   "opening_balance": 50000,
+//!   "price_index": { "2020": 100.0, "2023": 105.6 },
+//!   "quote_sources": [{ "type": "file", "path": "store/quotes.json" }],
+//!   "import_columns": ["date", "name", "amount", "category"],
+//!   "default_accounts": { "食費": "Cash", "趣味": "CreditCard", "給与": "Bank" },
+//!   "prompt": { "timeout_secs": 60, "defaults": { "口座を入力してください": "1" } },
+//!   "store": "sqlite://store/kakeibo.db"
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::BufReader;
+
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{KakeiboError, Result};
+use crate::models::{Account, Category, Item, Money};
+use crate::services::import::{self, ImportColumn};
+use crate::services::prompt::PromptSettings;
+use crate::services::quote::{self, QuoteSource};
+
+/// 通貨を表す列挙型
+///
+/// - `JPY`: 日本円
+/// - `USD`: 米ドル
+/// - `EUR`: ユーロ
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Currency {
+    JPY,
+    USD,
+    EUR,
+}
+
+impl Currency {
+    /// 数値の文字列に通貨の単位を付ける
+    ///
+    /// 日本円は後置（`1000円`）、それ以外は記号を前置（`$1000`）します。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::config::Currency;
+    ///
+    /// assert_eq!(Currency::JPY.attach_unit("1000"), "1000円");
+    /// assert_eq!(Currency::USD.attach_unit("1000"), "$1000");
+    /// ```
+    pub fn attach_unit(&self, number: &str) -> String {
+        match self {
+            Currency::JPY => format!("{}円", number),
+            Currency::USD => format!("${}", number),
+            Currency::EUR => format!("€{}", number),
+        }
+    }
+
+    /// 通貨の最小単位の小数点以下の桁数を取得する
+    ///
+    /// 日本円は1円が最小単位のため`0`、米ドル・ユーロはセントが最小単位のため`2`です。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::config::Currency;
+    ///
+    /// assert_eq!(Currency::JPY.get_minor_digits(), 0);
+    /// assert_eq!(Currency::USD.get_minor_digits(), 2);
+    /// ```
+    pub fn get_minor_digits(&self) -> u32 {
+        match self {
+            Currency::JPY => 0,
+            Currency::USD | Currency::EUR => 2,
+        }
+    }
+}
+
+/// アプリの設定を表す構造体
+///
+/// - `currency`: 既定通貨
+/// - `thousands_separator`: 桁区切り文字（`None`の場合は区切らない）
+/// - `week_start`: 週の開始曜日
+/// - `show_category_emoji`: カテゴリの表示に絵文字を付けるかどうか
+/// - `category_emojis`: カテゴリ（`食費`・`収入:その他`など）ごとの表示に使う絵文字。設定が無いカテゴリは既定の絵文字を使う
+/// - `double_entry`: 複式モード（登録時に借方・貸方の勘定科目を尋ねる）を使うかどうか
+/// - `opening_balance`: 家計簿を付け始める前の残高（残高の推移の起点）
+/// - `price_index`: 年ごとの物価指数（消費者物価指数など）。過去の金額を実質値に換算するときに使う
+/// - `quote_sources`: 為替レートや株価などの相場の取得先（先頭から順に問い合わせる）
+/// - `import_columns`: 取り込むCSVファイルの列の並び
+/// - `default_accounts`: カテゴリ（`食費`・`収入:その他`など）ごとの既定の口座（支払方法）。登録時に口座の初期値として提示する
+/// - `prompt`: 対話プロンプトの既定値（空行で採用する値）と入力待ちのタイムアウト
+/// - `store`: 家計簿データの保存先（`None`の場合は`store/data.json`）。`sqlite://`で始まる場合はSQLiteのデータベースに保存する
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Config {
+    pub currency: Currency,
+    pub thousands_separator: Option<char>,
+    pub week_start: Weekday,
+    pub show_category_emoji: bool,
+    pub category_emojis: BTreeMap<String, String>,
+    pub double_entry: bool,
+    pub opening_balance: Money,
+    pub price_index: BTreeMap<i32, f64>,
+    pub quote_sources: Vec<QuoteSource>,
+    pub import_columns: Vec<ImportColumn>,
+    pub default_accounts: BTreeMap<String, Account>,
+    pub prompt: PromptSettings,
+    pub store: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            currency: Currency::JPY,
+            thousands_separator: None,
+            week_start: Weekday::Mon,
+            show_category_emoji: true,
+            category_emojis: BTreeMap::new(),
+            double_entry: false,
+            opening_balance: Money::ZERO,
+            price_index: BTreeMap::new(),
+            quote_sources: vec![QuoteSource::File { path: quote::DEFAULT_QUOTES_PATH.to_string() }],
+            import_columns: ImportColumn::get_default_columns(),
+            default_accounts: BTreeMap::new(),
+            prompt: PromptSettings::default(),
+            store: None,
+        }
+    }
+}
+
+impl Config {
+    /// 設定ファイルを読み込むか、既定値の設定を返します。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::config::Config;
+    ///
+    /// let config = Config::load_or_default("store/config.json").unwrap();
+    /// ```
+    ///
+    /// #### エラー
+    ///
+    /// 設定ファイルの形式が不正な場合は、`KakeiboError::InvalidData`を返します。
+    pub fn load_or_default(file_path: &str) -> Result<Self> {
+        match File::open(file_path) {
+            Ok(f) => {
+                let buf_reader = BufReader::new(f);
+                serde_json::from_reader(buf_reader)
+                    .map_err(|error| KakeiboError::InvalidData(format!("{}の読み込みに失敗しました: {}", file_path, error)))
+            },
+            Err(_) => Ok(Config::default()),
+        }
+    }
+
+    /// カテゴリの既定の口座を取得する
+    ///
+    /// `default_accounts`のカテゴリは、取り込みのCSVと同じく`区分:名前`か名前だけで書きます。設定が無いカテゴリは`None`を返します。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use std::collections::BTreeMap;
+    /// use kakeibo_app::config::Config;
+    /// use kakeibo_app::models::{Account, Category, ExpenseCategory};
+    ///
+    /// let config = Config { default_accounts: BTreeMap::from([(String::from("趣味"), Account::CreditCard)]), ..Config::default() };
+    /// assert_eq!(config.get_default_account(&Category::Expense(ExpenseCategory::Hobby)), Some(Account::CreditCard));
+    /// assert_eq!(config.get_default_account(&Category::Expense(ExpenseCategory::Food)), None);
+    /// ```
+    pub fn get_default_account(&self, category: &Category) -> Option<Account> {
+        self.default_accounts
+            .iter()
+            .find(|(name, _)| !name.trim().is_empty() && import::parse_category(name.trim()).as_ref() == Some(category))
+            .map(|(_, account)| account.clone())
+    }
+
+    /// 数値を設定の桁区切りでフォーマットする
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::config::Config;
+    ///
+    /// let config = Config { thousands_separator: Some(','), ..Config::default() };
+    /// assert_eq!(config.format_number(1234567), "1,234,567");
+    /// assert_eq!(config.format_number(-1000), "-1,000");
+    /// ```
+    pub fn format_number(&self, number: i64) -> String {
+        let digits = number.unsigned_abs().to_string();
+        let grouped = match self.thousands_separator {
+            Some(separator) => {
+                let mut grouped = String::new();
+                for (i, c) in digits.chars().enumerate() {
+                    if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                        grouped.push(separator);
+                    }
+                    grouped.push(c);
+                }
+                grouped
+            },
+            None => digits,
+        };
+
+        if number < 0 {
+            format!("-{}", grouped)
+        } else {
+            grouped
+        }
+    }
+
+    /// 金額を設定の通貨・桁区切りでフォーマットする
+    ///
+    /// 米ドル・ユーロの金額は、セント単位の金額を小数点以下2桁で表示します。
+    /// 負の金額は、通貨の単位の前に符号を付けます（`-$0.05`など）。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::config::{Config, Currency};
+    /// use kakeibo_app::models::Money;
+    ///
+    /// let config = Config::default();
+    /// assert_eq!(config.format_currency(Money::new(-5000)), "-5000円");
+    ///
+    /// let config = Config { currency: Currency::USD, ..Config::default() };
+    /// assert_eq!(config.format_currency(Money::new(123456)), "$1234.56");
+    /// assert_eq!(config.format_currency(Money::new(-123456)), "-$1234.56");
+    /// ```
+    pub fn format_currency(&self, price: Money) -> String {
+        let minor_units = price.get_minor_units();
+        let sign = if minor_units < 0 { "-" } else { "" };
+        let digits = self.currency.get_minor_digits();
+        let unit = 10_i64.pow(digits);
+        let major = self.format_number(minor_units / unit);
+        let major = major.trim_start_matches('-');
+        let amount = match digits {
+            0 => major.to_string(),
+            _ => format!("{}.{:0width$}", major, (minor_units % unit).unsigned_abs(), width = digits as usize),
+        };
+        format!("{}{}", sign, self.currency.attach_unit(&amount))
+    }
+
+    /// 入力された金額を設定の通貨の金額に変換する
+    ///
+    /// 桁区切りの`,`を含む入力や、米ドル・ユーロの場合は小数点以下2桁までの入力（`12.5`など）を受け付けます。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::config::{Config, Currency};
+    /// use kakeibo_app::models::Money;
+    ///
+    /// assert_eq!(Config::default().parse_price("1,000").unwrap(), Money::new(1000));
+    ///
+    /// let config = Config { currency: Currency::USD, ..Config::default() };
+    /// assert_eq!(config.parse_price("12.5").unwrap(), Money::new(1250));
+    /// ```
+    ///
+    /// #### エラー
+    ///
+    /// 0以上の数値として解釈できない場合や、通貨の最小単位より細かい場合は、`KakeiboError::InvalidInput`を返します。
+    pub fn parse_price(&self, text: &str) -> Result<Money> {
+        let error = || KakeiboError::invalid_input("金額は数値で入力してください");
+        let digits = self.currency.get_minor_digits() as usize;
+        let text = text.trim();
+        let (major, fraction) = text.split_once('.').unwrap_or((text, ""));
+        if text.starts_with('-') || fraction.len() > digits || !fraction.chars().all(|c| c.is_ascii_digit()) {
+            return Err(error());
+        }
+
+        let major = major.parse::<Money>()?.get_minor_units();
+        let fraction = format!("{:0<width$}", fraction, width = digits);
+        let fraction = if fraction.is_empty() { 0 } else { fraction.parse::<i64>().map_err(|_| error())? };
+        major.checked_mul(10_i64.pow(digits as u32))
+            .and_then(|units| units.checked_add(fraction))
+            .map(Money::new)
+            .ok_or_else(error)
+    }
+
+    /// 項目の金額を一覧表示用にフォーマットする
+    ///
+    /// 収入は正の値、支出は負の値で表示します。口座間の振替は収支ではないため、符号を付けずに振替額を表示します。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::config::Config;
+    /// use kakeibo_app::models::{Account, Category, ExpenseCategory, Item, Money};
+    /// use chrono::NaiveDate;
+    ///
+    /// let date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+    /// let config = Config::default();
+    /// assert_eq!(config.format_item_price(&Item::new(String::from("ランチ"), Category::Expense(ExpenseCategory::Food), Money::new(1000), date)), "-1000円");
+    /// assert_eq!(config.format_item_price(&Item::new(String::from("ATM"), Category::Transfer(Account::Cash), Money::new(10000), date)), "10000円");
+    /// ```
+    pub fn format_item_price(&self, item: &Item) -> String {
+        match item.get_item_category() {
+            Category::Transfer(_) => self.format_currency(item.get_price()),
+            _ => self.format_currency(item.get_price_for_summary()),
+        }
+    }
+
+    /// カテゴリを設定に従って表示用にフォーマットする
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::config::Config;
+    /// use kakeibo_app::models::{Category, IncomeCategory};
+    ///
+    /// let config = Config::default();
+    /// assert_eq!(config.format_category(&Category::Income(IncomeCategory::Salary)), "💴給与");
+    /// ```
+    pub fn format_category(&self, category: &Category) -> String {
+        if self.show_category_emoji {
+            format!("{}{}", self.get_category_emoji(category), category.get_name())
+        } else {
+            category.get_name().to_string()
+        }
+    }
+
+    /// カテゴリの表示に使う絵文字を取得する
+    ///
+    /// `category_emojis`のカテゴリは、`default_accounts`と同じく`区分:名前`か名前だけで書きます。設定が無いカテゴリは既定の絵文字を返します。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use std::collections::BTreeMap;
+    /// use kakeibo_app::config::Config;
+    /// use kakeibo_app::models::{Category, ExpenseCategory};
+    ///
+    /// let config = Config { category_emojis: BTreeMap::from([(String::from("食費"), String::from("🍜"))]), ..Config::default() };
+    /// assert_eq!(config.get_category_emoji(&Category::Expense(ExpenseCategory::Food)), "🍜");
+    /// assert_eq!(config.get_category_emoji(&Category::Expense(ExpenseCategory::Hobby)), "🎮");
+    /// ```
+    pub fn get_category_emoji(&self, category: &Category) -> &str {
+        self.category_emojis
+            .iter()
+            .find(|(name, _)| !name.trim().is_empty() && import::parse_category(name.trim()).as_ref() == Some(category))
+            .map_or(category.get_emoji(), |(_, emoji)| emoji.as_str())
+    }
+
+    /// 実質値の基準年（物価指数が設定されている最も新しい年）を取得する
+    ///
+    /// 物価指数が設定されていない場合は`None`を返します。
+    pub fn get_base_year(&self) -> Option<i32> {
+        self.price_index.keys().next_back().copied()
+    }
+
+    /// 指定された年の金額を、基準年の物価で換算した実質値に変換する
+    ///
+    /// 実質値は、金額に「基準年の物価指数 / その年の物価指数」を掛けて計算し、通貨の最小単位未満は四捨五入します。
+    /// その年の物価指数が設定されていない場合は`None`を返します。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::config::Config;
+    /// use kakeibo_app::models::Money;
+    ///
+    /// let config = Config { price_index: [(2020, 100.0), (2023, 105.0)].into(), ..Config::default() };
+    /// assert_eq!(config.get_base_year(), Some(2023));
+    /// assert_eq!(config.to_real_value(Money::new(-10000), 2020), Some(Money::new(-10500)));
+    /// assert_eq!(config.to_real_value(Money::new(-10000), 2021), None);
+    /// ```
+    pub fn to_real_value(&self, price: Money, year: i32) -> Option<Money> {
+        let base_index = self.price_index.get(&self.get_base_year()?)?;
+        let index = self.price_index.get(&year).filter(|index| **index > 0.0)?;
+        Some(Money::new((price.get_minor_units() as f64 * base_index / index).round() as i64))
+    }
+
+    /// 指定された日付を含む週の開始日を取得する
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::config::Config;
+    /// use chrono::{NaiveDate, Weekday};
+    ///
+    /// let config = Config { week_start: Weekday::Sun, ..Config::default() };
+    /// let date = NaiveDate::from_ymd_opt(2023, 1, 4).unwrap();
+    /// assert_eq!(config.get_week_start_date(date), NaiveDate::from_ymd_opt(2023, 1, 1).unwrap());
+    /// ```
+    pub fn get_week_start_date(&self, date: NaiveDate) -> NaiveDate {
+        let offset = date.weekday().days_since(self.week_start);
+        date.checked_sub_days(Days::new(offset as u64)).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod config_test {
+    use super::*;
+
+    #[test]
+    fn test_format_number() {
+        let config = Config::default();
+        assert_eq!(config.format_number(1234567), "1234567");
+
+        let config = Config { thousands_separator: Some(','), ..Config::default() };
+        assert_eq!(config.format_number(0), "0");
+        assert_eq!(config.format_number(999), "999");
+        assert_eq!(config.format_number(1000), "1,000");
+        assert_eq!(config.format_number(-123456), "-123,456");
+    }
+
+    #[test]
+    fn test_format_category() {
+        let food = Category::Expense(crate::models::ExpenseCategory::Food);
+        let other_income = Category::Income(crate::models::IncomeCategory::Other);
+        let config = Config {
+            category_emojis: BTreeMap::from([
+                ("食費".to_string(), "🍜".to_string()),
+                ("収入:その他".to_string(), "🪙".to_string()),
+            ]),
+            ..Config::default()
+        };
+        assert_eq!(config.format_category(&food), "🍜食費");
+        assert_eq!(config.format_category(&other_income), "🪙その他");
+        assert_eq!(config.format_category(&Category::Expense(crate::models::ExpenseCategory::Other)), "📦その他");
+
+        let config = Config { show_category_emoji: false, ..config };
+        assert_eq!(config.format_category(&food), "食費");
+    }
+
+    #[test]
+    fn test_get_default_account() {
+        let config = Config {
+            default_accounts: BTreeMap::from([
+                ("支出:その他".to_string(), Account::CreditCard),
+                ("給与".to_string(), Account::Bank),
+                ("不明".to_string(), Account::Bank),
+            ]),
+            ..Config::default()
+        };
+        assert_eq!(config.get_default_account(&Category::Expense(crate::models::ExpenseCategory::Other)), Some(Account::CreditCard));
+        assert_eq!(config.get_default_account(&Category::Income(crate::models::IncomeCategory::Salary)), Some(Account::Bank));
+        assert_eq!(config.get_default_account(&Category::Income(crate::models::IncomeCategory::Other)), None);
+    }
+
+    #[test]
+    fn test_format_currency() {
+        let config = Config { currency: Currency::USD, ..Config::default() };
+        assert_eq!(config.format_currency(Money::new(1000)), "$10.00");
+        assert_eq!(config.format_currency(Money::new(-5)), "-$0.05");
+        assert_eq!(config.format_currency(Money::new(-123456)), "-$1234.56");
+
+        let config = Config { currency: Currency::EUR, thousands_separator: Some(','), ..Config::default() };
+        assert_eq!(config.format_currency(Money::new(123456789)), "€1,234,567.89");
+    }
+
+    #[test]
+    fn test_parse_price() {
+        let config = Config::default();
+        assert_eq!(config.parse_price(" 6000000000 ").unwrap(), Money::new(6_000_000_000));
+        assert!(config.parse_price("12.5").is_err());
+        assert!(config.parse_price("-100").is_err());
+        assert!(config.parse_price("abc").is_err());
+
+        let config = Config { currency: Currency::USD, ..Config::default() };
+        assert_eq!(config.parse_price("1,234.56").unwrap(), Money::new(123456));
+        assert_eq!(config.parse_price("3").unwrap(), Money::new(300));
+        assert!(config.parse_price("0.125").is_err());
+    }
+
+    #[test]
+    fn test_get_week_start_date() {
+        let config = Config::default();
+        let date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        assert_eq!(config.get_week_start_date(date), NaiveDate::from_ymd_opt(2022, 12, 26).unwrap());
+    }
+
+    #[test]
+    fn test_deserialize_partial_config() {
+        let config: Config = serde_json::from_str(r#"{"week_start": "Sun"}"#).unwrap();
+        assert_eq!(config.currency, Currency::JPY);
+        assert_eq!(config.week_start, Weekday::Sun);
+    }
+}
@@ -0,0 +1,139 @@
+//! エラー型
+//!
+//! このモジュールは、アプリ全体で使うエラー型`KakeiboError`と、それを使う`Result`型を定義します。
+//! 各サービスはエラーを`Result`で呼び出し元に返し、`main`関数でまとめてメッセージを表示して終了します。
+
+use std::fmt;
+use std::io;
+
+use crate::services::validate::ValidationError;
+
+/// アプリのエラーを表す列挙型
+///
+/// - `InvalidInput`: ユーザーの入力値が不正
+/// - `Validation`: 選択肢の番号などの入力値が許容範囲外（入力項目と許容範囲を保持）
+/// - `Stdin`: 標準入力からの読み込みに失敗
+/// - `Io`: ファイルの読み書きに失敗
+/// - `InvalidData`: データファイルの内容が不正
+/// - `NoData`: データが存在しない（データファイルが無い場合を含む）
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::error::KakeiboError;
+///
+/// let error = KakeiboError::invalid_input("金額は数値で入力してください");
+/// assert_eq!(error.to_string(), "金額は数値で入力してください");
+/// ```
+#[derive(Debug)]
+pub enum KakeiboError {
+    InvalidInput(String),
+    Validation(ValidationError),
+    Stdin(io::Error),
+    Io { path: String, source: io::Error },
+    InvalidData(String),
+    NoData,
+}
+
+/// `KakeiboError`をエラーとする`Result`型
+pub type Result<T> = std::result::Result<T, KakeiboError>;
+
+impl KakeiboError {
+    /// 入力値が不正であることを表すエラーを作成する
+    ///
+    /// #### 引数
+    ///
+    /// - `message`: ユーザーに表示するメッセージ
+    pub fn invalid_input(message: &str) -> Self {
+        KakeiboError::InvalidInput(message.to_string())
+    }
+
+    /// ファイルの読み書きに失敗したことを表すエラーを作成する
+    ///
+    /// #### 引数
+    ///
+    /// - `path`: 読み書きしようとしたファイルのパス
+    /// - `source`: 発生したI/Oエラー
+    pub fn io(path: &str, source: io::Error) -> Self {
+        KakeiboError::Io { path: path.to_string(), source }
+    }
+
+    /// 入力待ちがタイムアウトしたことを表すエラーかどうかを返す
+    ///
+    /// タイムアウトした後の入力はすべてエラーになるため、対話メニューなどはこのエラーで終了します。
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, KakeiboError::Stdin(source) if source.kind() == io::ErrorKind::TimedOut)
+    }
+
+    /// プロセスの終了コードを取得する
+    ///
+    /// データが無い場合は`3`、それ以外のエラーは`1`を返します（`2`はコマンドライン引数の誤りで使われます）。
+    /// スクリプトから実行した場合に、データが無いだけなのか、処理に失敗したのかを区別できます。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::error::KakeiboError;
+    ///
+    /// assert_eq!(KakeiboError::NoData.get_exit_code(), 3);
+    /// assert_eq!(KakeiboError::invalid_input("金額は数値で入力してください").get_exit_code(), 1);
+    /// ```
+    pub fn get_exit_code(&self) -> i32 {
+        match self {
+            KakeiboError::NoData => 3,
+            _ => 1,
+        }
+    }
+}
+
+impl fmt::Display for KakeiboError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KakeiboError::InvalidInput(message) => write!(f, "{}", message),
+            KakeiboError::Validation(error) => write!(f, "{}", error),
+            KakeiboError::Stdin(source) if source.kind() == io::ErrorKind::TimedOut => write!(f, "{}", source),
+            KakeiboError::Stdin(source) => write!(f, "入力の読み込みに失敗しました: {}", source),
+            KakeiboError::Io { path, source } if source.kind() == io::ErrorKind::PermissionDenied => {
+                write!(f, "{}にアクセスする権限がありません。ファイルやディレクトリの権限を確認してください ({})", path, source)
+            },
+            KakeiboError::Io { path, source } => write!(f, "ファイルの読み書きに失敗しました: {} ({})", path, source),
+            KakeiboError::InvalidData(message) => write!(f, "データの形式が不正です\n{}", message),
+            KakeiboError::NoData => write!(f, "データがありません。まず登録してください"),
+        }
+    }
+}
+
+impl std::error::Error for KakeiboError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            KakeiboError::Stdin(source) | KakeiboError::Io { source, .. } => Some(source),
+            KakeiboError::Validation(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<ValidationError> for KakeiboError {
+    fn from(error: ValidationError) -> Self {
+        KakeiboError::Validation(error)
+    }
+}
+
+#[cfg(test)]
+mod error_test {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let error = KakeiboError::io("store/data.json", io::Error::new(io::ErrorKind::NotFound, "not found"));
+        assert_eq!(error.to_string(), "ファイルの読み書きに失敗しました: store/data.json (not found)");
+        let error = KakeiboError::io("store", io::Error::new(io::ErrorKind::PermissionDenied, "permission denied"));
+        assert_eq!(error.to_string(), "storeにアクセスする権限がありません。ファイルやディレクトリの権限を確認してください (permission denied)");
+        assert_eq!(KakeiboError::InvalidData(String::from("1件目のdateが不正です: '2023-13-01'")).to_string(), "データの形式が不正です\n1件目のdateが不正です: '2023-13-01'");
+        assert_eq!(KakeiboError::NoData.to_string(), "データがありません。まず登録してください");
+
+        let error = KakeiboError::Stdin(io::Error::new(io::ErrorKind::TimedOut, "30秒間入力が無かったため、入力を打ち切りました"));
+        assert!(error.is_timeout());
+        assert_eq!(error.to_string(), "30秒間入力が無かったため、入力を打ち切りました");
+    }
+}
@@ -2,6 +2,13 @@
 //!
 //! - サービスモジュール
 //! - モデルモジュール
+//! - 設定モジュール
+//! - エラーモジュール
+//!
+//! このクレートは家計簿のコアのライブラリで、コマンドラインアプリは`cli`のクレートにあります。
+//! chrono・serde以外の重い依存は、機能（`watch`・`http`・`pdf`・`terminal`）を無効にすると外せます。
 
 pub mod services;
-pub mod models;
\ No newline at end of file
+pub mod models;
+pub mod config;
+pub mod error;
@@ -2,9 +2,18 @@
 //!
 //! このモジュールは、データ構造の定義やデータ操作のための機能を実装したものです。
 
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use serde_json::{Map, Value};
 use chrono::{NaiveDate, Datelike};
 
+use crate::error::KakeiboError;
+
 /// 税金カテゴリを表す列挙型
 ///
 /// これは、収入のカテゴリを表します。
@@ -21,7 +30,7 @@ use chrono::{NaiveDate, Datelike};
 /// let bonus = IncomeCategory::Bonus;
 /// let other = IncomeCategory::Other;
 /// ```
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum IncomeCategory {
     Salary,
     Bonus,
@@ -44,7 +53,7 @@ pub enum IncomeCategory {
 /// let hobby = ExpenseCategory::Hobby;
 /// let other = ExpenseCategory::Other;
 /// ```
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ExpenseCategory {
     Food,
     Hobby,
@@ -56,6 +65,9 @@ pub enum ExpenseCategory {
 /// これは、収入と支出のカテゴリをまとめたものです。
 /// - `Income`: 収入カテゴリ
 /// - `Expense`: 支出カテゴリ
+/// - `Transfer`: 口座間の振替（値は振替先の口座。振替元は項目の口座）
+///
+/// 振替は収入にも支出にも含めず、口座別の残高だけを増減させます。
 ///
 /// #### 例
 /// 
@@ -65,39 +77,610 @@ pub enum ExpenseCategory {
 /// let income = Category::Income(IncomeCategory::Salary);
 /// let expense = Category::Expense(ExpenseCategory::Food);
 /// ~~~
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Category {
     Income(IncomeCategory),
     Expense(ExpenseCategory),
+    Transfer(Account),
+}
+
+impl Category {
+    /// すべてのカテゴリを取得する
+    ///
+    /// 収入カテゴリ、支出カテゴリの順に並べて返します。振替は含みません。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Category, IncomeCategory};
+    ///
+    /// let categories = Category::get_all();
+    /// assert_eq!(categories.len(), 6);
+    /// assert_eq!(categories[0], Category::Income(IncomeCategory::Salary));
+    /// ```
+    pub fn get_all() -> Vec<Category> {
+        vec![
+            Category::Income(IncomeCategory::Salary),
+            Category::Income(IncomeCategory::Bonus),
+            Category::Income(IncomeCategory::Other),
+            Category::Expense(ExpenseCategory::Food),
+            Category::Expense(ExpenseCategory::Hobby),
+            Category::Expense(ExpenseCategory::Other),
+        ]
+    }
+
+    /// 旧形式や別名で書かれたカテゴリを読み替える
+    ///
+    /// データファイルのカテゴリ表記が変わっても古いデータファイルを読めるよう、以下の表記をカテゴリに読み替えます。
+    /// - 区分: `Income`/`Expense`（大文字・小文字を区別しない）、または`収入`/`支出`
+    /// - 名前: 列挙子の名前（`Food`など。大文字・小文字を区別しない）、または表示名（`食費`など）
+    ///
+    /// 区分が`None`の場合は、名前だけで一意に決まるときに限り読み替えます（`その他`は収入と支出の両方にあるため読み替えません）。
+    /// カテゴリをリネームした場合は、旧名をここに追加して読み替えられるようにします。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Category, ExpenseCategory};
+    ///
+    /// assert_eq!(Category::from_alias(Some("expense"), "food"), Some(Category::Expense(ExpenseCategory::Food)));
+    /// assert_eq!(Category::from_alias(Some("支出"), "その他"), Some(Category::Expense(ExpenseCategory::Other)));
+    /// assert_eq!(Category::from_alias(None, "趣味"), Some(Category::Expense(ExpenseCategory::Hobby)));
+    /// assert_eq!(Category::from_alias(None, "その他"), None);
+    /// ```
+    pub fn from_alias(kind: Option<&str>, name: &str) -> Option<Category> {
+        let is_income = match kind.map(str::trim) {
+            Some(kind) if kind.eq_ignore_ascii_case("Income") || kind == "収入" => Some(true),
+            Some(kind) if kind.eq_ignore_ascii_case("Expense") || kind == "支出" => Some(false),
+            Some(_) => return None,
+            None => None,
+        };
+        let name = name.trim();
+        let mut candidates = Category::get_all().into_iter().filter(|category| {
+            is_income.is_none_or(|is_income| matches!(category, Category::Income(_)) == is_income)
+                && (category.get_variant_name().eq_ignore_ascii_case(name) || category.get_name() == name)
+        });
+        match (candidates.next(), candidates.next()) {
+            (Some(category), None) => Some(category),
+            _ => None,
+        }
+    }
+
+    /// 列挙子の名前（データファイルでの表記）を取得する
+    fn get_variant_name(&self) -> &'static str {
+        match self {
+            Category::Income(IncomeCategory::Salary) => "Salary",
+            Category::Income(IncomeCategory::Bonus) => "Bonus",
+            Category::Income(IncomeCategory::Other) => "Other",
+            Category::Expense(ExpenseCategory::Food) => "Food",
+            Category::Expense(ExpenseCategory::Hobby) => "Hobby",
+            Category::Expense(ExpenseCategory::Other) => "Other",
+            Category::Transfer(Account::Cash) => "Cash",
+            Category::Transfer(Account::Bank) => "Bank",
+            Category::Transfer(Account::CreditCard) => "CreditCard",
+        }
+    }
+
+    /// 複式簿記で既定とする借方と貸方の勘定科目を取得する
+    ///
+    /// 支出は「借方: カテゴリの勘定科目 / 貸方: 現金」、収入は「借方: 現金 / 貸方: カテゴリの勘定科目」、
+    /// 振替は「借方: 振替先の口座の勘定科目 / 貸方: 現金」になります。
+    /// カテゴリの勘定科目はカテゴリの表示名と同じですが、収入と支出で名前が同じ「その他」は「雑収入」「雑費」とします。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Category, IncomeCategory, ExpenseCategory};
+    ///
+    /// assert_eq!(Category::Income(IncomeCategory::Salary).get_default_accounts(), ("現金", "給与"));
+    /// assert_eq!(Category::Expense(ExpenseCategory::Other).get_default_accounts(), ("雑費", "現金"));
+    /// ```
+    pub fn get_default_accounts(&self) -> (&'static str, &'static str) {
+        self.get_default_accounts_for(&Account::Cash)
+    }
+
+    /// 口座を指定して、複式簿記で既定とする借方と貸方の勘定科目を取得する
+    ///
+    /// 仕訳の相手の勘定科目を、現金ではなく口座に対応する勘定科目（`Account::get_journal_account`）にします。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Account, Category, IncomeCategory};
+    ///
+    /// assert_eq!(Category::Income(IncomeCategory::Salary).get_default_accounts_for(&Account::Bank), ("普通預金", "給与"));
+    /// ```
+    pub fn get_default_accounts_for(&self, account: &Account) -> (&'static str, &'static str) {
+        let category_account = match self {
+            Category::Income(IncomeCategory::Other) => "雑収入",
+            Category::Expense(ExpenseCategory::Other) => "雑費",
+            Category::Transfer(to) => to.get_journal_account(),
+            _ => self.get_name(),
+        };
+        match self {
+            Category::Expense(_) | Category::Transfer(_) => (category_account, account.get_journal_account()),
+            Category::Income(_) => (account.get_journal_account(), category_account),
+        }
+    }
+
+    /// カテゴリの表示名を取得する
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Category, ExpenseCategory};
+    ///
+    /// assert_eq!(Category::Expense(ExpenseCategory::Food).get_name(), "食費");
+    /// ```
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Category::Income(IncomeCategory::Salary) => "給与",
+            Category::Income(IncomeCategory::Bonus) => "ボーナス",
+            Category::Income(IncomeCategory::Other) => "その他",
+            Category::Expense(ExpenseCategory::Food) => "食費",
+            Category::Expense(ExpenseCategory::Hobby) => "趣味",
+            Category::Expense(ExpenseCategory::Other) => "その他",
+            Category::Transfer(Account::Cash) => "現金への振替",
+            Category::Transfer(Account::Bank) => "銀行への振替",
+            Category::Transfer(Account::CreditCard) => "クレジットカードへの振替",
+        }
+    }
+
+    /// カテゴリの絵文字を取得する
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Category, ExpenseCategory};
+    ///
+    /// assert_eq!(Category::Expense(ExpenseCategory::Hobby).get_emoji(), "🎮");
+    /// ```
+    pub fn get_emoji(&self) -> &'static str {
+        match self {
+            Category::Income(IncomeCategory::Salary) => "💴",
+            Category::Income(IncomeCategory::Bonus) => "🎁",
+            Category::Income(IncomeCategory::Other) => "💰",
+            Category::Expense(ExpenseCategory::Food) => "🍙",
+            Category::Expense(ExpenseCategory::Hobby) => "🎮",
+            Category::Expense(ExpenseCategory::Other) => "📦",
+            Category::Transfer(_) => "🔁",
+        }
+    }
+
+    /// 表示用のラベルを取得する
+    ///
+    /// `with_emoji`が`true`の場合は、表示名の前に絵文字を付けます。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Category, ExpenseCategory};
+    ///
+    /// let food = Category::Expense(ExpenseCategory::Food);
+    /// assert_eq!(food.get_label(true), "🍙食費");
+    /// assert_eq!(food.get_label(false), "食費");
+    /// ```
+    pub fn get_label(&self, with_emoji: bool) -> String {
+        if with_emoji {
+            format!("{}{}", self.get_emoji(), self.get_name())
+        } else {
+            self.get_name().to_string()
+        }
+    }
+}
+
+/// 項目を登録した端末と、その端末内での連番を表す構造体
+///
+/// 複数の端末でオフラインに追記したデータを統合する際に、同じ項目かどうかを判定するために使います。
+/// - `device_id`: 項目を登録した端末のID
+/// - `seq`: 端末内で1から振られる連番
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::models::Origin;
+///
+/// let origin = Origin::new(String::from("a1b2c3d4"), 1);
+/// assert_eq!(origin.get_device_id(), "a1b2c3d4");
+/// assert_eq!(origin.get_seq(), 1);
+/// ```
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Origin {
+    device_id: String,
+    seq: u64,
+}
+
+impl Origin {
+    /// 新しい登録元を作成する
+    ///
+    /// #### 引数
+    ///
+    /// - `device_id`: 端末のID
+    /// - `seq`: 端末内の連番
+    pub fn new(device_id: String, seq: u64) -> Self {
+        Origin { device_id, seq }
+    }
+
+    /// 端末のIDを取得する
+    pub fn get_device_id(&self) -> &str {
+        &self.device_id
+    }
+
+    /// 端末内の連番を取得する
+    pub fn get_seq(&self) -> u64 {
+        self.seq
+    }
+}
+
+/// 複式簿記で仕訳の相手となる既定の勘定科目
+pub const DEFAULT_ACCOUNT: &str = "現金";
+
+/// 口座を表す列挙型
+///
+/// これは、項目の入出金に使った口座（お金の置き場所）を表します。
+/// - `Cash`: 現金（既定）
+/// - `Bank`: 銀行口座
+/// - `CreditCard`: クレジットカード
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::models::Account;
+///
+/// assert_eq!(Account::default(), Account::Cash);
+/// assert_eq!(Account::CreditCard.get_name(), "クレジットカード");
+/// ```
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Account {
+    #[default]
+    Cash,
+    Bank,
+    CreditCard,
+}
+
+impl Account {
+    /// 口座を取得する
+    ///
+    /// #### 引数
+    ///
+    /// - `account_type`: 口座種別（0: 現金, 1: 銀行, 2: クレジットカード）
+    ///
+    /// 口座種別が範囲外の場合は`None`を返します。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::Account;
+    ///
+    /// assert_eq!(Account::get_account(1), Some(Account::Bank));
+    /// assert_eq!(Account::get_account(3), None);
+    /// ```
+    pub fn get_account(account_type: u8) -> Option<Account> {
+        match account_type {
+            0 => Some(Account::Cash),
+            1 => Some(Account::Bank),
+            2 => Some(Account::CreditCard),
+            _ => None,
+        }
+    }
+
+    /// 口座の表示名を取得する
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Account::Cash => "現金",
+            Account::Bank => "銀行",
+            Account::CreditCard => "クレジットカード",
+        }
+    }
+
+    /// 複式簿記で口座に対応する勘定科目を取得する
+    ///
+    /// クレジットカードは、引き落とされるまでの未払いの金額として「未払金」とします。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::Account;
+    ///
+    /// assert_eq!(Account::Cash.get_journal_account(), "現金");
+    /// assert_eq!(Account::CreditCard.get_journal_account(), "未払金");
+    /// ```
+    pub fn get_journal_account(&self) -> &'static str {
+        match self {
+            Account::Cash => DEFAULT_ACCOUNT,
+            Account::Bank => "普通預金",
+            Account::CreditCard => "未払金",
+        }
+    }
+
+    /// 既定の口座（現金）かどうかを判定する
+    pub fn is_default(&self) -> bool {
+        *self == Account::Cash
+    }
+}
+
+/// 支出の振り返りのための3分類を表す列挙型
+///
+/// これは、支出が家計にとってどのような支出だったかを表します。
+/// - `Need`: 必要（生活に欠かせない支出）
+/// - `Waste`: 浪費（無くても困らなかった支出）
+/// - `Investment`: 投資（将来の自分や家計のためになる支出）
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::models::Judgment;
+///
+/// assert_eq!(Judgment::get_judgment(1), Some(Judgment::Waste));
+/// assert_eq!(Judgment::Investment.get_name(), "投資");
+/// ```
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Judgment {
+    Need,
+    Waste,
+    Investment,
+}
+
+impl Judgment {
+    /// すべての分類を取得する
+    pub fn get_all() -> [Judgment; 3] {
+        [Judgment::Need, Judgment::Waste, Judgment::Investment]
+    }
+
+    /// 分類を取得する
+    ///
+    /// #### 引数
+    ///
+    /// - `judgment_type`: 分類種別（0: 必要, 1: 浪費, 2: 投資）
+    ///
+    /// 分類種別が範囲外の場合は`None`を返します。
+    pub fn get_judgment(judgment_type: u8) -> Option<Judgment> {
+        match judgment_type {
+            0 => Some(Judgment::Need),
+            1 => Some(Judgment::Waste),
+            2 => Some(Judgment::Investment),
+            _ => None,
+        }
+    }
+
+    /// 分類の表示名を取得する
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Judgment::Need => "必要",
+            Judgment::Waste => "浪費",
+            Judgment::Investment => "投資",
+        }
+    }
+}
+
+/// 複式簿記の仕訳（借方と貸方の勘定科目）を表す構造体
+///
+/// - `debit`: 借方の勘定科目
+/// - `credit`: 貸方の勘定科目
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::models::JournalEntry;
+///
+/// let entry = JournalEntry::new(String::from("消耗品費"), String::from("普通預金"));
+/// assert_eq!(entry.get_debit(), "消耗品費");
+/// assert_eq!(entry.get_credit(), "普通預金");
+/// ```
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct JournalEntry {
+    debit: String,
+    credit: String,
+}
+
+impl JournalEntry {
+    /// 新しい仕訳を作成する
+    ///
+    /// #### 引数
+    ///
+    /// - `debit`: 借方の勘定科目
+    /// - `credit`: 貸方の勘定科目
+    pub fn new(debit: String, credit: String) -> Self {
+        JournalEntry { debit, credit }
+    }
+
+    /// 借方の勘定科目を取得する
+    pub fn get_debit(&self) -> &str {
+        &self.debit
+    }
+
+    /// 貸方の勘定科目を取得する
+    pub fn get_credit(&self) -> &str {
+        &self.credit
+    }
+}
+
+/// 金額を表す構造体
+///
+/// これは、通貨の最小単位（日本円は1円、米ドルは1セント）の整数で金額を保持します。
+/// 64ビットの整数を使うため、数十億円を超える金額や合計でも桁あふれしません。
+/// 足し算・引き算・符号の反転は、64ビットの整数の範囲を超える場合は最大値・最小値で止まります（パニックしたり、符号が反転したりしません）。
+/// データファイルには、これまでの金額と同じく整数のまま保存されます。
+/// 通貨の単位を付けた表示や小数を含む入力は、`Config::format_currency`・`Config::parse_price`を使います。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::models::Money;
+///
+/// let price = Money::new(5_000_000_000);
+/// assert_eq!(price + Money::new(1000), Money::new(5_000_001_000));
+/// assert_eq!(-Money::new(500), Money::new(-500));
+/// assert_eq!(Money::new(i64::MAX) + Money::new(1), Money::new(i64::MAX));
+/// assert_eq!("1,000".parse::<Money>().unwrap(), Money::new(1000));
+/// assert_eq!(Money::new(-1200).to_string(), "-1200");
+/// ```
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(transparent)]
+pub struct Money(i64);
+
+impl Money {
+    /// 0円の金額
+    pub const ZERO: Money = Money(0);
+
+    /// 新しい金額を作成する
+    ///
+    /// #### 引数
+    ///
+    /// - `minor_units`: 通貨の最小単位での金額
+    pub const fn new(minor_units: i64) -> Self {
+        Money(minor_units)
+    }
+
+    /// 通貨の最小単位での金額を取得する
+    pub fn get_minor_units(&self) -> i64 {
+        self.0
+    }
+
+    /// 金額の絶対値を取得する
+    pub fn abs(self) -> Self {
+        Money(self.0.saturating_abs())
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for Money {
+    type Err = KakeiboError;
+
+    /// 通貨の最小単位の整数（`1000`・`1,000`・`-500`など）を金額に変換する
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let digits = s.strip_prefix('-').unwrap_or(s);
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit() || c == ',') {
+            return Err(KakeiboError::invalid_input("金額は数値で入力してください"));
+        }
+        s.replace(',', "").parse().map(Money).map_err(|_| KakeiboError::invalid_input("金額は数値で入力してください"))
+    }
+}
+
+impl From<u32> for Money {
+    fn from(minor_units: u32) -> Self {
+        Money(minor_units as i64)
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, other: Money) -> Money {
+        Money(self.0.saturating_add(other.0))
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, other: Money) -> Money {
+        Money(self.0.saturating_sub(other.0))
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        Money(self.0.saturating_neg())
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, other: Money) {
+        self.0 = self.0.saturating_add(other.0);
+    }
+}
+
+impl SubAssign for Money {
+    fn sub_assign(&mut self, other: Money) {
+        self.0 = self.0.saturating_sub(other.0);
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Money>>(iter: I) -> Money {
+        iter.fold(Money::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Money> for Money {
+    fn sum<I: Iterator<Item = &'a Money>>(iter: I) -> Money {
+        iter.copied().sum()
+    }
 }
 
 /// 項目を表す構造体
 ///
 /// これは、家計簿アプリの項目を表します。
+/// - `id`: 項目を一意に識別するID（1から始まる連番。`0`は未採番）
 /// - `name`: 項目の名前
 /// - `category`: 項目のカテゴリ
+/// - `subcategory`: カテゴリの下位区分（食費に対する外食・自炊など。無い場合は`None`）
+/// - `account`: 入出金に使った口座（口座が導入される前の項目は現金）
 /// - `price`: 項目の金額
 /// - `date`: 項目の日付
+/// - `fixed`: 固定費かどうか（家賃・通信費など毎月決まって出ていく支出）
+/// - `judgment`: 支出の振り返りのための分類（必要・浪費・投資。未分類の場合は`None`）
+/// - `event`: 項目が属するイベント名（旅行・結婚式など複数日にまたがる出来事）
+/// - `payee`: 支払先（店舗・取引先など。支払先マスタの正式名）
+/// - `extra`: ユーザー独自の属性（店舗コードなど）を保持する任意のキー/値
+/// - `reference`: 領収書番号・注文番号などの参照番号（返品時の照合など）
+/// - `pinned`: ピン留めされているかどうか（大きな買い物など頻繁に参照したい項目）
+/// - `origin`: 項目を登録した端末と端末内の連番（端末IDが導入される前の項目は`None`）
+/// - `entry`: 複式簿記の仕訳（`None`の場合はカテゴリと口座から決まる）
 ///
 /// #### 例
 /// 
 /// ```rust
-/// use kakeibo_app::models::{Item, Category, IncomeCategory};
+/// use kakeibo_app::models::{Item, Category, IncomeCategory, Money};
 /// use chrono::{NaiveDate, Datelike};
 /// 
 /// let item = Item::new(
 ///     String::from("給与"),
 ///     Category::Income(IncomeCategory::Salary),
-///     100000,
+///     Money::new(100000),
 ///     NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
 /// );
 /// ```
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq)]
 pub struct Item {
+    #[serde(default)]
+    id: u64,
     name: String,
     category: Category,
-    price: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    subcategory: Option<String>,
+    #[serde(default, skip_serializing_if = "Account::is_default")]
+    account: Account,
+    #[schemars(range(min = 0))]
+    price: Money,
     date: NaiveDate,
+    #[serde(default)]
+    fixed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    judgment: Option<Judgment>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    event: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    payee: Option<String>,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    extra: Map<String, Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    reference: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pinned: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    origin: Option<Origin>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    entry: Option<JournalEntry>,
 }
 
 impl Item {
@@ -113,26 +696,28 @@ impl Item {
     /// #### 例
     /// 
     /// ```rust
-    /// use kakeibo_app::models::{Item, Category, IncomeCategory};
+    /// use kakeibo_app::models::{Item, Category, IncomeCategory, Money};
     /// use chrono::{NaiveDate, Datelike};
     /// 
     /// let item = Item::new(
     ///     String::from("給与"),
     ///     Category::Income(IncomeCategory::Salary),
-    ///     100000,
+    ///     Money::new(100000),
     ///     NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
     /// );
     /// ```
-    pub fn new(name: String, category: Category, price: u32, date: NaiveDate) -> Self {
-        Item { name, category, price, date }
+    pub fn new(name: String, category: Category, price: Money, date: NaiveDate) -> Self {
+        Item { id: 0, name, category, subcategory: None, account: Account::Cash, price, date, fixed: false, judgment: None, event: None, payee: None, extra: Map::new(), reference: None, pinned: false, origin: None, entry: None }
     }
 
     /// カテゴリを取得する
     ///
     /// #### 引数
     /// 
-    /// - `register_type`: 登録タイプ（0: 収入, 1: 支出）
-    /// - `category_type`: カテゴリタイプ（0, 1, 2）
+    /// - `register_type`: 登録タイプ（0: 収入, 1: 支出, 2: 振替）
+    /// - `category_type`: カテゴリタイプ（0, 1, 2。振替の場合は振替先の口座種別）
+    ///
+    /// 登録タイプ・カテゴリタイプが範囲外の場合は`None`を返します。
     ///
     /// #### 例
     /// 
@@ -140,38 +725,538 @@ impl Item {
     /// use kakeibo_app::models::{Item, Category, IncomeCategory};
     /// 
     /// let category = Item::get_category(0, 0);
-    /// assert_eq!(category, Category::Income(IncomeCategory::Salary));
-    /// ```
-    pub fn get_category(register_type: u8, category_type: u8) -> Category {
-        if register_type == 0 {
-            match category_type {
-                0 => Category::Income(IncomeCategory::Salary),
-                1 => Category::Income(IncomeCategory::Bonus),
-                2 => Category::Income(IncomeCategory::Other),
-                _ => panic!("不正なカテゴリ種別です")
-            }
-        } else {
-            match category_type {
-                0 => Category::Expense(ExpenseCategory::Food),
-                1 => Category::Expense(ExpenseCategory::Hobby),
-                2 => Category::Expense(ExpenseCategory::Other),
-                _ => panic!("不正なカテゴリ種別です")
-            }
+    /// assert_eq!(category, Some(Category::Income(IncomeCategory::Salary)));
+    /// assert_eq!(Item::get_category(1, 3), None);
+    /// ```
+    pub fn get_category(register_type: u8, category_type: u8) -> Option<Category> {
+        match (register_type, category_type) {
+            (0, 0) => Some(Category::Income(IncomeCategory::Salary)),
+            (0, 1) => Some(Category::Income(IncomeCategory::Bonus)),
+            (0, 2) => Some(Category::Income(IncomeCategory::Other)),
+            (1, 0) => Some(Category::Expense(ExpenseCategory::Food)),
+            (1, 1) => Some(Category::Expense(ExpenseCategory::Hobby)),
+            (1, 2) => Some(Category::Expense(ExpenseCategory::Other)),
+            (2, _) => Account::get_account(category_type).map(Category::Transfer),
+            _ => None,
         }
     }
 
+    /// 項目のIDを取得する
+    ///
+    /// まだ採番されていない項目は`0`を返します。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Item, Category, IncomeCategory, Money};
+    /// use chrono::NaiveDate;
+    ///
+    /// let mut item = Item::new(
+    ///     String::from("給与"),
+    ///     Category::Income(IncomeCategory::Salary),
+    ///     Money::new(100000),
+    ///     NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+    /// );
+    /// assert_eq!(item.get_id(), 0);
+    /// item.set_id(3);
+    /// assert_eq!(item.get_id(), 3);
+    /// ```
+    pub fn get_id(&self) -> u64 {
+        self.id
+    }
+
+    /// 項目のIDを設定する
+    pub fn set_id(&mut self, id: u64) {
+        self.id = id;
+    }
+
+    /// IDを除いた内容が同じ項目かどうかを判定する
+    ///
+    /// 別のデータファイルから読み込んだ同じ項目は、IDが異なる場合があります。
+    pub fn is_same_content(&self, other: &Item) -> bool {
+        self.name == other.name
+            && self.category == other.category
+            && self.subcategory == other.subcategory
+            && self.account == other.account
+            && self.price == other.price
+            && self.date == other.date
+            && self.fixed == other.fixed
+            && self.judgment == other.judgment
+            && self.event == other.event
+            && self.payee == other.payee
+            && self.extra == other.extra
+            && self.reference == other.reference
+            && self.pinned == other.pinned
+            && self.origin == other.origin
+            && self.entry == other.entry
+    }
+
+    /// 項目の名前を取得する
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Item, Category, IncomeCategory, Money};
+    /// use chrono::NaiveDate;
+    ///
+    /// let item = Item::new(
+    ///     String::from("給与"),
+    ///     Category::Income(IncomeCategory::Salary),
+    ///     Money::new(100000),
+    ///     NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+    /// );
+    /// assert_eq!(item.get_name(), "給与");
+    /// ```
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// 項目のカテゴリを取得する
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Item, Category, IncomeCategory, Money};
+    /// use chrono::NaiveDate;
+    ///
+    /// let item = Item::new(
+    ///     String::from("給与"),
+    ///     Category::Income(IncomeCategory::Salary),
+    ///     Money::new(100000),
+    ///     NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+    /// );
+    /// assert_eq!(item.get_item_category(), &Category::Income(IncomeCategory::Salary));
+    /// ```
+    pub fn get_item_category(&self) -> &Category {
+        &self.category
+    }
+
+    /// 項目のカテゴリを設定する
+    ///
+    /// 収入のカテゴリを設定した場合は、固定費を解除します。
+    ///
+    /// #### 引数
+    ///
+    /// - `category`: 項目のカテゴリ
+    pub fn set_category(&mut self, category: Category) {
+        if let Category::Income(_) = category {
+            self.fixed = false;
+        }
+        self.category = category;
+    }
+
+    /// 項目の金額を取得する
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Item, Category, IncomeCategory, Money};
+    /// use chrono::NaiveDate;
+    ///
+    /// let item = Item::new(
+    ///     String::from("給与"),
+    ///     Category::Income(IncomeCategory::Salary),
+    ///     Money::new(100000),
+    ///     NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+    /// );
+    /// assert_eq!(item.get_price(), Money::new(100000));
+    /// ```
+    pub fn get_price(&self) -> Money {
+        self.price
+    }
+
+    /// 項目の日付を取得する
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Item, Category, IncomeCategory, Money};
+    /// use chrono::NaiveDate;
+    ///
+    /// let item = Item::new(
+    ///     String::from("給与"),
+    ///     Category::Income(IncomeCategory::Salary),
+    ///     Money::new(100000),
+    ///     NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+    /// );
+    /// assert_eq!(item.get_date(), NaiveDate::from_ymd_opt(2023, 1, 1).unwrap());
+    /// ```
+    pub fn get_date(&self) -> NaiveDate {
+        self.date
+    }
+
+    /// 固定費かどうかを取得する
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+    /// use chrono::NaiveDate;
+    ///
+    /// let mut item = Item::new(
+    ///     String::from("家賃"),
+    ///     Category::Expense(ExpenseCategory::Other),
+    ///     Money::new(80000),
+    ///     NaiveDate::from_ymd_opt(2023, 1, 27).unwrap(),
+    /// );
+    /// assert!(!item.is_fixed());
+    /// item.set_fixed(true);
+    /// assert!(item.is_fixed());
+    /// ```
+    pub fn is_fixed(&self) -> bool {
+        self.fixed
+    }
+
+    /// 固定費かどうかを設定する
+    ///
+    /// #### 引数
+    ///
+    /// - `fixed`: 固定費の場合は`true`
+    pub fn set_fixed(&mut self, fixed: bool) {
+        self.fixed = fixed;
+    }
+
+    /// 支出の分類（必要・浪費・投資）を取得する
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Item, Category, ExpenseCategory, Judgment, Money};
+    /// use chrono::NaiveDate;
+    ///
+    /// let mut item = Item::new(
+    ///     String::from("資格の参考書"),
+    ///     Category::Expense(ExpenseCategory::Hobby),
+    ///     Money::new(3000),
+    ///     NaiveDate::from_ymd_opt(2023, 1, 27).unwrap(),
+    /// );
+    /// assert_eq!(item.get_judgment(), None);
+    /// item.set_judgment(Some(Judgment::Investment));
+    /// assert_eq!(item.get_judgment(), Some(Judgment::Investment));
+    /// ```
+    pub fn get_judgment(&self) -> Option<Judgment> {
+        self.judgment
+    }
+
+    /// 支出の分類（必要・浪費・投資）を設定する
+    ///
+    /// #### 引数
+    ///
+    /// - `judgment`: 分類（未分類の場合は`None`）
+    pub fn set_judgment(&mut self, judgment: Option<Judgment>) {
+        self.judgment = judgment;
+    }
+
+    /// サブカテゴリを取得する
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+    /// use chrono::NaiveDate;
+    ///
+    /// let mut item = Item::new(
+    ///     String::from("ランチ"),
+    ///     Category::Expense(ExpenseCategory::Food),
+    ///     Money::new(1000),
+    ///     NaiveDate::from_ymd_opt(2023, 1, 5).unwrap(),
+    /// );
+    /// assert_eq!(item.get_subcategory(), None);
+    /// item.set_subcategory(Some(String::from("外食")));
+    /// assert_eq!(item.get_subcategory(), Some("外食"));
+    /// ```
+    pub fn get_subcategory(&self) -> Option<&str> {
+        self.subcategory.as_deref()
+    }
+
+    /// サブカテゴリを設定する
+    ///
+    /// #### 引数
+    ///
+    /// - `subcategory`: サブカテゴリ名（カテゴリを細分しない場合は`None`）
+    pub fn set_subcategory(&mut self, subcategory: Option<String>) {
+        self.subcategory = subcategory;
+    }
+
+    /// 口座を取得する
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Item, Category, ExpenseCategory, Account, Money};
+    /// use chrono::NaiveDate;
+    ///
+    /// let mut item = Item::new(
+    ///     String::from("ランチ"),
+    ///     Category::Expense(ExpenseCategory::Food),
+    ///     Money::new(1000),
+    ///     NaiveDate::from_ymd_opt(2023, 1, 5).unwrap(),
+    /// );
+    /// assert_eq!(item.get_account(), &Account::Cash);
+    /// item.set_account(Account::CreditCard);
+    /// assert_eq!(item.get_account(), &Account::CreditCard);
+    /// ```
+    pub fn get_account(&self) -> &Account {
+        &self.account
+    }
+
+    /// 口座を設定する
+    pub fn set_account(&mut self, account: Account) {
+        self.account = account;
+    }
+
+    /// イベント名を取得する
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+    /// use chrono::NaiveDate;
+    ///
+    /// let mut item = Item::new(
+    ///     String::from("ホテル"),
+    ///     Category::Expense(ExpenseCategory::Hobby),
+    ///     Money::new(30000),
+    ///     NaiveDate::from_ymd_opt(2023, 8, 1).unwrap(),
+    /// );
+    /// assert_eq!(item.get_event(), None);
+    /// item.set_event(Some(String::from("北海道旅行")));
+    /// assert_eq!(item.get_event(), Some("北海道旅行"));
+    /// ```
+    pub fn get_event(&self) -> Option<&str> {
+        self.event.as_deref()
+    }
+
+    /// イベント名を設定する
+    ///
+    /// #### 引数
+    ///
+    /// - `event`: イベント名（イベントに属さない場合は`None`）
+    pub fn set_event(&mut self, event: Option<String>) {
+        self.event = event;
+    }
+
+    /// 支払先を取得する
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+    /// use chrono::NaiveDate;
+    ///
+    /// let mut item = Item::new(
+    ///     String::from("コーヒー"),
+    ///     Category::Expense(ExpenseCategory::Food),
+    ///     Money::new(480),
+    ///     NaiveDate::from_ymd_opt(2023, 8, 1).unwrap(),
+    /// );
+    /// assert_eq!(item.get_payee(), None);
+    /// item.set_payee(Some(String::from("スターバックス")));
+    /// assert_eq!(item.get_payee(), Some("スターバックス"));
+    /// ```
+    pub fn get_payee(&self) -> Option<&str> {
+        self.payee.as_deref()
+    }
+
+    /// 支払先を設定する
+    ///
+    /// #### 引数
+    ///
+    /// - `payee`: 支払先（無い場合は`None`）
+    pub fn set_payee(&mut self, payee: Option<String>) {
+        self.payee = payee;
+    }
+
+    /// 参照番号（領収書番号・注文番号など）を取得する
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+    /// use chrono::NaiveDate;
+    ///
+    /// let mut item = Item::new(
+    ///     String::from("本"),
+    ///     Category::Expense(ExpenseCategory::Hobby),
+    ///     Money::new(1500),
+    ///     NaiveDate::from_ymd_opt(2023, 8, 1).unwrap(),
+    /// );
+    /// assert_eq!(item.get_reference(), None);
+    /// item.set_reference(Some(String::from("249-1234567-1234567")));
+    /// assert_eq!(item.get_reference(), Some("249-1234567-1234567"));
+    /// ```
+    pub fn get_reference(&self) -> Option<&str> {
+        self.reference.as_deref()
+    }
+
+    /// 参照番号（領収書番号・注文番号など）を設定する
+    ///
+    /// #### 引数
+    ///
+    /// - `reference`: 参照番号（無い場合は`None`）
+    pub fn set_reference(&mut self, reference: Option<String>) {
+        self.reference = reference;
+    }
+
+    /// 複式簿記の仕訳を取得する
+    ///
+    /// 仕訳が設定されていない場合は`None`を返します。借方と貸方の勘定科目は`get_accounts`で取得してください。
+    pub fn get_entry(&self) -> Option<&JournalEntry> {
+        self.entry.as_ref()
+    }
+
+    /// 複式簿記の仕訳を設定する
+    ///
+    /// #### 引数
+    ///
+    /// - `entry`: 仕訳（カテゴリから決まる既定の仕訳にする場合は`None`）
+    pub fn set_entry(&mut self, entry: Option<JournalEntry>) {
+        self.entry = entry;
+    }
+
+    /// 借方と貸方の勘定科目を取得する
+    ///
+    /// 仕訳が設定されていない場合は、カテゴリと口座から決まる既定の勘定科目（`Category::get_default_accounts_for`）を返します。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Item, Category, ExpenseCategory, JournalEntry, Account, Money};
+    /// use chrono::NaiveDate;
+    ///
+    /// let mut item = Item::new(
+    ///     String::from("ランチ"),
+    ///     Category::Expense(ExpenseCategory::Food),
+    ///     Money::new(1000),
+    ///     NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+    /// );
+    /// assert_eq!(item.get_accounts(), ("食費", "現金"));
+    /// item.set_account(Account::CreditCard);
+    /// assert_eq!(item.get_accounts(), ("食費", "未払金"));
+    /// item.set_entry(Some(JournalEntry::new(String::from("会議費"), String::from("普通預金"))));
+    /// assert_eq!(item.get_accounts(), ("会議費", "普通預金"));
+    /// ```
+    pub fn get_accounts(&self) -> (&str, &str) {
+        match &self.entry {
+            Some(entry) => (entry.get_debit(), entry.get_credit()),
+            None => self.category.get_default_accounts_for(&self.account),
+        }
+    }
+
+    /// 拡張属性の一覧を取得する
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+    /// use chrono::NaiveDate;
+    /// use serde_json::json;
+    ///
+    /// let mut item = Item::new(
+    ///     String::from("牛乳"),
+    ///     Category::Expense(ExpenseCategory::Food),
+    ///     Money::new(200),
+    ///     NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+    /// );
+    /// item.set_extra(String::from("shop_code"), json!("S001"));
+    /// assert_eq!(item.get_extra().get("shop_code"), Some(&json!("S001")));
+    /// ```
+    pub fn get_extra(&self) -> &Map<String, Value> {
+        &self.extra
+    }
+
+    /// 拡張属性を設定する
+    ///
+    /// 同じキーの属性が既にある場合は上書きします。
+    ///
+    /// #### 引数
+    ///
+    /// - `key`: 属性のキー
+    /// - `value`: 属性の値
+    pub fn set_extra(&mut self, key: String, value: Value) {
+        self.extra.insert(key, value);
+    }
+
+    /// 拡張属性を削除する
+    ///
+    /// 削除した属性の値を返します。属性が無い場合は`None`を返します。
+    pub fn remove_extra(&mut self, key: &str) -> Option<Value> {
+        self.extra.remove(key)
+    }
+
+    /// 登録元の端末と連番を取得する
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Item, Category, ExpenseCategory, Origin, Money};
+    /// use chrono::NaiveDate;
+    ///
+    /// let mut item = Item::new(
+    ///     String::from("ランチ"),
+    ///     Category::Expense(ExpenseCategory::Food),
+    ///     Money::new(1000),
+    ///     NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+    /// );
+    /// assert_eq!(item.get_origin(), None);
+    /// item.set_origin(Some(Origin::new(String::from("a1b2c3d4"), 1)));
+    /// assert_eq!(item.get_origin().unwrap().get_seq(), 1);
+    /// ```
+    pub fn get_origin(&self) -> Option<&Origin> {
+        self.origin.as_ref()
+    }
+
+    /// 登録元の端末と連番を設定する
+    ///
+    /// #### 引数
+    ///
+    /// - `origin`: 登録元（端末IDが無い場合は`None`）
+    pub fn set_origin(&mut self, origin: Option<Origin>) {
+        self.origin = origin;
+    }
+
+    /// ピン留めされているかどうかを取得する
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+    /// use chrono::NaiveDate;
+    ///
+    /// let mut item = Item::new(
+    ///     String::from("冷蔵庫"),
+    ///     Category::Expense(ExpenseCategory::Other),
+    ///     Money::new(150000),
+    ///     NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+    /// );
+    /// item.set_pinned(true);
+    /// assert!(item.is_pinned());
+    /// ```
+    pub fn is_pinned(&self) -> bool {
+        self.pinned
+    }
+
+    /// ピン留めするかどうかを設定する
+    ///
+    /// #### 引数
+    ///
+    /// - `pinned`: ピン留めする場合は`true`
+    pub fn set_pinned(&mut self, pinned: bool) {
+        self.pinned = pinned;
+    }
+
     /// 年を取得する
     ///
     /// #### 例
     /// 
     /// ```rust
-    /// use kakeibo_app::models::{Item, Category, IncomeCategory};
+    /// use kakeibo_app::models::{Item, Category, IncomeCategory, Money};
     /// use chrono::{NaiveDate, Datelike};
     /// 
     /// let item = Item::new(
     ///     String::from("給与"),
     ///     Category::Income(IncomeCategory::Salary),
-    ///     100000,
+    ///     Money::new(100000),
     ///     NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
     /// );
     /// assert_eq!(item.get_year(), 2023);
@@ -185,13 +1270,13 @@ impl Item {
     /// #### 例
     /// 
     /// ```rust
-    /// use kakeibo_app::models::{Item, Category, IncomeCategory};
+    /// use kakeibo_app::models::{Item, Category, IncomeCategory, Money};
     /// use chrono::{NaiveDate, Datelike};
     /// 
     /// let item = Item::new(
     ///     String::from("給与"),
     ///     Category::Income(IncomeCategory::Salary),
-    ///     100000,
+    ///     Money::new(100000),
     ///     NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
     /// );
     /// assert_eq!(item.get_month(), 1);
@@ -205,13 +1290,13 @@ impl Item {
     /// #### 例
     /// 
     /// ```rust
-    /// use kakeibo_app::models::{Item, Category, IncomeCategory};
+    /// use kakeibo_app::models::{Item, Category, IncomeCategory, Money};
     /// use chrono::{NaiveDate, Datelike};
     /// 
     /// let item = Item::new(
     ///     String::from("給与"),
     ///     Category::Income(IncomeCategory::Salary),
-    ///     100000,
+    ///     Money::new(100000),
     ///     NaiveDate::from_ymd_opt(2023, 1, 15).unwrap(),
     /// );
     /// assert_eq!(item.get_first_day(), NaiveDate::from_ymd_opt(2023, 1, 1).unwrap());
@@ -223,32 +1308,65 @@ impl Item {
     /// まとめのための金額を取得する
     ///
     /// 収入の場合は正の値、支出の場合は負の値を返します。
+    /// 振替は収支に含めないため、`0`を返します（口座別の残高は`get_account_changes`で求めます）。
     ///
     /// #### 例
     /// 
     /// ```rust
-    /// use kakeibo_app::models::{Item, Category, IncomeCategory, ExpenseCategory};
+    /// use kakeibo_app::models::{Item, Category, IncomeCategory, ExpenseCategory, Money};
     /// use chrono::{NaiveDate, Datelike};
     /// 
     /// let income_item = Item::new(
     ///     String::from("給与"),
     ///     Category::Income(IncomeCategory::Salary),
-    ///     100000,
+    ///     Money::new(100000),
     ///     NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
     /// );
     /// let expense_item = Item::new(
     ///     String::from("食費"),
     ///     Category::Expense(ExpenseCategory::Food),
-    ///     5000,
+    ///     Money::new(5000),
     ///     NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
     /// );
-    /// assert_eq!(income_item.get_price_for_summary(), 100000);
-    /// assert_eq!(expense_item.get_price_for_summary(), -5000);
+    /// assert_eq!(income_item.get_price_for_summary(), Money::new(100000));
+    /// assert_eq!(expense_item.get_price_for_summary(), Money::new(-5000));
     /// ```
-    pub fn get_price_for_summary(&self) -> i32 {
+    pub fn get_price_for_summary(&self) -> Money {
         match self.category {
-            Category::Income(_) => self.price as i32,
-            Category::Expense(_) => -1 * self.price as i32,
+            Category::Income(_) => self.price,
+            Category::Expense(_) => -self.price,
+            Category::Transfer(_) => Money::ZERO,
+        }
+    }
+
+    /// 口座ごとの残高の増減を取得する
+    ///
+    /// 収入・支出は項目の口座の増減（`get_price_for_summary`と同じ値）を、
+    /// 振替は振替元（項目の口座）の減少と振替先の増加を返します。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Item, Category, Account, Money};
+    /// use chrono::NaiveDate;
+    ///
+    /// let mut item = Item::new(
+    ///     String::from("ATM引き出し"),
+    ///     Category::Transfer(Account::Cash),
+    ///     Money::new(10000),
+    ///     NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+    /// );
+    /// item.set_account(Account::Bank);
+    /// assert_eq!(item.get_price_for_summary(), Money::ZERO);
+    /// assert_eq!(item.get_account_changes(), vec![(Account::Bank, Money::new(-10000)), (Account::Cash, Money::new(10000))]);
+    /// ```
+    pub fn get_account_changes(&self) -> Vec<(Account, Money)> {
+        match &self.category {
+            Category::Transfer(to) => vec![
+                (self.account.clone(), -self.price),
+                (to.clone(), self.price),
+            ],
+            _ => vec![(self.account.clone(), self.get_price_for_summary())],
         }
     }
 }
\ No newline at end of file
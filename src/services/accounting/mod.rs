@@ -0,0 +1,178 @@
+//! 会計ソフト出力サービス
+//!
+//! このモジュールは、各項目の仕訳（借方・貸方の勘定科目）を会計ソフト（freee・弥生など）の勘定科目コードに
+//! マッピングし、取り込み用のCSVファイルに出力する機能を提供します。
+//! 勘定科目コードの対応表は、JSONファイル`store/account_codes.json`に勘定科目名をキーとして記述します。
+//!
+//! #### 対応表の例
+//!
+//! ```json
+//! {
+//!   "現金": "100",
+//!   "給与": "500",
+//!   "食費": "741",
+//!   "趣味": "742",
+//!   "雑費": "749"
+//! }
+//! ```
+//!
+//! #### 出力するCSVの列
+//!
+//! | 列 | 内容 |
+//! | --- | --- |
+//! | 日付 | 取引の日付（yyyy/mm/dd） |
+//! | 借方勘定科目 / 借方コード | 借方の勘定科目と、対応表から引いたコード |
+//! | 貸方勘定科目 / 貸方コード | 貸方の勘定科目と、対応表から引いたコード |
+//! | 金額 | 取引の金額 |
+//! | 摘要 | 品目名 |
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::BufReader;
+
+use crate::error::{KakeiboError, Result};
+use crate::models;
+use crate::services;
+
+/// CSVの既定の保存先
+const DEFAULT_OUTPUT_PATH: &str = "kakeibo_journal.csv";
+
+/// CSVのヘッダー行
+const HEADER: [&str; 7] = ["日付", "借方勘定科目", "借方コード", "貸方勘定科目", "貸方コード", "金額", "摘要"];
+
+/// 仕訳を勘定科目コード付きのCSVファイルに出力します。
+///
+/// この関数は、保存先を尋ね、すべての項目を日付順の仕訳としてCSVファイルに書き込みます。
+/// 対応表にコードが無い勘定科目はコードを空欄にして出力し、最後にその勘定科目の一覧を表示します。
+///
+/// #### 例
+///
+/// ```rust
+/// // ドキュメンテーションテストでFailになるのでコメントアウト
+/// // use kakeibo_app::services;
+/// // services::accounting::run("store/data.json", "store/account_codes.json");
+/// ```
+///
+/// #### エラー
+///
+/// データや対応表の読み込み、CSVファイルの書き込みに失敗した場合は、`KakeiboError`を返します。
+pub fn run(file_path: &str, codes_path: &str) -> Result<()> {
+    let data = services::io::read_data(file_path)?;
+    let codes = load_account_codes(codes_path)?;
+    let output_path = input_output_path()?;
+
+    let rows = create_rows(&data, &codes);
+    services::io::write_csv(&rows, &output_path)?;
+    println!("{}件の仕訳を{}に出力しました", rows.len() - 1, output_path);
+
+    let unmapped = get_unmapped_accounts(&data, &codes);
+    if !unmapped.is_empty() {
+        let unmapped: Vec<_> = unmapped.into_iter().collect();
+        println!("{}に勘定科目コードが無い勘定科目: {}", codes_path, unmapped.join(", "));
+    }
+    Ok(())
+}
+
+/// 勘定科目コードの対応表を読み込みます。
+///
+/// ファイルが無い場合は、空の対応表を返します。
+fn load_account_codes(codes_path: &str) -> Result<BTreeMap<String, String>> {
+    let Ok(file) = File::open(codes_path) else {
+        return Ok(BTreeMap::new());
+    };
+    serde_json::from_reader(BufReader::new(file))
+        .map_err(|error| KakeiboError::InvalidData(format!("{}の読み込みに失敗しました: {}", codes_path, error)))
+}
+
+/// 項目を日付順に並べ、ヘッダー行を先頭に付けたCSVの行の一覧を作成します。
+fn create_rows(data: &[models::Item], codes: &BTreeMap<String, String>) -> Vec<Vec<String>> {
+    let mut items: Vec<_> = data.iter().collect();
+    items.sort_by_key(|item| item.get_date());
+
+    let code = |account: &str| codes.get(account).cloned().unwrap_or_default();
+    let mut rows = vec![HEADER.iter().map(|column| column.to_string()).collect()];
+    rows.extend(items.into_iter().map(|item| {
+        let (debit, credit) = item.get_accounts();
+        vec![
+            item.get_date().format("%Y/%m/%d").to_string(),
+            debit.to_string(),
+            code(debit),
+            credit.to_string(),
+            code(credit),
+            item.get_price().to_string(),
+            item.get_name().to_string(),
+        ]
+    }));
+    rows
+}
+
+/// 対応表に勘定科目コードが無い勘定科目を取得します。
+fn get_unmapped_accounts<'a>(data: &'a [models::Item], codes: &BTreeMap<String, String>) -> BTreeSet<&'a str> {
+    data.iter()
+        .flat_map(|item| {
+            let (debit, credit) = item.get_accounts();
+            [debit, credit]
+        })
+        .filter(|account| !codes.contains_key(*account))
+        .collect()
+}
+
+/// ユーザーにCSVの保存先を尋ね、文字列で返します。
+///
+/// 空行が入力された場合は、既定の保存先`kakeibo_journal.csv`を返します。
+fn input_output_path() -> Result<String> {
+    let output_path = services::prompt::ask(&format!("保存先を入力してください (空行で{})", DEFAULT_OUTPUT_PATH))?;
+    let output_path = output_path.trim();
+    if output_path.is_empty() {
+        Ok(DEFAULT_OUTPUT_PATH.to_string())
+    } else {
+        Ok(output_path.to_string())
+    }
+}
+
+#[cfg(test)]
+mod accounting_test {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn get_test_data() -> Vec<models::Item> {
+        vec![
+            models::Item::new(
+                "ランチ".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                models::Money::new(1000),
+                NaiveDate::from_ymd_opt(2023, 1, 5).unwrap()
+            ),
+            models::Item::new(
+                "給与".to_string(),
+                models::Category::Income(models::IncomeCategory::Salary),
+                models::Money::new(300000),
+                NaiveDate::from_ymd_opt(2023, 1, 2).unwrap()
+            ),
+        ]
+    }
+
+    fn get_test_codes() -> BTreeMap<String, String> {
+        BTreeMap::from([
+            ("現金".to_string(), "100".to_string()),
+            ("食費".to_string(), "741".to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_create_rows() {
+        let rows = create_rows(&get_test_data(), &get_test_codes());
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0][0], "日付");
+        assert_eq!(rows[1], vec!["2023/01/02", "現金", "100", "給与", "", "300000", "給与"]);
+        assert_eq!(rows[2], vec!["2023/01/05", "食費", "741", "現金", "100", "1000", "ランチ"]);
+    }
+
+    #[test]
+    fn test_get_unmapped_accounts() {
+        let data = get_test_data();
+        let unmapped = get_unmapped_accounts(&data, &get_test_codes());
+        assert_eq!(unmapped.into_iter().collect::<Vec<_>>(), vec!["給与"]);
+    }
+}
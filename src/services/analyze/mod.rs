@@ -0,0 +1,416 @@
+//! 分析サービス
+//!
+//! このモジュールは、登録済みのデータから支出の傾向を分析する機能を提供します。
+
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, NaiveDate, Weekday};
+
+use crate::{config::Config, models, services};
+use crate::error::Result;
+
+/// 週末の1日あたりの支出が平日の何倍を超えたら警告するか
+const WEEKEND_WARNING_RATIO: f64 = 1.5;
+
+/// 月の支出のうち「その他」の比率がいくつを超えたら警告するか
+const UNCATEGORIZED_WARNING_RATIO: f64 = 0.3;
+
+/// 曜日別の支出分析を実行する。
+///
+/// この関数は、指定されたファイルパスから家計簿データを読み込み、曜日ごとの支出合計と1日あたりの支出を表示します。
+/// 週末の1日あたりの支出が平日と比べて多すぎる場合は、警告を表示します。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// services::analyze::run("store/data.json", &Config::default()).unwrap();
+/// ```
+pub fn run(file_path: &str, config: &Config) -> Result<()> {
+    println!("曜日別の支出を分析します");
+    let data = services::io::read_data(file_path)?;
+
+    let expenses = get_weekday_expenses(&data);
+    let first_date = data.iter().map(|item| item.get_date()).min().unwrap();
+    let last_date = data.iter().map(|item| item.get_date()).max().unwrap();
+    let day_counts = count_weekdays(first_date, last_date);
+
+    let mut weekday = config.week_start;
+    for _ in 0..7 {
+        let index = weekday.num_days_from_monday() as usize;
+        println!(
+            "{}: {} (1日あたり {})",
+            format_weekday(weekday),
+            config.format_currency(models::Money::new(expenses[index])),
+            config.format_currency(models::Money::new(get_daily_average(expenses[index], day_counts[index])))
+        );
+        weekday = weekday.succ();
+    }
+
+    let ratio = get_weekend_ratio(&expenses, &day_counts);
+    if ratio > WEEKEND_WARNING_RATIO {
+        println!("週末の1日あたりの支出は平日の{:.1}倍です。週末に使いすぎているかもしれません", ratio);
+    }
+    Ok(())
+}
+
+/// 未分類（「その他」カテゴリ）の支出を検出する。
+///
+/// この関数は、指定されたファイルパスから家計簿データを読み込み、月ごとの支出に占める「その他」の比率を表示します。
+/// 比率が高い月には警告を付け、最後に「その他」カテゴリの支出項目を一覧表示します。
+/// OFXファイルなどから取り込み、カテゴリを付け直す必要がある項目（収入を含む）には「要分類」を付けて表示します。
+/// カテゴリを付け直す際は、カテゴリ付け替えや一括削除と組み合わせてください。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// services::analyze::run_uncategorized("store/data.json", &Config::default()).unwrap();
+/// ```
+pub fn run_uncategorized(file_path: &str, config: &Config) -> Result<()> {
+    println!("未分類の支出を検出します");
+    let data = services::io::read_data(file_path)?;
+
+    for (month, (total, uncategorized)) in get_uncategorized_expenses(&data) {
+        let ratio = get_ratio(uncategorized, total);
+        print!(
+            "{}: 支出 {} のうち その他 {} ({:.1}%)",
+            services::summarize::format_date(month),
+            config.format_currency(models::Money::new(total)),
+            config.format_currency(models::Money::new(uncategorized)),
+            ratio * 100.0
+        );
+        if ratio > UNCATEGORIZED_WARNING_RATIO {
+            print!(" ⚠ 未分類の支出が多い月です");
+        }
+        println!();
+    }
+
+    let items: Vec<_> = data
+        .iter()
+        .filter(|item| is_uncategorized(item) || services::import::needs_category(item))
+        .collect();
+    if items.is_empty() {
+        println!("未分類の項目はありません");
+        return Ok(());
+    }
+    println!("未分類の項目 ({}件)", items.len());
+    for item in items {
+        println!(
+            "  {} {} {}{}",
+            item.get_date(),
+            item.get_name(),
+            config.format_currency(item.get_price()),
+            if services::import::needs_category(item) { " (要分類)" } else { "" }
+        );
+    }
+    Ok(())
+}
+
+/// 年ごとの支出カテゴリのランキングと、その推移を表示する。
+///
+/// この関数は、指定されたファイルパスから家計簿データを読み込み、年ごとに支出の多いカテゴリから順位を付けて表示します。
+/// 2年目以降は、前年からの順位の変動（食費が2位→1位など）を併記します。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// services::analyze::run_ranking("store/data.json", &Config::default()).unwrap();
+/// ```
+pub fn run_ranking(file_path: &str, config: &Config) -> Result<()> {
+    println!("年ごとの支出カテゴリのランキングを表示します");
+    let data = services::io::read_data(file_path)?;
+
+    let rankings = get_yearly_rankings(&data);
+    if rankings.is_empty() {
+        println!("支出の項目はありません");
+        return Ok(());
+    }
+    let mut previous: Option<&Vec<(models::ExpenseCategory, models::Money)>> = None;
+    for (year, ranking) in &rankings {
+        println!("{}年", year);
+        for (index, (category, total)) in ranking.iter().enumerate() {
+            let rank = index + 1;
+            let change = previous.map(|previous| {
+                match previous.iter().position(|(previous_category, _)| previous_category == category) {
+                    Some(previous_index) => format_rank_change(previous_index + 1, rank),
+                    None => String::from(" (新規)"),
+                }
+            });
+            println!(
+                "  {}位 {} {}{}",
+                rank,
+                config.format_category(&models::Category::Expense(category.clone())),
+                config.format_currency(*total),
+                change.unwrap_or_default()
+            );
+        }
+        previous = Some(ranking);
+    }
+    Ok(())
+}
+
+/// 家計簿データから年ごとの支出カテゴリのランキングを取得する。
+///
+/// この関数は、年をキーとし、支出合計の多い順（同額の場合はカテゴリの順）に並べた(カテゴリ, 支出合計)の一覧を値とするマップを返します。
+/// 支出が無い年やカテゴリは含みません。
+fn get_yearly_rankings(data: &[models::Item]) -> BTreeMap<i32, Vec<(models::ExpenseCategory, models::Money)>> {
+    let mut totals: BTreeMap<i32, BTreeMap<models::ExpenseCategory, models::Money>> = BTreeMap::new();
+    for item in data {
+        if let models::Category::Expense(category) = item.get_item_category() {
+            *totals.entry(item.get_year()).or_default().entry(category.clone()).or_default() += item.get_price();
+        }
+    }
+    totals.into_iter().map(|(year, totals)| {
+        let mut ranking: Vec<_> = totals.into_iter().collect();
+        ranking.sort_by(|(_, a), (_, b)| b.cmp(a));
+        (year, ranking)
+    }).collect()
+}
+
+/// 前年からの順位の変動をフォーマットする。
+///
+/// この関数は、順位が上がった場合は " (↑ 2位→1位)"、下がった場合は " (↓ 1位→2位)"、変わらない場合は " (→)" を返します。
+fn format_rank_change(previous_rank: usize, rank: usize) -> String {
+    match rank.cmp(&previous_rank) {
+        std::cmp::Ordering::Less => format!(" (↑ {}位→{}位)", previous_rank, rank),
+        std::cmp::Ordering::Greater => format!(" (↓ {}位→{}位)", previous_rank, rank),
+        std::cmp::Ordering::Equal => String::from(" (→)"),
+    }
+}
+
+/// 項目が未分類（「その他」カテゴリの支出）かどうかを判定する。
+fn is_uncategorized(item: &models::Item) -> bool {
+    *item.get_item_category() == models::Category::Expense(models::ExpenseCategory::Other)
+}
+
+/// 家計簿データから月ごとの支出合計と未分類の支出合計を取得する。
+///
+/// この関数は、月の最初の日をキーとし、(支出合計, 「その他」の支出合計)を通貨の最小単位の値とするマップを返します。
+/// 支出が無い月は含みません。
+fn get_uncategorized_expenses(data: &[models::Item]) -> BTreeMap<NaiveDate, (i64, i64)> {
+    let mut expenses = BTreeMap::new();
+    for item in data {
+        if let models::Category::Expense(_) = item.get_item_category() {
+            let (total, uncategorized) = expenses.entry(item.get_first_day()).or_insert((0, 0));
+            *total += item.get_price().get_minor_units();
+            if is_uncategorized(item) {
+                *uncategorized += item.get_price().get_minor_units();
+            }
+        }
+    }
+    expenses
+}
+
+/// 全体に占める割合を計算する。
+///
+/// この関数は、部分を全体で割った値を返します。全体が0の場合は0を返します。
+fn get_ratio(part: i64, total: i64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
+
+/// 家計簿データから曜日別の支出合計を取得する。
+///
+/// この関数は、支出項目の金額を曜日ごとに合計し、通貨の最小単位の値で月曜日を0とする配列として返します。収入項目は含みません。
+fn get_weekday_expenses(data: &[models::Item]) -> [i64; 7] {
+    let mut expenses = [0; 7];
+    for item in data {
+        if let models::Category::Expense(_) = item.get_item_category() {
+            let index = item.get_date().weekday().num_days_from_monday() as usize;
+            expenses[index] += item.get_price().get_minor_units();
+        }
+    }
+    expenses
+}
+
+/// 期間内の曜日ごとの日数を数える。
+///
+/// この関数は、開始日から終了日まで（両端を含む）の各曜日の日数を、月曜日を0とする配列として返します。
+fn count_weekdays(first_date: NaiveDate, last_date: NaiveDate) -> [i64; 7] {
+    let mut counts = [0; 7];
+    for date in first_date.iter_days().take_while(|date| *date <= last_date) {
+        counts[date.weekday().num_days_from_monday() as usize] += 1;
+    }
+    counts
+}
+
+/// 1日あたりの支出を計算する。
+///
+/// この関数は、支出合計を日数で割った値を返します。日数が0の場合は0を返します。
+fn get_daily_average(expense: i64, day_count: i64) -> i64 {
+    if day_count == 0 {
+        0
+    } else {
+        expense / day_count
+    }
+}
+
+/// 週末と平日の1日あたりの支出の比率を計算する。
+///
+/// この関数は、週末（土日）の1日あたりの支出を平日の1日あたりの支出で割った値を返します。
+/// 平日の支出が無い場合は0を返します。
+fn get_weekend_ratio(expenses: &[i64; 7], day_counts: &[i64; 7]) -> f64 {
+    let average = |days: &[usize]| {
+        let total: i64 = days.iter().map(|i| expenses[*i]).sum();
+        let count: i64 = days.iter().map(|i| day_counts[*i]).sum();
+        if count == 0 { 0.0 } else { total as f64 / count as f64 }
+    };
+    let weekday_average = average(&[0, 1, 2, 3, 4]);
+    let weekend_average = average(&[5, 6]);
+
+    if weekday_average == 0.0 {
+        0.0
+    } else {
+        weekend_average / weekday_average
+    }
+}
+
+/// 曜日を日本語の表記でフォーマットする。
+///
+/// この関数は、指定された曜日を "月曜日" のような日本語の表記で返します。
+fn format_weekday(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "月曜日",
+        Weekday::Tue => "火曜日",
+        Weekday::Wed => "水曜日",
+        Weekday::Thu => "木曜日",
+        Weekday::Fri => "金曜日",
+        Weekday::Sat => "土曜日",
+        Weekday::Sun => "日曜日",
+    }
+}
+
+#[cfg(test)]
+mod analyze_test {
+    use super::*;
+
+    fn get_test_data() -> Vec<models::Item> {
+        vec![
+            models::Item::new(
+                "ランチ".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                models::Money::new(1000),
+                NaiveDate::from_ymd_opt(2023, 1, 2).unwrap()
+            ),
+            models::Item::new(
+                "給料".to_string(),
+                models::Category::Income(models::IncomeCategory::Salary),
+                models::Money::new(300000),
+                NaiveDate::from_ymd_opt(2023, 1, 6).unwrap()
+            ),
+            models::Item::new(
+                "映画".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Hobby),
+                models::Money::new(5000),
+                NaiveDate::from_ymd_opt(2023, 1, 7).unwrap()
+            ),
+            models::Item::new(
+                "外食".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                models::Money::new(3000),
+                NaiveDate::from_ymd_opt(2023, 1, 8).unwrap()
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_get_weekday_expenses() {
+        let test_data = get_test_data();
+        assert_eq!(get_weekday_expenses(&test_data), [1000, 0, 0, 0, 0, 5000, 3000]);
+    }
+
+    #[test]
+    fn test_count_weekdays() {
+        let first_date = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap();
+        let last_date = NaiveDate::from_ymd_opt(2023, 1, 10).unwrap();
+        assert_eq!(count_weekdays(first_date, last_date), [2, 2, 1, 1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn test_get_daily_average() {
+        assert_eq!(get_daily_average(3000, 2), 1500);
+        assert_eq!(get_daily_average(3000, 0), 0);
+    }
+
+    #[test]
+    fn test_get_weekend_ratio() {
+        let expenses = [1000, 0, 0, 0, 0, 5000, 3000];
+        let day_counts = [1, 1, 1, 1, 1, 1, 1];
+        assert_eq!(get_weekend_ratio(&expenses, &day_counts), 20.0);
+        assert_eq!(get_weekend_ratio(&[0; 7], &day_counts), 0.0);
+    }
+
+    #[test]
+    fn test_get_uncategorized_expenses() {
+        let mut test_data = get_test_data();
+        test_data.push(models::Item::new(
+            "雑貨".to_string(),
+            models::Category::Expense(models::ExpenseCategory::Other),
+            models::Money::new(3000),
+            NaiveDate::from_ymd_opt(2023, 1, 9).unwrap()
+        ));
+        test_data.push(models::Item::new(
+            "臨時収入".to_string(),
+            models::Category::Income(models::IncomeCategory::Other),
+            models::Money::new(1000),
+            NaiveDate::from_ymd_opt(2023, 2, 1).unwrap()
+        ));
+        let expenses = get_uncategorized_expenses(&test_data);
+
+        assert_eq!(expenses.len(), 1);
+        assert_eq!(expenses[&NaiveDate::from_ymd_opt(2023, 1, 1).unwrap()], (12000, 3000));
+    }
+
+    #[test]
+    fn test_get_ratio() {
+        assert_eq!(get_ratio(3000, 12000), 0.25);
+        assert_eq!(get_ratio(0, 0), 0.0);
+    }
+
+    #[test]
+    fn test_get_yearly_rankings() {
+        let mut data = get_test_data();
+        data.push(models::Item::new(
+            "旅行".to_string(),
+            models::Category::Expense(models::ExpenseCategory::Hobby),
+            models::Money::new(80000),
+            NaiveDate::from_ymd_opt(2024, 5, 3).unwrap()
+        ));
+        data.push(models::Item::new(
+            "ランチ".to_string(),
+            models::Category::Expense(models::ExpenseCategory::Food),
+            models::Money::new(1000),
+            NaiveDate::from_ymd_opt(2024, 5, 4).unwrap()
+        ));
+
+        let rankings = get_yearly_rankings(&data);
+        assert_eq!(rankings[&2023], vec![
+            (models::ExpenseCategory::Hobby, models::Money::new(5000)),
+            (models::ExpenseCategory::Food, models::Money::new(4000)),
+        ]);
+        assert_eq!(rankings[&2024], vec![
+            (models::ExpenseCategory::Hobby, models::Money::new(80000)),
+            (models::ExpenseCategory::Food, models::Money::new(1000)),
+        ]);
+    }
+
+    #[test]
+    fn test_format_rank_change() {
+        assert_eq!(format_rank_change(2, 1), " (↑ 2位→1位)");
+        assert_eq!(format_rank_change(1, 3), " (↓ 1位→3位)");
+        assert_eq!(format_rank_change(2, 2), " (→)");
+    }
+
+    #[test]
+    fn test_format_weekday() {
+        assert_eq!(format_weekday(Weekday::Sat), "土曜日");
+    }
+}
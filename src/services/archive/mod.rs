@@ -0,0 +1,264 @@
+//! アーカイブサービス
+//!
+//! このモジュールは、家計簿データ・設定・締め情報を1つのJSONファイル（アーカイブ）にまとめて
+//! エクスポート/インポートする機能を提供します。PCの買い替え時などに、アーカイブを1つコピーするだけで移行できます。
+//!
+//! #### アーカイブの例
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "data": [
+//!     { "name": "ランチ", "category": { "Expense": "Food" }, "price": 1000, "date": "2023-01-15" }
+//!   ],
+//!   "config": { "currency": "JPY", "thousands_separator": ",", "week_start": "Mon", "show_category_emoji": true },
+//!   "closed_months": [ { "month": "2022-12-01", "income": 300000, "expense": 120000, "carried_over": 180000 } ]
+//! }
+//! ```
+
+use std::fs::File;
+use std::io::{BufReader, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::models;
+use crate::services;
+use crate::services::close::ClosedMonths;
+use crate::error::{KakeiboError, Result};
+
+/// アーカイブ形式のバージョン
+const ARCHIVE_VERSION: u32 = 1;
+
+/// アーカイブの既定の保存先
+const DEFAULT_ARCHIVE_PATH: &str = "kakeibo_archive.json";
+
+/// アーカイブを表す構造体
+///
+/// - `version`: アーカイブ形式のバージョン
+/// - `data`: 家計簿データ
+/// - `config`: 設定（設定ファイルが無い場合は`None`）
+/// - `closed_months`: 締め済みの月の一覧
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Archive {
+    version: u32,
+    data: Vec<models::Item>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    config: Option<Config>,
+    #[serde(default)]
+    closed_months: ClosedMonths,
+}
+
+/// アーカイブ内のファイルの保存先を表す構造体
+///
+/// - `file_path`: 家計簿データのJSONファイル
+/// - `config_path`: 設定ファイル
+/// - `closed_path`: 締め情報のJSONファイル
+pub struct ArchivePaths<'a> {
+    pub file_path: &'a str,
+    pub config_path: &'a str,
+    pub closed_path: &'a str,
+}
+
+/// アーカイブのエクスポート/インポートを行います。
+///
+/// この関数は、エクスポートかインポートかを尋ね、アーカイブの保存先（読み込み元）を受け付けて処理します。
+/// インポートの場合は、既存のデータがアーカイブの内容で置き換えられることを確認してから書き込みます。
+///
+/// #### 例
+///
+/// ```rust
+/// // ドキュメンテーションテストでFailになるのでコメントアウト
+/// // use kakeibo_app::services;
+/// // use kakeibo_app::services::archive::ArchivePaths;
+/// // services::archive::run(&ArchivePaths {
+/// //     file_path: "store/data.json",
+/// //     config_path: "store/config.json",
+/// //     closed_path: "store/closed.json",
+/// // });
+/// ```
+///
+/// #### エラー
+///
+/// 不正な入力があった場合や、アーカイブの形式・バージョンが不正な場合、ファイルの読み書きに失敗した場合は、`KakeiboError`を返します。
+pub fn run(paths: &ArchivePaths) -> Result<()> {
+    let export = input_action()?;
+    let archive_path = input_archive_path()?;
+    if export {
+        let archive = export_archive(paths)?;
+        write_archive(&archive, &archive_path)?;
+        println!("{}件の項目を{}にエクスポートしました", archive.data.len(), archive_path);
+    } else {
+        let archive = read_archive(&archive_path)?;
+        println!("既存のデータは{}の内容で置き換えられます", archive_path);
+        if !input_confirmation("インポートしてよろしいですか (y/n)")? {
+            println!("インポートを中止しました");
+            return Ok(());
+        }
+        import_archive(&archive, paths)?;
+        println!("{}件の項目を{}からインポートしました", archive.data.len(), archive_path);
+    }
+    Ok(())
+}
+
+/// 現在のデータ・設定・締め情報からアーカイブを作成します。
+fn export_archive(paths: &ArchivePaths) -> Result<Archive> {
+    let config = if Path::new(paths.config_path).exists() {
+        Some(Config::load_or_default(paths.config_path)?)
+    } else {
+        None
+    };
+    Ok(Archive {
+        version: ARCHIVE_VERSION,
+        data: services::io::read_data_or_create_new_data(paths.file_path)?,
+        config,
+        closed_months: ClosedMonths::load_or_default(paths.closed_path)?,
+    })
+}
+
+/// アーカイブの内容をデータ・設定・締め情報の各ファイルに書き込みます。
+///
+/// 3つのファイルは1つのトランザクションで書き込み、途中で失敗した場合はすべて元に戻します。
+/// 保存先のディレクトリが無い場合は作成します。
+fn import_archive(archive: &Archive, paths: &ArchivePaths) -> Result<()> {
+    let mut transaction = services::io::Transaction::new();
+    transaction.write_to_json(&archive.data, paths.file_path);
+    if let Some(config) = &archive.config {
+        transaction.write_json(config, paths.config_path);
+    }
+    transaction.write_json(&archive.closed_months, paths.closed_path);
+    transaction.commit()
+}
+
+/// アーカイブをJSONファイルに書き込みます。
+///
+/// #### エラー
+///
+/// ファイルへの書き込みに失敗した場合、`KakeiboError::Io`を返します。
+fn write_archive(archive: &Archive, archive_path: &str) -> Result<()> {
+    let json_data = serde_json::to_string_pretty(archive).expect("JSONへのシリアライズに失敗しました");
+    let mut file = File::create(archive_path).map_err(|error| KakeiboError::io(archive_path, error))?;
+    writeln!(file, "{}", json_data).map_err(|error| KakeiboError::io(archive_path, error))
+}
+
+/// JSONファイルからアーカイブを読み込みます。
+///
+/// #### エラー
+///
+/// ファイルが存在しない場合は`KakeiboError::Io`を、形式・バージョンが不正な場合は`KakeiboError::InvalidData`を返します。
+fn read_archive(archive_path: &str) -> Result<Archive> {
+    let file = File::open(archive_path).map_err(|error| KakeiboError::io(archive_path, error))?;
+    let archive: Archive = serde_json::from_reader(BufReader::new(file))
+        .map_err(|error| KakeiboError::InvalidData(format!("{}の読み込みに失敗しました: {}", archive_path, error)))?;
+    check_version(&archive)?;
+    Ok(archive)
+}
+
+/// アーカイブのバージョンがこのアプリで読み込めるものかを検証します。
+///
+/// #### エラー
+///
+/// 対応していないバージョンの場合は、`KakeiboError::InvalidData`を返します。
+fn check_version(archive: &Archive) -> Result<()> {
+    if archive.version != ARCHIVE_VERSION {
+        return Err(KakeiboError::InvalidData(format!("対応していないアーカイブのバージョンです: {}", archive.version)));
+    }
+    Ok(())
+}
+
+/// ユーザーにエクスポートかインポートかを尋ね、エクスポートの場合は`true`を返します。
+///
+/// #### エラー
+///
+/// 不正な入力があった場合、`KakeiboError`を返します。
+fn input_action() -> Result<bool> {
+    let action = services::prompt::ask("e:エクスポート / i:インポート")?;
+
+    match action.trim() {
+        "e" | "E" => Ok(true),
+        "i" | "I" => Ok(false),
+        _ => Err(KakeiboError::invalid_input("操作はe, iのいずれかで入力してください")),
+    }
+}
+
+/// ユーザーにアーカイブのパスを尋ね、文字列で返します。
+///
+/// 空行が入力された場合は、既定のパス`kakeibo_archive.json`を返します。
+///
+/// #### エラー
+///
+/// 標準入力からの読み込みに失敗した場合、`KakeiboError`を返します。
+fn input_archive_path() -> Result<String> {
+    let archive_path = services::prompt::ask(&format!("アーカイブのパスを入力してください (空行で{})", DEFAULT_ARCHIVE_PATH))?;
+    let archive_path = archive_path.trim();
+    if archive_path.is_empty() {
+        Ok(DEFAULT_ARCHIVE_PATH.to_string())
+    } else {
+        Ok(archive_path.to_string())
+    }
+}
+
+/// ユーザーにインポートしてよいかを尋ね、真偽値で返します。
+///
+/// `y`以外が入力された場合は、インポートしないものとして`false`を返します。
+///
+/// #### エラー
+///
+/// 標準入力からの読み込みに失敗した場合、`KakeiboError`を返します。
+fn input_confirmation(message: &str) -> Result<bool> {
+    let answer = services::prompt::ask(message)?;
+
+    Ok(matches!(answer.trim(), "y" | "Y"))
+}
+
+#[cfg(test)]
+mod archive_test {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_deserialize_archive() {
+        let json = serde_json::json!({
+            "version": 1,
+            "data": [{
+                "name": "ランチ",
+                "category": {"Expense": "Food"},
+                "price": 1000,
+                "date": "2023-01-15"
+            }]
+        });
+        let archive: Archive = serde_json::from_value(json).unwrap();
+
+        assert_eq!(archive.data.len(), 1);
+        assert_eq!(archive.data[0].get_date(), NaiveDate::from_ymd_opt(2023, 1, 15).unwrap());
+        assert_eq!(archive.config, None);
+        assert_eq!(archive.closed_months, ClosedMonths::default());
+    }
+
+    #[test]
+    fn test_serialize_archive() {
+        let mut closed_months = ClosedMonths::default();
+        closed_months.close(NaiveDate::from_ymd_opt(2022, 12, 1).unwrap(), &[]);
+        let archive = Archive {
+            version: ARCHIVE_VERSION,
+            data: vec![],
+            config: Some(Config::default()),
+            closed_months,
+        };
+        let json = serde_json::to_string(&archive).unwrap();
+
+        assert_eq!(serde_json::from_str::<Archive>(&json).unwrap(), archive);
+    }
+
+    #[test]
+    fn test_check_version_for_ng() {
+        let error = check_version(&Archive {
+            version: 2,
+            data: vec![],
+            config: None,
+            closed_months: ClosedMonths::default(),
+        }).unwrap_err();
+        assert!(error.to_string().ends_with("対応していないアーカイブのバージョンです: 2"));
+    }
+}
@@ -0,0 +1,448 @@
+//! 予算サービス
+//!
+//! このモジュールは、支出カテゴリごとの月の予算を設定し、月ごとの予算と実績を比較する機能を提供します。
+//! また、今月の経過率と予算の消化率を比べ、月の途中で使いすぎているカテゴリを警告します。
+//! 予算は、JSONファイル`store/budget.json`に保存されます。
+//!
+//! #### 予算の例
+//!
+//! ```json
+//! { "Food": 40000, "Hobby": 10000 }
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::BufReader;
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::error::{KakeiboError, Result};
+use crate::models;
+use crate::services;
+
+/// 支出カテゴリごとの月の予算を表す構造体
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::models::{ExpenseCategory, Money};
+/// use kakeibo_app::services::budget::Budget;
+///
+/// let mut budget = Budget::default();
+/// budget.set_limit(ExpenseCategory::Food, Some(Money::new(40000)));
+/// assert_eq!(budget.get_limit(&ExpenseCategory::Food), Some(Money::new(40000)));
+/// assert_eq!(budget.get_limit(&ExpenseCategory::Hobby), None);
+/// ```
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct Budget {
+    limits: BTreeMap<models::ExpenseCategory, models::Money>,
+}
+
+impl Budget {
+    /// JSONファイルから予算を読み込む
+    ///
+    /// ファイルが存在しない場合は、予算が無いものとして空の予算を返します。
+    ///
+    /// #### エラー
+    ///
+    /// ファイルの内容が不正な場合は、`KakeiboError::InvalidData`を返します。
+    pub fn load_or_default(file_path: &str) -> Result<Self> {
+        match File::open(file_path) {
+            Ok(f) => {
+                let buf_reader = BufReader::new(f);
+                serde_json::from_reader(buf_reader)
+                    .map_err(|error| KakeiboError::InvalidData(format!("{}の読み込みに失敗しました: {}", file_path, error)))
+            },
+            Err(_) => Ok(Budget::default()),
+        }
+    }
+
+    /// 予算をJSONファイルに書き込む
+    ///
+    /// 一時ファイルに書き込んでから置き換えるため、書き込みに失敗しても元の予算は壊れません。
+    /// 他の変更と合わせて保存する場合は、`Transaction::write_json`で予算をトランザクションに追加してください。
+    ///
+    /// #### エラー
+    ///
+    /// ファイルへの書き込みに失敗した場合、`KakeiboError::Io`を返します。
+    pub fn save(&self, file_path: &str) -> Result<()> {
+        let mut transaction = services::io::Transaction::new();
+        transaction.write_json(self, file_path);
+        transaction.commit()
+    }
+
+    /// 支出カテゴリの月の予算を取得する
+    pub fn get_limit(&self, category: &models::ExpenseCategory) -> Option<models::Money> {
+        self.limits.get(category).copied()
+    }
+
+    /// 支出カテゴリの月の予算を設定する
+    ///
+    /// `None`を指定した場合は、そのカテゴリの予算を削除します。
+    pub fn set_limit(&mut self, category: models::ExpenseCategory, limit: Option<models::Money>) {
+        match limit {
+            Some(limit) => self.limits.insert(category, limit),
+            None => self.limits.remove(&category),
+        };
+    }
+}
+
+/// ある月のカテゴリの予算と実績を表す構造体
+///
+/// - `month`: 月の最初の日
+/// - `category`: 支出カテゴリ
+/// - `budget`: 予算
+/// - `actual`: 支出の実績
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BudgetRow {
+    pub month: NaiveDate,
+    pub category: models::ExpenseCategory,
+    pub budget: models::Money,
+    pub actual: models::Money,
+}
+
+impl BudgetRow {
+    /// 予算の残り（予算 - 実績）を取得する
+    ///
+    /// 予算を超えた場合は負の値になります。
+    pub fn get_remaining(&self) -> models::Money {
+        self.budget - self.actual
+    }
+}
+
+/// 消費ペースメーターの目盛りの数
+const METER_WIDTH: usize = 20;
+
+/// 月の経過率（0.0〜1.0）を取得します。
+///
+/// 今日までの日数（今日を含む）を、月の日数で割った値を返します。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::services::budget;
+/// use chrono::NaiveDate;
+///
+/// assert_eq!(budget::get_elapsed_ratio(NaiveDate::from_ymd_opt(2023, 4, 15).unwrap()), 0.5);
+/// assert_eq!(budget::get_elapsed_ratio(NaiveDate::from_ymd_opt(2023, 2, 28).unwrap()), 1.0);
+/// ```
+pub fn get_elapsed_ratio(today: NaiveDate) -> f64 {
+    let first_day = today.with_day(1).unwrap();
+    let days = services::summarize::get_last_day(first_day).day();
+    today.day() as f64 / days as f64
+}
+
+impl BudgetRow {
+    /// 予算の消化率（実績 / 予算）を取得する
+    ///
+    /// 予算が0の場合は、実績があれば無限大、無ければ0.0を返します。
+    pub fn get_consumed_ratio(&self) -> f64 {
+        if self.budget == models::Money::ZERO {
+            return if self.actual > models::Money::ZERO { f64::INFINITY } else { 0.0 };
+        }
+        self.actual.get_minor_units() as f64 / self.budget.get_minor_units() as f64
+    }
+
+    /// 予算の消化率が月の経過率を上回っているかどうかを判定する
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{ExpenseCategory, Money};
+    /// use kakeibo_app::services::budget::BudgetRow;
+    /// use chrono::NaiveDate;
+    ///
+    /// let row = BudgetRow {
+    ///     month: NaiveDate::from_ymd_opt(2023, 4, 1).unwrap(),
+    ///     category: ExpenseCategory::Food,
+    ///     budget: Money::new(40000),
+    ///     actual: Money::new(32000),
+    /// };
+    /// assert!(row.is_over_pace(0.5));
+    /// assert!(!row.is_over_pace(0.9));
+    /// ```
+    pub fn is_over_pace(&self, elapsed_ratio: f64) -> bool {
+        self.get_consumed_ratio() > elapsed_ratio
+    }
+}
+
+/// 家計簿データのある月ごとに、予算を設定したカテゴリの予算と実績を作成します。
+///
+/// 月の古い順、カテゴリの順に並べて返します。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+/// use kakeibo_app::services::budget::{self, Budget};
+/// use chrono::NaiveDate;
+///
+/// let data = vec![Item::new(
+///     String::from("ランチ"),
+///     Category::Expense(ExpenseCategory::Food),
+///     Money::new(45000),
+///     NaiveDate::from_ymd_opt(2023, 1, 5).unwrap(),
+/// )];
+/// let mut budget = Budget::default();
+/// budget.set_limit(ExpenseCategory::Food, Some(Money::new(40000)));
+///
+/// let rows = budget::create_report(&data, &budget);
+/// assert_eq!(rows[0].get_remaining(), Money::new(-5000));
+/// ```
+pub fn create_report(data: &[models::Item], budget: &Budget) -> Vec<BudgetRow> {
+    let months: BTreeSet<_> = data.iter().map(|item| item.get_first_day()).collect();
+    let mut actuals: BTreeMap<_, models::Money> = BTreeMap::new();
+    for item in data {
+        if let models::Category::Expense(category) = item.get_item_category() {
+            *actuals.entry((item.get_first_day(), category.clone())).or_default() += item.get_price();
+        }
+    }
+
+    months.into_iter()
+        .flat_map(|month| budget.limits.iter().map(move |(category, limit)| (month, category, *limit)))
+        .map(|(month, category, limit)| BudgetRow {
+            month,
+            category: category.clone(),
+            budget: limit,
+            actual: actuals.get(&(month, category.clone())).copied().unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// 予算を表示し、支出カテゴリの予算を設定します。
+///
+/// #### 例
+///
+/// ```rust
+/// // ドキュメンテーションテストでFailになるのでコメントアウト
+/// // use kakeibo_app::config::Config;
+/// // use kakeibo_app::services;
+/// // services::budget::run("store/budget.json", &Config::default());
+/// ```
+///
+/// #### エラー
+///
+/// 不正な入力があった場合や、予算の読み書きに失敗した場合は、`KakeiboError`を返します。
+pub fn run(budget_path: &str, config: &Config) -> Result<()> {
+    let mut budget = Budget::load_or_default(budget_path)?;
+    print_budget(&budget, config);
+
+    let category_type = services::prompt::ask("予算を設定するカテゴリを入力してください (0:食費, 1:趣味, 2:その他, 空行で終了)")?;
+    if category_type.trim().is_empty() {
+        return Ok(());
+    }
+    let category_type: u8 = category_type
+                                .trim()
+                                .parse()
+                                .map_err(|_| KakeiboError::invalid_input("カテゴリは数値で入力してください"))?;
+    services::validate::InputValidator::validate_category_type(1, category_type)?;
+    let Some(models::Category::Expense(category)) = models::Item::get_category(1, category_type) else {
+        return Err(KakeiboError::invalid_input("カテゴリ種別が不正です"));
+    };
+
+    let limit = services::prompt::ask("月の予算を入力してください (空行で予算を削除)")?;
+    let limit = match limit.trim() {
+        "" => None,
+        limit => Some(config.parse_price(limit)?),
+    };
+    budget.set_limit(category, limit);
+    budget.save(budget_path)?;
+    println!("予算を保存しました");
+    Ok(())
+}
+
+/// 月ごとの予算と実績を表示します。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// services::budget::run_report("store/data.json", "store/budget.json", &Config::default()).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// 予算やデータの読み込みに失敗した場合は、`KakeiboError`を返します。
+pub fn run_report(file_path: &str, budget_path: &str, config: &Config) -> Result<()> {
+    let budget = Budget::load_or_default(budget_path)?;
+    if budget.limits.is_empty() {
+        println!("予算が設定されていません");
+        return Ok(());
+    }
+    let data = services::io::read_data(file_path)?;
+
+    let rows = create_report(&data, &budget);
+    let mut month = None;
+    for row in &rows {
+        if month != Some(row.month) {
+            println!("{}", services::summarize::format_date(row.month));
+            month = Some(row.month);
+        }
+        let remaining = row.get_remaining();
+        let status = if remaining < models::Money::ZERO {
+            format!("{}超過", config.format_currency(-remaining))
+        } else {
+            format!("残り{}", config.format_currency(remaining))
+        };
+        println!(
+            "  {} 実績 {} / 予算 {} ({})",
+            config.format_category(&models::Category::Expense(row.category.clone())),
+            config.format_currency(row.actual),
+            config.format_currency(row.budget),
+            status
+        );
+    }
+    Ok(())
+}
+
+/// 今月の経過率と、予算を設定したカテゴリごとの予算の消化率を比べるメーターを表示します。
+///
+/// 消化率が経過率を上回っているカテゴリには、使いすぎの警告を表示します。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// services::budget::run_pace("store/data.json", "store/budget.json", &Config::default()).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// 予算やデータの読み込みに失敗した場合は、`KakeiboError`を返します。
+pub fn run_pace(file_path: &str, budget_path: &str, config: &Config) -> Result<()> {
+    let budget = Budget::load_or_default(budget_path)?;
+    if budget.limits.is_empty() {
+        println!("予算が設定されていません");
+        return Ok(());
+    }
+    let today = chrono::Local::now().date_naive();
+    let this_month = today.with_day(1).unwrap();
+    let data: Vec<_> = services::io::read_data_or_create_new_data(file_path)?
+        .into_iter()
+        .filter(|item| item.get_first_day() == this_month && item.get_date() <= today)
+        .collect();
+    let elapsed_ratio = get_elapsed_ratio(today);
+
+    println!(
+        "{} 経過 {:.0}% [{}]",
+        services::summarize::format_date(this_month),
+        elapsed_ratio * 100.0,
+        render_meter(elapsed_ratio, elapsed_ratio)
+    );
+    let rows = budget.limits.iter().map(|(category, limit)| BudgetRow {
+        month: this_month,
+        category: category.clone(),
+        budget: *limit,
+        actual: data.iter()
+            .filter(|item| item.get_item_category() == &models::Category::Expense(category.clone()))
+            .map(|item| item.get_price())
+            .sum(),
+    });
+    for row in rows {
+        let consumed_ratio = row.get_consumed_ratio();
+        let status = if row.get_remaining() < models::Money::ZERO {
+            "  ⚠ 予算超過"
+        } else if row.is_over_pace(elapsed_ratio) {
+            "  ⚠ ペース超過"
+        } else {
+            ""
+        };
+        println!(
+            "  {} 消化 {:.0}% [{}] {} / {}{}",
+            config.format_category(&models::Category::Expense(row.category.clone())),
+            consumed_ratio * 100.0,
+            render_meter(consumed_ratio, elapsed_ratio),
+            config.format_currency(row.actual),
+            config.format_currency(row.budget),
+            status
+        );
+    }
+    Ok(())
+}
+
+/// 消化率を`#`の長さで、経過率の位置を`|`で表したメーターを作成します。
+///
+/// 消化率が1.0を超える場合は、メーターをすべて`#`で埋めます。
+fn render_meter(consumed_ratio: f64, elapsed_ratio: f64) -> String {
+    let filled = (consumed_ratio.min(1.0) * METER_WIDTH as f64).round() as usize;
+    let marker = ((elapsed_ratio.min(1.0) * METER_WIDTH as f64).round() as usize).clamp(1, METER_WIDTH) - 1;
+    (0..METER_WIDTH).map(|index| {
+        if index == marker {
+            '|'
+        } else if index < filled {
+            '#'
+        } else {
+            '-'
+        }
+    }).collect()
+}
+
+/// 設定されている予算を出力します。
+fn print_budget(budget: &Budget, config: &Config) {
+    if budget.limits.is_empty() {
+        println!("予算は設定されていません");
+        return;
+    }
+    println!("月の予算");
+    for (category, limit) in &budget.limits {
+        println!(
+            "  {} {}",
+            config.format_category(&models::Category::Expense(category.clone())),
+            config.format_currency(*limit)
+        );
+    }
+}
+
+#[cfg(test)]
+mod budget_test {
+    use super::*;
+
+    fn get_test_item(category: models::ExpenseCategory, price: i64, date: NaiveDate) -> models::Item {
+        models::Item::new("テスト".to_string(), models::Category::Expense(category), models::Money::new(price), date)
+    }
+
+    #[test]
+    fn test_create_report() {
+        let january = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let february = NaiveDate::from_ymd_opt(2023, 2, 1).unwrap();
+        let data = vec![
+            get_test_item(models::ExpenseCategory::Food, 30000, NaiveDate::from_ymd_opt(2023, 1, 10).unwrap()),
+            get_test_item(models::ExpenseCategory::Food, 15000, NaiveDate::from_ymd_opt(2023, 1, 20).unwrap()),
+            get_test_item(models::ExpenseCategory::Hobby, 8000, NaiveDate::from_ymd_opt(2023, 1, 20).unwrap()),
+            get_test_item(models::ExpenseCategory::Other, 5000, NaiveDate::from_ymd_opt(2023, 2, 3).unwrap()),
+        ];
+        let mut budget = Budget::default();
+        budget.set_limit(models::ExpenseCategory::Hobby, Some(models::Money::new(10000)));
+        budget.set_limit(models::ExpenseCategory::Food, Some(models::Money::new(40000)));
+
+        let rows = create_report(&data, &budget);
+        let summary: Vec<_> = rows.iter().map(|row| (row.month, row.category.clone(), row.actual, row.get_remaining())).collect();
+        assert_eq!(summary, vec![
+            (january, models::ExpenseCategory::Food, models::Money::new(45000), models::Money::new(-5000)),
+            (january, models::ExpenseCategory::Hobby, models::Money::new(8000), models::Money::new(2000)),
+            (february, models::ExpenseCategory::Food, models::Money::ZERO, models::Money::new(40000)),
+            (february, models::ExpenseCategory::Hobby, models::Money::ZERO, models::Money::new(10000)),
+        ]);
+    }
+
+    #[test]
+    fn test_render_meter() {
+        assert_eq!(render_meter(0.8, 0.5), "#########|######----");
+        assert_eq!(render_meter(0.25, 0.5), "#####----|----------");
+        assert_eq!(render_meter(1.5, 1.0), "###################|");
+        assert_eq!(render_meter(f64::INFINITY, 0.0), "|###################");
+    }
+
+    #[test]
+    fn test_set_limit() {
+        let mut budget = Budget::default();
+        budget.set_limit(models::ExpenseCategory::Food, Some(models::Money::new(40000)));
+        budget.set_limit(models::ExpenseCategory::Food, None);
+        assert_eq!(budget, Budget::default());
+    }
+}
@@ -0,0 +1,215 @@
+//! カレンダー機能
+//!
+//! このモジュールは、日本の祝日の判定と、休日に当たる日付を営業日に調整する機能を提供します。
+//! 祝日は「国民の祝日に関する法律」の規則から計算するため、祝日データのファイルは不要です。
+//! 2016年（山の日の施行）以降の祝日に対応しています。春分の日・秋分の日は計算式による推定のため、2099年までが対象です。
+//!
+//! | 調整ルール | 内容 |
+//! | --- | --- |
+//! | `BusinessDayRule::Previous` | 休日なら前営業日にする（例: 給料日） |
+//! | `BusinessDayRule::Next` | 休日なら翌営業日にする（例: 口座振替日） |
+//!
+//! 調整ルールは、定期取引のルールなどのJSONファイルでは`"previous"`・`"next"`と記述します。
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// 休日に当たる日付の調整ルールを表す列挙型
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BusinessDayRule {
+    Previous,
+    Next,
+}
+
+impl BusinessDayRule {
+    /// 日付が休日であれば、ルールに従って営業日に調整する
+    ///
+    /// 営業日であれば、日付をそのまま返します。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::services::calendar::BusinessDayRule;
+    /// use chrono::NaiveDate;
+    ///
+    /// // 2023/6/25は日曜日
+    /// let payday = NaiveDate::from_ymd_opt(2023, 6, 25).unwrap();
+    /// assert_eq!(BusinessDayRule::Previous.apply(payday), NaiveDate::from_ymd_opt(2023, 6, 23).unwrap());
+    /// assert_eq!(BusinessDayRule::Next.apply(payday), NaiveDate::from_ymd_opt(2023, 6, 26).unwrap());
+    /// ```
+    pub fn apply(&self, date: NaiveDate) -> NaiveDate {
+        let mut date = date;
+        while !is_business_day(date) {
+            date = match self {
+                BusinessDayRule::Previous => date.pred_opt().unwrap(),
+                BusinessDayRule::Next => date.succ_opt().unwrap(),
+            };
+        }
+        date
+    }
+}
+
+/// 日付が営業日（土日・祝日以外）かどうかを判定する
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::services::calendar;
+/// use chrono::NaiveDate;
+///
+/// assert!(calendar::is_business_day(NaiveDate::from_ymd_opt(2023, 1, 4).unwrap()));
+/// assert!(!calendar::is_business_day(NaiveDate::from_ymd_opt(2023, 1, 9).unwrap()));
+/// ```
+pub fn is_business_day(date: NaiveDate) -> bool {
+    !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && get_holiday_name(date).is_none()
+}
+
+/// 日付が祝日（振替休日・国民の休日を含む）であれば、その名前を取得する
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::services::calendar;
+/// use chrono::NaiveDate;
+///
+/// assert_eq!(calendar::get_holiday_name(NaiveDate::from_ymd_opt(2023, 1, 9).unwrap()), Some("成人の日"));
+/// assert_eq!(calendar::get_holiday_name(NaiveDate::from_ymd_opt(2023, 1, 10).unwrap()), None);
+/// ```
+pub fn get_holiday_name(date: NaiveDate) -> Option<&'static str> {
+    if let Some(name) = get_national_holiday_name(date) {
+        return Some(name);
+    }
+    if is_substitute_holiday(date) {
+        return Some("振替休日");
+    }
+    if is_citizens_holiday(date) {
+        return Some("国民の休日");
+    }
+    None
+}
+
+/// 振替休日かどうかを判定する
+///
+/// 日曜日に当たる祝日の後、最も近い祝日でない日が振替休日になります。
+fn is_substitute_holiday(date: NaiveDate) -> bool {
+    let mut previous = date.pred_opt().unwrap();
+    while get_national_holiday_name(previous).is_some() {
+        if previous.weekday() == Weekday::Sun {
+            return true;
+        }
+        previous = previous.pred_opt().unwrap();
+    }
+    false
+}
+
+/// 国民の休日（前日と翌日が祝日である日）かどうかを判定する
+fn is_citizens_holiday(date: NaiveDate) -> bool {
+    date.weekday() != Weekday::Sun
+        && get_national_holiday_name(date.pred_opt().unwrap()).is_some()
+        && get_national_holiday_name(date.succ_opt().unwrap()).is_some()
+}
+
+/// 国民の祝日に関する法律で定められた祝日であれば、その名前を取得する
+///
+/// 振替休日と国民の休日は含みません。
+fn get_national_holiday_name(date: NaiveDate) -> Option<&'static str> {
+    let year = date.year();
+    let name = match (date.month(), date.day()) {
+        (1, 1) => "元日",
+        (1, _) if is_nth_monday(date, 2) => "成人の日",
+        (2, 11) => "建国記念の日",
+        (2, 23) if year >= 2020 => "天皇誕生日",
+        (3, day) if day == get_vernal_equinox_day(year) => "春分の日",
+        (4, 29) => "昭和の日",
+        (5, 3) => "憲法記念日",
+        (5, 4) => "みどりの日",
+        (5, 5) => "こどもの日",
+        (7, 23) if year == 2020 => "海の日",
+        (7, 22) if year == 2021 => "海の日",
+        (7, _) if year != 2020 && year != 2021 && is_nth_monday(date, 3) => "海の日",
+        (7, 24) if year == 2020 => "スポーツの日",
+        (7, 23) if year == 2021 => "スポーツの日",
+        (8, 10) if year == 2020 => "山の日",
+        (8, 8) if year == 2021 => "山の日",
+        (8, 11) if year != 2020 && year != 2021 => "山の日",
+        (9, _) if is_nth_monday(date, 3) => "敬老の日",
+        (9, day) if day == get_autumnal_equinox_day(year) => "秋分の日",
+        (10, _) if year <= 2019 && is_nth_monday(date, 2) => "体育の日",
+        (10, _) if year >= 2022 && is_nth_monday(date, 2) => "スポーツの日",
+        (11, 3) => "文化の日",
+        (11, 23) => "勤労感謝の日",
+        (12, 23) if year <= 2018 => "天皇誕生日",
+        (5, 1) if year == 2019 => "天皇の即位の日",
+        (10, 22) if year == 2019 => "即位礼正殿の儀の行われる日",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// 日付がその月の第n月曜日かどうかを判定する
+fn is_nth_monday(date: NaiveDate, n: u32) -> bool {
+    date.weekday() == Weekday::Mon && (date.day() - 1) / 7 + 1 == n
+}
+
+/// 春分の日の日にちを計算する（1980〜2099年）
+fn get_vernal_equinox_day(year: i32) -> u32 {
+    let elapsed = (year - 1980) as f64;
+    (20.8431 + 0.242194 * elapsed - (elapsed / 4.0).floor()).floor() as u32
+}
+
+/// 秋分の日の日にちを計算する（1980〜2099年）
+fn get_autumnal_equinox_day(year: i32) -> u32 {
+    let elapsed = (year - 1980) as f64;
+    (23.2488 + 0.242194 * elapsed - (elapsed / 4.0).floor()).floor() as u32
+}
+
+#[cfg(test)]
+mod calendar_test {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_get_holiday_name() {
+        assert_eq!(get_holiday_name(date(2023, 3, 21)), Some("春分の日"));
+        assert_eq!(get_holiday_name(date(2023, 9, 23)), Some("秋分の日"));
+        assert_eq!(get_holiday_name(date(2023, 7, 17)), Some("海の日"));
+        assert_eq!(get_holiday_name(date(2021, 7, 23)), Some("スポーツの日"));
+        assert_eq!(get_holiday_name(date(2023, 10, 9)), Some("スポーツの日"));
+        assert_eq!(get_holiday_name(date(2018, 12, 23)), Some("天皇誕生日"));
+        assert_eq!(get_holiday_name(date(2019, 12, 23)), None);
+        assert_eq!(get_holiday_name(date(2023, 2, 23)), Some("天皇誕生日"));
+    }
+
+    #[test]
+    fn test_get_holiday_name_for_substitute_holiday() {
+        // 2023/1/1は日曜日
+        assert_eq!(get_holiday_name(date(2023, 1, 2)), Some("振替休日"));
+        // 2020/5/3は日曜日のため、5/4・5/5の後の5/6が振替休日
+        assert_eq!(get_holiday_name(date(2020, 5, 6)), Some("振替休日"));
+        assert_eq!(get_holiday_name(date(2023, 1, 3)), None);
+    }
+
+    #[test]
+    fn test_get_holiday_name_for_citizens_holiday() {
+        // 2026/9/21は敬老の日、9/23は秋分の日
+        assert_eq!(get_holiday_name(date(2026, 9, 22)), Some("国民の休日"));
+        // 2019/5/1の前後
+        assert_eq!(get_holiday_name(date(2019, 4, 30)), Some("国民の休日"));
+        assert_eq!(get_holiday_name(date(2019, 5, 2)), Some("国民の休日"));
+    }
+
+    #[test]
+    fn test_apply() {
+        // 2023/12/23(土)〜12/25(月)の前後
+        assert_eq!(BusinessDayRule::Previous.apply(date(2023, 12, 24)), date(2023, 12, 22));
+        assert_eq!(BusinessDayRule::Next.apply(date(2023, 12, 24)), date(2023, 12, 25));
+        // 2024/5/3(金)〜5/6(月・振替休日)
+        assert_eq!(BusinessDayRule::Next.apply(date(2024, 5, 3)), date(2024, 5, 7));
+        assert_eq!(BusinessDayRule::Previous.apply(date(2024, 5, 6)), date(2024, 5, 2));
+        assert_eq!(BusinessDayRule::Previous.apply(date(2023, 1, 4)), date(2023, 1, 4));
+    }
+}
@@ -0,0 +1,150 @@
+//! 棒グラフ機能
+//!
+//! このモジュールは、金額を端末に表示する横棒グラフ（ASCII文字）に変換する機能を提供します。
+//! 棒は中央の軸（`|`）から、正の金額は右へ、負の金額は左へ伸ばし、長さはすべての棒の絶対値の最大値に比例させます。
+//! グラフの幅は端末の幅に合わせます（端末の幅を取得できない場合は80桁）。
+//!
+//! #### 出力の例
+//!
+//! ```text
+//! 2023/1 収入                     |++++++++++++++++++++ +300000円
+//!        支出              -------|                     -105000円
+//!        収支                     |#############        +195000円
+//! ```
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::config::Config;
+use crate::models;
+
+/// 端末の幅を取得できない場合のグラフの幅
+pub const DEFAULT_WIDTH: usize = 80;
+
+/// 軸の片側の棒の最小の長さ
+///
+/// 端末が狭い場合でも、棒の長さの違いが分かるようにこの長さは確保します。
+const MIN_HALF_WIDTH: usize = 10;
+
+/// グラフの1本の棒を表す構造体
+///
+/// - `label`: 棒の見出し（例: 2023/1）。前の棒と同じ見出しの場合は空文字列にする
+/// - `kind`: 棒の種類（例: 収入）
+/// - `value`: 金額
+/// - `symbol`: 棒を描く文字
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bar {
+    pub label: String,
+    pub kind: &'static str,
+    pub value: models::Money,
+    pub symbol: char,
+}
+
+/// 端末の幅を取得します。
+///
+/// 端末に接続されていない場合（パイプやリダイレクトで出力した場合）や、`terminal`機能を無効にしてビルドした場合は、`DEFAULT_WIDTH`を返します。
+pub fn get_terminal_width() -> usize {
+    #[cfg(feature = "terminal")]
+    return terminal_size::terminal_size().map_or(DEFAULT_WIDTH, |(width, _)| width.0 as usize);
+    #[cfg(not(feature = "terminal"))]
+    DEFAULT_WIDTH
+}
+
+/// 棒の一覧を、指定した幅に収まる横棒グラフの行に変換します。
+///
+/// 各行は「見出し 種類 左側の棒|右側の棒 金額」の形式です。見出しと金額は、表示幅をそろえて出力します。
+/// 0でない金額の棒は、最大値に比べて小さくても1文字以上の長さにします。
+/// 幅が狭すぎる場合でも、軸の片側に`MIN_HALF_WIDTH`文字分の長さは確保します（その場合、行は指定した幅を超えます）。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::models::Money;
+/// use kakeibo_app::services::chart::{self, Bar};
+///
+/// let bars = vec![
+///     Bar { label: String::from("2023/1"), kind: "収支", value: Money::new(2000), symbol: '#' },
+///     Bar { label: String::from("2023/2"), kind: "収支", value: Money::new(-1000), symbol: '#' },
+/// ];
+/// let lines = chart::render(&bars, 60, &Config::default());
+/// assert_eq!(lines[0], "2023/1 収支                    |################### +2000円");
+/// assert_eq!(lines[1], "2023/2 収支          ##########|                    -1000円");
+/// ```
+pub fn render(bars: &[Bar], width: usize, config: &Config) -> Vec<String> {
+    let values: Vec<String> = bars.iter().map(|bar| format_value(bar.value, config)).collect();
+    let label_width = bars.iter().map(|bar| bar.label.width()).max().unwrap_or(0);
+    let kind_width = bars.iter().map(|bar| bar.kind.width()).max().unwrap_or(0);
+    let value_width = values.iter().map(|value| value.width()).max().unwrap_or(0);
+    // 見出し・種類・軸・金額と、その間の空白を除いた幅を、軸の左右に半分ずつ割り当てる
+    let fixed_width = label_width + 1 + kind_width + 1 + 1 + 1 + value_width;
+    let half_width = (width.saturating_sub(fixed_width) / 2).max(MIN_HALF_WIDTH);
+    let max_value = bars.iter().map(|bar| bar.value.abs()).max().unwrap_or(models::Money::ZERO);
+
+    bars.iter().zip(&values).map(|(bar, value)| {
+        let length = get_bar_length(bar.value, max_value, half_width);
+        let (left, right) = if bar.value < models::Money::ZERO { (length, 0) } else { (0, length) };
+        format!(
+            "{}{} {}{} {}{}|{}{} {}",
+            bar.label,
+            " ".repeat(label_width - bar.label.width()),
+            bar.kind,
+            " ".repeat(kind_width - bar.kind.width()),
+            " ".repeat(half_width - left),
+            bar.symbol.to_string().repeat(left),
+            bar.symbol.to_string().repeat(right),
+            " ".repeat(half_width - right),
+            value
+        )
+    }).collect()
+}
+
+/// 金額の絶対値を、最大値を`half_width`文字とした棒の長さに換算します。
+///
+/// 0でない金額は、1文字以上の長さにします。
+fn get_bar_length(value: models::Money, max_value: models::Money, half_width: usize) -> usize {
+    if value == models::Money::ZERO || max_value == models::Money::ZERO {
+        return 0;
+    }
+    let ratio = value.abs().get_minor_units() as f64 / max_value.get_minor_units() as f64;
+    ((ratio * half_width as f64).round() as usize).clamp(1, half_width)
+}
+
+/// 金額を符号付きでフォーマットします。
+fn format_value(value: models::Money, config: &Config) -> String {
+    if value > models::Money::ZERO {
+        format!("+{}", config.format_currency(value))
+    } else {
+        config.format_currency(value)
+    }
+}
+
+#[cfg(test)]
+mod chart_test {
+    use super::*;
+
+    #[test]
+    fn test_get_bar_length() {
+        let max_value = models::Money::new(10000);
+        assert_eq!(get_bar_length(models::Money::new(10000), max_value, 20), 20);
+        assert_eq!(get_bar_length(models::Money::new(-5000), max_value, 20), 10);
+        assert_eq!(get_bar_length(models::Money::new(1), max_value, 20), 1);
+        assert_eq!(get_bar_length(models::Money::ZERO, max_value, 20), 0);
+        assert_eq!(get_bar_length(models::Money::ZERO, models::Money::ZERO, 20), 0);
+    }
+
+    #[test]
+    fn test_render() {
+        let bars = vec![
+            Bar { label: "2023/12".to_string(), kind: "収入", value: models::Money::new(300000), symbol: '+' },
+            Bar { label: String::new(), kind: "支出", value: models::Money::new(-150000), symbol: '-' },
+            Bar { label: String::new(), kind: "収支", value: models::Money::ZERO, symbol: '#' },
+        ];
+        let lines = render(&bars, 0, &Config::default());
+
+        assert_eq!(lines, vec![
+            "2023/12 収入           |++++++++++ +300000円",
+            "        支出      -----|           -150000円",
+            "        収支           |           0円",
+        ]);
+    }
+}
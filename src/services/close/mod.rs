@@ -0,0 +1,394 @@
+//! 締めサービス
+//!
+//! このモジュールは、月を「締め」状態にして、その月の項目の追加・変更をロックする機能を提供します。
+//! 締め済みの月は、JSONファイル`store/closed.json`に保存されます。
+//! 締め済みの月の項目を修正するには、明示的に再オープンする必要があります。
+//!
+//! 締めた時点の収入・支出・繰越残高はスナップショットとして保存され、後から過去日付の項目が修正されても、
+//! 締め時点の数値を参照できます。
+
+use std::fs::File;
+use std::io::{BufReader, Write};
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::models;
+use crate::services;
+use crate::error::{KakeiboError, Result};
+
+/// 締め済みの月と、締めた時点のスナップショットを表す構造体
+///
+/// - `month`: 締めた月の最初の日
+/// - `income`: 締めた時点のその月の収入合計
+/// - `expense`: 締めた時点のその月の支出合計
+/// - `carried_over`: 締めた時点の月末の繰越残高（それまでの全期間の収支の合計）
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ClosedMonth {
+    month: NaiveDate,
+    #[serde(default)]
+    income: models::Money,
+    #[serde(default)]
+    expense: models::Money,
+    #[serde(default)]
+    carried_over: models::Money,
+}
+
+impl ClosedMonth {
+    /// 指定された月のスナップショットを作成する
+    fn take_snapshot(month: NaiveDate, data: &[models::Item]) -> Self {
+        let mut closed = ClosedMonth { month, income: models::Money::ZERO, expense: models::Money::ZERO, carried_over: models::Money::ZERO };
+        for item in data.iter().filter(|item| item.get_first_day() <= month) {
+            let price = item.get_price_for_summary();
+            closed.carried_over += price;
+            if item.get_first_day() != month {
+                continue;
+            }
+            match item.get_item_category() {
+                models::Category::Income(_) => closed.income += price,
+                models::Category::Expense(_) => closed.expense -= price,
+                models::Category::Transfer(_) => {},
+            }
+        }
+        closed
+    }
+
+    /// 締めた月の最初の日を取得する
+    pub fn get_month(&self) -> NaiveDate {
+        self.month
+    }
+
+    /// 締めた時点のその月の収入合計を取得する
+    pub fn get_income(&self) -> models::Money {
+        self.income
+    }
+
+    /// 締めた時点のその月の支出合計を取得する
+    pub fn get_expense(&self) -> models::Money {
+        self.expense
+    }
+
+    /// 締めた時点の月末の繰越残高を取得する
+    pub fn get_carried_over(&self) -> models::Money {
+        self.carried_over
+    }
+}
+
+/// 締め済みの月の一覧を表す構造体
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::services::close::ClosedMonths;
+/// use chrono::NaiveDate;
+///
+/// let mut closed_months = ClosedMonths::default();
+/// closed_months.close(NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(), &[]);
+/// assert!(closed_months.is_closed(NaiveDate::from_ymd_opt(2023, 1, 31).unwrap()));
+/// assert!(!closed_months.is_closed(NaiveDate::from_ymd_opt(2023, 2, 1).unwrap()));
+/// ```
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct ClosedMonths {
+    months: Vec<ClosedMonth>,
+}
+
+impl ClosedMonths {
+    /// JSONファイルから締め済みの月の一覧を読み込む
+    ///
+    /// ファイルが存在しない場合は、締め済みの月が無いものとして空の一覧を返します。
+    ///
+    /// #### エラー
+    ///
+    /// ファイルの内容が不正な場合は、`KakeiboError::InvalidData`を返します。
+    pub fn load_or_default(file_path: &str) -> Result<Self> {
+        match File::open(file_path) {
+            Ok(f) => {
+                let buf_reader = BufReader::new(f);
+                serde_json::from_reader(buf_reader)
+                    .map_err(|error| KakeiboError::InvalidData(format!("{}の読み込みに失敗しました: {}", file_path, error)))
+            },
+            Err(_) => Ok(ClosedMonths::default()),
+        }
+    }
+
+    /// 締め済みの月の一覧をJSONファイルに書き込む
+    ///
+    /// #### エラー
+    ///
+    /// ファイルへの書き込みに失敗した場合、`KakeiboError::Io`を返します。
+    pub fn save(&self, file_path: &str) -> Result<()> {
+        let json_data = serde_json::to_string_pretty(self).expect("JSONへのシリアライズに失敗しました");
+        let mut file = File::create(file_path).map_err(|error| KakeiboError::io(file_path, error))?;
+        writeln!(file, "{}", json_data).map_err(|error| KakeiboError::io(file_path, error))
+    }
+
+    /// 指定された日付の月が締め済みかどうかを判定する
+    pub fn is_closed(&self, date: NaiveDate) -> bool {
+        let month = get_first_day(date);
+        self.months.iter().any(|closed| closed.month == month)
+    }
+
+    /// 指定された日付の月を締め、その時点のスナップショットを保存する
+    ///
+    /// 既に締め済みの場合は何もせずに`false`を返します。
+    ///
+    /// #### 引数
+    ///
+    /// - `date`: 締める月の日付
+    /// - `data`: スナップショットの作成に使う家計簿データ
+    pub fn close(&mut self, date: NaiveDate, data: &[models::Item]) -> bool {
+        if self.is_closed(date) {
+            return false;
+        }
+        self.months.push(ClosedMonth::take_snapshot(get_first_day(date), data));
+        self.months.sort_by_key(|closed| closed.month);
+        true
+    }
+
+    /// 指定された日付の月を再オープンする
+    ///
+    /// 締め済みでない場合は何もせずに`false`を返します。
+    pub fn reopen(&mut self, date: NaiveDate) -> bool {
+        let month = get_first_day(date);
+        let len = self.months.len();
+        self.months.retain(|closed| closed.month != month);
+        self.months.len() != len
+    }
+
+    /// 締め済みの月の最初の日を古い順に取得する
+    pub fn get_months(&self) -> Vec<NaiveDate> {
+        self.months.iter().map(|closed| closed.month).collect()
+    }
+
+    /// 指定された日付の月を締めた時点のスナップショットを取得する
+    ///
+    /// 締め済みでない場合は`None`を返します。
+    pub fn get_snapshot(&self, date: NaiveDate) -> Option<&ClosedMonth> {
+        let month = get_first_day(date);
+        self.months.iter().find(|closed| closed.month == month)
+    }
+
+    /// 項目のうち、締め済みの月に属するものがあればその月の最初の日を返す
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+    /// use kakeibo_app::services::close::ClosedMonths;
+    /// use chrono::NaiveDate;
+    ///
+    /// let item = Item::new(
+    ///     String::from("ランチ"),
+    ///     Category::Expense(ExpenseCategory::Food),
+    ///     Money::new(1000),
+    ///     NaiveDate::from_ymd_opt(2023, 1, 15).unwrap(),
+    /// );
+    /// let mut closed_months = ClosedMonths::default();
+    /// assert_eq!(closed_months.find_closed_month([&item]), None);
+    /// closed_months.close(NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(), &[]);
+    /// assert_eq!(closed_months.find_closed_month([&item]), NaiveDate::from_ymd_opt(2023, 1, 1));
+    /// ```
+    pub fn find_closed_month<'a>(&self, items: impl IntoIterator<Item = &'a models::Item>) -> Option<NaiveDate> {
+        items
+            .into_iter()
+            .find(|item| self.is_closed(item.get_date()))
+            .map(|item| get_first_day(item.get_date()))
+    }
+}
+
+/// 月の締め・再オープンを行います。
+///
+/// この関数は、締め済みの月の一覧をスナップショットとともに表示したうえで操作を尋ね、対象の月を締めるか再オープンします。
+/// 締めた後に過去日付の項目が修正され、現在の繰越残高がスナップショットと異なる月には、その旨を表示します。
+///
+/// #### 例
+///
+/// ```rust
+/// // ドキュメンテーションテストでFailになるのでコメントアウト
+/// // use kakeibo_app::config::Config;
+/// // use kakeibo_app::services;
+/// // services::close::run("store/data.json", "store/closed.json", &Config::default());
+/// ```
+///
+/// #### エラー
+///
+/// 不正な入力があった場合や、データの読み書きに失敗した場合は、`KakeiboError`を返します。
+pub fn run(file_path: &str, closed_path: &str, config: &Config) -> Result<()> {
+    let data = services::io::read_data(file_path)?;
+    let mut closed_months = ClosedMonths::load_or_default(closed_path)?;
+    print_closed_months(&closed_months, &data, config);
+
+    let close = match input_action()? {
+        Some(close) => close,
+        None => return Ok(()),
+    };
+    let month = input_month()?;
+    let label = services::summarize::format_date(month);
+    if close {
+        if !closed_months.close(month, &data) {
+            println!("{}は既に締め済みです", label);
+            return Ok(());
+        }
+        closed_months.save(closed_path)?;
+        println!("{}を締めました", label);
+    } else {
+        if !closed_months.reopen(month) {
+            println!("{}は締められていません", label);
+            return Ok(());
+        }
+        closed_months.save(closed_path)?;
+        println!("{}を再オープンしました", label);
+    }
+    Ok(())
+}
+
+/// 締め済みの月のために操作を中止したことを出力します。
+///
+/// 項目の追加・変更を行うサービスから、締め済みの月の項目が対象に含まれていた場合に呼び出します。
+pub fn print_locked(month: NaiveDate) {
+    println!(
+        "{}は締め済みのため変更できません。修正する場合は先に再オープンしてください",
+        services::summarize::format_date(month)
+    );
+}
+
+/// 日付をその月の最初の日に変換します。
+fn get_first_day(date: NaiveDate) -> NaiveDate {
+    date.with_day0(0).unwrap()
+}
+
+/// 締め済みの月の一覧をスナップショットとともに出力します。
+///
+/// 現在の繰越残高がスナップショットと異なる月には、現在の繰越残高を併せて出力します。
+fn print_closed_months(closed_months: &ClosedMonths, data: &[models::Item], config: &Config) {
+    if closed_months.months.is_empty() {
+        println!("締め済みの月はありません");
+        return;
+    }
+
+    println!("締め済みの月");
+    for closed in &closed_months.months {
+        print!(
+            "  {} 収入: {} 支出: {} 繰越残高: {}",
+            services::summarize::format_date(closed.month),
+            config.format_currency(closed.income),
+            config.format_currency(closed.expense),
+            config.format_currency(closed.carried_over)
+        );
+        let current = ClosedMonth::take_snapshot(closed.month, data);
+        if current.carried_over != closed.carried_over {
+            print!(" (締め後に変更あり: 現在の繰越残高 {})", config.format_currency(current.carried_over));
+        }
+        println!();
+    }
+}
+
+/// ユーザーに操作を尋ね、締める場合は`Some(true)`、再オープンする場合は`Some(false)`を返します。
+///
+/// 空行が入力された場合は、何もせずに終了するものとして`None`を返します。
+///
+/// #### エラー
+///
+/// 不正な入力があった場合、`KakeiboError`を返します。
+fn input_action() -> Result<Option<bool>> {
+    let action = services::prompt::ask("c:締め / r:再オープン / 空行:終了")?;
+
+    match action.trim() {
+        "c" | "C" => Ok(Some(true)),
+        "r" | "R" => Ok(Some(false)),
+        "" => Ok(None),
+        _ => Err(KakeiboError::invalid_input("操作はc, rのいずれかで入力してください")),
+    }
+}
+
+/// ユーザーに対象の年月を尋ね、その月の最初の日で返します。
+///
+/// #### エラー
+///
+/// 不正な入力があった場合、`KakeiboError`を返します。
+fn input_month() -> Result<NaiveDate> {
+    let month = services::prompt::ask("対象の年月を入力してください (例: 2022/1)")?;
+
+    services::summarize::parse_year_month(month.trim())
+        .ok_or_else(|| KakeiboError::invalid_input("年月はyyyy/mの形式で入力してください"))
+}
+
+#[cfg(test)]
+mod close_test {
+    use super::*;
+
+    #[test]
+    fn test_close_and_reopen() {
+        let mut closed_months = ClosedMonths::default();
+        let february = NaiveDate::from_ymd_opt(2023, 2, 10).unwrap();
+        let january = NaiveDate::from_ymd_opt(2023, 1, 20).unwrap();
+
+        assert!(closed_months.close(february, &[]));
+        assert!(closed_months.close(january, &[]));
+        assert!(!closed_months.close(january, &[]));
+        assert_eq!(
+            closed_months.get_months(),
+            vec![NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(), NaiveDate::from_ymd_opt(2023, 2, 1).unwrap()]
+        );
+
+        assert!(closed_months.reopen(NaiveDate::from_ymd_opt(2023, 1, 1).unwrap()));
+        assert!(!closed_months.reopen(january));
+        assert!(!closed_months.is_closed(january));
+        assert!(closed_months.is_closed(february));
+    }
+
+    #[test]
+    fn test_serialize() {
+        let mut closed_months = ClosedMonths::default();
+        closed_months.close(NaiveDate::from_ymd_opt(2023, 1, 20).unwrap(), &[]);
+        let json = serde_json::to_value(&closed_months).unwrap();
+
+        assert_eq!(json, serde_json::json!([{"month": "2023-01-01", "income": 0, "expense": 0, "carried_over": 0}]));
+        assert_eq!(serde_json::from_value::<ClosedMonths>(json).unwrap(), closed_months);
+
+        // スナップショットの無い古い形式も読み込める
+        let json = serde_json::json!([{"month": "2023-01-01"}]);
+        assert_eq!(serde_json::from_value::<ClosedMonths>(json).unwrap(), closed_months);
+    }
+
+    #[test]
+    fn test_take_snapshot() {
+        let data = vec![
+            models::Item::new(
+                "給与".to_string(),
+                models::Category::Income(models::IncomeCategory::Salary),
+                models::Money::new(300000),
+                NaiveDate::from_ymd_opt(2023, 1, 25).unwrap()
+            ),
+            models::Item::new(
+                "家賃".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Other),
+                models::Money::new(80000),
+                NaiveDate::from_ymd_opt(2023, 2, 1).unwrap()
+            ),
+            models::Item::new(
+                "給与".to_string(),
+                models::Category::Income(models::IncomeCategory::Salary),
+                models::Money::new(300000),
+                NaiveDate::from_ymd_opt(2023, 2, 25).unwrap()
+            ),
+            models::Item::new(
+                "ランチ".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                models::Money::new(1000),
+                NaiveDate::from_ymd_opt(2023, 3, 1).unwrap()
+            ),
+        ];
+        let mut closed_months = ClosedMonths::default();
+        closed_months.close(NaiveDate::from_ymd_opt(2023, 2, 1).unwrap(), &data);
+        let snapshot = closed_months.get_snapshot(NaiveDate::from_ymd_opt(2023, 2, 28).unwrap()).unwrap();
+
+        assert_eq!(snapshot.get_month(), NaiveDate::from_ymd_opt(2023, 2, 1).unwrap());
+        assert_eq!(snapshot.get_income(), models::Money::new(300000));
+        assert_eq!(snapshot.get_expense(), models::Money::new(80000));
+        assert_eq!(snapshot.get_carried_over(), models::Money::new(520000));
+        assert!(closed_months.get_snapshot(NaiveDate::from_ymd_opt(2023, 3, 1).unwrap()).is_none());
+    }
+}
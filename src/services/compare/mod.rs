@@ -0,0 +1,247 @@
+//! 期間比較サービス
+//!
+//! このモジュールは、任意の2つの期間（例: 2023年1〜6月と2024年1〜6月）の収支をカテゴリ別に比較する機能を提供します。
+//! 期間は`2023-01..2023-06`のように開始と終了を`..`でつないで指定します（両端を含む）。
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+
+use crate::config::Config;
+use crate::error::{KakeiboError, Result};
+use crate::models;
+use crate::services;
+
+/// 比較する期間を表す構造体
+///
+/// - `first_date`: 期間の開始日
+/// - `last_date`: 期間の終了日（その日を含む）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Period {
+    pub first_date: NaiveDate,
+    pub last_date: NaiveDate,
+}
+
+impl Period {
+    /// `開始..終了`の形式の文字列から期間を作成する
+    ///
+    /// 開始・終了は、月（yyyy-mm）または日付（yyyy-mm-dd）で指定します。月の場合、開始はその月の1日、終了はその月の末日になります。
+    /// `..`を含まない場合は、その月または日付だけを期間とします。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::services::compare::Period;
+    /// use chrono::NaiveDate;
+    ///
+    /// let period = Period::parse("2023-01..2023-06").unwrap();
+    /// assert_eq!(period.first_date, NaiveDate::from_ymd_opt(2023, 1, 1).unwrap());
+    /// assert_eq!(period.last_date, NaiveDate::from_ymd_opt(2023, 6, 30).unwrap());
+    /// assert!(Period::parse("2023-06..2023-01").is_err());
+    /// ```
+    ///
+    /// #### エラー
+    ///
+    /// 形式が不正な場合や、開始が終了より後の場合は、`KakeiboError::InvalidInput`を返します。
+    pub fn parse(text: &str) -> Result<Self> {
+        let (first, last) = text.split_once("..").unwrap_or((text, text));
+        let period = Period {
+            first_date: parse_bound(first, false)?,
+            last_date: parse_bound(last, true)?,
+        };
+        if period.first_date > period.last_date {
+            return Err(KakeiboError::InvalidInput(format!("期間{}の開始が終了より後になっています", text)));
+        }
+        Ok(period)
+    }
+
+    /// 日付が期間に含まれるかどうかを判定する
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.first_date <= date && date <= self.last_date
+    }
+}
+
+/// 期間の開始または終了を解析します。
+///
+/// 月（yyyy-mm）で指定された場合、開始はその月の1日、終了（`is_last`が`true`）はその月の末日にします。
+fn parse_bound(text: &str, is_last: bool) -> Result<NaiveDate> {
+    let text = text.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+        return Ok(date);
+    }
+    let first_date = services::list::parse_month(text)
+        .map_err(|_| KakeiboError::invalid_input("期間はyyyy-mm..yyyy-mmまたはyyyy-mm-dd..yyyy-mm-ddの形式で入力してください"))?;
+    Ok(if is_last { services::summarize::get_last_day(first_date) } else { first_date })
+}
+
+/// カテゴリ別の比較結果を表す構造体
+///
+/// - `category`: カテゴリ
+/// - `a`: 期間Aの合計（支出・収入とも正の値）
+/// - `b`: 期間Bの合計（支出・収入とも正の値）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CategoryComparison {
+    pub category: models::Category,
+    pub a: models::Money,
+    pub b: models::Money,
+}
+
+impl CategoryComparison {
+    /// 期間Aから期間Bへの増減（B - A）を取得する
+    pub fn get_difference(&self) -> models::Money {
+        self.b - self.a
+    }
+}
+
+/// 2つの期間の収支をカテゴリ別に比較します。
+///
+/// どちらかの期間に項目があるカテゴリを、カテゴリの順（収入・支出の順）に並べて返します。口座間の振替は含みません。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+/// use kakeibo_app::services::compare::{self, Period};
+/// use chrono::NaiveDate;
+///
+/// let data = vec![
+///     Item::new(String::from("ランチ"), Category::Expense(ExpenseCategory::Food), Money::new(1000), NaiveDate::from_ymd_opt(2023, 1, 5).unwrap()),
+///     Item::new(String::from("ランチ"), Category::Expense(ExpenseCategory::Food), Money::new(1500), NaiveDate::from_ymd_opt(2024, 1, 5).unwrap()),
+/// ];
+/// let comparisons = compare::compare(&data, Period::parse("2023-01").unwrap(), Period::parse("2024-01").unwrap());
+/// assert_eq!(comparisons[0].get_difference(), Money::new(500));
+/// ```
+pub fn compare(data: &[models::Item], a: Period, b: Period) -> Vec<CategoryComparison> {
+    let mut totals: BTreeMap<models::Category, (models::Money, models::Money)> = BTreeMap::new();
+    for item in data {
+        if matches!(item.get_item_category(), models::Category::Transfer(_)) {
+            continue;
+        }
+        let (in_a, in_b) = (a.contains(item.get_date()), b.contains(item.get_date()));
+        if !in_a && !in_b {
+            continue;
+        }
+        let (total_a, total_b) = totals.entry(item.get_item_category().clone()).or_default();
+        if in_a {
+            *total_a += item.get_price();
+        }
+        if in_b {
+            *total_b += item.get_price();
+        }
+    }
+    totals.into_iter().map(|(category, (a, b))| CategoryComparison { category, a, b }).collect()
+}
+
+/// 2つの期間の収支をカテゴリ別に比較して表示します。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// services::compare::run("store/data.json", "2023-01..2023-06", "2024-01..2024-06", &Config::default()).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// 期間の形式が不正な場合や、データの読み込みに失敗した場合は、`KakeiboError`を返します。
+pub fn run(file_path: &str, a: &str, b: &str, config: &Config) -> Result<()> {
+    let (a, b) = (Period::parse(a)?, Period::parse(b)?);
+    let data = services::io::read_data(file_path)?;
+
+    let comparisons = compare(&data, a, b);
+    println!("A: {}〜{} / B: {}〜{}", a.first_date, a.last_date, b.first_date, b.last_date);
+    if comparisons.is_empty() {
+        println!("どちらの期間にも項目はありません");
+        return Ok(());
+    }
+    for comparison in &comparisons {
+        println!(
+            "  {} A {} / B {} ({})",
+            config.format_category(&comparison.category),
+            config.format_currency(comparison.a),
+            config.format_currency(comparison.b),
+            format_difference(comparison, config)
+        );
+    }
+    Ok(())
+}
+
+/// 比較する2つの期間を尋ね、カテゴリ別に比較して表示します。
+///
+/// #### 例
+///
+/// ```rust
+/// // ドキュメンテーションテストでFailになるのでコメントアウト
+/// // use kakeibo_app::config::Config;
+/// // use kakeibo_app::services;
+/// // services::compare::run_interactive("store/data.json", &Config::default());
+/// ```
+///
+/// #### エラー
+///
+/// 不正な入力があった場合や、データの読み込みに失敗した場合は、`KakeiboError`を返します。
+pub fn run_interactive(file_path: &str, config: &Config) -> Result<()> {
+    let a = services::prompt::ask("期間Aを入力してください (例: 2023-01..2023-06)")?;
+    let b = services::prompt::ask("期間Bを入力してください (例: 2024-01..2024-06)")?;
+    run(file_path, a.trim(), b.trim(), config)
+}
+
+/// 期間Aから期間Bへの増減と増減率をフォーマットします。
+///
+/// 期間Aの合計が0の場合は、増減率を省略します。
+fn format_difference(comparison: &CategoryComparison, config: &Config) -> String {
+    let difference = comparison.get_difference();
+    let sign = if difference > models::Money::ZERO { "+" } else { "" };
+    if comparison.a == models::Money::ZERO {
+        format!("{}{}", sign, config.format_currency(difference))
+    } else {
+        format!(
+            "{}{}, {}{:.1}%",
+            sign,
+            config.format_currency(difference),
+            sign,
+            difference.get_minor_units() as f64 / comparison.a.get_minor_units() as f64 * 100.0
+        )
+    }
+}
+
+#[cfg(test)]
+mod compare_test {
+    use super::*;
+
+    fn get_test_item(category: models::Category, price: i64, date: NaiveDate) -> models::Item {
+        models::Item::new("テスト".to_string(), category, models::Money::new(price), date)
+    }
+
+    #[test]
+    fn test_parse() {
+        let period = Period::parse("2024-02-10..2024-02").unwrap();
+        assert_eq!(period.first_date, NaiveDate::from_ymd_opt(2024, 2, 10).unwrap());
+        assert_eq!(period.last_date, NaiveDate::from_ymd_opt(2024, 2, 29).unwrap());
+        assert!(matches!(Period::parse("2024/01..2024/06"), Err(KakeiboError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_compare() {
+        let food = models::Category::Expense(models::ExpenseCategory::Food);
+        let hobby = models::Category::Expense(models::ExpenseCategory::Hobby);
+        let salary = models::Category::Income(models::IncomeCategory::Salary);
+        let data = vec![
+            get_test_item(food.clone(), 30000, NaiveDate::from_ymd_opt(2023, 3, 1).unwrap()),
+            get_test_item(food.clone(), 25000, NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()),
+            get_test_item(hobby.clone(), 8000, NaiveDate::from_ymd_opt(2024, 6, 30).unwrap()),
+            get_test_item(hobby.clone(), 9000, NaiveDate::from_ymd_opt(2024, 7, 1).unwrap()),
+            get_test_item(salary.clone(), 300000, NaiveDate::from_ymd_opt(2023, 1, 25).unwrap()),
+            get_test_item(models::Category::Transfer(models::Account::Cash), 10000, NaiveDate::from_ymd_opt(2023, 1, 25).unwrap()),
+        ];
+        let a = Period::parse("2023-01..2023-06").unwrap();
+        let b = Period::parse("2024-01..2024-06").unwrap();
+
+        assert_eq!(compare(&data, a, b), vec![
+            CategoryComparison { category: salary, a: models::Money::new(300000), b: models::Money::ZERO },
+            CategoryComparison { category: food, a: models::Money::new(30000), b: models::Money::new(25000) },
+            CategoryComparison { category: hobby, a: models::Money::ZERO, b: models::Money::new(8000) },
+        ]);
+    }
+}
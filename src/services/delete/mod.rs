@@ -0,0 +1,166 @@
+//! 削除サービス
+//!
+//! このモジュールは、JOSNファイル`store/data.json`から条件に一致する項目、またはIDで指定した項目を削除する機能を提供します。
+
+use crate::config::Config;
+use crate::models;
+use crate::services;
+use crate::services::filter::Filter;
+use crate::error::{KakeiboError, Result};
+
+/// 条件に一致する項目を一括削除します。
+///
+/// この関数は、ユーザーから条件式を受け付け、一致した項目と件数を表示したうえで、確認後に削除します。
+/// `filter`に条件式を指定した場合は、条件式を尋ねずにその条件式を使います（確認は省略しません）。
+/// 一致した項目に締め済みの月の項目が含まれる場合は、削除しません。
+///
+/// #### 例
+///
+/// ```rust
+/// // ドキュメンテーションテストでFailになるのでコメントアウト
+/// // use kakeibo_app::config::Config;
+/// // use kakeibo_app::services;
+/// // services::delete::run("store/data.json", "store/closed.json", Some("category=趣味 and date<2022-01-01"), &Config::default());
+/// ```
+///
+/// #### エラー
+///
+/// 不正な入力があった場合や、データの読み書きに失敗した場合は、`KakeiboError`を返します。
+pub fn run(file_path: &str, closed_path: &str, filter: Option<&str>, config: &Config) -> Result<()> {
+    println!("条件に一致する項目を一括削除します");
+    let data = services::io::read_data(file_path)?;
+    let filter = match filter {
+        Some(filter) => Filter::parse(filter.trim(), config)?,
+        None => input_filter(config)?,
+    };
+
+    let (deleted, remaining): (Vec<_>, Vec<_>) = data.into_iter().partition(|item| filter.matches(item));
+    if deleted.is_empty() {
+        println!("条件に一致する項目はありません");
+        return Ok(());
+    }
+    let closed_months = services::close::ClosedMonths::load_or_default(closed_path)?;
+    if let Some(month) = closed_months.find_closed_month(&deleted) {
+        services::close::print_locked(month);
+        return Ok(());
+    }
+
+    print_items(&deleted, config);
+    println!("{}件の項目が条件に一致しました", deleted.len());
+    if !input_confirmation()? {
+        println!("削除を中止しました");
+        return Ok(());
+    }
+
+    services::io::write_to_json(&remaining, file_path)?;
+    println!("{}件の項目を削除しました", deleted.len());
+    Ok(())
+}
+
+/// IDで指定した項目を削除します。
+///
+/// この関数は、指定されたIDの項目を表示し、確認後に削除します。
+/// `force`が`true`の場合は、確認せずに削除します（スクリプトからの実行用）。
+/// 項目が締め済みの月のものである場合は、削除しません。
+///
+/// #### 例
+///
+/// ```rust
+/// // ドキュメンテーションテストでFailになるのでコメントアウト
+/// // use kakeibo_app::config::Config;
+/// // use kakeibo_app::services;
+/// // services::delete::run_by_id("store/data.json", "store/closed.json", 3, false, &Config::default());
+/// ```
+///
+/// #### エラー
+///
+/// 指定されたIDの項目が無い場合や、データの読み書きに失敗した場合は、`KakeiboError`を返します。
+pub fn run_by_id(file_path: &str, closed_path: &str, id: u64, force: bool, config: &Config) -> Result<()> {
+    let mut data = services::io::read_data(file_path)?;
+    let index = get_index(&data, id)?;
+
+    let closed_months = services::close::ClosedMonths::load_or_default(closed_path)?;
+    if let Some(month) = closed_months.find_closed_month(&data[index..=index]) {
+        services::close::print_locked(month);
+        return Ok(());
+    }
+
+    print_items(&data[index..=index], config);
+    if !force && !input_confirmation()? {
+        println!("削除を中止しました");
+        return Ok(());
+    }
+
+    data.remove(index);
+    services::io::write_to_json(&data, file_path)?;
+    println!("ID {}の項目を削除しました", id);
+    Ok(())
+}
+
+/// 指定されたIDの項目の、データのインデックスを取得します。
+///
+/// IDの項目が無い場合は、`KakeiboError::InvalidInput`を返します。
+fn get_index(data: &[models::Item], id: u64) -> Result<usize> {
+    data.iter()
+        .position(|item| item.get_id() == id)
+        .ok_or_else(|| KakeiboError::InvalidInput(format!("ID {}の項目はありません", id)))
+}
+
+/// ユーザーに削除条件を尋ね、`Filter`で返します。
+///
+/// #### エラー
+///
+/// 不正な入力があった場合、`KakeiboError`を返します。
+fn input_filter(config: &Config) -> Result<Filter> {
+    let filter = services::prompt::ask("削除条件を入力してください (例: category=趣味 and date<2022-01-01)")?;
+
+    Filter::parse(filter.trim(), config)
+}
+
+/// ユーザーに削除してよいかを尋ね、真偽値で返します。
+///
+/// `y`以外が入力された場合は、削除しないものとして`false`を返します。
+///
+/// #### エラー
+///
+/// 標準入力からの読み込みに失敗した場合、`KakeiboError`を返します。
+fn input_confirmation() -> Result<bool> {
+    let answer = services::prompt::ask("これらの項目を削除しますか (y/n)")?;
+
+    Ok(matches!(answer.trim(), "y" | "Y"))
+}
+
+/// 削除対象の項目を出力します。
+fn print_items(items: &[models::Item], config: &Config) {
+    for item in items {
+        println!(
+            "  {} {} {} {}",
+            item.get_date(),
+            item.get_name(),
+            config.format_category(item.get_item_category()),
+            config.format_item_price(item)
+        );
+    }
+}
+
+#[cfg(test)]
+mod delete_test {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_get_index() {
+        let mut data: Vec<_> = (1..=2).map(|day| models::Item::new(
+            "ランチ".to_string(),
+            models::Category::Expense(models::ExpenseCategory::Food),
+            models::Money::new(1000),
+            NaiveDate::from_ymd_opt(2023, 1, day).unwrap()
+        )).collect();
+        data[0].set_id(5);
+        data[1].set_id(2);
+
+        assert_eq!(get_index(&data, 5).unwrap(), 0);
+        assert_eq!(get_index(&data, 2).unwrap(), 1);
+        assert!(get_index(&data, 1).is_err());
+    }
+}
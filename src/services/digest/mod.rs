@@ -0,0 +1,201 @@
+//! ダイジェストサービス
+//!
+//! このモジュールは、先週分の支出の要約（合計・カテゴリ上位・前週比）を生成する機能を提供します。
+//! 対話入力を必要としないため、cronなどで毎週決まった曜日に実行し、出力を通知に連携できます。
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+use chrono::{Days, Local, NaiveDate};
+
+use crate::config::Config;
+use crate::models;
+use crate::services;
+use crate::error::Result;
+
+/// ダイジェストに表示するカテゴリの件数
+const TOP_CATEGORY_COUNT: usize = 3;
+
+/// 週次ダイジェストを表す構造体
+///
+/// - `first_date`: 対象の週の開始日
+/// - `last_date`: 対象の週の終了日
+/// - `total`: 対象の週の支出合計
+/// - `previous_total`: 前週の支出合計
+/// - `top_categories`: 支出の多いカテゴリと支出合計（多い順）
+#[derive(Debug, PartialEq, Eq)]
+pub struct Digest {
+    pub first_date: NaiveDate,
+    pub last_date: NaiveDate,
+    pub total: models::Money,
+    pub previous_total: models::Money,
+    pub top_categories: Vec<(models::Category, models::Money)>,
+}
+
+/// 先週分の週次ダイジェストを表示します。
+///
+/// この関数は、今日を含む週の前の週（週の開始曜日は設定に従う）の支出合計・カテゴリ上位・前週比を表示します。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// services::digest::run("store/data.json", &Config::default()).unwrap();
+/// ```
+pub fn run(file_path: &str, config: &Config) -> Result<()> {
+    let data = services::io::read_data_or_create_new_data(file_path)?;
+    let this_week = config.get_week_start_date(Local::now().date_naive());
+    let digest = create_digest(&data, this_week.checked_sub_days(Days::new(7)).unwrap());
+    print_digest(&digest, config);
+    Ok(())
+}
+
+/// 指定された日から始まる1週間の週次ダイジェストを作成します。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+/// use kakeibo_app::services::digest;
+/// use chrono::NaiveDate;
+///
+/// let data = vec![Item::new(
+///     String::from("ランチ"),
+///     Category::Expense(ExpenseCategory::Food),
+///     Money::new(1000),
+///     NaiveDate::from_ymd_opt(2023, 1, 3).unwrap(),
+/// )];
+/// let digest = digest::create_digest(&data, NaiveDate::from_ymd_opt(2023, 1, 2).unwrap());
+/// assert_eq!(digest.last_date, NaiveDate::from_ymd_opt(2023, 1, 8).unwrap());
+/// assert_eq!(digest.total, Money::new(1000));
+/// assert_eq!(digest.previous_total, Money::ZERO);
+/// ```
+pub fn create_digest(data: &[models::Item], first_date: NaiveDate) -> Digest {
+    let last_date = first_date.checked_add_days(Days::new(6)).unwrap();
+    let previous_first_date = first_date.checked_sub_days(Days::new(7)).unwrap();
+
+    let mut categories = BTreeMap::new();
+    let mut total = models::Money::ZERO;
+    let mut previous_total = models::Money::ZERO;
+    for item in data {
+        if !matches!(item.get_item_category(), models::Category::Expense(_)) {
+            continue;
+        }
+        let price = item.get_price();
+        if first_date <= item.get_date() && item.get_date() <= last_date {
+            total += price;
+            *categories.entry(item.get_item_category().clone()).or_default() += price;
+        } else if previous_first_date <= item.get_date() && item.get_date() < first_date {
+            previous_total += price;
+        }
+    }
+
+    let mut top_categories: Vec<_> = categories.into_iter().collect();
+    top_categories.sort_by_key(|(_, price)| Reverse(*price));
+    top_categories.truncate(TOP_CATEGORY_COUNT);
+
+    Digest { first_date, last_date, total, previous_total, top_categories }
+}
+
+/// 週次ダイジェストを出力します。
+fn print_digest(digest: &Digest, config: &Config) {
+    println!("週次ダイジェスト ({}〜{})", digest.first_date, digest.last_date);
+    println!(
+        "支出合計: {} (前週比 {})",
+        config.format_currency(digest.total),
+        format_difference(digest.total, digest.previous_total, config)
+    );
+    if digest.top_categories.is_empty() {
+        println!("支出はありません");
+        return;
+    }
+    println!("支出の多いカテゴリ");
+    for (rank, (category, price)) in digest.top_categories.iter().enumerate() {
+        println!("  {}. {} {}", rank + 1, config.format_category(category), config.format_currency(*price));
+    }
+}
+
+/// 前週との差額と増減率をフォーマットします。
+///
+/// 前週の支出が無い場合は、増減率を省略します。
+fn format_difference(total: models::Money, previous_total: models::Money, config: &Config) -> String {
+    let difference = total - previous_total;
+    let sign = if difference > models::Money::ZERO { "+" } else { "" };
+    if previous_total == models::Money::ZERO {
+        format!("{}{}", sign, config.format_currency(difference))
+    } else {
+        format!(
+            "{}{}, {}{:.1}%",
+            sign,
+            config.format_currency(difference),
+            sign,
+            difference.get_minor_units() as f64 / previous_total.get_minor_units() as f64 * 100.0
+        )
+    }
+}
+
+#[cfg(test)]
+mod digest_test {
+    use super::*;
+
+    fn get_test_data() -> Vec<models::Item> {
+        vec![
+            models::Item::new(
+                "ランチ".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                models::Money::new(1000),
+                NaiveDate::from_ymd_opt(2023, 1, 1).unwrap()
+            ),
+            models::Item::new(
+                "給料".to_string(),
+                models::Category::Income(models::IncomeCategory::Salary),
+                models::Money::new(300000),
+                NaiveDate::from_ymd_opt(2023, 1, 6).unwrap()
+            ),
+            models::Item::new(
+                "映画".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Hobby),
+                models::Money::new(2000),
+                NaiveDate::from_ymd_opt(2023, 1, 2).unwrap()
+            ),
+            models::Item::new(
+                "外食".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                models::Money::new(3000),
+                NaiveDate::from_ymd_opt(2023, 1, 8).unwrap()
+            ),
+            models::Item::new(
+                "雑貨".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Other),
+                models::Money::new(500),
+                NaiveDate::from_ymd_opt(2023, 1, 9).unwrap()
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_create_digest() {
+        let digest = create_digest(&get_test_data(), NaiveDate::from_ymd_opt(2023, 1, 2).unwrap());
+
+        assert_eq!(digest.first_date, NaiveDate::from_ymd_opt(2023, 1, 2).unwrap());
+        assert_eq!(digest.last_date, NaiveDate::from_ymd_opt(2023, 1, 8).unwrap());
+        assert_eq!(digest.total, models::Money::new(5000));
+        assert_eq!(digest.previous_total, models::Money::new(1000));
+        assert_eq!(
+            digest.top_categories,
+            vec![
+                (models::Category::Expense(models::ExpenseCategory::Food), models::Money::new(3000)),
+                (models::Category::Expense(models::ExpenseCategory::Hobby), models::Money::new(2000)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_difference() {
+        let config = Config::default();
+        assert_eq!(format_difference(models::Money::new(5000), models::Money::new(1000), &config), "+4000円, +400.0%");
+        assert_eq!(format_difference(models::Money::new(500), models::Money::new(1000), &config), "-500円, -50.0%");
+        assert_eq!(format_difference(models::Money::new(500), models::Money::new(0), &config), "+500円");
+    }
+}
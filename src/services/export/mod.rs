@@ -0,0 +1,674 @@
+//! エクスポートサービス
+//!
+//! このモジュールは、集計結果を表計算ソフトに貼り付けやすい形式のファイルに書き出す機能を提供します。
+//!
+//! #### 集計結果のCSVの列
+//!
+//! | 列 | 内容 |
+//! | --- | --- |
+//! | 月 | 対象の月（yyyy-mm） |
+//! | 収入 / 支出 / 収支 | 月の収入・支出（負の値）・収支 |
+//! | カテゴリ名 | カテゴリ別の内訳を含める場合、カテゴリごとの合計（支出は負の値） |
+//!
+//! #### 全項目のCSVの列
+//!
+//! | 列 | 内容 |
+//! | --- | --- |
+//! | ID | 項目のID |
+//! | 日付 | 日付（yyyy-mm-dd） |
+//! | 品目 | 品目名 |
+//! | カテゴリ | `区分:名前`の形式（例: `支出:食費`、`振替:銀行`） |
+//! | 金額 | 収入は正の値、支出は負の値、口座間の振替は振替額 |
+//!
+//! 金額は通貨の記号や桁区切りを付けない数値で出力します（米ドル・ユーロは小数点以下2桁）。
+//! CSVの文字コードは、UTF-8（既定）・BOM付きのUTF-8・Shift_JISから選べます。Shift_JISへの変換には`bank`機能が必要です。
+//! 登録の無い月も0として出力するため、表計算ソフトでそのままグラフにできます。
+//!
+//! #### HTMLレポート
+//!
+//! 月ごとの収入・支出の棒グラフ（SVG）、月ごとの収支の表、月ごとのカテゴリ別の内訳の表を1つのHTMLファイルに書き出します。
+//! スタイルとグラフはファイルに埋め込むため、外部のファイルやネットワークが無くてもブラウザでそのまま表示できます。
+
+use std::collections::BTreeSet;
+
+use chrono::Datelike;
+
+use crate::config::Config;
+use crate::error::{KakeiboError, Result};
+use crate::models;
+use crate::services;
+use crate::services::summarize::{SummarizeOptions, SummaryReport};
+
+/// 集計結果の既定の保存先
+pub const DEFAULT_SUMMARY_PATH: &str = "kakeibo_summary.csv";
+
+/// 全項目の既定の保存先
+pub const DEFAULT_DATA_PATH: &str = "kakeibo_data.csv";
+
+/// レポートの既定の保存先
+pub const DEFAULT_REPORT_PATH: &str = "kakeibo_report.html";
+
+/// グラフの1か月分の棒の組の幅
+const CHART_GROUP_WIDTH: usize = 48;
+
+/// グラフの棒の幅
+const CHART_BAR_WIDTH: usize = 16;
+
+/// グラフの棒を描く領域の高さ
+const CHART_HEIGHT: usize = 200;
+
+/// カテゴリ別の内訳の表に描く棒の最大の幅
+const CATEGORY_BAR_WIDTH: usize = 200;
+
+/// レポートに埋め込むスタイル
+const REPORT_STYLE: &str = "body { font-family: sans-serif; margin: 2em; color: #333; }
+table { border-collapse: collapse; margin-bottom: 1.5em; }
+th, td { border: 1px solid #ccc; padding: 0.3em 0.8em; }
+th { background: #f0f0f0; }
+td.amount { text-align: right; }
+.income { fill: #4a90d9; }
+.expense { fill: #e06c5a; }
+.negative { color: #c0392b; }
+";
+
+/// 書き出すファイルの形式を表す列挙型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Html,
+}
+
+impl ExportFormat {
+    /// 形式の名前（`csv`・`html`）から形式を取得する
+    ///
+    /// 大文字・小文字は区別しません。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::services::export::ExportFormat;
+    ///
+    /// assert_eq!(ExportFormat::parse("CSV").unwrap(), ExportFormat::Csv);
+    /// assert_eq!(ExportFormat::parse("html").unwrap(), ExportFormat::Html);
+    /// assert!(ExportFormat::parse("xlsx").is_err());
+    /// ```
+    ///
+    /// #### エラー
+    ///
+    /// 対応していない形式の場合は、`KakeiboError::InvalidInput`を返します。
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "html" => Ok(ExportFormat::Html),
+            _ => Err(KakeiboError::InvalidInput(format!("形式{}には対応していません (csv・htmlで指定してください)", name))),
+        }
+    }
+}
+
+/// 書き出すCSVファイルの文字コードを表す列挙型
+///
+/// - `Utf8`: UTF-8（BOMなし）
+/// - `Utf8Bom`: BOM付きのUTF-8（Excelでそのまま開ける）
+/// - `ShiftJis`: Shift_JIS（古い表計算ソフトや会計ソフト向け）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CsvEncoding {
+    #[default]
+    Utf8,
+    Utf8Bom,
+    ShiftJis,
+}
+
+impl CsvEncoding {
+    /// 文字コードの名前（`utf8`・`utf8-bom`・`shift_jis`）から文字コードを取得する
+    ///
+    /// 大文字・小文字は区別しません。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::services::export::CsvEncoding;
+    ///
+    /// assert_eq!(CsvEncoding::parse("UTF8-BOM").unwrap(), CsvEncoding::Utf8Bom);
+    /// assert_eq!(CsvEncoding::parse("shift_jis").unwrap(), CsvEncoding::ShiftJis);
+    /// assert!(CsvEncoding::parse("euc-jp").is_err());
+    /// ```
+    ///
+    /// #### エラー
+    ///
+    /// 対応していない文字コードの場合は、`KakeiboError::InvalidInput`を返します。
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "utf8" | "utf-8" => Ok(CsvEncoding::Utf8),
+            "utf8-bom" | "utf-8-bom" => Ok(CsvEncoding::Utf8Bom),
+            "shift_jis" | "shift-jis" | "sjis" => Ok(CsvEncoding::ShiftJis),
+            _ => Err(KakeiboError::InvalidInput(format!("文字コード{}には対応していません (utf8・utf8-bom・shift_jisで指定してください)", name))),
+        }
+    }
+
+    /// 文字列を、この文字コードのバイト列に変換する
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::services::export::CsvEncoding;
+    ///
+    /// assert_eq!(CsvEncoding::Utf8Bom.encode("月").unwrap(), b"\xEF\xBB\xBF\xE6\x9C\x88");
+    /// ```
+    ///
+    /// #### エラー
+    ///
+    /// Shift_JISで表せない文字（絵文字など）を含む場合は`KakeiboError::InvalidData`を、
+    /// `bank`機能が無効でShift_JISを指定した場合は`KakeiboError::InvalidInput`を返します。
+    pub fn encode(&self, text: &str) -> Result<Vec<u8>> {
+        match self {
+            CsvEncoding::Utf8 => Ok(text.as_bytes().to_vec()),
+            CsvEncoding::Utf8Bom => Ok(["\u{feff}", text].concat().into_bytes()),
+            CsvEncoding::ShiftJis => encode_shift_jis(text),
+        }
+    }
+}
+
+/// 文字列をShift_JISのバイト列に変換します。
+#[cfg(feature = "bank")]
+fn encode_shift_jis(text: &str) -> Result<Vec<u8>> {
+    let (bytes, _, had_errors) = encoding_rs::SHIFT_JIS.encode(text);
+    if had_errors {
+        return Err(KakeiboError::InvalidData("Shift_JISで表せない文字が含まれています".to_string()));
+    }
+    Ok(bytes.into_owned())
+}
+
+/// 文字列をShift_JISのバイト列に変換します。
+///
+/// `bank`機能が無効の場合は、Shift_JISには対応しません。
+#[cfg(not(feature = "bank"))]
+fn encode_shift_jis(_text: &str) -> Result<Vec<u8>> {
+    Err(KakeiboError::invalid_input("Shift_JISで書き出せません: bank機能を有効にしてビルドしてください"))
+}
+
+/// 月ごとの集計結果を書き出します。
+///
+/// `with_categories`が`true`の場合は、カテゴリ別の合計の列も書き出します。CSVファイルは`encoding`の文字コードで書き出します。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// use kakeibo_app::services::export::{CsvEncoding, ExportFormat};
+/// services::export::run_summary(&["store/data.json"], ExportFormat::Csv, CsvEncoding::Utf8Bom, "summary.csv", true, &Config::default()).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// csv以外の形式を指定した場合や、文字コードに変換できない場合、データの読み込み、ファイルの書き込みに失敗した場合は、`KakeiboError`を返します。
+pub fn run_summary(
+    file_paths: &[&str],
+    format: ExportFormat,
+    encoding: CsvEncoding,
+    output_path: &str,
+    with_categories: bool,
+    config: &Config
+) -> Result<()> {
+    let data = services::io::read_data_from_files(file_paths)?;
+    let options = SummarizeOptions { fill_empty_months: true, ..SummarizeOptions::default() };
+    let report = services::summarize::summarize(&data, &options);
+
+    let rows = create_summary_rows(&report, with_categories, config);
+    match format {
+        ExportFormat::Csv => write_csv(&rows, output_path, encoding)?,
+        ExportFormat::Html => return Err(KakeiboError::invalid_input("集計結果はcsv形式で指定してください")),
+    }
+    println!("{}か月分の集計結果を{}に書き出しました", report.months.len(), output_path);
+    Ok(())
+}
+
+/// 家計簿データのすべての項目を書き出します。
+///
+/// CSVファイルは`encoding`の文字コードで書き出します。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// use kakeibo_app::services::export::{CsvEncoding, ExportFormat};
+/// services::export::run_data(&["store/data.json"], ExportFormat::Csv, CsvEncoding::ShiftJis, "data.csv", &Config::default()).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// csv以外の形式を指定した場合や、文字コードに変換できない場合、データの読み込み、ファイルの書き込みに失敗した場合は、`KakeiboError`を返します。
+pub fn run_data(file_paths: &[&str], format: ExportFormat, encoding: CsvEncoding, output_path: &str, config: &Config) -> Result<()> {
+    let data = services::io::read_data_from_files(file_paths)?;
+    let rows = create_data_rows(&data, config);
+    match format {
+        ExportFormat::Csv => write_csv(&rows, output_path, encoding)?,
+        ExportFormat::Html => return Err(KakeiboError::invalid_input("全項目はcsv形式で指定してください")),
+    }
+    println!("{}件の項目を{}に書き出しました", data.len(), output_path);
+    Ok(())
+}
+
+/// 月ごとの表とグラフをまとめたレポートを書き出します。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// use kakeibo_app::services::export::ExportFormat;
+/// services::export::run_report(&["store/data.json"], ExportFormat::Html, "report.html", &Config::default()).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// html以外の形式を指定した場合や、データの読み込み、ファイルの書き込みに失敗した場合は、`KakeiboError`を返します。
+pub fn run_report(file_paths: &[&str], format: ExportFormat, output_path: &str, config: &Config) -> Result<()> {
+    if format != ExportFormat::Html {
+        return Err(KakeiboError::invalid_input("レポートはhtml形式で指定してください"));
+    }
+    let data = services::io::read_data_from_files(file_paths)?;
+    let options = SummarizeOptions { fill_empty_months: true, ..SummarizeOptions::default() };
+    let report = services::summarize::summarize(&data, &options);
+
+    services::io::write_file(output_path, &create_html_report(&report, config))?;
+    println!("{}か月分のレポートを{}に書き出しました", report.months.len(), output_path);
+    Ok(())
+}
+
+/// 書き出す内容・形式・保存先を尋ね、ファイルに書き出します。
+///
+/// #### 例
+///
+/// ```rust
+/// // ドキュメンテーションテストでFailになるのでコメントアウト
+/// // use kakeibo_app::config::Config;
+/// // use kakeibo_app::services;
+/// // services::export::run(&["store/data.json"], &Config::default());
+/// ```
+///
+/// #### エラー
+///
+/// 不正な入力があった場合や、ファイルの読み書きに失敗した場合は、`KakeiboError`を返します。
+pub fn run(file_paths: &[&str], config: &Config) -> Result<()> {
+    let target = services::prompt::ask("書き出す内容を入力してください (s:集計結果, r:レポート, d:全項目)")?;
+    match target.trim() {
+        "s" | "S" => {
+            let with_categories = matches!(services::prompt::ask("カテゴリ別の合計も書き出しますか (y/n)")?.trim(), "y" | "Y");
+            let output_path = input_output_path(DEFAULT_SUMMARY_PATH)?;
+            run_summary(file_paths, ExportFormat::Csv, CsvEncoding::Utf8, &output_path, with_categories, config)
+        },
+        "r" | "R" => {
+            let output_path = input_output_path(DEFAULT_REPORT_PATH)?;
+            run_report(file_paths, ExportFormat::Html, &output_path, config)
+        },
+        "d" | "D" => {
+            let output_path = input_output_path(DEFAULT_DATA_PATH)?;
+            run_data(file_paths, ExportFormat::Csv, CsvEncoding::Utf8, &output_path, config)
+        },
+        _ => Err(KakeiboError::invalid_input("書き出す内容はs・r・dのいずれかで入力してください")),
+    }
+}
+
+/// 行の一覧を、指定された文字コードのCSVファイルに書き込みます。
+fn write_csv(rows: &[Vec<String>], output_path: &str, encoding: CsvEncoding) -> Result<()> {
+    let text = format!("{}\n", services::io::format_delimited(rows, ','));
+    services::io::write_bytes(output_path, &encoding.encode(&text)?)
+}
+
+/// 保存先を尋ねます。空行の場合は既定の保存先を返します。
+fn input_output_path(default_path: &str) -> Result<String> {
+    let output_path = services::prompt::ask(&format!("保存先を入力してください (空行で{})", default_path))?;
+    match output_path.trim() {
+        "" => Ok(default_path.to_string()),
+        output_path => Ok(output_path.to_string()),
+    }
+}
+
+/// 集計結果から、ヘッダー行と月ごとの行を作成します。
+///
+/// カテゴリ別の列は、いずれかの月に項目があるカテゴリを、カテゴリの順（収入・支出の順）に並べます。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::models::{Item, Category, ExpenseCategory, IncomeCategory, Money};
+/// use kakeibo_app::services::{export, summarize::{self, SummarizeOptions}};
+/// use chrono::NaiveDate;
+///
+/// let data = vec![
+///     Item::new(String::from("給与"), Category::Income(IncomeCategory::Salary), Money::new(300000), NaiveDate::from_ymd_opt(2023, 1, 25).unwrap()),
+///     Item::new(String::from("ランチ"), Category::Expense(ExpenseCategory::Food), Money::new(1000), NaiveDate::from_ymd_opt(2023, 1, 26).unwrap()),
+/// ];
+/// let report = summarize::summarize(&data, &SummarizeOptions::default());
+/// let rows = export::create_summary_rows(&report, true, &Config::default());
+/// assert_eq!(rows[0], vec!["月", "収入", "支出", "収支", "給与", "食費"]);
+/// assert_eq!(rows[1], vec!["2023-01", "300000", "-1000", "299000", "300000", "-1000"]);
+/// ```
+pub fn create_summary_rows(report: &SummaryReport, with_categories: bool, config: &Config) -> Vec<Vec<String>> {
+    let categories: BTreeSet<&models::Category> = if with_categories {
+        report.months.iter().flat_map(|month| month.categories.iter().map(|total| &total.category)).collect()
+    } else {
+        BTreeSet::new()
+    };
+
+    let mut header: Vec<String> = ["月", "収入", "支出", "収支"].map(String::from).to_vec();
+    header.extend(categories.iter().map(|category| category.get_name().to_string()));
+    let mut rows = vec![header];
+    for month in &report.months {
+        let mut row = vec![
+            format!("{}-{:02}", month.month.year(), month.month.month()),
+            format_amount(month.income, config),
+            format_amount(month.expense, config),
+            format_amount(month.balance, config),
+        ];
+        row.extend(categories.iter().map(|category| {
+            let total = month.categories.iter()
+                .filter(|total| &total.category == *category)
+                .map(|total| total.total)
+                .sum();
+            format_amount(total, config)
+        }));
+        rows.push(row);
+    }
+    rows
+}
+
+/// 家計簿データから、ヘッダー行と項目ごとの行を作成します。
+///
+/// 項目は、データファイルでの並び順のまま出力します。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+/// use kakeibo_app::services::export;
+/// use chrono::NaiveDate;
+///
+/// let mut item = Item::new(String::from("ランチ"), Category::Expense(ExpenseCategory::Food), Money::new(1000), NaiveDate::from_ymd_opt(2023, 1, 26).unwrap());
+/// item.set_id(1);
+/// let rows = export::create_data_rows(&[item], &Config::default());
+/// assert_eq!(rows[0], vec!["ID", "日付", "品目", "カテゴリ", "金額"]);
+/// assert_eq!(rows[1], vec!["1", "2023-01-26", "ランチ", "支出:食費", "-1000"]);
+/// ```
+pub fn create_data_rows(data: &[models::Item], config: &Config) -> Vec<Vec<String>> {
+    let mut rows = vec![["ID", "日付", "品目", "カテゴリ", "金額"].map(String::from).to_vec()];
+    for item in data {
+        let (category, amount) = match item.get_item_category() {
+            models::Category::Transfer(account) => (format!("振替:{}", account.get_name()), item.get_price()),
+            category @ models::Category::Income(_) => (format!("収入:{}", category.get_name()), item.get_price_for_summary()),
+            category @ models::Category::Expense(_) => (format!("支出:{}", category.get_name()), item.get_price_for_summary()),
+        };
+        rows.push(vec![
+            item.get_id().to_string(),
+            item.get_date().to_string(),
+            item.get_name().to_string(),
+            category,
+            format_amount(amount, config),
+        ]);
+    }
+    rows
+}
+
+/// 集計結果から、単体で表示できるHTMLレポートを作成します。
+///
+/// 月ごとの収入・支出の棒グラフ、月ごとの収支の表、月ごとのカテゴリ別の内訳の表を、月の古い順に並べます。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+/// use kakeibo_app::services::{export, summarize::{self, SummarizeOptions}};
+/// use chrono::NaiveDate;
+///
+/// let data = vec![
+///     Item::new(String::from("ランチ"), Category::Expense(ExpenseCategory::Food), Money::new(1000), NaiveDate::from_ymd_opt(2023, 1, 26).unwrap()),
+/// ];
+/// let report = summarize::summarize(&data, &SummarizeOptions::default());
+/// let html = export::create_html_report(&report, &Config::default());
+/// assert!(html.starts_with("<!DOCTYPE html>"));
+/// assert!(html.contains("<svg"));
+/// assert!(html.contains("<td>2023/1</td>"));
+/// ```
+pub fn create_html_report(report: &SummaryReport, config: &Config) -> String {
+    let mut html = String::from("<!DOCTYPE html>\n<html lang=\"ja\">\n<head>\n<meta charset=\"utf-8\">\n<title>家計簿レポート</title>\n");
+    html.push_str(&format!("<style>\n{}</style>\n</head>\n<body>\n<h1>家計簿レポート</h1>\n", REPORT_STYLE));
+    if let (Some(first), Some(last)) = (report.months.first(), report.months.last()) {
+        html.push_str(&format!(
+            "<p>{}〜{}（{}件）</p>\n",
+            services::summarize::format_date(first.month),
+            services::summarize::format_date(last.month),
+            report.item_count
+        ));
+    }
+
+    html.push_str("<h2>月ごとの収入と支出</h2>\n");
+    html.push_str(&create_monthly_chart(report, config));
+
+    html.push_str("<h2>月ごとの収支</h2>\n<table>\n<tr><th>月</th><th>収入</th><th>支出</th><th>収支</th><th>累積収支</th></tr>\n");
+    for month in &report.months {
+        html.push_str(&format!(
+            "<tr><td>{}</td>{}{}{}{}</tr>\n",
+            services::summarize::format_date(month.month),
+            format_amount_cell(month.income, config),
+            format_amount_cell(month.expense, config),
+            format_amount_cell(month.balance, config),
+            format_amount_cell(month.cumulative, config)
+        ));
+    }
+    html.push_str("</table>\n");
+
+    html.push_str("<h2>カテゴリ別の内訳</h2>\n");
+    for month in report.months.iter().filter(|month| !month.categories.is_empty()) {
+        let max_total = month.categories.iter().map(|total| total.total.abs()).max().unwrap_or(models::Money::ZERO);
+        html.push_str(&format!(
+            "<h3>{}</h3>\n<table>\n<tr><th>カテゴリ</th><th>金額</th><th></th></tr>\n",
+            services::summarize::format_date(month.month)
+        ));
+        for total in &month.categories {
+            let class = if total.total < models::Money::ZERO { "expense" } else { "income" };
+            let width = get_scaled_length(total.total, max_total, CATEGORY_BAR_WIDTH);
+            html.push_str(&format!(
+                "<tr><td>{}</td>{}<td><svg width=\"{}\" height=\"12\"><rect class=\"{}\" width=\"{}\" height=\"12\"/></svg></td></tr>\n",
+                escape_html(total.category.get_name()),
+                format_amount_cell(total.total, config),
+                CATEGORY_BAR_WIDTH,
+                class,
+                width
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// 月ごとの収入（左）と支出（右）を並べた棒グラフのSVGを作成します。
+///
+/// 棒の高さは、すべての月の収入と支出の絶対値の最大値に比例させます。棒にマウスを重ねると金額を表示します。
+fn create_monthly_chart(report: &SummaryReport, config: &Config) -> String {
+    let max_value = report.months.iter()
+        .flat_map(|month| [month.income.abs(), month.expense.abs()])
+        .max()
+        .unwrap_or(models::Money::ZERO);
+    let width = CHART_GROUP_WIDTH * report.months.len().max(1);
+    let mut svg = format!(
+        "<svg width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" role=\"img\">\n",
+        width, CHART_HEIGHT + 20, width, CHART_HEIGHT + 20
+    );
+    svg.push_str(&format!("<line x1=\"0\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"#999\"/>\n", CHART_HEIGHT, width, CHART_HEIGHT));
+    for (index, month) in report.months.iter().enumerate() {
+        let x = index * CHART_GROUP_WIDTH + (CHART_GROUP_WIDTH - CHART_BAR_WIDTH * 2) / 2;
+        let label = services::summarize::format_date(month.month);
+        for (offset, class, value) in [(0, "income", month.income), (CHART_BAR_WIDTH, "expense", month.expense)] {
+            let height = get_scaled_length(value, max_value, CHART_HEIGHT);
+            svg.push_str(&format!(
+                "<rect class=\"{}\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"><title>{} {}</title></rect>\n",
+                class,
+                x + offset,
+                CHART_HEIGHT - height,
+                CHART_BAR_WIDTH,
+                height,
+                label,
+                escape_html(&config.format_currency(value.abs()))
+            ));
+        }
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-size=\"10\" text-anchor=\"middle\">{}</text>\n",
+            index * CHART_GROUP_WIDTH + CHART_GROUP_WIDTH / 2,
+            CHART_HEIGHT + 14,
+            label
+        ));
+    }
+    svg.push_str("</svg>\n");
+    svg.push_str("<p><svg width=\"10\" height=\"10\"><rect class=\"income\" width=\"10\" height=\"10\"/></svg> 収入 <svg width=\"10\" height=\"10\"><rect class=\"expense\" width=\"10\" height=\"10\"/></svg> 支出</p>\n");
+    svg
+}
+
+/// 金額の絶対値を、最大値を`max_length`とした長さに換算します。
+fn get_scaled_length(value: models::Money, max_value: models::Money, max_length: usize) -> usize {
+    if max_value == models::Money::ZERO {
+        return 0;
+    }
+    let ratio = value.abs().get_minor_units() as f64 / max_value.get_minor_units() as f64;
+    (ratio * max_length as f64).round() as usize
+}
+
+/// 金額を、右寄せの表のセルにフォーマットします。負の金額は赤字にします。
+fn format_amount_cell(price: models::Money, config: &Config) -> String {
+    let class = if price < models::Money::ZERO { "amount negative" } else { "amount" };
+    format!("<td class=\"{}\">{}</td>", class, escape_html(&config.format_currency(price)))
+}
+
+/// HTMLで特別な意味を持つ文字をエスケープします。
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// 金額を、通貨の記号や桁区切りを付けない数値の文字列にフォーマットします。
+///
+/// 米ドル・ユーロの金額は、小数点以下2桁で出力します。
+fn format_amount(price: models::Money, config: &Config) -> String {
+    let digits = config.currency.get_minor_digits();
+    let minor_units = price.get_minor_units();
+    if digits == 0 {
+        return minor_units.to_string();
+    }
+    let unit = 10_i64.pow(digits);
+    let sign = if minor_units < 0 { "-" } else { "" };
+    format!("{}{}.{:0width$}", sign, (minor_units / unit).abs(), (minor_units % unit).abs(), width = digits as usize)
+}
+
+#[cfg(test)]
+mod export_test {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_create_summary_rows() {
+        let data = vec![
+            models::Item::new(
+                "外食".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                models::Money::new(3000),
+                NaiveDate::from_ymd_opt(2023, 1, 15).unwrap()
+            ),
+            models::Item::new(
+                "本".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Hobby),
+                models::Money::new(1500),
+                NaiveDate::from_ymd_opt(2023, 3, 1).unwrap()
+            ),
+        ];
+        let options = SummarizeOptions { fill_empty_months: true, ..SummarizeOptions::default() };
+        let report = services::summarize::summarize(&data, &options);
+
+        assert_eq!(create_summary_rows(&report, false, &Config::default()), vec![
+            vec!["月", "収入", "支出", "収支"],
+            vec!["2023-01", "0", "-3000", "-3000"],
+            vec!["2023-02", "0", "0", "0"],
+            vec!["2023-03", "0", "-1500", "-1500"],
+        ]);
+        let rows = create_summary_rows(&report, true, &Config::default());
+        assert_eq!(rows[0][4..], ["食費", "趣味"]);
+        assert_eq!(rows[3][4..], ["0", "-1500"]);
+    }
+
+    #[test]
+    fn test_create_data_rows() {
+        let data = vec![
+            models::Item::new(
+                "給料".to_string(),
+                models::Category::Income(models::IncomeCategory::Other),
+                models::Money::new(300000),
+                NaiveDate::from_ymd_opt(2023, 1, 25).unwrap()
+            ),
+            models::Item::new(
+                "ATM, 駅前".to_string(),
+                models::Category::Transfer(models::Account::Cash),
+                models::Money::new(10000),
+                NaiveDate::from_ymd_opt(2023, 1, 26).unwrap()
+            ),
+        ];
+        let rows = create_data_rows(&data, &Config::default());
+        assert_eq!(rows[1], vec!["0", "2023-01-25", "給料", "収入:その他", "300000"]);
+        assert_eq!(rows[2], vec!["0", "2023-01-26", "ATM, 駅前", "振替:現金", "10000"]);
+    }
+
+    #[test]
+    fn test_create_html_report() {
+        let data = vec![
+            models::Item::new(
+                "給料".to_string(),
+                models::Category::Income(models::IncomeCategory::Salary),
+                models::Money::new(300000),
+                NaiveDate::from_ymd_opt(2023, 1, 25).unwrap()
+            ),
+            models::Item::new(
+                "外食".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                models::Money::new(150000),
+                NaiveDate::from_ymd_opt(2023, 1, 26).unwrap()
+            ),
+        ];
+        let report = services::summarize::summarize(&data, &SummarizeOptions::default());
+        let html = create_html_report(&report, &Config::default());
+
+        assert!(html.contains("<rect class=\"income\" x=\"8\" y=\"0\" width=\"16\" height=\"200\"><title>2023/1 300000円</title></rect>"));
+        assert!(html.contains("<rect class=\"expense\" x=\"24\" y=\"100\" width=\"16\" height=\"100\"><title>2023/1 150000円</title></rect>"));
+        assert!(html.contains("<tr><td>2023/1</td><td class=\"amount\">300000円</td><td class=\"amount negative\">-150000円</td>"));
+        assert!(html.contains("<tr><td>食費</td><td class=\"amount negative\">-150000円</td>"));
+    }
+
+    #[test]
+    fn test_encode_csv() {
+        assert_eq!(CsvEncoding::Utf8.encode("月,収入\n").unwrap(), "月,収入\n".as_bytes());
+        assert_eq!(CsvEncoding::Utf8Bom.encode("月").unwrap()[..3], [0xEF, 0xBB, 0xBF]);
+        if cfg!(feature = "bank") {
+            assert_eq!(CsvEncoding::ShiftJis.encode("食費,-1500").unwrap(), b"\x90\x48\x94\xEF,-1500");
+            assert!(CsvEncoding::ShiftJis.encode("🍙食費").is_err());
+        } else {
+            assert!(CsvEncoding::ShiftJis.encode("食費").is_err());
+        }
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(escape_html("<a href=\"x\">&</a>"), "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;");
+    }
+
+    #[test]
+    fn test_format_amount() {
+        let config = Config { currency: crate::config::Currency::USD, ..Config::default() };
+        assert_eq!(format_amount(models::Money::new(-123405), &config), "-1234.05");
+        assert_eq!(format_amount(models::Money::new(-5), &config), "-0.05");
+        assert_eq!(format_amount(models::Money::new(-5), &Config::default()), "-5");
+    }
+}
@@ -0,0 +1,315 @@
+//! フィルタ機能
+//!
+//! このモジュールは、`category=趣味 and date<2022-01-01`のような条件式で項目を絞り込む機能を提供します。
+//!
+//! 条件式は`フィールド 演算子 値`を`and`で繋いだもので、すべての条件を満たす項目に一致します。
+//!
+//! | フィールド | 値の例 | 使える演算子 |
+//! |---|---|---|
+//! | `name` | `ランチ` | `=`, `!=`, `~`（部分一致） |
+//! | `category` | `食費` | `=`, `!=` |
+//! | `event` | `北海道旅行` | `=`, `!=`, `~`（部分一致） |
+//! | `reference` | `249-1234567-1234567` | `=`, `!=`, `~`（部分一致） |
+//! | `date` | `2022-01-01` | `=`, `!=`, `<`, `<=`, `>`, `>=` |
+//! | `price` | `1000`（設定の通貨の単位。米ドルなら`12.5`） | `=`, `!=`, `<`, `<=`, `>`, `>=` |
+//!
+//! #### 例
+//!
+//! ```rust
+//! use kakeibo_app::config::Config;
+//! use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+//! use kakeibo_app::services::filter::Filter;
+//! use chrono::NaiveDate;
+//!
+//! let item = Item::new(
+//!     String::from("ゲーム"),
+//!     Category::Expense(ExpenseCategory::Hobby),
+//!     Money::new(6000),
+//!     NaiveDate::from_ymd_opt(2021, 12, 24).unwrap(),
+//! );
+//! let filter = Filter::parse("category=趣味 and date<2022-01-01", &Config::default()).unwrap();
+//! assert!(filter.matches(&item));
+//! ```
+
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+
+use crate::config::Config;
+use crate::error::{KakeiboError, Result};
+use crate::models;
+
+/// 条件の対象となるフィールドを表す列挙型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Name,
+    Category,
+    Event,
+    Reference,
+    Date,
+    Price,
+}
+
+/// 条件の演算子を表す列挙型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+}
+
+/// 条件の値を表す列挙型
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    Text(String),
+    Date(NaiveDate),
+    Price(models::Money),
+}
+
+/// 1つの条件を表す構造体
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Condition {
+    field: Field,
+    operator: Operator,
+    value: Value,
+}
+
+/// 項目を絞り込むフィルタを表す構造体
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    conditions: Vec<Condition>,
+}
+
+impl Filter {
+    /// 条件式を解析してフィルタを作成する
+    ///
+    /// `price`の値は、設定の通貨の単位で解釈します。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::config::Config;
+    /// use kakeibo_app::services::filter::Filter;
+    ///
+    /// let filter = Filter::parse("name~ランチ and price>=1000", &Config::default()).unwrap();
+    /// assert!(Filter::parse("price~1000", &Config::default()).is_err());
+    /// ```
+    ///
+    /// #### エラー
+    ///
+    /// 条件式の形式が不正な場合は、`KakeiboError::InvalidInput`を返します。
+    pub fn parse(expression: &str, config: &Config) -> Result<Self> {
+        let conditions = expression
+            .split(" and ")
+            .flat_map(|condition| condition.split(" AND "))
+            .map(|condition| parse_condition(condition, config))
+            .collect::<Result<_>>()?;
+        Ok(Filter { conditions })
+    }
+
+    /// 項目がフィルタのすべての条件を満たすかどうかを判定する
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::config::Config;
+    /// use kakeibo_app::models::{Item, Category, IncomeCategory, Money};
+    /// use kakeibo_app::services::filter::Filter;
+    /// use chrono::NaiveDate;
+    ///
+    /// let item = Item::new(
+    ///     String::from("給与"),
+    ///     Category::Income(IncomeCategory::Salary),
+    ///     Money::new(100000),
+    ///     NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+    /// );
+    /// assert!(!Filter::parse("price<100000", &Config::default()).unwrap().matches(&item));
+    /// ```
+    pub fn matches(&self, item: &models::Item) -> bool {
+        self.conditions.iter().all(|condition| condition.matches(item))
+    }
+}
+
+impl Condition {
+    /// 項目が条件を満たすかどうかを判定する
+    fn matches(&self, item: &models::Item) -> bool {
+        match (&self.value, self.field) {
+            (Value::Text(value), Field::Name) => compare_text(item.get_name(), self.operator, value),
+            (Value::Text(value), Field::Category) => {
+                compare_text(item.get_item_category().get_name(), self.operator, value)
+            },
+            (Value::Text(value), Field::Event) => {
+                compare_text(item.get_event().unwrap_or(""), self.operator, value)
+            },
+            (Value::Text(value), Field::Reference) => {
+                compare_text(item.get_reference().unwrap_or(""), self.operator, value)
+            },
+            (Value::Date(value), _) => compare_ord(&item.get_date(), self.operator, value),
+            (Value::Price(value), _) => compare_ord(&item.get_price(), self.operator, value),
+            _ => false,
+        }
+    }
+}
+
+/// 条件式の1つの条件を解析する
+///
+/// `price`の値は、`config.parse_price`で設定の通貨の金額に変換します。
+///
+/// #### エラー
+///
+/// 条件の形式が不正な場合は、`KakeiboError::InvalidInput`を返します。
+fn parse_condition(condition: &str, config: &Config) -> Result<Condition> {
+    let invalid = |message: &str| KakeiboError::InvalidInput(format!("{}: '{}'", message, condition));
+    let index = condition
+        .find(['=', '!', '<', '>', '~'])
+        .ok_or_else(|| invalid("フィルタ条件が不正です"))?;
+    let (field, rest) = condition.split_at(index);
+
+    let (operator, value) = [
+        ("<=", Operator::Le),
+        (">=", Operator::Ge),
+        ("!=", Operator::Ne),
+        ("=", Operator::Eq),
+        ("<", Operator::Lt),
+        (">", Operator::Gt),
+        ("~", Operator::Contains),
+    ]
+        .iter()
+        .find_map(|(symbol, operator)| rest.strip_prefix(symbol).map(|value| (*operator, value.trim())))
+        .ok_or_else(|| invalid("フィルタ条件の演算子が不正です"))?;
+
+    let field = match field.trim() {
+        "name" => Field::Name,
+        "category" => Field::Category,
+        "event" => Field::Event,
+        "reference" => Field::Reference,
+        "date" => Field::Date,
+        "price" => Field::Price,
+        _ => return Err(KakeiboError::InvalidInput(format!("フィルタ条件のフィールドが不正です: '{}'", field.trim()))),
+    };
+
+    let value = match field {
+        Field::Name | Field::Category | Field::Event | Field::Reference => {
+            if !matches!(operator, Operator::Eq | Operator::Ne | Operator::Contains) {
+                return Err(invalid("文字列のフィールドには=, !=, ~のみ使えます"));
+            }
+            Value::Text(value.to_string())
+        },
+        Field::Date => {
+            if operator == Operator::Contains {
+                return Err(invalid("dateには~は使えません"));
+            }
+            Value::Date(NaiveDate::from_str(value).map_err(|_| KakeiboError::invalid_input("日付はyyyy-mm-ddの形式で入力してください"))?)
+        },
+        Field::Price => {
+            if operator == Operator::Contains {
+                return Err(invalid("priceには~は使えません"));
+            }
+            Value::Price(config.parse_price(value)?)
+        },
+    };
+
+    Ok(Condition { field, operator, value })
+}
+
+/// 文字列を演算子に従って比較する
+fn compare_text(target: &str, operator: Operator, value: &str) -> bool {
+    match operator {
+        Operator::Eq => target == value,
+        Operator::Ne => target != value,
+        Operator::Contains => target.contains(value),
+        _ => false,
+    }
+}
+
+/// 順序を持つ値を演算子に従って比較する
+fn compare_ord<T: PartialOrd>(target: &T, operator: Operator, value: &T) -> bool {
+    match operator {
+        Operator::Eq => target == value,
+        Operator::Ne => target != value,
+        Operator::Lt => target < value,
+        Operator::Le => target <= value,
+        Operator::Gt => target > value,
+        Operator::Ge => target >= value,
+        Operator::Contains => false,
+    }
+}
+
+#[cfg(test)]
+mod filter_test {
+    use super::*;
+
+    fn get_test_item() -> models::Item {
+        let mut item = models::Item::new(
+            "ゲームソフト".to_string(),
+            models::Category::Expense(models::ExpenseCategory::Hobby),
+            models::Money::new(6000),
+            NaiveDate::from_ymd_opt(2021, 12, 24).unwrap()
+        );
+        item.set_event(Some("クリスマス".to_string()));
+        item.set_reference(Some("249-1234567-7654321".to_string()));
+        item
+    }
+
+    #[test]
+    fn test_parse() {
+        let filter = Filter::parse("category=趣味 and date<2022-01-01", &Config::default()).unwrap();
+        let expected = Filter {
+            conditions: vec![
+                Condition {
+                    field: Field::Category,
+                    operator: Operator::Eq,
+                    value: Value::Text("趣味".to_string()),
+                },
+                Condition {
+                    field: Field::Date,
+                    operator: Operator::Lt,
+                    value: Value::Date(NaiveDate::from_ymd_opt(2022, 1, 1).unwrap()),
+                },
+            ],
+        };
+
+        assert_eq!(filter, expected);
+    }
+
+    #[test]
+    fn test_matches() {
+        let item = get_test_item();
+        assert!(Filter::parse("category=趣味 and date<2022-01-01", &Config::default()).unwrap().matches(&item));
+        assert!(Filter::parse("name~ゲーム AND price >= 6000", &Config::default()).unwrap().matches(&item));
+        assert!(Filter::parse("event=クリスマス", &Config::default()).unwrap().matches(&item));
+        assert!(Filter::parse("reference~1234567", &Config::default()).unwrap().matches(&item));
+        assert!(!Filter::parse("reference=249-1234567", &Config::default()).unwrap().matches(&item));
+        assert!(!Filter::parse("category!=趣味", &Config::default()).unwrap().matches(&item));
+        assert!(!Filter::parse("date>=2022-01-01", &Config::default()).unwrap().matches(&item));
+        assert!(!Filter::parse("price>6000", &Config::default()).unwrap().matches(&item));
+    }
+
+    #[test]
+    fn test_matches_price_in_config_currency() {
+        // 米ドルの場合、金額はセント単位で保存している
+        let config = Config { currency: crate::config::Currency::USD, ..Config::default() };
+        let item = get_test_item();
+        assert!(Filter::parse("price=60", &config).unwrap().matches(&item));
+        assert!(Filter::parse("price>=59.99", &config).unwrap().matches(&item));
+        assert!(!Filter::parse("price>60.00", &config).unwrap().matches(&item));
+        assert!(Filter::parse("price=6000", &Config::default()).unwrap().matches(&item));
+        assert!(Filter::parse("price=60.001", &config).is_err());
+    }
+
+    #[test]
+    fn test_parse_for_unknown_field() {
+        let error = Filter::parse("shop=コンビニ", &Config::default()).unwrap_err();
+        assert!(error.to_string().contains("フィルタ条件のフィールドが不正です"));
+    }
+
+    #[test]
+    fn test_parse_for_invalid_operator() {
+        let error = Filter::parse("name<ゲーム", &Config::default()).unwrap_err();
+        assert!(error.to_string().contains("文字列のフィールドには=, !=, ~のみ使えます"));
+    }
+}
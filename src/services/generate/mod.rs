@@ -0,0 +1,230 @@
+//! サンプルデータ生成サービス
+//!
+//! このモジュールは、動作確認やデモ、パフォーマンス検証のための擬似データを乱数で生成する機能を提供します。
+
+use std::str::FromStr;
+
+use chrono::{Datelike, Days, Months, NaiveDate};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::models;
+use crate::services;
+use crate::services::calendar::BusinessDayRule;
+use crate::error::{KakeiboError, Result};
+
+/// 生成したデータの既定の保存先
+const DEFAULT_OUTPUT_PATH: &str = "store/sample.json";
+
+/// 支出のひな形（品目名, カテゴリ, 最小金額, 最大金額）
+const EXPENSE_TEMPLATES: [(&str, models::ExpenseCategory, u32, u32); 9] = [
+    ("ランチ", models::ExpenseCategory::Food, 600, 1500),
+    ("スーパー", models::ExpenseCategory::Food, 1500, 6000),
+    ("コンビニ", models::ExpenseCategory::Food, 200, 1200),
+    ("外食", models::ExpenseCategory::Food, 2000, 8000),
+    ("書籍", models::ExpenseCategory::Hobby, 800, 3000),
+    ("ゲーム", models::ExpenseCategory::Hobby, 3000, 9000),
+    ("映画", models::ExpenseCategory::Hobby, 1800, 2500),
+    ("日用品", models::ExpenseCategory::Other, 300, 3000),
+    ("交通費", models::ExpenseCategory::Other, 200, 1500),
+];
+
+/// サンプルデータを生成してJSONファイルに保存します。
+///
+/// この関数は、ユーザーから期間・件数・シード値・保存先を受け付け、擬似データを生成して保存します。
+///
+/// #### 例
+///
+/// ```rust
+/// // ドキュメンテーションテストでFailになるのでコメントアウト
+/// // use kakeibo_app::services;
+/// // services::generate::run();
+/// ```
+///
+/// #### エラー
+///
+/// 不正な入力があった場合や、入力待ちがタイムアウトした場合、ファイルへの書き込みに失敗した場合は、`KakeiboError`を返します。
+pub fn run() -> Result<()> {
+    println!("サンプルデータを生成します");
+    let first_date = input_date("開始日を入力してください")?;
+    let last_date = input_date("終了日を入力してください")?;
+    if first_date > last_date {
+        return Err(KakeiboError::invalid_input("終了日は開始日以降の日付を入力してください"));
+    }
+    let count = input_count()?;
+    let seed = input_seed()?;
+    let output_path = input_output_path()?;
+
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let mut data = generate_items(first_date, last_date, count, &mut rng);
+    services::io::assign_ids(&mut data);
+    services::io::write_to_json(&data, &output_path)?;
+    println!("{}件のサンプルデータを{}に保存しました", data.len(), output_path);
+    Ok(())
+}
+
+/// 擬似データを生成します。
+///
+/// 期間内の毎月25日に給与を、6月・12月の10日に賞与を登録し、さらに`count`件の支出を期間内のランダムな日付に登録します。
+/// 給与・賞与の支給日が土日・祝日に当たる場合は、前営業日に支給したものとします。
+/// 生成した項目は日付順に並べて返します。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::services::generate;
+/// use chrono::NaiveDate;
+/// use rand::SeedableRng;
+///
+/// let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+/// let data = generate::generate_items(
+///     NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+///     NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
+///     100,
+///     &mut rng,
+/// );
+/// assert_eq!(data.len(), 100 + 12 + 2);
+/// ```
+pub fn generate_items<R: Rng>(first_date: NaiveDate, last_date: NaiveDate, count: usize, rng: &mut R) -> Vec<models::Item> {
+    let mut data = Vec::new();
+
+    let mut month = NaiveDate::from_ymd_opt(first_date.year(), first_date.month(), 1).unwrap();
+    while month <= last_date {
+        let payday = BusinessDayRule::Previous.apply(month.with_day(25).unwrap());
+        if first_date <= payday && payday <= last_date {
+            data.push(models::Item::new(
+                String::from("給与"),
+                models::Category::Income(models::IncomeCategory::Salary),
+                round_price(rng.gen_range(250000..=350000), 1000).into(),
+                payday,
+            ));
+        }
+
+        let bonus_day = BusinessDayRule::Previous.apply(month.with_day(10).unwrap());
+        if matches!(month.month(), 6 | 12) && first_date <= bonus_day && bonus_day <= last_date {
+            data.push(models::Item::new(
+                String::from("賞与"),
+                models::Category::Income(models::IncomeCategory::Bonus),
+                round_price(rng.gen_range(500000..=800000), 10000).into(),
+                bonus_day,
+            ));
+        }
+        month = month.checked_add_months(Months::new(1)).unwrap();
+    }
+
+    let days = (last_date - first_date).num_days() as u64;
+    for _ in 0..count {
+        let (name, category, min, max) = &EXPENSE_TEMPLATES[rng.gen_range(0..EXPENSE_TEMPLATES.len())];
+        data.push(models::Item::new(
+            name.to_string(),
+            models::Category::Expense(category.clone()),
+            round_price(rng.gen_range(*min..=*max), 10).into(),
+            first_date.checked_add_days(Days::new(rng.gen_range(0..=days))).unwrap(),
+        ));
+    }
+
+    data.sort_by_key(|item| item.get_date());
+    data
+}
+
+/// 金額を指定された単位に丸めます。
+fn round_price(price: u32, unit: u32) -> u32 {
+    price / unit * unit
+}
+
+/// ユーザーに日付を尋ね、`NaiveDate`オブジェクトで返します。
+///
+/// #### エラー
+///
+/// 不正な入力があった場合、`KakeiboError`を返します。
+fn input_date(message: &str) -> Result<NaiveDate> {
+    let date = services::prompt::ask(message)?;
+    NaiveDate::from_str(date.trim()).map_err(|_| KakeiboError::invalid_input("日付はyyyy-mm-ddの形式で入力してください"))
+}
+
+/// ユーザーに生成する支出の件数を尋ね、数値で返します。
+///
+/// #### エラー
+///
+/// 不正な入力があった場合、`KakeiboError`を返します。
+fn input_count() -> Result<usize> {
+    let count = services::prompt::ask("生成する支出の件数を入力してください")?;
+    count.trim().parse().map_err(|_| KakeiboError::invalid_input("件数は数値で入力してください"))
+}
+
+/// ユーザーに乱数のシード値を尋ね、数値で返します。
+///
+/// 空行が入力された場合は、毎回異なるデータを生成するものとして`None`を返します。
+///
+/// #### エラー
+///
+/// 不正な入力があった場合、`KakeiboError`を返します。
+fn input_seed() -> Result<Option<u64>> {
+    let seed = services::prompt::ask("シード値を入力してください (空行でランダム)")?;
+    let seed = seed.trim();
+    if seed.is_empty() {
+        Ok(None)
+    } else {
+        seed.parse().map(Some).map_err(|_| KakeiboError::invalid_input("シード値は数値で入力してください"))
+    }
+}
+
+/// ユーザーに保存先を尋ね、文字列で返します。
+///
+/// 空行が入力された場合は、既定の保存先`store/sample.json`を返します。
+///
+/// #### エラー
+///
+/// 標準入力からの読み込みに失敗した場合、`KakeiboError`を返します。
+fn input_output_path() -> Result<String> {
+    let output_path = services::prompt::ask(&format!("保存先を入力してください (空行で{})", DEFAULT_OUTPUT_PATH))?;
+    let output_path = output_path.trim();
+    if output_path.is_empty() {
+        Ok(DEFAULT_OUTPUT_PATH.to_string())
+    } else {
+        Ok(output_path.to_string())
+    }
+}
+
+#[cfg(test)]
+mod generate_test {
+    use super::*;
+
+    #[test]
+    fn test_generate_items() {
+        let first_date = NaiveDate::from_ymd_opt(2023, 5, 26).unwrap();
+        let last_date = NaiveDate::from_ymd_opt(2023, 7, 20).unwrap();
+        let mut rng = StdRng::seed_from_u64(42);
+        let data = generate_items(first_date, last_date, 50, &mut rng);
+
+        // 給与は6/25(日)の前営業日の6/23のみ（5/25は期間外、7/25は期間外）、賞与は6/10(土)の前営業日の6/9
+        let incomes: Vec<_> = data.iter().filter(|item| {
+            matches!(item.get_item_category(), models::Category::Income(_))
+        }).collect();
+        assert_eq!(incomes.len(), 2);
+        assert_eq!(incomes[0].get_date(), NaiveDate::from_ymd_opt(2023, 6, 9).unwrap());
+        assert_eq!(incomes[1].get_date(), NaiveDate::from_ymd_opt(2023, 6, 23).unwrap());
+        assert_eq!(data.len(), 52);
+        assert!(data.iter().all(|item| first_date <= item.get_date() && item.get_date() <= last_date));
+        assert!(data.windows(2).all(|items| items[0].get_date() <= items[1].get_date()));
+    }
+
+    #[test]
+    fn test_generate_items_is_reproducible() {
+        let first_date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let last_date = NaiveDate::from_ymd_opt(2023, 3, 31).unwrap();
+        let data1 = generate_items(first_date, last_date, 10, &mut StdRng::seed_from_u64(1));
+        let data2 = generate_items(first_date, last_date, 10, &mut StdRng::seed_from_u64(1));
+
+        assert_eq!(data1, data2);
+    }
+
+    #[test]
+    fn test_round_price() {
+        assert_eq!(round_price(1234, 10), 1230);
+        assert_eq!(round_price(312345, 1000), 312000);
+    }
+}
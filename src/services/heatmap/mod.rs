@@ -0,0 +1,334 @@
+//! ヒートマップサービス
+//!
+//! このモジュールは、1年分の日ごとの支出額を、GitHubのコントリビューショングラフのような濃淡のヒートマップで
+//! 端末またはHTMLファイルに出力する機能を提供します。
+//!
+//! ヒートマップは、列が週、行が曜日（設定の`week_start`から始まる）の表です。
+//! 各日の濃さは、その年で支出額が最も多い日に対する割合で5段階（0: 支出なし〜4: 最大の75%超）に分けます。
+
+use std::collections::HashMap;
+
+use chrono::{Datelike, NaiveDate, Weekday};
+
+use crate::config::Config;
+use crate::error::{KakeiboError, Result};
+use crate::models;
+use crate::services;
+
+/// 端末に出力するときの、濃さごとの文字
+const TERMINAL_CELLS: [char; 5] = ['·', '░', '▒', '▓', '█'];
+
+/// HTMLに出力するときの、濃さごとの色
+const HTML_COLORS: [&str; 5] = ["#ebedf0", "#9be9a8", "#40c463", "#30a14e", "#216e39"];
+
+/// HTMLの既定の保存先
+const DEFAULT_HTML_PATH: &str = "kakeibo_heatmap.html";
+
+/// ヒートマップの1日分のマスを表す構造体
+///
+/// - `date`: 日付
+/// - `expense`: その日の支出額
+/// - `level`: 濃さ（0〜4）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeatmapCell {
+    pub date: NaiveDate,
+    pub expense: models::Money,
+    pub level: usize,
+}
+
+/// ヒートマップを表す構造体
+///
+/// - `year`: 対象の年
+/// - `weekdays`: 行の曜日（週の開始曜日から順に7つ）
+/// - `weeks`: 週ごとの列。対象の年に含まれない日は`None`
+#[derive(Debug, PartialEq, Eq)]
+pub struct Heatmap {
+    pub year: i32,
+    pub weekdays: [Weekday; 7],
+    pub weeks: Vec<[Option<HeatmapCell>; 7]>,
+}
+
+impl Heatmap {
+    /// 指定された年の日ごとの支出額からヒートマップを作成する
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+    /// use kakeibo_app::services::heatmap::Heatmap;
+    /// use chrono::{NaiveDate, Weekday};
+    ///
+    /// let data = vec![Item::new(
+    ///     String::from("ランチ"),
+    ///     Category::Expense(ExpenseCategory::Food),
+    ///     Money::new(1000),
+    ///     NaiveDate::from_ymd_opt(2023, 1, 4).unwrap(),
+    /// )];
+    /// let heatmap = Heatmap::new(&data, 2023, Weekday::Mon);
+    /// // 2023/1/1は日曜日のため、1列目は1/1だけ
+    /// assert_eq!(heatmap.weeks[0][6].unwrap().date, NaiveDate::from_ymd_opt(2023, 1, 1).unwrap());
+    /// assert_eq!(heatmap.weeks[1][2].unwrap().level, 4);
+    /// ```
+    pub fn new(data: &[models::Item], year: i32, week_start: Weekday) -> Self {
+        let expenses = get_daily_expenses(data, year);
+        let max_expense = expenses.values().copied().max().unwrap_or_default();
+
+        let mut weekdays = [week_start; 7];
+        for index in 1..7 {
+            weekdays[index] = weekdays[index - 1].succ();
+        }
+
+        let first_date = NaiveDate::from_ymd_opt(year, 1, 1).unwrap();
+        let offset = first_date.weekday().days_since(week_start) as usize;
+        let mut weeks = Vec::new();
+        for (index, date) in first_date.iter_days().take_while(|date| date.year() == year).enumerate() {
+            let (week, row) = ((index + offset) / 7, (index + offset) % 7);
+            if week == weeks.len() {
+                weeks.push([None; 7]);
+            }
+            let expense = expenses.get(&date).copied().unwrap_or_default();
+            let level = get_level(expense.get_minor_units(), max_expense.get_minor_units());
+            weeks[week][row] = Some(HeatmapCell { date, expense, level });
+        }
+        Heatmap { year, weekdays, weeks }
+    }
+
+    /// 支出が最も多い日を取得する
+    fn get_max_cell(&self) -> Option<HeatmapCell> {
+        self.weeks.iter().flatten().flatten().copied().filter(|cell| cell.expense > models::Money::ZERO).max_by_key(|cell| cell.expense)
+    }
+
+    /// 年間の支出合計を取得する
+    fn get_total(&self) -> models::Money {
+        self.weeks.iter().flatten().flatten().map(|cell| cell.expense).sum()
+    }
+}
+
+/// ヒートマップを端末またはHTMLファイルに出力します。
+///
+/// この関数は、対象の年と出力先を尋ねます。年を省略した場合は、データの中で最も新しい年を対象にします。
+///
+/// #### 例
+///
+/// ```rust
+/// // ドキュメンテーションテストでFailになるのでコメントアウト
+/// // use kakeibo_app::config::Config;
+/// // use kakeibo_app::services;
+/// // services::heatmap::run("store/data.json", &Config::default());
+/// ```
+///
+/// #### エラー
+///
+/// 不正な入力があった場合や、データの読み書きに失敗した場合は、`KakeiboError`を返します。
+pub fn run(file_path: &str, config: &Config) -> Result<()> {
+    let year = services::prompt::ask("対象の年を入力してください (空行で最新の年)")?;
+    let year = match year.trim() {
+        "" => None,
+        year => Some(year.parse().map_err(|_| KakeiboError::invalid_input("年は数値で入力してください"))?),
+    };
+    let action = services::prompt::ask("t:端末に表示 / h:HTMLに出力")?;
+    let html_path = match action.trim() {
+        "t" | "T" => None,
+        "h" | "H" => Some(DEFAULT_HTML_PATH),
+        _ => return Err(KakeiboError::invalid_input("操作はtまたはhで入力してください")),
+    };
+    run_with_options(file_path, year, html_path, config)
+}
+
+/// 指定された年のヒートマップを出力します。
+///
+/// `year`が`None`の場合は、データの中で最も新しい年を対象にします。
+/// `html_path`を指定した場合はHTMLファイルに、指定しない場合は端末に出力します。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// services::heatmap::run_with_options("store/data.json", Some(2023), None, &Config::default()).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// データの読み込みやHTMLファイルの書き込みに失敗した場合は、`KakeiboError`を返します。
+pub fn run_with_options(file_path: &str, year: Option<i32>, html_path: Option<&str>, config: &Config) -> Result<()> {
+    let data = services::io::read_data(file_path)?;
+    let year = year.unwrap_or_else(|| data.iter().map(|item| item.get_date().year()).max().unwrap());
+    let heatmap = Heatmap::new(&data, year, config.week_start);
+    match html_path {
+        Some(html_path) => {
+            services::io::write_file(html_path, &render_html(&heatmap, config))?;
+            println!("{}年のヒートマップを{}に出力しました", year, html_path);
+        },
+        None => print_heatmap(&heatmap, config),
+    }
+    Ok(())
+}
+
+/// 指定された年の日ごとの支出額を集計します。
+fn get_daily_expenses(data: &[models::Item], year: i32) -> HashMap<NaiveDate, models::Money> {
+    let mut expenses = HashMap::new();
+    for item in data {
+        if item.get_date().year() == year && matches!(item.get_item_category(), models::Category::Expense(_)) {
+            *expenses.entry(item.get_date()).or_default() += item.get_price();
+        }
+    }
+    expenses
+}
+
+/// 支出額を、最大の支出額に対する割合で0〜4の濃さに変換します。
+///
+/// 支出が無い日は0、それ以外は最大の25%ごとに1〜4にします。
+fn get_level(expense: i64, max_expense: i64) -> usize {
+    if expense <= 0 || max_expense <= 0 {
+        return 0;
+    }
+    ((expense * 4 + max_expense - 1) / max_expense).clamp(1, 4) as usize
+}
+
+/// 曜日の名前を取得します。
+fn get_weekday_name(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "月",
+        Weekday::Tue => "火",
+        Weekday::Wed => "水",
+        Weekday::Thu => "木",
+        Weekday::Fri => "金",
+        Weekday::Sat => "土",
+        Weekday::Sun => "日",
+    }
+}
+
+/// ヒートマップを端末に出力します。
+fn print_heatmap(heatmap: &Heatmap, config: &Config) {
+    println!("{}年の支出ヒートマップ", heatmap.year);
+    for (row, weekday) in heatmap.weekdays.iter().enumerate() {
+        let cells: String = heatmap.weeks.iter()
+            .map(|week| week[row].map_or(' ', |cell| TERMINAL_CELLS[cell.level]))
+            .collect();
+        println!("{} {}", get_weekday_name(*weekday), cells);
+    }
+    println!("少 {} 多", TERMINAL_CELLS.iter().collect::<String>());
+    println!("年間の支出: {}", config.format_currency(heatmap.get_total()));
+    if let Some(cell) = heatmap.get_max_cell() {
+        println!("最も支出が多い日: {} ({})", cell.date, config.format_currency(cell.expense));
+    }
+}
+
+/// ヒートマップをHTML文書にします。
+///
+/// 各マスにマウスを重ねると、日付と支出額が表示されます。
+fn render_html(heatmap: &Heatmap, config: &Config) -> String {
+    let mut rows = String::new();
+    for (row, weekday) in heatmap.weekdays.iter().enumerate() {
+        rows.push_str(&format!("      <tr><th>{}</th>", get_weekday_name(*weekday)));
+        for week in &heatmap.weeks {
+            match week[row] {
+                Some(cell) => rows.push_str(&format!(
+                    "<td style=\"background:{}\" title=\"{} {}\"></td>",
+                    HTML_COLORS[cell.level],
+                    cell.date,
+                    config.format_currency(cell.expense)
+                )),
+                None => rows.push_str("<td class=\"empty\"></td>"),
+            }
+        }
+        rows.push_str("</tr>\n");
+    }
+    let legend: String = HTML_COLORS.iter()
+        .map(|color| format!("<span style=\"background:{}\"></span>", color))
+        .collect();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="ja">
+  <head>
+    <meta charset="utf-8">
+    <title>{year}年の支出ヒートマップ</title>
+    <style>
+      body {{ font-family: sans-serif; }}
+      table {{ border-spacing: 3px; }}
+      th {{ font-size: 10px; font-weight: normal; }}
+      td {{ width: 11px; height: 11px; border-radius: 2px; }}
+      td.empty {{ background: none; }}
+      .legend span {{ display: inline-block; width: 11px; height: 11px; margin: 0 1px; border-radius: 2px; }}
+    </style>
+  </head>
+  <body>
+    <h1>{year}年の支出ヒートマップ</h1>
+    <table>
+{rows}    </table>
+    <p class="legend">少 {legend} 多</p>
+    <p>年間の支出: {total}</p>
+  </body>
+</html>"#,
+        year = heatmap.year,
+        rows = rows,
+        legend = legend,
+        total = config.format_currency(heatmap.get_total()),
+    )
+}
+
+#[cfg(test)]
+mod heatmap_test {
+    use super::*;
+
+    fn get_test_data() -> Vec<models::Item> {
+        vec![
+            models::Item::new(
+                "ランチ".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                models::Money::new(1000),
+                NaiveDate::from_ymd_opt(2023, 1, 2).unwrap()
+            ),
+            models::Item::new(
+                "ディナー".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                models::Money::new(3000),
+                NaiveDate::from_ymd_opt(2023, 1, 2).unwrap()
+            ),
+            models::Item::new(
+                "給料".to_string(),
+                models::Category::Income(models::IncomeCategory::Salary),
+                models::Money::new(300000),
+                NaiveDate::from_ymd_opt(2023, 1, 3).unwrap()
+            ),
+            models::Item::new(
+                "本".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Hobby),
+                models::Money::new(1500),
+                NaiveDate::from_ymd_opt(2023, 12, 31).unwrap()
+            ),
+            models::Item::new(
+                "本".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Hobby),
+                models::Money::new(9000),
+                NaiveDate::from_ymd_opt(2022, 12, 31).unwrap()
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_get_level() {
+        assert_eq!(get_level(0, 4000), 0);
+        assert_eq!(get_level(1, 4000), 1);
+        assert_eq!(get_level(1000, 4000), 1);
+        assert_eq!(get_level(1001, 4000), 2);
+        assert_eq!(get_level(4000, 4000), 4);
+        assert_eq!(get_level(0, 0), 0);
+    }
+
+    #[test]
+    fn test_new() {
+        let heatmap = Heatmap::new(&get_test_data(), 2023, Weekday::Sun);
+
+        assert_eq!(heatmap.weekdays[0], Weekday::Sun);
+        assert_eq!(heatmap.weeks.len(), 53);
+        let cell = heatmap.weeks[0][1].unwrap();
+        assert_eq!((cell.date, cell.expense, cell.level), (NaiveDate::from_ymd_opt(2023, 1, 2).unwrap(), models::Money::new(4000), 4));
+        assert_eq!(heatmap.weeks[0][2].unwrap().expense, models::Money::ZERO);
+        assert_eq!(heatmap.weeks[52][0].unwrap().level, 2);
+        assert!(heatmap.weeks[52][1].is_none());
+        assert_eq!(heatmap.get_total(), models::Money::new(5500));
+    }
+}
@@ -0,0 +1,924 @@
+//! 取り込みサービス
+//!
+//! このモジュールは、他のアプリや表計算ソフトで管理していた取引をファイルから読み込み、家計簿データに追加する機能を提供します。
+//!
+//! #### CSVファイルの列
+//!
+//! 列の並びは、設定の`import_columns`（既定は`["date", "name", "amount", "category"]`）か、コマンドの`--columns`で指定します。
+//!
+//! | 列 | 内容 |
+//! | --- | --- |
+//! | `date` | 日付（yyyy-mm-dd または yyyy/mm/dd） |
+//! | `name` | 品目名 |
+//! | `amount` | 金額（`1,280`・`1280円`・`-1280`のような表記も可。符号は無視する） |
+//! | `category` | カテゴリ（`食費`・`Food`・`支出:その他`など。空欄や`その他`は支出のその他） |
+//! | `skip` | 読み飛ばす列 |
+//!
+//! `date`・`name`・`amount`は必須です。`category`を指定しない場合は、すべて支出のその他として取り込みます。
+//! 1行でも不正な行がある場合は、すべての不正な行を表示し、何も取り込みません。
+//! 既に登録されている項目と日付・品目名・カテゴリ・金額が同じ行は、重複として取り込みません。
+//!
+//! #### 銀行の明細
+//!
+//! 銀行ごとのCSVの形式（文字コード・日付の形式・出金/入金の列・見出しの行数）は、`store/banks/<プロファイル名>.toml`に記述します。
+//! 列の番号は1から数えます。出金の列の金額は支出のその他、入金の列の金額は収入のその他として取り込みます。
+//! 入出金が1つの列にまとまっている場合は、`debit_column`・`credit_column`の代わりに`amount_column`を指定します（マイナスの金額を出金とみなします）。
+//! TOMLの読み込みと文字コードの変換には、`bank`機能が必要です。
+//!
+//! #### OFXファイル
+//!
+//! 銀行やカード会社が出力するOFX/QFXファイル（UTF-8）は、`ofx`モジュールで読み取り、すべての取引をカテゴリ「その他」で取り込みます。
+//! 取り込んだ項目には追加属性`needs_category`を付け、未分類チェックでカテゴリを付け直す対象として表示します（カテゴリを付け替えると外れます）。
+//!
+//! #### QIFファイル
+//!
+//! 家計簿ソフトが出力するQIFファイル（UTF-8）は、`qif`モジュールで読み取ります。
+//! QIFのカテゴリは、ユーザーが編集する読み替え表（`store/qif_categories.json`）で家計簿のカテゴリに読み替え、読み替え表に無いカテゴリの取引はOFXと同じくカテゴリ「その他」で取り込みます。
+//!
+//! ```toml
+//! name = "三井住友銀行"
+//! encoding = "shift_jis"
+//! date_format = "%Y/%m/%d"
+//! header_rows = 1
+//! date_column = 1
+//! description_column = 2
+//! debit_column = 3
+//! credit_column = 4
+//! ```
+
+use std::fs;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::error::{KakeiboError, Result};
+use crate::models;
+use crate::services;
+
+pub mod ofx;
+pub mod qif;
+
+/// カテゴリを付け直す必要がある項目に付ける追加属性のキー
+pub const NEEDS_CATEGORY_KEY: &str = "needs_category";
+
+/// 取り込むCSVファイルの列を表す列挙型
+///
+/// - `Date`: 日付
+/// - `Name`: 品目名
+/// - `Amount`: 金額
+/// - `Category`: カテゴリ
+/// - `Skip`: 読み飛ばす列
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportColumn {
+    Date,
+    Name,
+    Amount,
+    Category,
+    Skip,
+}
+
+impl ImportColumn {
+    /// 既定の列の並び（日付・品目名・金額・カテゴリ）を取得する
+    pub fn get_default_columns() -> Vec<ImportColumn> {
+        vec![ImportColumn::Date, ImportColumn::Name, ImportColumn::Amount, ImportColumn::Category]
+    }
+
+    /// カンマ区切りの列名（例: `date,name,amount,category`）から列の並びを取得する
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::services::import::ImportColumn;
+    ///
+    /// let columns = ImportColumn::parse_columns("name, date, skip, amount").unwrap();
+    /// assert_eq!(columns, vec![ImportColumn::Name, ImportColumn::Date, ImportColumn::Skip, ImportColumn::Amount]);
+    /// assert!(ImportColumn::parse_columns("date,name").is_err());
+    /// ```
+    ///
+    /// #### エラー
+    ///
+    /// 不明な列名がある場合や、列の並びが不正な場合は、`KakeiboError::InvalidInput`を返します。
+    pub fn parse_columns(text: &str) -> Result<Vec<ImportColumn>> {
+        let columns = text.split(',')
+            .map(|name| match name.trim().to_lowercase().as_str() {
+                "date" => Ok(ImportColumn::Date),
+                "name" => Ok(ImportColumn::Name),
+                "amount" => Ok(ImportColumn::Amount),
+                "category" => Ok(ImportColumn::Category),
+                "skip" => Ok(ImportColumn::Skip),
+                _ => Err(KakeiboError::InvalidInput(format!("列{}には対応していません (date・name・amount・category・skipで指定してください)", name.trim()))),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        validate_columns(&columns)?;
+        Ok(columns)
+    }
+}
+
+/// 銀行の明細のCSVの形式を表す構造体
+///
+/// - `name`: 銀行の名前（表示用。省略時はプロファイル名）
+/// - `encoding`: 文字コード（`utf-8`・`shift_jis`など。省略時は`utf-8`）
+/// - `date_format`: 日付の形式（chronoの書式。省略時は`%Y/%m/%d`）
+/// - `header_rows`: 読み飛ばす先頭の行数
+/// - `date_column`: 日付の列の番号（1から数える）
+/// - `description_column`: 摘要（品目名）の列の番号
+/// - `debit_column`: 出金の列の番号
+/// - `credit_column`: 入金の列の番号
+/// - `amount_column`: 入出金をまとめた列の番号（マイナスの金額を出金とみなす）
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct BankProfile {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default = "get_default_encoding")]
+    pub encoding: String,
+    #[serde(default = "get_default_date_format")]
+    pub date_format: String,
+    #[serde(default)]
+    pub header_rows: usize,
+    pub date_column: usize,
+    pub description_column: usize,
+    #[serde(default)]
+    pub debit_column: Option<usize>,
+    #[serde(default)]
+    pub credit_column: Option<usize>,
+    #[serde(default)]
+    pub amount_column: Option<usize>,
+}
+
+/// 銀行の明細の既定の文字コードを取得します。
+fn get_default_encoding() -> String {
+    "utf-8".to_string()
+}
+
+/// 銀行の明細の既定の日付の形式を取得します。
+fn get_default_date_format() -> String {
+    "%Y/%m/%d".to_string()
+}
+
+impl BankProfile {
+    /// プロファイルのディレクトリから、プロファイル名のTOMLファイル（`<プロファイル名>.toml`）を読み込む
+    ///
+    /// #### エラー
+    ///
+    /// ファイルが存在しない場合は`KakeiboError::Io`、内容が不正な場合は`KakeiboError::InvalidData`を返します。
+    /// `bank`機能が無効の場合は、`KakeiboError::InvalidInput`を返します。
+    pub fn load(banks_dir: &str, profile: &str) -> Result<Self> {
+        let path = std::path::Path::new(banks_dir).join(format!("{}.toml", profile));
+        let path = path.to_string_lossy();
+        let text = fs::read_to_string(path.as_ref()).map_err(|error| KakeiboError::io(&path, error))?;
+        let mut bank = Self::parse(&text).map_err(|error| KakeiboError::InvalidData(format!("{}: {}", path, error)))?;
+        if bank.name.is_empty() {
+            bank.name = profile.to_string();
+        }
+        Ok(bank)
+    }
+
+    /// TOMLの文字列からプロファイルを読み取る
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::services::import::BankProfile;
+    ///
+    /// let bank = BankProfile::parse("date_column = 1\ndescription_column = 2\namount_column = 3").unwrap();
+    /// assert_eq!(bank.encoding, "utf-8");
+    /// assert_eq!(bank.amount_column, Some(3));
+    /// assert!(BankProfile::parse("date_column = 1\ndescription_column = 2").is_err());
+    /// ```
+    ///
+    /// #### エラー
+    ///
+    /// 内容が不正な場合や、列の指定が不正な場合は、`KakeiboError::InvalidData`を返します。
+    #[cfg(feature = "bank")]
+    pub fn parse(text: &str) -> Result<Self> {
+        let bank: BankProfile = toml::from_str(text).map_err(|error| KakeiboError::InvalidData(error.message().to_string()))?;
+        bank.validate()?;
+        Ok(bank)
+    }
+
+    /// TOMLの文字列からプロファイルを読み取る
+    ///
+    /// #### エラー
+    ///
+    /// `bank`機能が無効のため、常に`KakeiboError::InvalidInput`を返します。
+    #[cfg(not(feature = "bank"))]
+    pub fn parse(_text: &str) -> Result<Self> {
+        Err(KakeiboError::invalid_input("銀行の明細を取り込めません: bank機能を有効にしてビルドしてください"))
+    }
+
+    /// 列の指定を検証します。
+    #[cfg_attr(not(feature = "bank"), allow(dead_code))]
+    fn validate(&self) -> Result<()> {
+        let columns = [Some(self.date_column), Some(self.description_column), self.debit_column, self.credit_column, self.amount_column];
+        if columns.iter().flatten().any(|column| *column == 0) {
+            return Err(KakeiboError::InvalidData("列の番号は1から数えてください".to_string()));
+        }
+        match (self.debit_column.is_some() || self.credit_column.is_some(), self.amount_column.is_some()) {
+            (false, false) => Err(KakeiboError::InvalidData("debit_column・credit_columnかamount_columnを指定してください".to_string())),
+            (true, true) => Err(KakeiboError::InvalidData("debit_column・credit_columnとamount_columnは同時に指定できません".to_string())),
+            _ => Ok(()),
+        }
+    }
+
+    /// 明細の1行分の列を項目に変換します。
+    fn parse_fields(&self, fields: &[String], config: &Config) -> std::result::Result<models::Item, String> {
+        let get_field = |column: usize| fields.get(column - 1).map_or("", |field| field.trim());
+
+        let date = get_field(self.date_column);
+        let date = NaiveDate::parse_from_str(date, &self.date_format).map_err(|_| format!("日付{}が不正です", date))?;
+        let name = get_field(self.description_column);
+        if name.is_empty() {
+            return Err("摘要が空です".to_string());
+        }
+
+        let amounts = match self.amount_column {
+            Some(column) => {
+                let amount = get_field(column);
+                let is_debit = amount.starts_with('-') || amount.starts_with('△');
+                (is_debit.then_some(amount), (!is_debit).then_some(amount))
+            },
+            None => (self.debit_column.map(get_field), self.credit_column.map(get_field)),
+        };
+        let (amount, category) = match amounts {
+            (Some(debit), _) if !debit.is_empty() => (debit, models::Category::Expense(models::ExpenseCategory::Other)),
+            (_, Some(credit)) if !credit.is_empty() => (credit, models::Category::Income(models::IncomeCategory::Other)),
+            _ => return Err("出金・入金の金額が空です".to_string()),
+        };
+        let price = parse_amount(&amount.replace('△', ""), config).ok_or_else(|| format!("金額{}が不正です", amount))?;
+
+        Ok(models::Item::new(name.to_string(), category, price, date))
+    }
+}
+
+/// 列の並びを検証します。
+///
+/// #### エラー
+///
+/// 日付・品目名・金額の列が無い場合や、`skip`以外の列が重複している場合は、`KakeiboError::InvalidInput`を返します。
+fn validate_columns(columns: &[ImportColumn]) -> Result<()> {
+    for column in [ImportColumn::Date, ImportColumn::Name, ImportColumn::Amount, ImportColumn::Category] {
+        let count = columns.iter().filter(|c| **c == column).count();
+        if count > 1 {
+            return Err(KakeiboError::InvalidInput(format!("列{:?}が重複しています", column)));
+        }
+        if count == 0 && column != ImportColumn::Category {
+            return Err(KakeiboError::invalid_input("列の並びには、date・name・amountを含めてください"));
+        }
+    }
+    Ok(())
+}
+
+/// CSVファイルの取引を家計簿データに取り込みます。
+///
+/// `columns`が`None`の場合は、設定の`import_columns`の列の並びで読み込みます。
+/// `has_header`が`true`の場合は、1行目を見出しとして読み飛ばします。
+/// 取り込む項目に締め済みの月の項目が含まれる場合は、取り込みません。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// services::import::run_csv("store/data.json", "store/closed.json", "store/device.json", "transactions.csv", None, true, &Config::default()).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// 列の並びや、ファイルの内容が不正な場合、ファイルの読み書きに失敗した場合は、`KakeiboError`を返します。
+pub fn run_csv(
+    file_path: &str,
+    closed_path: &str,
+    device_path: &str,
+    csv_path: &str,
+    columns: Option<&str>,
+    has_header: bool,
+    config: &Config
+) -> Result<()> {
+    let columns = match columns {
+        Some(columns) => ImportColumn::parse_columns(columns)?,
+        None => {
+            validate_columns(&config.import_columns)?;
+            config.import_columns.clone()
+        },
+    };
+    let text = fs::read_to_string(csv_path).map_err(|error| KakeiboError::io(csv_path, error))?;
+    let lines: Vec<&str> = text.lines().skip(if has_header { 1 } else { 0 }).collect();
+    let first_line_number = if has_header { 2 } else { 1 };
+    let candidates = parse_csv_lines(&lines, first_line_number, &columns, config)?;
+
+    append_items(file_path, closed_path, device_path, candidates, remove_duplicates, config)
+}
+
+/// OFX/QFXファイルの取引を、カテゴリ「その他」の項目として家計簿データに取り込みます。
+///
+/// 取り込んだ項目には、カテゴリを付け直す対象であることを示す追加属性`needs_category`を付けます。
+/// 重複の確認には取引ID（FITID）を使うため、同じ日に同じ内容の取引が複数あってもすべて取り込みます。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// services::import::run_ofx("store/data.json", "store/closed.json", "store/device.json", "statement.ofx", &Config::default()).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// ファイルの内容が不正な場合や、ファイルの読み書きに失敗した場合は、`KakeiboError`を返します。
+pub fn run_ofx(file_path: &str, closed_path: &str, device_path: &str, ofx_path: &str, config: &Config) -> Result<()> {
+    let text = fs::read_to_string(ofx_path).map_err(|error| KakeiboError::io(ofx_path, error))?;
+    let candidates = ofx::parse(&text, config)?.iter().map(ofx::OfxTransaction::to_item).collect();
+
+    append_items(file_path, closed_path, device_path, candidates, remove_duplicate_transactions, config)?;
+    println!("取り込んだ項目のカテゴリは「その他」です。未分類チェックで確認し、カテゴリを付け直してください");
+    Ok(())
+}
+
+/// QIFファイルの取引を、読み替え表でカテゴリを読み替えて家計簿データに取り込みます。
+///
+/// 読み替え表（`categories_path`）に無いカテゴリの取引は、カテゴリ「その他」で取り込み、読み替え表に追加するよう案内を表示します。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// services::import::run_qif("store/data.json", "store/closed.json", "store/device.json", "store/qif_categories.json", "export.qif", &Config::default()).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// 読み替え表やファイルの内容が不正な場合、ファイルの読み書きに失敗した場合は、`KakeiboError`を返します。
+pub fn run_qif(
+    file_path: &str,
+    closed_path: &str,
+    device_path: &str,
+    categories_path: &str,
+    qif_path: &str,
+    config: &Config
+) -> Result<()> {
+    let categories = qif::QifCategoryMap::load_or_default(categories_path)?.resolve()?;
+    let text = fs::read_to_string(qif_path).map_err(|error| KakeiboError::io(qif_path, error))?;
+    let transactions = qif::parse(&text, config)?;
+    let unmapped = qif::get_unmapped_categories(&transactions, &categories);
+    let candidates = transactions.iter().map(|transaction| transaction.to_item(&categories)).collect();
+
+    append_items(file_path, closed_path, device_path, candidates, remove_duplicates, config)?;
+    if !unmapped.is_empty() {
+        println!("読み替え表に無いカテゴリの取引は「その他」で取り込みました: {}", unmapped.join(", "));
+        println!("{}に読み替えを追加すると、次回から家計簿のカテゴリで取り込みます", categories_path);
+    }
+    Ok(())
+}
+
+/// 取り込んだ後にカテゴリを付け直す必要がある項目かどうかを判定します。
+pub fn needs_category(item: &models::Item) -> bool {
+    item.get_extra().get(NEEDS_CATEGORY_KEY) == Some(&serde_json::Value::Bool(true))
+}
+
+/// 銀行の明細のCSVファイルを、プロファイルの形式で読み込んで家計簿データに取り込みます。
+///
+/// プロファイルは、`banks_dir`の`<プロファイル名>.toml`から読み込みます。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// services::import::run_bank("store/data.json", "store/closed.json", "store/device.json", "store/banks", "smbc", "statement.csv", &Config::default()).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// プロファイルや明細の内容が不正な場合、ファイルの読み書きに失敗した場合は、`KakeiboError`を返します。
+pub fn run_bank(
+    file_path: &str,
+    closed_path: &str,
+    device_path: &str,
+    banks_dir: &str,
+    profile: &str,
+    statement_path: &str,
+    config: &Config
+) -> Result<()> {
+    let bank = BankProfile::load(banks_dir, profile)?;
+    let text = read_text(statement_path, &bank.encoding)?;
+    let lines: Vec<&str> = text.lines().skip(bank.header_rows).collect();
+    let candidates = parse_bank_lines(&lines, bank.header_rows + 1, &bank, config)?;
+
+    println!("{}の明細を取り込みます", bank.name);
+    append_items(file_path, closed_path, device_path, candidates, remove_duplicates, config)
+}
+
+/// ファイルの形式・パスを尋ね、取引を家計簿データに取り込みます。
+///
+/// CSVファイルの列の並びは、設定の`import_columns`を使います。銀行の明細は、`banks_dir`のプロファイルの形式で読み込みます。
+/// QIFファイルのカテゴリは、`qif_categories_path`の読み替え表で読み替えます。
+///
+/// #### 例
+///
+/// ```rust
+/// // ドキュメンテーションテストでFailになるのでコメントアウト
+/// // use kakeibo_app::config::Config;
+/// // use kakeibo_app::services;
+/// // services::import::run("store/data.json", "store/closed.json", "store/device.json", "store/banks", "store/qif_categories.json", &Config::default());
+/// ```
+///
+/// #### エラー
+///
+/// 不正な入力があった場合や、ファイルの内容が不正な場合、ファイルの読み書きに失敗した場合は、`KakeiboError`を返します。
+pub fn run(file_path: &str, closed_path: &str, device_path: &str, banks_dir: &str, qif_categories_path: &str, config: &Config) -> Result<()> {
+    let kind = services::prompt::ask("取り込むファイルの形式を入力してください (c:CSV, b:銀行の明細, o:OFX, q:QIF)")?;
+    match kind.trim() {
+        "c" | "C" => {
+            let csv_path = input_path()?;
+            let has_header = matches!(services::prompt::ask("1行目は見出しですか (y/n)")?.trim(), "y" | "Y");
+            run_csv(file_path, closed_path, device_path, &csv_path, None, has_header, config)
+        },
+        "b" | "B" => {
+            let profile = services::prompt::ask(&format!("プロファイル名を入力してください ({}/<プロファイル名>.toml)", banks_dir))?;
+            let statement_path = input_path()?;
+            run_bank(file_path, closed_path, device_path, banks_dir, profile.trim(), &statement_path, config)
+        },
+        "o" | "O" => {
+            let ofx_path = input_path()?;
+            run_ofx(file_path, closed_path, device_path, &ofx_path, config)
+        },
+        "q" | "Q" => {
+            let qif_path = input_path()?;
+            run_qif(file_path, closed_path, device_path, qif_categories_path, &qif_path, config)
+        },
+        _ => Err(KakeiboError::invalid_input("取り込むファイルの形式はc, b, o, qのいずれかで入力してください")),
+    }
+}
+
+/// 取り込むファイルのパスを尋ねます。
+fn input_path() -> Result<String> {
+    let path = services::prompt::ask("取り込むファイルのパスを入力してください")?;
+    match path.trim() {
+        "" => Err(KakeiboError::invalid_input("ファイルのパスを入力してください")),
+        path => Ok(path.to_string()),
+    }
+}
+
+/// ファイルを、指定された文字コードの文字列として読み込みます。
+///
+/// 先頭のBOMは取り除きます。
+#[cfg(feature = "bank")]
+fn read_text(path: &str, encoding: &str) -> Result<String> {
+    let bytes = fs::read(path).map_err(|error| KakeiboError::io(path, error))?;
+    let encoding = encoding_rs::Encoding::for_label(encoding.trim().as_bytes())
+        .ok_or_else(|| KakeiboError::InvalidInput(format!("文字コード{}には対応していません", encoding)))?;
+    let (text, _, had_errors) = encoding.decode(&bytes);
+    if had_errors {
+        return Err(KakeiboError::InvalidData(format!("{}を文字コード{}として読み込めません", path, encoding.name())));
+    }
+    Ok(text.into_owned())
+}
+
+/// ファイルを、UTF-8の文字列として読み込みます。
+///
+/// `bank`機能が無効の場合は、UTF-8以外の文字コードには対応しません。
+#[cfg(not(feature = "bank"))]
+fn read_text(path: &str, encoding: &str) -> Result<String> {
+    if !matches!(encoding.trim().to_lowercase().as_str(), "utf-8" | "utf8") {
+        return Err(KakeiboError::InvalidInput(format!("文字コード{}を読み込めません: bank機能を有効にしてビルドしてください", encoding)));
+    }
+    let text = fs::read_to_string(path).map_err(|error| KakeiboError::io(path, error))?;
+    Ok(text.trim_start_matches('\u{feff}').to_string())
+}
+
+/// 取り込む項目から重複を取り除き、重複しない項目と取り除いた件数を返す関数
+type RemoveDuplicates = fn(Vec<models::Item>, &[models::Item]) -> (Vec<models::Item>, usize);
+
+/// 取り込む項目を、重複と締め済みの月を確認したうえで家計簿データに追加します。
+///
+/// 重複の確認には`remove_duplicated`を使います。取り込んだ項目には、この端末のデバイスIDと連番を付与します。
+fn append_items(
+    file_path: &str,
+    closed_path: &str,
+    device_path: &str,
+    candidates: Vec<models::Item>,
+    remove_duplicated: RemoveDuplicates,
+    config: &Config
+) -> Result<()> {
+    let mut data = services::io::read_data_or_create_new_data(file_path)?;
+    let (items, duplicated) = remove_duplicated(candidates, &data);
+
+    let closed_months = services::close::ClosedMonths::load_or_default(closed_path)?;
+    if let Some(month) = closed_months.find_closed_month(&items) {
+        services::close::print_locked(month);
+        return Ok(());
+    }
+
+    let count = items.len();
+    if count > 0 {
+        let device = services::journal::Device::load_or_create(device_path)?;
+        for mut item in items {
+            item.set_origin(Some(device.next_origin(&data)));
+            println!(
+                "  {} {} {} {}",
+                item.get_date(),
+                item.get_name(),
+                config.format_category(item.get_item_category()),
+                config.format_item_price(&item)
+            );
+            data.push(item);
+        }
+        services::io::assign_ids(&mut data);
+        services::io::write_to_json(&data, file_path)?;
+    }
+    println!("{}件の項目を取り込みました (重複{}件は取り込みません)", count, duplicated);
+    Ok(())
+}
+
+/// CSVの各行を、列の並びに従って項目に変換します。
+///
+/// 空行は読み飛ばします。`first_line_number`は、エラーの表示に使う最初の行の行番号です。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::models::{Category, IncomeCategory, Money};
+/// use kakeibo_app::services::import::{self, ImportColumn};
+///
+/// let lines = ["2023/01/25,給料,\"300,000\",給与"];
+/// let items = import::parse_csv_lines(&lines, 1, &ImportColumn::get_default_columns(), &Config::default()).unwrap();
+/// assert_eq!(items[0].get_item_category(), &Category::Income(IncomeCategory::Salary));
+/// assert_eq!(items[0].get_price(), Money::new(300000));
+/// ```
+///
+/// #### エラー
+///
+/// 不正な行がある場合は、すべての不正な行の行番号と理由をまとめた`KakeiboError::InvalidData`を返します。
+pub fn parse_csv_lines<S: AsRef<str>>(lines: &[S], first_line_number: usize, columns: &[ImportColumn], config: &Config) -> Result<Vec<models::Item>> {
+    parse_lines(lines, first_line_number, |fields| parse_fields(fields, columns, config))
+}
+
+/// 銀行の明細の各行を、プロファイルの形式に従って項目に変換します。
+///
+/// 空行は読み飛ばします。`first_line_number`は、エラーの表示に使う最初の行の行番号です。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::models::{Category, ExpenseCategory, Money};
+/// use kakeibo_app::services::import::{self, BankProfile};
+///
+/// let bank = BankProfile {
+///     name: String::from("テスト銀行"),
+///     encoding: String::from("utf-8"),
+///     date_format: String::from("%Y年%m月%d日"),
+///     header_rows: 0,
+///     date_column: 1,
+///     description_column: 2,
+///     debit_column: Some(3),
+///     credit_column: Some(4),
+///     amount_column: None,
+/// };
+/// let lines = ["2023年01月27日,カード,\"12,000\",,88000"];
+/// let items = import::parse_bank_lines(&lines, 1, &bank, &Config::default()).unwrap();
+/// assert_eq!(items[0].get_item_category(), &Category::Expense(ExpenseCategory::Other));
+/// assert_eq!(items[0].get_price(), Money::new(12000));
+/// ```
+///
+/// #### エラー
+///
+/// 不正な行がある場合は、すべての不正な行の行番号と理由をまとめた`KakeiboError::InvalidData`を返します。
+pub fn parse_bank_lines<S: AsRef<str>>(lines: &[S], first_line_number: usize, bank: &BankProfile, config: &Config) -> Result<Vec<models::Item>> {
+    parse_lines(lines, first_line_number, |fields| bank.parse_fields(fields, config))
+}
+
+/// CSVの各行を列に分割し、`parse`で項目に変換します。
+///
+/// 不正な行がある場合は、すべての不正な行の行番号と理由をまとめたエラーを返します。
+fn parse_lines<S, F>(lines: &[S], first_line_number: usize, parse: F) -> Result<Vec<models::Item>>
+where
+    S: AsRef<str>,
+    F: Fn(&[String]) -> std::result::Result<models::Item, String>,
+{
+    let mut items = Vec::new();
+    let mut errors = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        if line.as_ref().trim().is_empty() {
+            continue;
+        }
+        let fields = split_csv_line(line.as_ref());
+        match parse(&fields) {
+            Ok(item) => items.push(item),
+            Err(error) => errors.push(format!("{}行目: {}", first_line_number + index, error)),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(KakeiboError::InvalidData(errors.join("\n")));
+    }
+    Ok(items)
+}
+
+/// 1行分の列を項目に変換します。
+fn parse_fields(fields: &[String], columns: &[ImportColumn], config: &Config) -> std::result::Result<models::Item, String> {
+    let get_field = |column: ImportColumn| {
+        columns.iter().position(|c| *c == column).map(|index| fields.get(index).map_or("", |field| field.trim()))
+    };
+
+    let date = get_field(ImportColumn::Date).unwrap_or("");
+    let date = parse_date(date).ok_or_else(|| format!("日付{}が不正です", date))?;
+    let name = get_field(ImportColumn::Name).unwrap_or("");
+    if name.is_empty() {
+        return Err("品目名が空です".to_string());
+    }
+    let amount = get_field(ImportColumn::Amount).unwrap_or("");
+    let price = parse_amount(amount, config).ok_or_else(|| format!("金額{}が不正です", amount))?;
+    let category = get_field(ImportColumn::Category).unwrap_or("");
+    let category = parse_category(category).ok_or_else(|| format!("カテゴリ{}が不正です", category))?;
+
+    Ok(models::Item::new(name.to_string(), category, price, date))
+}
+
+/// yyyy-mm-dd または yyyy/mm/dd 形式の日付を読み取ります。
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    ["%Y-%m-%d", "%Y/%m/%d"].iter().find_map(|format| NaiveDate::parse_from_str(text, format).ok())
+}
+
+/// 金額を読み取ります。
+///
+/// 桁区切りのカンマ・通貨の記号・符号は取り除いて読み取ります。
+fn parse_amount(text: &str, config: &Config) -> Option<models::Money> {
+    let text: String = text.chars().filter(|c| !matches!(c, ',' | '円' | '¥' | '￥' | '$' | '€' | '-' | '+' | ' ')).collect();
+    if text.is_empty() {
+        return None;
+    }
+    config.parse_price(&text).ok()
+}
+
+/// OFX・QIFの符号付きの金額（`-1,234.00`など）を読み取ります。
+///
+/// 符号を除いた金額と、入金（マイナスでない）かどうかを返します。小数部の末尾の0は取り除いて読み取ります。
+fn parse_signed_amount(text: &str, config: &Config) -> Option<(models::Money, bool)> {
+    let text = text.trim();
+    let is_credit = !text.starts_with('-');
+    let digits = text.trim_start_matches(['-', '+']);
+    let digits = if digits.contains('.') { digits.trim_end_matches('0').trim_end_matches('.') } else { digits };
+    config.parse_price(digits).ok().map(|amount| (amount, is_credit))
+}
+
+/// カテゴリを読み取ります。
+///
+/// `区分:名前`の形式（例: `支出:食費`）か、名前だけで指定します。名前だけで区分が決まらない場合（`その他`）と、空欄の場合は支出とみなします。
+pub(crate) fn parse_category(text: &str) -> Option<models::Category> {
+    if text.is_empty() {
+        return Some(models::Category::Expense(models::ExpenseCategory::Other));
+    }
+    match text.split_once(':') {
+        Some((kind, name)) => models::Category::from_alias(Some(kind), name),
+        None => models::Category::from_alias(None, text).or_else(|| models::Category::from_alias(Some("支出"), text)),
+    }
+}
+
+/// CSVの1行を列に分割します。
+///
+/// ダブルクォートで囲まれた列は、その中のカンマを区切りとみなしません（`""`は`"`1文字になります）。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::services::import;
+///
+/// assert_eq!(import::split_csv_line(r#"2023/05/02,"スタバ ""新宿""","1,280""#), vec!["2023/05/02", "スタバ \"新宿\"", "1,280"]);
+/// ```
+pub fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            },
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// 既に登録されている項目と重複する項目を取り除きます。
+///
+/// 日付・品目名・カテゴリ・金額が同じ項目を重複とみなします（取り込むファイルの中での重複も取り除きます）。
+/// 重複しない項目と、取り除いた件数を返します。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+/// use kakeibo_app::services::import;
+/// use chrono::NaiveDate;
+///
+/// let new_item = |price| Item::new(String::from("ランチ"), Category::Expense(ExpenseCategory::Food), Money::new(price), NaiveDate::from_ymd_opt(2023, 5, 2).unwrap());
+/// let (items, duplicated) = import::remove_duplicates(vec![new_item(1000), new_item(1200)], &[new_item(1000)]);
+/// assert_eq!(items.len(), 1);
+/// assert_eq!(duplicated, 1);
+/// ```
+pub fn remove_duplicates(candidates: Vec<models::Item>, data: &[models::Item]) -> (Vec<models::Item>, usize) {
+    let mut items: Vec<models::Item> = Vec::new();
+    let mut duplicated = 0;
+    for candidate in candidates {
+        if data.iter().chain(items.iter()).any(|item| is_duplicate(item, &candidate)) {
+            duplicated += 1;
+        } else {
+            items.push(candidate);
+        }
+    }
+    (items, duplicated)
+}
+
+/// OFXファイルの取引のうち、既に登録されている取引を取り除きます。
+///
+/// 取引ID（FITID、参照番号として保存）がある取引は、同じ参照番号の項目が既にある場合に重複とみなします（取り込むファイルの中での重複も取り除きます）。
+/// そのため、同じ日に同じ内容の取引が複数あっても、取引IDが異なればすべて取り込みます。
+/// 取引IDが無い取引は、日付・品目名・カテゴリ・金額が同じ項目が既に登録されている場合だけ重複とみなします。
+/// 重複しない項目と、取り除いた件数を返します。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+/// use kakeibo_app::services::import;
+/// use chrono::NaiveDate;
+///
+/// let new_item = |id: &str| {
+///     let mut item = Item::new(String::from("コンビニ"), Category::Expense(ExpenseCategory::Other), Money::new(500), NaiveDate::from_ymd_opt(2023, 5, 2).unwrap());
+///     item.set_reference(Some(id.to_string()));
+///     item
+/// };
+/// let (items, duplicated) = import::remove_duplicate_transactions(vec![new_item("0001"), new_item("0002")], &[new_item("0001")]);
+/// assert_eq!(items.len(), 1);
+/// assert_eq!(duplicated, 1);
+/// ```
+pub fn remove_duplicate_transactions(candidates: Vec<models::Item>, data: &[models::Item]) -> (Vec<models::Item>, usize) {
+    let mut items: Vec<models::Item> = Vec::new();
+    let mut duplicated = 0;
+    for candidate in candidates {
+        let is_registered = match candidate.get_reference() {
+            Some(reference) => data.iter().chain(items.iter()).any(|item| item.get_reference() == Some(reference)),
+            None => data.iter().any(|item| is_duplicate(item, &candidate)),
+        };
+        if is_registered {
+            duplicated += 1;
+        } else {
+            items.push(candidate);
+        }
+    }
+    (items, duplicated)
+}
+
+/// 日付・品目名・カテゴリ・金額が同じ項目かどうかを判定します。
+fn is_duplicate(a: &models::Item, b: &models::Item) -> bool {
+    a.get_date() == b.get_date()
+        && a.get_name() == b.get_name()
+        && a.get_item_category() == b.get_item_category()
+        && a.get_price() == b.get_price()
+}
+
+#[cfg(test)]
+mod import_test {
+    use super::*;
+
+    fn get_test_item(name: &str, price: i64) -> models::Item {
+        models::Item::new(
+            name.to_string(),
+            models::Category::Expense(models::ExpenseCategory::Food),
+            models::Money::new(price),
+            NaiveDate::from_ymd_opt(2023, 5, 2).unwrap()
+        )
+    }
+
+    #[test]
+    fn test_parse_csv_lines() {
+        let columns = ImportColumn::parse_columns("name,skip,amount,date").unwrap();
+        let lines = vec![
+            "ランチ,メモ,1000円,2023-01-05",
+            "",
+            "本,,-1500,2023/01/06",
+        ];
+        let items = parse_csv_lines(&lines, 1, &columns, &Config::default()).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[1].get_name(), "本");
+        assert_eq!(items[1].get_price(), models::Money::new(1500));
+        assert_eq!(items[1].get_date(), NaiveDate::from_ymd_opt(2023, 1, 6).unwrap());
+        assert_eq!(items[1].get_item_category(), &models::Category::Expense(models::ExpenseCategory::Other));
+
+        let lines = vec![
+            "2023-01-05,ランチ,1000,食費",
+            "2023-13-01,本,1500,趣味",
+            "2023-01-07,,abc,家賃",
+        ];
+        let error = parse_csv_lines(&lines, 2, &ImportColumn::get_default_columns(), &Config::default()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "データの形式が不正です\n3行目: 日付2023-13-01が不正です\n4行目: 品目名が空です"
+        );
+    }
+
+    #[test]
+    fn test_parse_bank_lines() {
+        let bank = BankProfile {
+            name: "テスト銀行".to_string(),
+            encoding: "utf-8".to_string(),
+            date_format: "%Y/%m/%d".to_string(),
+            header_rows: 1,
+            date_column: 1,
+            description_column: 2,
+            debit_column: None,
+            credit_column: None,
+            amount_column: Some(3),
+        };
+        let lines = vec![
+            "2023/01/25,給与,\"300,000\"",
+            "2023/01/27,カード,-12000",
+            "2023/01/28,手数料,△110",
+            "2023/01/31,,",
+        ];
+        let error = parse_bank_lines(&lines, 2, &bank, &Config::default()).unwrap_err();
+        assert_eq!(error.to_string(), "データの形式が不正です\n5行目: 摘要が空です");
+
+        let items = parse_bank_lines(&lines[..3], 2, &bank, &Config::default()).unwrap();
+        assert_eq!(items.iter().map(|item| (item.get_item_category().clone(), item.get_price())).collect::<Vec<_>>(), vec![
+            (models::Category::Income(models::IncomeCategory::Other), models::Money::new(300000)),
+            (models::Category::Expense(models::ExpenseCategory::Other), models::Money::new(12000)),
+            (models::Category::Expense(models::ExpenseCategory::Other), models::Money::new(110)),
+        ]);
+    }
+
+    #[test]
+    fn test_parse_category() {
+        assert_eq!(parse_category("食費"), Some(models::Category::Expense(models::ExpenseCategory::Food)));
+        assert_eq!(parse_category("その他"), Some(models::Category::Expense(models::ExpenseCategory::Other)));
+        assert_eq!(parse_category("収入:その他"), Some(models::Category::Income(models::IncomeCategory::Other)));
+        assert_eq!(parse_category("家賃"), None);
+    }
+
+    #[test]
+    fn test_split_csv_line() {
+        assert_eq!(split_csv_line("日付,品目,金額"), vec!["日付", "品目", "金額"]);
+        assert_eq!(split_csv_line("2023-05-02,,\"680\""), vec!["2023-05-02", "", "680"]);
+        assert_eq!(split_csv_line(""), vec![""]);
+    }
+
+    #[test]
+    fn test_remove_duplicates() {
+        let data = vec![get_test_item("ランチ", 1000)];
+        let candidates = vec![
+            get_test_item("ランチ", 1000),
+            get_test_item("ディナー", 3000),
+            get_test_item("ディナー", 3000),
+            get_test_item("ランチ", 900),
+        ];
+        let (items, duplicated) = remove_duplicates(candidates, &data);
+
+        assert_eq!(duplicated, 2);
+        assert_eq!(items.iter().map(|item| item.get_price()).collect::<Vec<_>>(), vec![
+            models::Money::new(3000),
+            models::Money::new(900),
+        ]);
+    }
+
+    #[test]
+    fn test_remove_duplicate_transactions() {
+        let get_transaction = |id: Option<&str>| {
+            let mut item = get_test_item("コンビニ", 500);
+            item.set_reference(id.map(str::to_string));
+            item
+        };
+        let data = vec![get_transaction(Some("0001")), get_test_item("ランチ", 1000)];
+        let candidates = vec![
+            get_transaction(Some("0001")),
+            // 同じ日の同じ内容の取引でも、取引IDが異なれば別の取引
+            get_transaction(Some("0002")),
+            get_transaction(Some("0003")),
+            get_transaction(Some("0003")),
+            // 取引IDが無い取引は、既に登録されている項目とだけ比べる
+            get_test_item("ランチ", 1000),
+            get_test_item("ディナー", 3000),
+            get_test_item("ディナー", 3000),
+        ];
+        let (items, duplicated) = remove_duplicate_transactions(candidates, &data);
+
+        assert_eq!(duplicated, 3);
+        assert_eq!(items.iter().map(|item| item.get_reference()).collect::<Vec<_>>(), vec![
+            Some("0002"),
+            Some("0003"),
+            None,
+            None,
+        ]);
+        assert_eq!(items[3].get_name(), "ディナー");
+    }
+}
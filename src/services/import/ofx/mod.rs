@@ -0,0 +1,185 @@
+//! OFXファイルの読み込み
+//!
+//! このモジュールは、銀行やカード会社が出力するOFX/QFXファイル（OFX 1.xのSGML形式と、OFX 2.xのXML形式）から取引を読み取る機能を提供します。
+//! 取引（`<STMTTRN>`）ごとに、取引日（`DTPOSTED`）・金額（`TRNAMT`）・摘要（`NAME`、無い場合は`MEMO`）・取引ID（`FITID`）を読み取ります。
+//!
+//! #### OFXファイルの例
+//!
+//! ```text
+//! <OFX>
+//!   <BANKMSGSRSV1><STMTTRNRS><STMTRS><BANKTRANLIST>
+//!     <STMTTRN>
+//!       <TRNTYPE>DEBIT
+//!       <DTPOSTED>20230201120000[+9:JST]
+//!       <TRNAMT>-10000
+//!       <FITID>202302010001
+//!       <NAME>ATM
+//!     </STMTTRN>
+//!   </BANKTRANLIST></STMTRS></STMTTRNRS></BANKMSGSRSV1>
+//! </OFX>
+//! ```
+
+use chrono::NaiveDate;
+
+use crate::config::Config;
+use crate::error::{KakeiboError, Result};
+use crate::models;
+
+/// OFXファイルの1件の取引を表す構造体
+///
+/// - `id`: 金融機関が付けた取引ID（`FITID`）
+/// - `date`: 取引日
+/// - `amount`: 金額（符号を除いたもの）
+/// - `is_credit`: 入金かどうか（金額がマイナスの場合は出金）
+/// - `name`: 摘要
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OfxTransaction {
+    pub id: Option<String>,
+    pub date: NaiveDate,
+    pub amount: models::Money,
+    pub is_credit: bool,
+    pub name: String,
+}
+
+impl OfxTransaction {
+    /// 取引を、カテゴリが「その他」の項目に変換する
+    ///
+    /// 入金は収入のその他、出金は支出のその他になります。取引IDは参照番号に設定します。
+    /// 後からカテゴリを付け直せるよう、項目の追加属性`needs_category`を`true`にします。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Category, ExpenseCategory, Money};
+    /// use kakeibo_app::services::import::{self, ofx::OfxTransaction};
+    /// use chrono::NaiveDate;
+    ///
+    /// let transaction = OfxTransaction {
+    ///     id: Some(String::from("0001")),
+    ///     date: NaiveDate::from_ymd_opt(2023, 2, 1).unwrap(),
+    ///     amount: Money::new(10000),
+    ///     is_credit: false,
+    ///     name: String::from("ATM"),
+    /// };
+    /// let item = transaction.to_item();
+    /// assert_eq!(item.get_item_category(), &Category::Expense(ExpenseCategory::Other));
+    /// assert_eq!(item.get_reference(), Some("0001"));
+    /// assert!(import::needs_category(&item));
+    /// ```
+    pub fn to_item(&self) -> models::Item {
+        let category = if self.is_credit {
+            models::Category::Income(models::IncomeCategory::Other)
+        } else {
+            models::Category::Expense(models::ExpenseCategory::Other)
+        };
+        let mut item = models::Item::new(self.name.clone(), category, self.amount, self.date);
+        item.set_reference(self.id.clone());
+        item.set_extra(super::NEEDS_CATEGORY_KEY.to_string(), serde_json::Value::Bool(true));
+        item
+    }
+}
+
+/// OFXファイルの内容から、すべての取引を読み取ります。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::models::Money;
+/// use kakeibo_app::services::import::ofx;
+///
+/// let text = "<STMTTRN><TRNTYPE>CREDIT<DTPOSTED>20230225<TRNAMT>250000.00<FITID>0002<NAME>給与</STMTTRN>";
+/// let transactions = ofx::parse(text, &Config::default()).unwrap();
+/// assert_eq!(transactions[0].amount, Money::new(250000));
+/// assert!(transactions[0].is_credit);
+/// ```
+///
+/// #### エラー
+///
+/// 取引が無い場合や、不正な取引がある場合は、すべての不正な取引の理由をまとめた`KakeiboError::InvalidData`を返します。
+pub fn parse(text: &str, config: &Config) -> Result<Vec<OfxTransaction>> {
+    let blocks: Vec<&str> = text.split("<STMTTRN>").skip(1).map(|block| block.split("</STMTTRN>").next().unwrap_or(block)).collect();
+    if blocks.is_empty() {
+        return Err(KakeiboError::InvalidData("OFXファイルに取引（<STMTTRN>）がありません".to_string()));
+    }
+
+    let mut transactions = Vec::new();
+    let mut errors = Vec::new();
+    for (index, block) in blocks.iter().enumerate() {
+        match parse_transaction(block, config) {
+            Ok(transaction) => transactions.push(transaction),
+            Err(error) => errors.push(format!("{}件目の取引: {}", index + 1, error)),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(KakeiboError::InvalidData(errors.join("\n")));
+    }
+    Ok(transactions)
+}
+
+/// 1件の取引（`<STMTTRN>`の中身）を読み取ります。
+fn parse_transaction(block: &str, config: &Config) -> std::result::Result<OfxTransaction, String> {
+    let date = get_element(block, "DTPOSTED").unwrap_or("");
+    let date = date.get(..8)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y%m%d").ok())
+        .ok_or_else(|| format!("取引日{}が不正です", date))?;
+
+    let amount = get_element(block, "TRNAMT").unwrap_or("");
+    let (amount, is_credit) = super::parse_signed_amount(amount, config).ok_or_else(|| format!("金額{}が不正です", amount))?;
+
+    let name = get_element(block, "NAME").or_else(|| get_element(block, "MEMO")).unwrap_or("");
+    if name.is_empty() {
+        return Err("摘要（NAME・MEMO）が空です".to_string());
+    }
+    let id = get_element(block, "FITID").filter(|id| !id.is_empty()).map(str::to_string);
+
+    Ok(OfxTransaction { id, date, amount, is_credit, name: unescape(name) })
+}
+
+/// 要素`<tag>`の値を取得します。
+///
+/// SGML形式のように終了タグが無い場合も読めるよう、次のタグの手前までを値とします。
+fn get_element<'a>(block: &'a str, tag: &str) -> Option<&'a str> {
+    let start = block.find(&format!("<{}>", tag))? + tag.len() + 2;
+    let value = &block[start..];
+    Some(value[..value.find('<').unwrap_or(value.len())].trim())
+}
+
+/// XMLの文字参照を元の文字に戻します。
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<").replace("&gt;", ">").replace("&quot;", "\"").replace("&apos;", "'").replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod ofx_test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let text = "OFXHEADER:100\nDATA:OFXSGML\n\n<OFX><BANKTRANLIST>\n\
+            <STMTTRN>\n<TRNTYPE>DEBIT\n<DTPOSTED>20230201120000[+9:JST]\n<TRNAMT>-10000\n<FITID>0001\n<MEMO>引出し\n</STMTTRN>\n\
+            <STMTTRN><TRNTYPE>POS</TRNTYPE><DTPOSTED>20230203</DTPOSTED><TRNAMT>-1280.00</TRNAMT><NAME>A&amp;B</NAME></STMTTRN>\n\
+            </BANKTRANLIST></OFX>";
+        let transactions = parse(text, &Config::default()).unwrap();
+        assert_eq!(transactions, vec![
+            OfxTransaction {
+                id: Some("0001".to_string()),
+                date: NaiveDate::from_ymd_opt(2023, 2, 1).unwrap(),
+                amount: models::Money::new(10000),
+                is_credit: false,
+                name: "引出し".to_string(),
+            },
+            OfxTransaction {
+                id: None,
+                date: NaiveDate::from_ymd_opt(2023, 2, 3).unwrap(),
+                amount: models::Money::new(1280),
+                is_credit: false,
+                name: "A&B".to_string(),
+            },
+        ]);
+
+        let error = parse("<STMTTRN><DTPOSTED>2023<TRNAMT>1.5<NAME>x</STMTTRN>", &Config::default()).unwrap_err();
+        assert_eq!(error.to_string(), "データの形式が不正です\n1件目の取引: 取引日2023が不正です");
+        assert!(parse("<OFX></OFX>", &Config::default()).is_err());
+    }
+}
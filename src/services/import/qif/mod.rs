@@ -0,0 +1,353 @@
+//! QIFファイルの読み込み
+//!
+//! このモジュールは、家計簿ソフトが出力するQIFファイルから取引を読み取り、QIFのカテゴリを家計簿のカテゴリに読み替える機能を提供します。
+//! 銀行・現金・クレジットカードなどの口座の取引（`!Type:Bank`・`!Type:Cash`・`!Type:CCard`など）だけを読み取り、投資やカテゴリの一覧などは読み飛ばします。
+//! 取引ごとに、日付（`D`）・金額（`T`）・支払先（`P`）・メモ（`M`）・カテゴリ（`L`）・番号（`N`）を読み取ります。
+//!
+//! QIFのカテゴリは、読み替え表（JSONファイル`store/qif_categories.json`）で家計簿のカテゴリに読み替えます。
+//! `食料品:外食`のようなサブカテゴリ付きのカテゴリは、`食料品:外食`・`食料品`の順に読み替え表を探します。
+//! 読み替え表に無いカテゴリの取引は、カテゴリ「その他」で取り込み、カテゴリを付け直す対象にします。
+//!
+//! #### QIFファイルの例
+//!
+//! ```text
+//! !Type:Bank
+//! D01/25/2023
+//! T300,000.00
+//! P給与
+//! LSalary
+//! ^
+//! D01/27'23
+//! T-1,280.00
+//! Pスーパー
+//! LGroceries:Food
+//! ^
+//! ```
+//!
+//! #### 読み替え表の例
+//!
+//! ```json
+//! { "Salary": "給与", "Groceries": "食費", "Hobbies": "支出:趣味" }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::BufReader;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::error::{KakeiboError, Result};
+use crate::models;
+
+/// 取引として読み取るQIFの口座の種類
+const ACCOUNT_TYPES: [&str; 5] = ["bank", "cash", "ccard", "oth a", "oth l"];
+
+/// QIFファイルの1件の取引を表す構造体
+///
+/// - `date`: 取引日
+/// - `amount`: 金額（符号を除いたもの）
+/// - `is_credit`: 入金かどうか（金額がマイナスの場合は出金）
+/// - `payee`: 支払先（無い場合は`None`）
+/// - `memo`: メモ（無い場合は`None`）
+/// - `category`: QIFのカテゴリ（無い場合や口座間の振替の場合は`None`）
+/// - `number`: 小切手番号などの番号（無い場合は`None`）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QifTransaction {
+    pub date: NaiveDate,
+    pub amount: models::Money,
+    pub is_credit: bool,
+    pub payee: Option<String>,
+    pub memo: Option<String>,
+    pub category: Option<String>,
+    pub number: Option<String>,
+}
+
+impl QifTransaction {
+    /// 取引を、読み替え表で読み替えたカテゴリの項目に変換する
+    ///
+    /// 品目名は支払先（無い場合はメモ）、番号は参照番号になります。支払先は項目の支払先にも設定します。
+    /// 読み替えられないカテゴリの場合は、入金は収入のその他、出金は支出のその他にし、追加属性`needs_category`を`true`にします。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Category, ExpenseCategory, Money};
+    /// use kakeibo_app::services::import::{self, qif::{QifCategoryMap, QifTransaction}};
+    /// use chrono::NaiveDate;
+    ///
+    /// let mut categories = QifCategoryMap::default();
+    /// categories.set_category(String::from("Groceries"), Some(String::from("食費")));
+    /// let transaction = QifTransaction {
+    ///     date: NaiveDate::from_ymd_opt(2023, 1, 27).unwrap(),
+    ///     amount: Money::new(1280),
+    ///     is_credit: false,
+    ///     payee: Some(String::from("スーパー")),
+    ///     memo: None,
+    ///     category: Some(String::from("Groceries:Food")),
+    ///     number: None,
+    /// };
+    /// let item = transaction.to_item(&categories.resolve().unwrap());
+    /// assert_eq!(item.get_item_category(), &Category::Expense(ExpenseCategory::Food));
+    /// assert!(!import::needs_category(&item));
+    /// ```
+    pub fn to_item(&self, categories: &BTreeMap<String, models::Category>) -> models::Item {
+        let name = self.payee.as_ref().or(self.memo.as_ref()).cloned().unwrap_or_default();
+        let category = self.category.as_deref().and_then(|category| find_category(category, categories));
+        let mut item = match category {
+            Some(category) => models::Item::new(name, category.clone(), self.amount, self.date),
+            None => {
+                let category = if self.is_credit {
+                    models::Category::Income(models::IncomeCategory::Other)
+                } else {
+                    models::Category::Expense(models::ExpenseCategory::Other)
+                };
+                let mut item = models::Item::new(name, category, self.amount, self.date);
+                item.set_extra(super::NEEDS_CATEGORY_KEY.to_string(), serde_json::Value::Bool(true));
+                item
+            },
+        };
+        item.set_payee(self.payee.clone());
+        item.set_reference(self.number.clone());
+        item
+    }
+}
+
+/// QIFのカテゴリを、読み替え表から探します。
+///
+/// `親:子`の形式のカテゴリは、`親:子`・`親`の順に探します。
+fn find_category<'a>(category: &str, categories: &'a BTreeMap<String, models::Category>) -> Option<&'a models::Category> {
+    categories.get(category).or_else(|| {
+        let (parent, _) = category.split_once(':')?;
+        categories.get(parent)
+    })
+}
+
+/// QIFのカテゴリから家計簿のカテゴリへの読み替え表を表す構造体
+///
+/// 家計簿のカテゴリは、`食費`・`収入:その他`のように、取り込みのCSVと同じ形式で書きます。
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct QifCategoryMap {
+    categories: BTreeMap<String, String>,
+}
+
+impl QifCategoryMap {
+    /// JSONファイルから読み替え表を読み込む
+    ///
+    /// ファイルが存在しない場合は、空の読み替え表を返します（すべての取引がカテゴリ「その他」になります）。
+    ///
+    /// #### エラー
+    ///
+    /// ファイルの内容が不正な場合は、`KakeiboError::InvalidData`を返します。
+    pub fn load_or_default(file_path: &str) -> Result<Self> {
+        match File::open(file_path) {
+            Ok(f) => {
+                let buf_reader = BufReader::new(f);
+                serde_json::from_reader(buf_reader)
+                    .map_err(|error| KakeiboError::InvalidData(format!("{}の読み込みに失敗しました: {}", file_path, error)))
+            },
+            Err(_) => Ok(QifCategoryMap::default()),
+        }
+    }
+
+    /// QIFのカテゴリの読み替え先を設定する
+    ///
+    /// `None`を指定した場合は、そのカテゴリの読み替えを削除します。
+    pub fn set_category(&mut self, qif_category: String, category: Option<String>) {
+        match category {
+            Some(category) => self.categories.insert(qif_category, category),
+            None => self.categories.remove(&qif_category),
+        };
+    }
+
+    /// 読み替え表の家計簿のカテゴリを読み取り、QIFのカテゴリをキーとする表を作成する
+    ///
+    /// #### エラー
+    ///
+    /// 読み取れないカテゴリがある場合は、すべての読み取れないカテゴリをまとめた`KakeiboError::InvalidData`を返します。
+    pub fn resolve(&self) -> Result<BTreeMap<String, models::Category>> {
+        let mut categories = BTreeMap::new();
+        let mut errors = Vec::new();
+        for (qif_category, category) in &self.categories {
+            match super::parse_category(category.trim()) {
+                Some(resolved) => {
+                    categories.insert(qif_category.clone(), resolved);
+                },
+                None => errors.push(format!("{}: カテゴリ{}が不正です", qif_category, category)),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(KakeiboError::InvalidData(errors.join("\n")));
+        }
+        Ok(categories)
+    }
+}
+
+/// QIFファイルの内容から、口座の取引をすべて読み取ります。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::models::Money;
+/// use kakeibo_app::services::import::qif;
+///
+/// let text = "!Type:Bank\nD01/25/2023\nT300,000.00\nP給与\nLSalary\n^\n";
+/// let transactions = qif::parse(text, &Config::default()).unwrap();
+/// assert_eq!(transactions[0].amount, Money::new(300000));
+/// assert_eq!(transactions[0].category.as_deref(), Some("Salary"));
+/// ```
+///
+/// #### エラー
+///
+/// 取引が無い場合や、不正な取引がある場合は、すべての不正な取引の理由をまとめた`KakeiboError::InvalidData`を返します。
+pub fn parse(text: &str, config: &Config) -> Result<Vec<QifTransaction>> {
+    let mut transactions = Vec::new();
+    let mut errors = Vec::new();
+    let mut is_account = false;
+    let mut fields: Vec<&str> = Vec::new();
+    for line in text.lines().map(|line| line.trim_start_matches('\u{feff}').trim_end()) {
+        if let Some(header) = line.strip_prefix('!') {
+            let header = header.to_lowercase();
+            if let Some(account_type) = header.strip_prefix("type:") {
+                is_account = ACCOUNT_TYPES.contains(&account_type.trim());
+            } else if header != "option:autoswitch" && header != "clear:autoswitch" {
+                is_account = false;
+            }
+            fields.clear();
+        } else if line == "^" {
+            if is_account && !fields.is_empty() {
+                match parse_transaction(&fields, config) {
+                    Ok(transaction) => transactions.push(transaction),
+                    Err(error) => errors.push(format!("{}件目の取引: {}", transactions.len() + errors.len() + 1, error)),
+                }
+            }
+            fields.clear();
+        } else if !line.is_empty() {
+            fields.push(line);
+        }
+    }
+    if !errors.is_empty() {
+        return Err(KakeiboError::InvalidData(errors.join("\n")));
+    }
+    if transactions.is_empty() {
+        return Err(KakeiboError::InvalidData("QIFファイルに口座の取引がありません".to_string()));
+    }
+    Ok(transactions)
+}
+
+/// 1件の取引の各行（先頭の1文字が項目の種類）を読み取ります。
+///
+/// 分割（`S`・`E`・`$`）の行は読み飛ばし、取引全体を1つの項目として読み取ります。
+fn parse_transaction(fields: &[&str], config: &Config) -> std::result::Result<QifTransaction, String> {
+    let get_field = |code: char| {
+        fields.iter()
+            .find(|field| field.starts_with(code))
+            .map(|field| field[code.len_utf8()..].trim())
+            .filter(|value| !value.is_empty())
+    };
+
+    let date = get_field('D').unwrap_or("");
+    let date = parse_date(date).ok_or_else(|| format!("日付{}が不正です", date))?;
+    let amount = get_field('T').or_else(|| get_field('U')).unwrap_or("");
+    let (amount, is_credit) = super::parse_signed_amount(amount, config).ok_or_else(|| format!("金額{}が不正です", amount))?;
+    let payee = get_field('P').map(str::to_string);
+    let memo = get_field('M').map(str::to_string);
+    if payee.is_none() && memo.is_none() {
+        return Err("支払先・メモが空です".to_string());
+    }
+    // `[口座名]`は口座間の振替を表すため、カテゴリとみなさない
+    let category = get_field('L').filter(|category| !category.starts_with('[')).map(str::to_string);
+
+    Ok(QifTransaction { date, amount, is_credit, payee, memo, category, number: get_field('N').map(str::to_string) })
+}
+
+/// QIFの日付（`01/25/2023`・`1/25'23`・`2023-01-25`など）を読み取ります。
+///
+/// 年が2桁の場合は2000年代とみなします。
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    let text = text.replace('\'', "/").replace(' ', "");
+    if let [month, day, year] = text.split('/').collect::<Vec<_>>()[..] {
+        if month.len() <= 2 && year.len() <= 2 {
+            let year: i32 = year.parse().ok()?;
+            return NaiveDate::parse_from_str(&format!("{}/{}/{}", month, day, 2000 + year), "%m/%d/%Y").ok();
+        }
+    }
+    ["%m/%d/%Y", "%Y-%m-%d", "%Y/%m/%d"].iter().find_map(|format| NaiveDate::parse_from_str(&text, format).ok())
+}
+
+/// 読み替え表に無いQIFのカテゴリを、重複なく取得します。
+pub fn get_unmapped_categories<'a>(transactions: &'a [QifTransaction], categories: &BTreeMap<String, models::Category>) -> Vec<&'a str> {
+    let mut unmapped: Vec<&str> = transactions.iter()
+        .filter_map(|transaction| transaction.category.as_deref())
+        .filter(|category| find_category(category, categories).is_none())
+        .collect();
+    unmapped.sort();
+    unmapped.dedup();
+    unmapped
+}
+
+#[cfg(test)]
+mod qif_test {
+    use super::*;
+    use crate::services;
+
+    #[test]
+    fn test_parse() {
+        let text = "!Account\nNお財布\nTCash\n^\n!Type:Cash\n\
+            D1/27'23\nT-1,280.00\nMスーパー\nLGroceries:Food\n^\n\
+            D2023-01-28\nT-500\nPATM\nL[普通預金]\nN101\n^\n\
+            !Type:Cat\nNGroceries\nE\n^\n";
+        let transactions = parse(text, &Config::default()).unwrap();
+        assert_eq!(transactions, vec![
+            QifTransaction {
+                date: NaiveDate::from_ymd_opt(2023, 1, 27).unwrap(),
+                amount: models::Money::new(1280),
+                is_credit: false,
+                payee: None,
+                memo: Some("スーパー".to_string()),
+                category: Some("Groceries:Food".to_string()),
+                number: None,
+            },
+            QifTransaction {
+                date: NaiveDate::from_ymd_opt(2023, 1, 28).unwrap(),
+                amount: models::Money::new(500),
+                is_credit: false,
+                payee: Some("ATM".to_string()),
+                memo: None,
+                category: None,
+                number: Some("101".to_string()),
+            },
+        ]);
+
+        assert_eq!(parse_date("2023/01/25"), NaiveDate::from_ymd_opt(2023, 1, 25));
+        let error = parse("!Type:Bank\nD13/01/2023\nT1\nPx\n^\n", &Config::default()).unwrap_err();
+        assert_eq!(error.to_string(), "データの形式が不正です\n1件目の取引: 日付13/01/2023が不正です");
+    }
+
+    #[test]
+    fn test_resolve() {
+        let mut categories = QifCategoryMap::default();
+        categories.set_category("Salary".to_string(), Some("給与".to_string()));
+        categories.set_category("Rent".to_string(), Some("家賃".to_string()));
+        assert_eq!(categories.resolve().unwrap_err().to_string(), "データの形式が不正です\nRent: カテゴリ家賃が不正です");
+
+        categories.set_category("Rent".to_string(), None);
+        let resolved = categories.resolve().unwrap();
+        let transaction = QifTransaction {
+            date: NaiveDate::from_ymd_opt(2023, 1, 25).unwrap(),
+            amount: models::Money::new(300000),
+            is_credit: true,
+            payee: None,
+            memo: None,
+            category: Some("Bonus".to_string()),
+            number: None,
+        };
+        assert_eq!(get_unmapped_categories(std::slice::from_ref(&transaction), &resolved), vec!["Bonus"]);
+        let item = transaction.to_item(&resolved);
+        assert_eq!(item.get_item_category(), &models::Category::Income(models::IncomeCategory::Other));
+        assert!(services::import::needs_category(&item));
+    }
+}
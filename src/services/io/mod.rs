@@ -1,12 +1,28 @@
 //! データ入出力サービス
 //!
 //! このモジュールは、JOSNファイル`store/data.json`へのデータ入出力処理の機能を提供します。
+//! 読み込み時には、データファイルのJSONスキーマで内容を検証します。
+//! ファイルはメモリマップで読み込み、期間を指定した読み込みでは期間内の項目だけをパースします。
+//! カテゴリが旧形式や別名で書かれている場合は、検証の前に現在の形式に読み替えます（次に書き込んだときに現在の形式で保存されます）。
+//! 複数のファイルをまとめて書き換える場合は、`Transaction`で書き込みを1つにまとめ、途中で失敗したときはすべて元に戻します。
+//! データファイルのパスを`sqlite://store/kakeibo.db`のように書くと、JSONファイルの代わりにSQLiteのデータベースを読み書きします（`sqlite`モジュール）。
+//! 読み書きは保存先を表す`DataStore`トレイト（`store`モジュール）を通して行い、このモジュールの関数はパスから保存先を作成して呼び出します。
 
-use std::fs::File;
-use std::io::BufReader;
+use std::fs::{self, File};
 use std::io::prelude::*;
 
+use chrono::NaiveDate;
+use memmap2::Mmap;
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+use serde_json::Value;
+
+use crate::error::{KakeiboError, Result};
 use crate::models;
+use crate::services::timing;
+
+pub mod sqlite;
+pub mod store;
 
 /// JSONファイルからデータを読み込むか、新しいデータを作成します。
 /// 
@@ -17,54 +33,415 @@ use crate::models;
 /// ```rust
 /// use kakeibo_app::services::io;
 /// let file_path = "store/data.json";
-/// let data = io::read_data_or_create_new_data(file_path);
-/// ```
-pub fn read_data_or_create_new_data(file_path: &str) -> Vec<models::Item> {
-    let file = File::open(file_path);
-    match file {
-        Ok(f) => {
-            let buf_reader = BufReader::new(f);
-            serde_json::from_reader(buf_reader).expect("デシリアライズに失敗しました")
-        },
-        Err(_) => {
-            println!("新規ファイルを作成します");
-            Vec::new()
-        }
-    }
+/// let data = io::read_data_or_create_new_data(file_path).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// ファイルを読み込めない場合や、データの形式が不正な場合はエラーを返します。
+pub fn read_data_or_create_new_data(file_path: &str) -> Result<Vec<models::Item>> {
+    store::load_or_create(store::open_store(file_path).as_ref())
 }
 
-/// JSONファイルからデータを読み込みます。データが存在しない場合はパニックになります。
+/// JSONファイルからデータを読み込みます。
 /// 
-/// 指定されたファイルパスからデータを読み込みます。ファイルが存在しないか、データが空の場合はパニックになります。
+/// 指定されたファイルパスからデータを読み込みます。
 /// 
 /// #### 例
 /// 
-/// ```rust
+/// ```rust,no_run
 /// use kakeibo_app::services::io;
 /// let file_path = "store/data.json";
-/// let data = io::read_data_or_panic(file_path);
+/// let data = io::read_data(file_path).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// ファイルが存在しない場合、データの形式が不正な場合、データが空の場合はエラーを返します。
+pub fn read_data(file_path: &str) -> Result<Vec<models::Item>> {
+    read_data_from_files(&[file_path])
+}
+
+/// 複数のJSONファイルからデータを読み込み、1つにまとめます。
+/// 
+/// 指定されたファイルパスから順にデータを読み込み、連結して返します。
+/// 
+/// #### 例
+/// 
+/// ```rust,no_run
+/// use kakeibo_app::services::io;
+/// let data = io::read_data_from_files(&["store/2022.json", "store/2023.json"]).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// いずれかのファイルが存在しないか形式が不正な場合、またはすべてのファイルのデータが空の場合はエラーを返します。
+pub fn read_data_from_files(file_paths: &[&str]) -> Result<Vec<models::Item>> {
+    store::load_all(&store::open_stores(file_paths))
+}
+
+/// 複数のJSONファイルから、指定された期間内のデータだけを読み込みます。
+/// 
+/// ファイルをメモリマップで読み込み、各項目の日付だけを先に読み取って、期間内（両端を含む）の項目だけを
+/// パースします。巨大なファイルから一部の期間だけを参照する場合に、全件を読み込むより高速です。
+/// なお、ファイル全体のスキーマ検証は行わず、期間内の項目のデシリアライズ時にのみ不正な箇所を検出します。
+/// また、IDが採番されていない項目のIDは`0`のままです。
+/// 
+/// #### 例
+/// 
+/// ```rust,no_run
+/// use kakeibo_app::services::io;
+/// use chrono::NaiveDate;
+/// let data = io::read_data_in_range(
+///     &["store/data.json"],
+///     NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+///     NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
+/// ).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// いずれかのファイルが存在しないか形式が不正な場合、または期間内のデータが無い場合はエラーを返します。
+pub fn read_data_in_range(file_paths: &[&str], first_date: NaiveDate, last_date: NaiveDate) -> Result<Vec<models::Item>> {
+    let mut data = Vec::new();
+    for data_store in store::open_stores(file_paths) {
+        data.extend(data_store.load_in_range(first_date, last_date)?);
+    }
+
+    if data.is_empty() {
+        return Err(KakeiboError::NoData);
+    }
+
+    Ok(data)
+}
+
+/// 複数のJSONファイルから、指定された期間内のデータだけを遅延読み込みします。
+///
+/// 集計や一覧表示で対象の期間が決まっている場合に使う読み込み層です。以下の手順で、期間外のデータをできるだけ読まずに済ませます。
+/// 1. `store/2023.json`のように年をファイル名とする年別ファイルは、期間と重ならない年のファイルを開かずに読み飛ばす。
+/// 2. 残りのファイルから、`read_data_in_range`で期間内の項目だけをパースする。
+///
+/// 何年分ものデータがあっても、当月分の表示などは期間内の項目だけのパースで済みます。
+/// `read_data_in_range`と同じく、IDが採番されていない項目のIDは`0`のままです。IDを使う場合は、呼び出し元で全件の読み込みに切り替えてください。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::services::io;
+/// use chrono::NaiveDate;
+/// let data = io::read_data_in_period(
+///     &["store/2022.json", "store/2023.json"],
+///     NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+///     NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
+/// ).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// 対象のファイルが存在しないか形式が不正な場合、または期間内のデータが無い場合はエラーを返します。
+pub fn read_data_in_period(file_paths: &[&str], first_date: NaiveDate, last_date: NaiveDate) -> Result<Vec<models::Item>> {
+    store::load_in_period(&store::open_stores(file_paths), first_date, last_date)
+}
+
+/// 複数のデータファイルから、指定されたカテゴリの項目だけを読み込みます。
+///
+/// SQLiteのデータベースでは、カテゴリの索引を使って該当する項目だけを読み込みます（IDが採番されていない項目のIDは`0`のままです）。
+/// JSONファイルでは、すべての項目を読み込んでから絞り込みます。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::models::{Category, ExpenseCategory};
+/// use kakeibo_app::services::io;
+/// let data = io::read_data_by_category(&["sqlite://store/kakeibo.db"], &Category::Expense(ExpenseCategory::Food)).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// いずれかのファイルが存在しないか形式が不正な場合、またはカテゴリの項目が無い場合はエラーを返します。
+pub fn read_data_by_category(file_paths: &[&str], category: &models::Category) -> Result<Vec<models::Item>> {
+    let mut data = Vec::new();
+    for data_store in store::open_stores(file_paths) {
+        data.extend(data_store.load_by_category(category)?);
+    }
+
+    if data.is_empty() {
+        return Err(KakeiboError::NoData);
+    }
+
+    Ok(data)
+}
+
+/// データファイル（SQLiteの場合はデータベースファイル）が存在するかどうかを返します。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::services::io;
+/// assert!(!io::data_exists("sqlite://store/not_found.db"));
+/// ```
+pub fn data_exists(file_path: &str) -> bool {
+    store::open_store(file_path).exists()
+}
+
+/// 年別ファイル（`2023.json`のように4桁の年をファイル名とするファイル）の年を取得します。
+///
+/// 年別ファイルでない場合は`None`を返します。
+fn get_file_year(file_path: &str) -> Option<i32> {
+    let stem = std::path::Path::new(file_path).file_stem()?.to_str()?;
+    if stem.len() != 4 || !stem.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    stem.parse().ok()
+}
+
+/// ファイルをメモリマップで読み込みます。
+fn map_file(file: &File, file_path: &str) -> Result<Mmap> {
+    // SAFETY: マップ中に他のプロセスがファイルを書き換えると内容が変わりうるが、
+    // 読み込みはこの関数の呼び出し元で完結し、書き込みは`write_to_json`で別途ファイルを作り直すため問題ない
+    unsafe { Mmap::map(file) }.map_err(|error| KakeiboError::io(file_path, error))
+}
+
+/// JSONデータを読み込み、スキーマで検証してから項目の一覧に変換し、IDが採番されていない項目に採番します。
+fn parse_data(bytes: &[u8]) -> Result<Vec<models::Item>> {
+    let mut data = parse_items(bytes)?;
+    assign_ids(&mut data);
+    Ok(data)
+}
+
+/// JSONデータを読み込み、スキーマで検証してから項目の一覧に変換します。IDは採番しません。
+///
+/// #### エラー
+///
+/// JSONとして解析できない場合は行・列の位置を、スキーマ検証やデシリアライズに失敗した場合は
+/// 何件目のどのフィールドが不正かを表すメッセージを持つエラーを返します。
+fn parse_items(bytes: &[u8]) -> Result<Vec<models::Item>> {
+    let mut json: Value = timing::measure("JSON解析", || serde_json::from_slice(bytes)).map_err(|error| {
+        KakeiboError::InvalidData(format!("JSONの解析に失敗しました ({}行{}列目): {}", error.line(), error.column(), error))
+    })?;
+    if let Value::Array(records) = &mut json {
+        records.iter_mut().for_each(upgrade_category);
+    }
+    let errors = timing::measure("スキーマ検証", || validate_json(&json));
+    if !errors.is_empty() {
+        return Err(KakeiboError::InvalidData(errors.join("\n")));
+    }
+
+    timing::measure("デシリアライズ", || deserialize_items(json)).map_err(KakeiboError::InvalidData)
+}
+
+/// IDが採番されていない項目に、データ内の最大のIDの次の値から順にIDを採番します。
+///
+/// IDが導入される前のデータファイルは、読み込み時にデータファイルでの並び順でIDを採番し、次に書き込んだときに保存されます。
+/// 項目を追加したときは、書き込む前にこの関数で新しい項目に採番してください。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+/// use kakeibo_app::services::io;
+/// use chrono::NaiveDate;
+///
+/// let new_item = || Item::new(
+///     String::from("ランチ"),
+///     Category::Expense(ExpenseCategory::Food),
+///     Money::new(1000),
+///     NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+/// );
+/// let mut data = vec![new_item(), new_item()];
+/// data[0].set_id(5);
+/// io::assign_ids(&mut data);
+/// assert_eq!(data[1].get_id(), 6);
 /// ```
-pub fn read_data_or_panic(file_path: &str) -> Vec<models::Item> {
-    let file = File::open(file_path).expect("ファイルをオープンできませんでした");
-    let buf_reader = BufReader::new(file);
-    let data: Vec<_> = serde_json::from_reader(buf_reader).expect("デシリアライズに失敗しました");
+pub fn assign_ids(data: &mut [models::Item]) {
+    let first_id = data.iter().map(|item| item.get_id()).max().unwrap_or(0) + 1;
+    let unassigned = data.iter_mut().filter(|item| item.get_id() == 0);
+    for (id, item) in (first_id..).zip(unassigned) {
+        item.set_id(id);
+    }
+}
+
+/// JSONデータを1件ずつデシリアライズして項目の一覧に変換します。
+///
+/// 失敗した場合は、"128件目のdateが不正です: '2023-13-01'" のように壊れたレコードを特定できるメッセージを返します。
+fn deserialize_items(json: Value) -> std::result::Result<Vec<models::Item>, String> {
+    let Value::Array(records) = json else {
+        return Err(String::from("データ全体がJSONの配列ではありません"));
+    };
+
+    records.into_iter().enumerate().map(|(index, record)| deserialize_record(index, record)).collect()
+}
+
+/// 日付だけを読み取るための構造体
+#[derive(Deserialize)]
+struct ItemDate {
+    date: NaiveDate,
+}
+
+/// JSONデータから期間内（両端を含む）の項目だけをデシリアライズして項目の一覧に変換します。
+///
+/// 各項目は日付だけを先に読み取り、期間外の項目は残りのフィールドをパースしません。
+fn parse_data_in_range(bytes: &[u8], first_date: NaiveDate, last_date: NaiveDate) -> std::result::Result<Vec<models::Item>, String> {
+    let records: Vec<&RawValue> = serde_json::from_slice(bytes).map_err(|error| {
+        format!("JSONの解析に失敗しました ({}行{}列目): {}", error.line(), error.column(), error)
+    })?;
+
+    records.into_iter().enumerate().filter_map(|(index, record)| {
+        let date = match serde_json::from_str::<ItemDate>(record.get()) {
+            Ok(item_date) => item_date.date,
+            Err(_) => return Some(deserialize_record(index, serde_json::from_str(record.get()).unwrap_or(Value::Null))),
+        };
+        if date < first_date || last_date < date {
+            return None;
+        }
+
+        let mut record = serde_json::from_str(record.get()).expect("JSONの解析に失敗しました");
+        upgrade_category(&mut record);
+        Some(deserialize_record(index, record))
+    }).collect()
+}
 
-    if data.len() == 0 {
-        panic!("データが存在しません");
+/// JSONデータの1件のカテゴリが旧形式や別名で書かれている場合に、現在の形式に読み替えます。
+///
+/// 以下の表記を読み替えます。読み替えられない場合は、そのままにします（スキーマ検証でエラーになります）。
+/// - `{"expense": "food"}`や`{"支出": "食費"}`のような、区分と名前の組
+/// - `"支出:食費"`や`"Expense/Food"`のような、区分と名前を区切った文字列
+/// - `"食費"`のような、名前だけで一意に決まる文字列
+fn upgrade_category(record: &mut Value) {
+    let Some(category) = record.get_mut("category") else {
+        return;
+    };
+    if serde_json::from_value::<models::Category>(category.clone()).is_ok() {
+        return;
     }
 
-    data
+    let upgraded = match &*category {
+        Value::String(text) => match text.split_once([':', '/']) {
+            Some((kind, name)) => models::Category::from_alias(Some(kind), name),
+            None => models::Category::from_alias(None, text),
+        },
+        Value::Object(map) if map.len() == 1 => map.iter()
+            .next()
+            .and_then(|(kind, name)| models::Category::from_alias(Some(kind), name.as_str()?)),
+        _ => None,
+    };
+    if let Some(upgraded) = upgraded {
+        *category = serde_json::to_value(upgraded).expect("カテゴリのシリアライズに失敗しました");
+    }
+}
+
+/// JSONデータの1件をデシリアライズして項目に変換します。
+///
+/// 失敗した場合は、"128件目のdateが不正です: '2023-13-01'" のように壊れたレコードを特定できるメッセージを返します。
+fn deserialize_record(index: usize, record: Value) -> std::result::Result<models::Item, String> {
+    let original = record.clone();
+    serde_path_to_error::deserialize(record).map_err(|error| {
+        let path = error.path().to_string();
+        let pointer = format!("/{}", path.replace('.', "/"));
+        match original.pointer(&pointer) {
+            Some(value) if path != "." => {
+                format!("{}件目の{}が不正です: {}", index + 1, path, format_value(value))
+            },
+            _ => format!("{}件目の項目が不正です: {}", index + 1, error.inner()),
+        }
+    })
+}
+
+/// エラーメッセージ用にJSONの値をフォーマットします。
+///
+/// 文字列はシングルクォートで囲み、それ以外はJSONの表記のまま返します。
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::String(value) => format!("'{}'", value),
+        _ => value.to_string(),
+    }
+}
+
+/// データファイルのJSONスキーマを生成します。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::services::io;
+/// let schema = io::generate_json_schema();
+/// assert_eq!(schema["type"], "array");
+/// ```
+pub fn generate_json_schema() -> Value {
+    serde_json::to_value(schemars::schema_for!(Vec<models::Item>)).expect("JSONスキーマの生成に失敗しました")
+}
+
+/// JSONデータをデータファイルのスキーマで検証します。
+///
+/// 不正な箇所ごとに "何件目のどのフィールドが不正か" を表すメッセージを返します。問題が無い場合は空のベクトルを返します。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::services::io;
+/// let json = serde_json::json!([{
+///     "name": "給与",
+///     "category": {"Income": "Salary"},
+///     "price": 100000,
+///     "date": "2023-13-01"
+/// }]);
+/// assert_eq!(io::validate_json(&json), vec!["1件目のdateが不正です: '2023-13-01'"]);
+/// ```
+pub fn validate_json(json: &Value) -> Vec<String> {
+    let validator = jsonschema::options()
+        .should_validate_formats(true)
+        .build(&generate_json_schema())
+        .expect("JSONスキーマの読み込みに失敗しました");
+
+    validator.iter_errors(json).map(|error| {
+        format!("{}が不正です: {}", format_instance_path(error.instance_path().as_str()), format_value(error.instance()))
+    }).collect()
+}
+
+/// JSONポインタを "何件目のどのフィールド" の形式でフォーマットします。
+///
+/// `/127/date`は`128件目のdate`に、空のポインタは`データ全体`になります。
+fn format_instance_path(instance_path: &str) -> String {
+    let mut segments = instance_path.split('/').skip(1);
+    let Some(index) = segments.next().and_then(|index| index.parse::<usize>().ok()) else {
+        return String::from("データ全体");
+    };
+
+    let field: Vec<_> = segments.collect();
+    if field.is_empty() {
+        format!("{}件目の項目", index + 1)
+    } else {
+        format!("{}件目の{}", index + 1, field.join("."))
+    }
+}
+
+/// JSONスキーマをファイルに書き込みます。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::services::io;
+/// io::write_json_schema("store/data.schema.json").unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// ファイルへの書き込みに失敗した場合はエラーを返します。
+pub fn write_json_schema(file_path: &str) -> Result<()> {
+    let json_schema = serde_json::to_string_pretty(&generate_json_schema()).expect("JSONへのシリアライズに失敗しました");
+    write_file(file_path, &json_schema)
 }
 
 /// データをJSONファイルに書き込みます。
 /// 
 /// 指定されたデータをJSON形式にシリアライズし、指定されたファイルパスに書き込みます。
+/// 保存先のディレクトリ（`store`など）が存在しない場合は、自動で作成します。
+/// パスが`sqlite://`で始まる場合は、SQLiteのデータベースの内容をデータで置き換えます。
 /// 
 /// #### 例
 /// 
-/// ```rust
+/// ```rust,no_run
 /// use kakeibo_app::services::io;
-/// use kakeibo_app::models::{Item, Category, IncomeCategory, ExpenseCategory};
+/// use kakeibo_app::models::{Item, Category, IncomeCategory, ExpenseCategory, Money};
 /// use chrono::{NaiveDate, Datelike};
 /// 
 /// let file_path = "store/data.json";
@@ -72,21 +449,528 @@ pub fn read_data_or_panic(file_path: &str) -> Vec<models::Item> {
 ///     Item::new(
 ///         String::from("給与"),
 ///         Category::Income(IncomeCategory::Salary),
-///         100000,
+///         Money::new(100000),
 ///         NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
 ///     ),
 ///     Item::new(
 ///         String::from("食費"),
 ///         Category::Expense(ExpenseCategory::Food),
-///         2000,
+///         Money::new(2000),
 ///         NaiveDate::from_ymd_opt(2023, 2, 1).unwrap(),
 ///     ),
 /// ];
-/// io::write_to_json(&data, file_path);
-/// ```
-pub fn write_to_json(data: &Vec<models::Item>, file_path: &str) {
-    let json_data = serde_json::to_string_pretty(data).expect("JSONへのシリアライズに失敗しました");
-    let mut file = File::create(file_path).expect("書き込みファイルのオープンに失敗しました");
-    writeln!(file, "{}", json_data).expect("ファイルへの書き込みに失敗しました");
-    println!("項目の登録が完了しました");
-}
\ No newline at end of file
+/// io::write_to_json(&data, file_path).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// ディレクトリの作成やファイルへの書き込みに失敗した場合は、`KakeiboError::Io`を返します。
+/// 書き込む権限が無い場合は、権限が無いことを表すメッセージになります。
+pub fn write_to_json(data: &[models::Item], file_path: &str) -> Result<()> {
+    store::open_store(file_path).save(data)
+}
+
+/// 行の一覧をCSVファイルに書き込みます。
+///
+/// カンマ・ダブルクォート・改行を含むフィールドは、ダブルクォートで囲んで書き込みます。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::services::io;
+///
+/// let rows = vec![
+///     vec![String::from("日付"), String::from("金額")],
+///     vec![String::from("2023-01-01"), String::from("1000")],
+/// ];
+/// io::write_csv(&rows, "kakeibo.csv").unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// ファイルへの書き込みに失敗した場合はエラーを返します。
+pub fn write_csv(rows: &[Vec<String>], file_path: &str) -> Result<()> {
+    write_file(file_path, &format_delimited(rows, ','))
+}
+
+/// 行の一覧を、区切り文字で区切ったテキスト（CSV・TSV）に変換します。
+///
+/// 区切り文字・ダブルクォート・改行を含むフィールドは、ダブルクォートで囲みます。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::services::io;
+///
+/// let rows = vec![
+///     vec![String::from("月"), String::from("収入")],
+///     vec![String::from("2023-01"), String::from("300000")],
+/// ];
+/// assert_eq!(io::format_delimited(&rows, '\t'), "月\t収入\n2023-01\t300000");
+/// ```
+pub fn format_delimited(rows: &[Vec<String>], separator: char) -> String {
+    let lines: Vec<_> = rows.iter().map(|row| {
+        row.iter().map(|field| format_csv_field(field, separator)).collect::<Vec<_>>().join(&separator.to_string())
+    }).collect();
+    lines.join("\n")
+}
+
+/// CSV・TSVのフィールドを必要に応じてダブルクォートで囲みます。
+fn format_csv_field(field: &str, separator: char) -> String {
+    if field.contains([separator, '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// 文字列をファイルに書き込みます。
+///
+/// HTMLレポートなど、JSON・CSV以外の形式のファイルを出力するときに使います。
+/// 保存先のディレクトリが存在しない場合は、自動で作成します。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::services::io;
+/// io::write_file("kakeibo_report.html", "<html></html>").unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// ディレクトリの作成やファイルへの書き込みに失敗した場合は、`KakeiboError::Io`を返します。
+pub fn write_file(file_path: &str, contents: &str) -> Result<()> {
+    write_bytes(file_path, format!("{}\n", contents).as_bytes())
+}
+
+/// バイト列をそのままファイルに書き込みます。
+///
+/// Shift_JISのCSVなど、UTF-8以外の文字コードに変換した内容を出力するときに使います。
+/// 保存先のディレクトリが存在しない場合は、自動で作成します。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::services::io;
+/// io::write_bytes("kakeibo.csv", b"\xEF\xBB\xBF2023-01-01,1000\n").unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// ディレクトリの作成やファイルへの書き込みに失敗した場合は、`KakeiboError::Io`を返します。
+pub fn write_bytes(file_path: &str, contents: &[u8]) -> Result<()> {
+    if let Some(parent) = std::path::Path::new(file_path).parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|error| KakeiboError::io(&parent.to_string_lossy(), error))?;
+    }
+    let mut file = File::create(file_path).map_err(|error| KakeiboError::io(file_path, error))?;
+    file.write_all(contents).map_err(|error| KakeiboError::io(file_path, error))
+}
+
+/// 複数のファイルへの書き込みを1つにまとめて適用するトランザクション
+///
+/// 書き込む内容は`commit`を呼ぶまでメモリに溜めておき、ファイルには書き込みません。
+/// `commit`では、すべての内容を一時ファイル（`<パス>.tmp`）に書き込んでから、元のファイルを`<パス>.bak`に退避して置き換えます。
+/// 途中で失敗した場合は、置き換えたファイルをすべて元に戻し、一時ファイルを削除します。
+/// `commit`せずに破棄した場合は、どのファイルも変更されません。
+/// 家計簿データの保存先がSQLiteのデータベースの場合は、すべてのファイルを置き換えた後にデータベースを書き換えます。
+/// 書き換える前に各データベースの内容を控えておき、途中のデータベースで失敗した場合は、書き換え済みのデータベースとファイルをすべて元に戻します。
+///
+/// 定期取引の登録（`recurring::stage_due_items`）のように、書き込む内容を追加するだけの関数と組み合わせて、複数の変更を1つにまとめられます。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::models::{ExpenseCategory, Money};
+/// use kakeibo_app::services::budget::Budget;
+/// use kakeibo_app::services::io::Transaction;
+/// use kakeibo_app::services::recurring::{self, RecurringPaths, RecurringRules};
+///
+/// let paths = RecurringPaths {
+///     file_path: "store/data.json",
+///     recurring_path: "store/recurring.json",
+///     accounts_path: "store/accounts.json",
+///     closed_path: "store/closed.json",
+///     device_path: "store/device.json",
+/// };
+/// let rules = RecurringRules::load_or_default(paths.recurring_path).unwrap();
+/// let mut budget = Budget::load_or_default("store/budget.json").unwrap();
+/// budget.set_limit(ExpenseCategory::Food, Some(Money::new(40000)));
+///
+/// // 定期取引の登録と予算の更新を、まとめて保存する（どちらかに失敗した場合はどちらも保存しない）
+/// let mut transaction = Transaction::new();
+/// recurring::stage_due_items(&paths, &rules, &Config::default(), &mut transaction).unwrap();
+/// transaction.write_json(&budget, "store/budget.json");
+/// transaction.commit().unwrap();
+/// ```
+#[derive(Debug, Default)]
+pub struct Transaction {
+    writes: Vec<(String, String)>,
+    databases: Vec<(String, String)>,
+}
+
+/// トランザクションで置き換えたファイルの状態を表す構造体
+///
+/// - `path`: 書き込み先のパス
+/// - `backed_up`: 元のファイルを退避したかどうか
+/// - `replaced`: 一時ファイルで置き換えたかどうか
+struct AppliedWrite<'a> {
+    path: &'a str,
+    backed_up: bool,
+    replaced: bool,
+}
+
+impl Transaction {
+    /// 空のトランザクションを作成する
+    pub fn new() -> Self {
+        Transaction::default()
+    }
+
+    /// ファイルに書き込む文字列を追加する
+    ///
+    /// 同じパスに複数回書き込んだ場合は、最後の内容だけを書き込みます。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::services::io::Transaction;
+    ///
+    /// let mut transaction = Transaction::new();
+    /// transaction.write_file("store/a.json", "1");
+    /// transaction.write_file("store/b.json", "2");
+    /// transaction.write_file("store/a.json", "3");
+    /// assert_eq!(transaction.get_paths(), vec!["store/a.json", "store/b.json"]);
+    /// ```
+    pub fn write_file(&mut self, file_path: &str, contents: &str) {
+        match self.writes.iter_mut().find(|(path, _)| path == file_path) {
+            Some((_, staged)) => *staged = contents.to_string(),
+            None => self.writes.push((file_path.to_string(), contents.to_string())),
+        }
+    }
+
+    /// 家計簿データをJSONファイル（パスが`sqlite://`で始まる場合はSQLiteのデータベース）に書き込む内容として追加する
+    pub fn write_to_json(&mut self, data: &[models::Item], file_path: &str) {
+        let Some(database_path) = sqlite::get_database_path(file_path) else {
+            return self.write_json(data, file_path);
+        };
+        let json_data = serde_json::to_string(data).expect("JSONへのシリアライズに失敗しました");
+        match self.databases.iter_mut().find(|(path, _)| path == database_path) {
+            Some((_, staged)) => *staged = json_data,
+            None => self.databases.push((database_path.to_string(), json_data)),
+        }
+    }
+
+    /// 予算や締め情報などの値を、整形したJSONファイルに書き込む内容として追加する
+    pub fn write_json<T: Serialize + ?Sized>(&mut self, value: &T, file_path: &str) {
+        let json_data = serde_json::to_string_pretty(value).expect("JSONへのシリアライズに失敗しました");
+        self.write_file(file_path, &json_data);
+    }
+
+    /// 書き込み先のパスの一覧を、追加した順に取得する
+    pub fn get_paths(&self) -> Vec<&str> {
+        let databases = self.databases.iter().map(|(path, _)| path.as_str());
+        self.writes.iter().map(|(path, _)| path.as_str()).chain(databases).collect()
+    }
+
+    /// 追加したすべての内容をファイルに書き込む
+    ///
+    /// #### エラー
+    ///
+    /// 書き込みやファイルの置き換え、データベースの書き換えに失敗した場合は、それまでに置き換えたファイルと書き換えたデータベースを元に戻してから`KakeiboError`を返します。
+    /// 元に戻すことにも失敗した場合は、元のファイルが`<パス>.bak`に残っていることをメッセージに含めます。
+    pub fn commit(self) -> Result<()> {
+        for (index, (path, contents)) in self.writes.iter().enumerate() {
+            if let Err(error) = write_file(&get_temp_path(path), contents) {
+                self.writes[..=index].iter().for_each(|(path, _)| { let _ = fs::remove_file(get_temp_path(path)); });
+                return Err(error);
+            }
+        }
+
+        let mut applied: Vec<AppliedWrite> = Vec::new();
+        for (path, _) in &self.writes {
+            let mut write = AppliedWrite { path, backed_up: false, replaced: false };
+            let result = Self::replace(&mut write);
+            applied.push(write);
+            if let Err(error) = result {
+                self.writes.iter().for_each(|(path, _)| { let _ = fs::remove_file(get_temp_path(path)); });
+                return Err(Self::rollback(&applied, error));
+            }
+        }
+        let snapshots = match self.take_snapshots() {
+            Ok(snapshots) => snapshots,
+            Err(error) => return Err(Self::rollback(&applied, error)),
+        };
+        for (index, (database_path, json_data)) in self.databases.iter().enumerate() {
+            let result = serde_json::from_str::<Vec<models::Item>>(json_data)
+                .map_err(|error| KakeiboError::InvalidData(error.to_string()))
+                .and_then(|data| sqlite::write_items(database_path, &data));
+            if let Err(error) = result {
+                let error = Self::restore_databases(&self.databases[..index], &snapshots, error);
+                return Err(Self::rollback(&applied, error));
+            }
+        }
+
+        for write in applied.iter().filter(|write| write.backed_up) {
+            let _ = fs::remove_file(get_backup_path(write.path));
+        }
+        Ok(())
+    }
+
+    /// 書き換える前の各データベースの内容を控えます。データベースが無い場合は`None`とします。
+    fn take_snapshots(&self) -> Result<Vec<Option<Vec<models::Item>>>> {
+        self.databases
+            .iter()
+            .map(|(database_path, _)| match std::path::Path::new(database_path).exists() {
+                true => sqlite::read_items(database_path).map(Some),
+                false => Ok(None),
+            })
+            .collect()
+    }
+
+    /// 書き換えたデータベースを、控えておいた内容に逆の順で戻します。控えが`None`のデータベースは削除します。
+    ///
+    /// 元に戻せなかったデータベースがある場合は、そのパスを加えたエラーを返します。
+    fn restore_databases(written: &[(String, String)], snapshots: &[Option<Vec<models::Item>>], error: KakeiboError) -> KakeiboError {
+        let failed: Vec<_> = written
+            .iter()
+            .zip(snapshots)
+            .rev()
+            .filter(|((database_path, _), snapshot)| match snapshot {
+                Some(data) => sqlite::write_items(database_path, data).is_err(),
+                None => fs::remove_file(database_path).is_err(),
+            })
+            .map(|((database_path, _), _)| database_path.as_str())
+            .collect();
+        if failed.is_empty() {
+            return error;
+        }
+        KakeiboError::InvalidData(format!("{} (元に戻せなかったデータベース: {})", error, failed.join("・")))
+    }
+
+    /// 元のファイルを退避し、一時ファイルで置き換えます。
+    fn replace(write: &mut AppliedWrite) -> Result<()> {
+        if std::path::Path::new(write.path).exists() {
+            fs::rename(write.path, get_backup_path(write.path)).map_err(|error| KakeiboError::io(write.path, error))?;
+            write.backed_up = true;
+        }
+        fs::rename(get_temp_path(write.path), write.path).map_err(|error| KakeiboError::io(write.path, error))?;
+        write.replaced = true;
+        Ok(())
+    }
+
+    /// 置き換えたファイルを、置き換えた順と逆の順に元に戻します。
+    ///
+    /// 元に戻せなかったファイルがある場合は、そのファイルのパスを加えたエラーを返します。
+    fn rollback(applied: &[AppliedWrite], error: KakeiboError) -> KakeiboError {
+        let mut failed = Vec::new();
+        for write in applied.iter().rev() {
+            let restored = match (write.replaced, write.backed_up) {
+                (_, true) => fs::rename(get_backup_path(write.path), write.path),
+                (true, false) => fs::remove_file(write.path),
+                (false, false) => Ok(()),
+            };
+            if restored.is_err() {
+                failed.push(get_backup_path(write.path));
+            }
+        }
+        match error {
+            KakeiboError::Io { path, source } if !failed.is_empty() => KakeiboError::Io {
+                path: format!("{} (元のファイルは{}に残っています)", path, failed.join("・")),
+                source,
+            },
+            error => error,
+        }
+    }
+}
+
+/// トランザクションの一時ファイルのパスを取得します。
+fn get_temp_path(path: &str) -> String {
+    format!("{}.tmp", path)
+}
+
+/// トランザクションで退避した元のファイルのパスを取得します。
+fn get_backup_path(path: &str) -> String {
+    format!("{}.bak", path)
+}
+
+/// 標準入力から1行読み込みます。
+///
+/// 読み込んだ行は、末尾の改行を含めてそのまま返します。
+/// プロンプトのタイムアウトが設定されている場合は、時間内に入力が無ければ空文字列（入力の終わり）を返します。
+///
+/// #### エラー
+///
+/// 標準入力からの読み込みに失敗した場合はエラーを返します。
+pub fn read_line() -> Result<String> {
+    super::prompt::read_line()
+}
+
+#[cfg(test)]
+mod io_test {
+    use super::*;
+
+    #[test]
+    fn test_transaction_write_file() {
+        let mut transaction = Transaction::new();
+        transaction.write_json(&vec![1, 2], "store/a.json");
+        transaction.write_file("store/b.json", "b");
+        transaction.write_json(&vec![3], "store/a.json");
+        assert_eq!(transaction.writes, vec![
+            ("store/a.json".to_string(), "[\n  3\n]".to_string()),
+            ("store/b.json".to_string(), "b".to_string()),
+        ]);
+        transaction.write_to_json(&Vec::new(), "sqlite://store/kakeibo.db");
+        assert_eq!(transaction.get_paths(), vec!["store/a.json", "store/b.json", "store/kakeibo.db"]);
+        assert!(Transaction::new().commit().is_ok());
+    }
+
+    #[test]
+    fn test_get_file_year() {
+        assert_eq!(get_file_year("store/2023.json"), Some(2023));
+        assert_eq!(get_file_year("2022.json"), Some(2022));
+        assert_eq!(get_file_year("store/data.json"), None);
+        assert_eq!(get_file_year("store/20231.json"), None);
+    }
+
+    #[test]
+    fn test_upgrade_category() {
+        let mut json = serde_json::json!([
+            { "name": "ランチ", "category": { "expense": "food" }, "price": 1000, "date": "2023-01-01" },
+            { "name": "給料", "category": "収入:給与", "price": 300000, "date": "2023-01-25" },
+            { "name": "本", "category": "趣味", "price": 1500, "date": "2023-01-30" },
+            { "name": "雑貨", "category": "その他", "price": 500, "date": "2023-01-31" },
+        ]);
+        json.as_array_mut().unwrap().iter_mut().for_each(upgrade_category);
+
+        assert_eq!(json[0]["category"], serde_json::json!({ "Expense": "Food" }));
+        assert_eq!(json[1]["category"], serde_json::json!({ "Income": "Salary" }));
+        assert_eq!(json[2]["category"], serde_json::json!({ "Expense": "Hobby" }));
+        assert_eq!(json[3]["category"], serde_json::json!("その他"));
+        assert_eq!(validate_json(&json), vec!["4件目のcategoryが不正です: 'その他'"]);
+    }
+
+    #[test]
+    fn test_validate_json_for_ok() {
+        let json = serde_json::json!([{
+            "name": "外食",
+            "category": {"Expense": "Food"},
+            "price": 3000,
+            "date": "2022-02-15",
+            "event": "出張"
+        }]);
+
+        assert!(validate_json(&json).is_empty());
+    }
+
+    #[test]
+    fn test_validate_json_for_ng() {
+        let json = serde_json::json!([
+            {"name": "外食", "category": {"Expense": "Food"}, "price": 3000, "date": "2022-02-15"},
+            {"name": "給料", "category": {"Income": "Food"}, "price": 300000, "date": "2022-02-25"},
+            {"name": "旅行", "category": {"Expense": "Hobby"}, "date": "2022-03-01"}
+        ]);
+        let errors = validate_json(&json);
+
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].starts_with("2件目のcategoryが不正です"));
+        assert!(errors[1].starts_with("3件目の項目が不正です"));
+    }
+
+    #[test]
+    fn test_deserialize_items_for_ok() {
+        let json = serde_json::json!([
+            {"name": "外食", "category": {"Expense": "Food"}, "price": 3000, "date": "2022-02-15"}
+        ]);
+        let expected = vec![models::Item::new(
+            "外食".to_string(),
+            models::Category::Expense(models::ExpenseCategory::Food),
+            models::Money::new(3000),
+            chrono::NaiveDate::from_ymd_opt(2022, 2, 15).unwrap()
+        )];
+
+        assert_eq!(deserialize_items(json), Ok(expected));
+    }
+
+    #[test]
+    fn test_deserialize_items_for_ng() {
+        let json = serde_json::json!([
+            {"name": "外食", "category": {"Expense": "Food"}, "price": 3000, "date": "2022-02-15"},
+            {"name": "給料", "category": {"Income": "Salary"}, "price": 300000, "date": "2023-13-01"}
+        ]);
+        assert_eq!(deserialize_items(json), Err("2件目のdateが不正です: '2023-13-01'".to_string()));
+
+        let json = serde_json::json!([
+            {"name": "外食", "category": {"Expense": "Food"}, "date": "2022-02-15"}
+        ]);
+        assert_eq!(deserialize_items(json), Err("1件目の項目が不正です: missing field `price`".to_string()));
+
+        let json = serde_json::json!({"name": "外食"});
+        assert_eq!(deserialize_items(json), Err("データ全体がJSONの配列ではありません".to_string()));
+    }
+
+    #[test]
+    fn test_parse_data_for_ng() {
+        let error = parse_data(b"[{\"name\": ").unwrap_err();
+        assert!(matches!(error, KakeiboError::InvalidData(_)));
+        assert!(error.to_string().contains("JSONの解析に失敗しました (1行10列目)"));
+
+        let error = read_data("store/存在しないファイル.json").unwrap_err();
+        assert!(matches!(error, KakeiboError::Io { .. }));
+    }
+
+    #[test]
+    fn test_parse_data_in_range() {
+        let bytes = r#"[
+            {"name": "外食", "category": {"Expense": "Food"}, "price": 3000, "date": "2022-01-15"},
+            {"name": "給料", "category": {"Income": "Salary"}, "price": 300000, "date": "2022-02-25"},
+            {"name": "旅行", "category": {"Expense": "Hobby"}, "price": "壊れた値", "date": "2022-03-01"}
+        ]"#.as_bytes();
+        let first_date = chrono::NaiveDate::from_ymd_opt(2022, 2, 1).unwrap();
+        let last_date = chrono::NaiveDate::from_ymd_opt(2022, 2, 28).unwrap();
+        let data = parse_data_in_range(bytes, first_date, last_date).unwrap();
+
+        assert_eq!(data.len(), 1);
+        assert_eq!(data[0].get_name(), "給料");
+
+        let last_date = chrono::NaiveDate::from_ymd_opt(2022, 3, 31).unwrap();
+        assert_eq!(
+            parse_data_in_range(bytes, first_date, last_date),
+            Err("3件目のpriceが不正です: '壊れた値'".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_data_in_range_for_invalid_date() {
+        let bytes = r#"[{"name": "外食", "category": {"Expense": "Food"}, "price": 3000, "date": "2022-13-15"}]"#.as_bytes();
+        let first_date = chrono::NaiveDate::from_ymd_opt(2022, 1, 1).unwrap();
+        let last_date = chrono::NaiveDate::from_ymd_opt(2022, 12, 31).unwrap();
+
+        assert_eq!(
+            parse_data_in_range(bytes, first_date, last_date),
+            Err("1件目のdateが不正です: '2022-13-15'".to_string())
+        );
+    }
+
+    #[test]
+    fn test_format_csv_field() {
+        assert_eq!(format_csv_field("ランチ", ','), "ランチ");
+        assert_eq!(format_csv_field("ランチ,夕食", ','), "\"ランチ,夕食\"");
+        assert_eq!(format_csv_field("ランチ,夕食", '\t'), "ランチ,夕食");
+        assert_eq!(format_csv_field("ランチ\t夕食", '\t'), "\"ランチ\t夕食\"");
+        assert_eq!(format_csv_field("\"特売\"の肉", ','), "\"\"\"特売\"\"の肉\"");
+    }
+
+    #[test]
+    fn test_format_value() {
+        assert_eq!(format_value(&serde_json::json!("2023-13-01")), "'2023-13-01'");
+        assert_eq!(format_value(&serde_json::json!(-1)), "-1");
+    }
+
+    #[test]
+    fn test_format_instance_path() {
+        assert_eq!(format_instance_path(""), "データ全体");
+        assert_eq!(format_instance_path("/127"), "128件目の項目");
+        assert_eq!(format_instance_path("/127/date"), "128件目のdate");
+        assert_eq!(format_instance_path("/0/category/Income"), "1件目のcategory.Income");
+    }
+}
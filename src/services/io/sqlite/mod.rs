@@ -0,0 +1,370 @@
+//! SQLiteのデータベースへの入出力
+//!
+//! このモジュールは、JSONファイルの代わりにSQLiteのデータベースへ家計簿データを保存する機能を提供します。
+//! データファイルのパスを`sqlite://store/kakeibo.db`のように書くと、`services::io`の読み書きがこのモジュールの`SqliteStore`に切り替わります。
+//!
+//! 各項目はJSONファイルと同じ形式のJSONとして`items`テーブルに1行ずつ保存し、並び順・追加属性を含めてJSONファイルと同じ内容を読み書きします。
+//! 日付とカテゴリは別の列にも保存して索引を張り、期間やカテゴリを指定した読み込みでは該当する行だけを読み込みます。
+//!
+//! ```text
+//! items (position INTEGER PRIMARY KEY, date TEXT, category TEXT, body TEXT)
+//! ```
+
+#[cfg(feature = "sqlite")]
+use std::path::Path;
+
+use chrono::NaiveDate;
+#[cfg(feature = "sqlite")]
+use rusqlite::{params, Connection, OpenFlags, Params};
+
+use crate::error::{KakeiboError, Result};
+use crate::models;
+use crate::services::timing;
+
+use super::store::DataStore;
+
+/// SQLiteのデータベースを表すパスの接頭辞
+pub const SCHEME: &str = "sqlite://";
+
+/// テーブルと索引を作成するSQL
+#[cfg(feature = "sqlite")]
+const CREATE_TABLE: &str = "
+    CREATE TABLE IF NOT EXISTS items (
+        position INTEGER PRIMARY KEY,
+        date TEXT NOT NULL,
+        category TEXT NOT NULL,
+        body TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS items_date ON items (date);
+    CREATE INDEX IF NOT EXISTS items_category ON items (category);
+";
+
+/// データファイルのパスがSQLiteのデータベースを表す場合は、データベースファイルのパスを取得する
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::services::io::sqlite;
+///
+/// assert_eq!(sqlite::get_database_path("sqlite://store/kakeibo.db"), Some("store/kakeibo.db"));
+/// assert_eq!(sqlite::get_database_path("store/data.json"), None);
+/// ```
+pub fn get_database_path(file_path: &str) -> Option<&str> {
+    file_path.strip_prefix(SCHEME)
+}
+
+/// SQLiteのデータベースの保存先
+///
+/// 期間・カテゴリを指定した読み込みでは、索引を使って該当する行だけを読み込みます。
+pub struct SqliteStore {
+    path: String,
+}
+
+impl SqliteStore {
+    /// データベースファイルのパスから保存先を作成する
+    pub fn new(database_path: &str) -> Self {
+        SqliteStore { path: database_path.to_string() }
+    }
+}
+
+impl DataStore for SqliteStore {
+    fn get_name(&self) -> String {
+        format!("SQLiteのデータベース {}", self.path)
+    }
+
+    fn exists(&self) -> bool {
+        std::path::Path::new(&self.path).exists()
+    }
+
+    fn load(&self) -> Result<Vec<models::Item>> {
+        read_items(&self.path)
+    }
+
+    fn load_in_range(&self, first_date: NaiveDate, last_date: NaiveDate) -> Result<Vec<models::Item>> {
+        timing::measure("期間内の読み込み", || read_items_in_range(&self.path, first_date, last_date))
+    }
+
+    fn load_by_category(&self, category: &models::Category) -> Result<Vec<models::Item>> {
+        read_items_by_category(&self.path, category)
+    }
+
+    fn save(&self, data: &[models::Item]) -> Result<()> {
+        write_items(&self.path, data)
+    }
+}
+
+/// データベースからすべての項目を、保存した順に読み込みます。
+///
+/// 読み込んだ項目は、JSONファイルと同じくスキーマで検証します。
+///
+/// #### エラー
+///
+/// データベースが存在しない場合や読み込みに失敗した場合は`KakeiboError::Io`を、項目の形式が不正な場合は`KakeiboError::InvalidData`を返します。
+#[cfg(feature = "sqlite")]
+pub fn read_items(database_path: &str) -> Result<Vec<models::Item>> {
+    let connection = open_existing(database_path)?;
+    select_items(&connection, database_path)
+}
+
+#[cfg(not(feature = "sqlite"))]
+pub fn read_items(database_path: &str) -> Result<Vec<models::Item>> {
+    Err(get_unsupported_error(database_path))
+}
+
+/// データベースから、指定された期間内（両端を含む）の項目だけを読み込みます。
+///
+/// 日付の索引を使い、期間外の行は読み込みません。JSONファイルの期間指定の読み込みと同じく、IDが採番されていない項目のIDは`0`のままです。
+/// 開始日に`NaiveDate::MIN`、終了日に`NaiveDate::MAX`を指定すると、その側の期間を限定せずに読み込みます。
+///
+/// #### エラー
+///
+/// データベースが存在しない場合や読み込みに失敗した場合は`KakeiboError::Io`を、項目の形式が不正な場合は`KakeiboError::InvalidData`を返します。
+#[cfg(feature = "sqlite")]
+pub fn read_items_in_range(database_path: &str, first_date: NaiveDate, last_date: NaiveDate) -> Result<Vec<models::Item>> {
+    let connection = open_existing(database_path)?;
+    select_items_in_range(&connection, database_path, first_date, last_date)
+}
+
+#[cfg(not(feature = "sqlite"))]
+pub fn read_items_in_range(database_path: &str, _first_date: NaiveDate, _last_date: NaiveDate) -> Result<Vec<models::Item>> {
+    Err(get_unsupported_error(database_path))
+}
+
+/// データベースから、指定されたカテゴリの項目だけを読み込みます。
+///
+/// カテゴリの索引を使い、他のカテゴリの行は読み込みません。IDが採番されていない項目のIDは`0`のままです。
+///
+/// #### エラー
+///
+/// データベースが存在しない場合や読み込みに失敗した場合は`KakeiboError::Io`を、項目の形式が不正な場合は`KakeiboError::InvalidData`を返します。
+#[cfg(feature = "sqlite")]
+pub fn read_items_by_category(database_path: &str, category: &models::Category) -> Result<Vec<models::Item>> {
+    let connection = open_existing(database_path)?;
+    select_items_by_category(&connection, database_path, category)
+}
+
+#[cfg(not(feature = "sqlite"))]
+pub fn read_items_by_category(database_path: &str, _category: &models::Category) -> Result<Vec<models::Item>> {
+    Err(get_unsupported_error(database_path))
+}
+
+/// データベースの内容を、指定された項目の一覧で置き換えます。
+///
+/// 置き換えは1つのトランザクションで行うため、途中で失敗した場合は元の内容のままです。データベースが無い場合は作成します。
+///
+/// #### エラー
+///
+/// ディレクトリやデータベースの作成、書き込みに失敗した場合は、`KakeiboError::Io`を返します。
+#[cfg(feature = "sqlite")]
+pub fn write_items(database_path: &str, data: &[models::Item]) -> Result<()> {
+    if let Some(parent) = Path::new(database_path).parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(|error| KakeiboError::io(database_path, error))?;
+    }
+    let mut connection = Connection::open(database_path).map_err(|error| to_io_error(database_path, error))?;
+    connection.execute_batch(CREATE_TABLE).map_err(|error| to_io_error(database_path, error))?;
+    replace_items(&mut connection, database_path, data)
+}
+
+#[cfg(not(feature = "sqlite"))]
+pub fn write_items(database_path: &str, _data: &[models::Item]) -> Result<()> {
+    Err(get_unsupported_error(database_path))
+}
+
+/// テーブルのすべての項目を、保存した順に読み込みます。
+#[cfg(feature = "sqlite")]
+fn select_items(connection: &Connection, database_path: &str) -> Result<Vec<models::Item>> {
+    let json = query_json(connection, database_path, "SELECT body FROM items ORDER BY position", [])?;
+    super::parse_data(json.as_bytes())
+}
+
+/// テーブルから、指定された期間内（両端を含む）の項目だけを読み込みます。
+///
+/// 日付の列は`2023-01-01`のような文字列で比べるため、4桁の年で表せない日付は、0年1月1日〜9999年12月31日に収めてから比べます。
+#[cfg(feature = "sqlite")]
+fn select_items_in_range(connection: &Connection, database_path: &str, first_date: NaiveDate, last_date: NaiveDate) -> Result<Vec<models::Item>> {
+    let (first_storable_date, last_storable_date) = get_storable_range();
+    if last_storable_date < first_date || last_date < first_storable_date {
+        return Ok(Vec::new());
+    }
+    let first_date = first_date.max(first_storable_date);
+    let last_date = last_date.min(last_storable_date);
+
+    let sql = "SELECT body FROM items WHERE date BETWEEN ?1 AND ?2 ORDER BY position";
+    let json = query_json(connection, database_path, sql, params![first_date.to_string(), last_date.to_string()])?;
+    super::parse_items(json.as_bytes())
+}
+
+/// テーブルから、指定されたカテゴリの項目だけを読み込みます。
+#[cfg(feature = "sqlite")]
+fn select_items_by_category(connection: &Connection, database_path: &str, category: &models::Category) -> Result<Vec<models::Item>> {
+    let sql = "SELECT body FROM items WHERE category = ?1 ORDER BY position";
+    let json = query_json(connection, database_path, sql, params![get_category_key(category)])?;
+    super::parse_items(json.as_bytes())
+}
+
+/// テーブルの内容を、1つのトランザクションで指定された項目の一覧に置き換えます。
+#[cfg(feature = "sqlite")]
+fn replace_items(connection: &mut Connection, database_path: &str, data: &[models::Item]) -> Result<()> {
+    let transaction = connection.transaction().map_err(|error| to_io_error(database_path, error))?;
+    transaction.execute("DELETE FROM items", []).map_err(|error| to_io_error(database_path, error))?;
+    {
+        let mut statement = transaction
+            .prepare("INSERT INTO items (position, date, category, body) VALUES (?1, ?2, ?3, ?4)")
+            .map_err(|error| to_io_error(database_path, error))?;
+        for (position, item) in data.iter().enumerate() {
+            let body = serde_json::to_string(item).expect("JSONへのシリアライズに失敗しました");
+            statement
+                .execute(params![position as i64, item.get_date().to_string(), get_category_key(item.get_item_category()), body])
+                .map_err(|error| to_io_error(database_path, error))?;
+        }
+    }
+    transaction.commit().map_err(|error| to_io_error(database_path, error))
+}
+
+/// 日付の列の文字列が日付順に並ぶ範囲（0年1月1日〜9999年12月31日）を取得します。
+#[cfg(feature = "sqlite")]
+fn get_storable_range() -> (NaiveDate, NaiveDate) {
+    (
+        NaiveDate::from_ymd_opt(0, 1, 1).unwrap(),
+        NaiveDate::from_ymd_opt(9999, 12, 31).unwrap(),
+    )
+}
+
+/// 既存のデータベースを開きます。データベースが無い場合は作成せずにエラーを返します。
+#[cfg(feature = "sqlite")]
+fn open_existing(database_path: &str) -> Result<Connection> {
+    if !Path::new(database_path).exists() {
+        return Err(KakeiboError::io(database_path, std::io::Error::from(std::io::ErrorKind::NotFound)));
+    }
+    let connection = Connection::open_with_flags(database_path, OpenFlags::SQLITE_OPEN_READ_WRITE)
+        .map_err(|error| to_io_error(database_path, error))?;
+    connection.execute_batch(CREATE_TABLE).map_err(|error| to_io_error(database_path, error))?;
+    Ok(connection)
+}
+
+/// 問い合わせ結果の`body`列を、JSONの配列の文字列にまとめます。
+#[cfg(feature = "sqlite")]
+fn query_json<P: Params>(connection: &Connection, database_path: &str, sql: &str, params: P) -> Result<String> {
+    let mut statement = connection.prepare(sql).map_err(|error| to_io_error(database_path, error))?;
+    let bodies = statement
+        .query_map(params, |row| row.get::<_, String>(0))
+        .and_then(|rows| rows.collect::<rusqlite::Result<Vec<String>>>())
+        .map_err(|error| to_io_error(database_path, error))?;
+    Ok(format!("[{}]", bodies.join(",")))
+}
+
+/// SQLiteのエラーを、データベースのパスを含む`KakeiboError::Io`に変換します。
+#[cfg(feature = "sqlite")]
+fn to_io_error(database_path: &str, error: rusqlite::Error) -> KakeiboError {
+    KakeiboError::io(database_path, std::io::Error::other(error))
+}
+
+/// sqlite機能を無効にしてビルドした場合のエラーを作成します。
+#[cfg(not(feature = "sqlite"))]
+fn get_unsupported_error(database_path: &str) -> KakeiboError {
+    KakeiboError::InvalidInput(format!("{}を読み書きできません: sqlite機能を有効にしてビルドしてください", database_path))
+}
+
+/// カテゴリの列に保存する値（`{"Expense":"Food"}`のようなJSON）を取得します。
+#[cfg_attr(not(feature = "sqlite"), allow(dead_code))]
+fn get_category_key(category: &models::Category) -> String {
+    serde_json::to_string(category).expect("JSONへのシリアライズに失敗しました")
+}
+
+#[cfg(test)]
+mod sqlite_test {
+    use super::*;
+
+    #[cfg(feature = "sqlite")]
+    fn get_test_data() -> Vec<models::Item> {
+        vec![
+            models::Item::new(
+                "給料".to_string(),
+                models::Category::Income(models::IncomeCategory::Salary),
+                models::Money::new(300000),
+                NaiveDate::from_ymd_opt(2022, 12, 25).unwrap()
+            ),
+            models::Item::new(
+                "新年会".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                models::Money::new(5000),
+                NaiveDate::from_ymd_opt(2023, 1, 10).unwrap()
+            ),
+            models::Item::new(
+                "旅行".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Hobby),
+                models::Money::new(100000),
+                NaiveDate::from_ymd_opt(2023, 1, 30).unwrap()
+            ),
+            models::Item::new(
+                "外食".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                models::Money::new(3000),
+                NaiveDate::from_ymd_opt(2023, 2, 15).unwrap()
+            ),
+        ]
+    }
+
+    /// テスト用に、テーブルを作成したメモリ上のデータベースに項目を書き込みます。
+    #[cfg(feature = "sqlite")]
+    fn open_test_database(data: &[models::Item]) -> Connection {
+        let mut connection = Connection::open_in_memory().unwrap();
+        connection.execute_batch(CREATE_TABLE).unwrap();
+        replace_items(&mut connection, ":memory:", data).unwrap();
+        connection
+    }
+
+    #[cfg(feature = "sqlite")]
+    fn get_names(data: &[models::Item]) -> Vec<String> {
+        data.iter().map(|item| item.get_name().to_string()).collect()
+    }
+
+    #[test]
+    #[cfg(feature = "sqlite")]
+    fn test_select_items() {
+        let test_data = get_test_data();
+        let mut connection = open_test_database(&test_data);
+        let data = select_items(&connection, ":memory:").unwrap();
+        assert_eq!(get_names(&data), vec!["給料", "新年会", "旅行", "外食"]);
+        assert_eq!(data.iter().map(|item| item.get_id()).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+
+        // 書き込み直すと、以前の内容はすべて置き換わる
+        replace_items(&mut connection, ":memory:", &test_data[2..]).unwrap();
+        assert_eq!(get_names(&select_items(&connection, ":memory:").unwrap()), vec!["旅行", "外食"]);
+    }
+
+    #[test]
+    #[cfg(feature = "sqlite")]
+    fn test_select_items_in_range() {
+        let connection = open_test_database(&get_test_data());
+        let select = |first_date, last_date| get_names(&select_items_in_range(&connection, ":memory:", first_date, last_date).unwrap());
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+        assert_eq!(select(date(2023, 1, 10), date(2023, 1, 30)), vec!["新年会", "旅行"]);
+        // 開始日・終了日の一方を限定しない場合
+        assert_eq!(select(date(2023, 1, 11), NaiveDate::MAX), vec!["旅行", "外食"]);
+        assert_eq!(select(NaiveDate::MIN, date(2023, 1, 10)), vec!["給料", "新年会"]);
+        assert_eq!(select(NaiveDate::MIN, NaiveDate::MAX), vec!["給料", "新年会", "旅行", "外食"]);
+        // 4桁の年で表せる範囲の外だけを指定した場合
+        assert!(select(date(10000, 1, 1), NaiveDate::MAX).is_empty());
+        assert!(select(NaiveDate::MIN, date(-1, 12, 31)).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "sqlite")]
+    fn test_select_items_by_category() {
+        let connection = open_test_database(&get_test_data());
+        let food = models::Category::Expense(models::ExpenseCategory::Food);
+        let data = select_items_by_category(&connection, ":memory:", &food).unwrap();
+        assert_eq!(get_names(&data), vec!["新年会", "外食"]);
+        let other = models::Category::Expense(models::ExpenseCategory::Other);
+        assert!(select_items_by_category(&connection, ":memory:", &other).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_get_category_key() {
+        let category = models::Category::Expense(models::ExpenseCategory::Food);
+        assert_eq!(get_category_key(&category), r#"{"Expense":"Food"}"#);
+        assert_eq!(get_database_path("sqlite://kakeibo.db"), Some("kakeibo.db"));
+    }
+}
@@ -0,0 +1,296 @@
+//! データの保存先
+//!
+//! このモジュールは、家計簿データの保存先を`DataStore`トレイトで抽象化し、読み込み（`load`）・保存（`save`）・追加（`append`）を提供します。
+//! 登録や集計などの機能はこのトレイトを通してデータを読み書きするため、保存先を追加しても各機能を変更する必要はありません。
+//!
+//! | 保存先 | 内容 |
+//! | --- | --- |
+//! | `JsonFileStore` | JSONファイル（既定） |
+//! | `SqliteStore` | SQLiteのデータベース（パスが`sqlite://`で始まる場合。`sqlite`モジュール） |
+//! | `MemoryStore` | メモリ上（テストや、ファイルに保存しない一時的な利用向け） |
+//!
+//! 新しい保存先（リモートのAPIなど）を追加する場合は、`DataStore`を実装し、`open_store`にパスの書き方を追加します。
+//!
+//! #### 例
+//!
+//! ```rust
+//! use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+//! use kakeibo_app::services::io::store::{DataStore, MemoryStore};
+//! use chrono::NaiveDate;
+//!
+//! let store = MemoryStore::default();
+//! let item = Item::new(
+//!     String::from("ランチ"),
+//!     Category::Expense(ExpenseCategory::Food),
+//!     Money::new(1000),
+//!     NaiveDate::from_ymd_opt(2023, 1, 5).unwrap(),
+//! );
+//! assert_eq!(store.append(vec![item]).unwrap(), vec![1]);
+//! assert_eq!(store.load().unwrap().len(), 1);
+//! ```
+
+use std::cell::RefCell;
+use std::fs::File;
+use std::path::Path;
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::error::{KakeiboError, Result};
+use crate::models;
+use crate::services::timing;
+
+use super::sqlite::{self, SqliteStore};
+
+/// 家計簿データの保存先を表すトレイト
+///
+/// 新しい保存先を追加する場合は、このトレイトを実装し、`open_store`にパスの書き方を追加します。
+/// 期間・カテゴリを指定した読み込みと追加は、既定ではすべての項目の読み込みと保存で実装しています。索引などで速くできる保存先は上書きしてください。
+pub trait DataStore {
+    /// 保存先の名前を取得する（表示用）
+    fn get_name(&self) -> String;
+
+    /// 保存先が存在するかどうかを返す
+    fn exists(&self) -> bool;
+
+    /// すべての項目を読み込む
+    ///
+    /// IDが採番されていない項目には、読み込み時に採番します。
+    ///
+    /// #### エラー
+    ///
+    /// 保存先が存在しない場合や読み込みに失敗した場合、データの形式が不正な場合は、`KakeiboError`を返します。
+    fn load(&self) -> Result<Vec<models::Item>>;
+
+    /// 指定された期間内（両端を含む）の項目だけを読み込む
+    ///
+    /// #### エラー
+    ///
+    /// `load`と同じです。
+    fn load_in_range(&self, first_date: NaiveDate, last_date: NaiveDate) -> Result<Vec<models::Item>> {
+        let data = self.load()?;
+        Ok(data.into_iter().filter(|item| first_date <= item.get_date() && item.get_date() <= last_date).collect())
+    }
+
+    /// 指定されたカテゴリの項目だけを読み込む
+    ///
+    /// #### エラー
+    ///
+    /// `load`と同じです。
+    fn load_by_category(&self, category: &models::Category) -> Result<Vec<models::Item>> {
+        let data = self.load()?;
+        Ok(data.into_iter().filter(|item| item.get_item_category() == category).collect())
+    }
+
+    /// 年別の保存先（`store/2023.json`など）の場合は、その年を取得する
+    fn get_year(&self) -> Option<i32> {
+        None
+    }
+
+    /// 保存先の内容を、指定された項目の一覧で置き換える
+    ///
+    /// #### エラー
+    ///
+    /// 書き込みに失敗した場合は、`KakeiboError`を返します。
+    fn save(&self, data: &[models::Item]) -> Result<()>;
+
+    /// 項目を末尾に追加して保存し、追加した項目のIDを返す
+    ///
+    /// 保存先が存在しない場合は、追加する項目だけで作成します。IDが採番されていない項目には、既存の項目の続きから採番します。
+    ///
+    /// #### エラー
+    ///
+    /// 読み込みや書き込みに失敗した場合は、`KakeiboError`を返します。
+    fn append(&self, items: Vec<models::Item>) -> Result<Vec<u64>> {
+        let mut data = if self.exists() { self.load()? } else { Vec::new() };
+        let first = data.len();
+        data.extend(items);
+        super::assign_ids(&mut data);
+        self.save(&data)?;
+        Ok(data[first..].iter().map(|item| item.get_id()).collect())
+    }
+}
+
+/// データファイルのパスから保存先を作成する
+///
+/// `sqlite://`で始まるパスはSQLiteのデータベース、それ以外はJSONファイルになります。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::services::io::store;
+///
+/// assert_eq!(store::open_store("store/data.json").get_name(), "JSONファイル store/data.json");
+/// assert_eq!(store::open_store("sqlite://store/kakeibo.db").get_name(), "SQLiteのデータベース store/kakeibo.db");
+/// ```
+pub fn open_store(file_path: &str) -> Box<dyn DataStore> {
+    match sqlite::get_database_path(file_path) {
+        Some(database_path) => Box::new(SqliteStore::new(database_path)),
+        None => Box::new(JsonFileStore::new(file_path)),
+    }
+}
+
+/// 複数のデータファイルのパスから、それぞれの保存先を作成する
+pub fn open_stores(file_paths: &[&str]) -> Vec<Box<dyn DataStore>> {
+    file_paths.iter().map(|file_path| open_store(file_path)).collect()
+}
+
+/// 保存先からデータを読み込むか、保存先が無い場合は新しいデータ（空のベクトル）を作成します。
+///
+/// #### エラー
+///
+/// 読み込みに失敗した場合や、データの形式が不正な場合はエラーを返します。
+pub fn load_or_create(store: &dyn DataStore) -> Result<Vec<models::Item>> {
+    if !store.exists() {
+        println!("新規ファイルを作成します");
+        return Ok(Vec::new());
+    }
+    store.load()
+}
+
+/// 複数の保存先からデータを読み込み、1つにまとめます。
+///
+/// #### エラー
+///
+/// いずれかの保存先の読み込みに失敗した場合、またはすべての保存先のデータが空の場合はエラーを返します。
+pub fn load_all(stores: &[Box<dyn DataStore>]) -> Result<Vec<models::Item>> {
+    let mut data = Vec::new();
+    for store in stores {
+        data.extend(store.load()?);
+    }
+    check_not_empty(data)
+}
+
+/// 複数の保存先から、指定された期間内のデータだけを読み込みます。
+///
+/// 年別の保存先は、期間と重ならない年のものを読み飛ばします。
+///
+/// #### エラー
+///
+/// 対象の保存先の読み込みに失敗した場合、または期間内のデータが無い場合はエラーを返します。
+pub fn load_in_period(stores: &[Box<dyn DataStore>], first_date: NaiveDate, last_date: NaiveDate) -> Result<Vec<models::Item>> {
+    let mut data = Vec::new();
+    for store in stores {
+        if store.get_year().is_none_or(|year| first_date.year() <= year && year <= last_date.year()) {
+            data.extend(store.load_in_range(first_date, last_date)?);
+        }
+    }
+    check_not_empty(data)
+}
+
+/// データが空の場合は`KakeiboError::NoData`を返します。
+fn check_not_empty(data: Vec<models::Item>) -> Result<Vec<models::Item>> {
+    if data.is_empty() {
+        return Err(KakeiboError::NoData);
+    }
+    Ok(data)
+}
+
+/// JSONファイルの保存先
+///
+/// ファイルはメモリマップで読み込み、期間を指定した読み込みでは期間内の項目だけをパースします。
+/// 期間を指定した読み込みでは、IDが採番されていない項目のIDは`0`のままです。
+pub struct JsonFileStore {
+    path: String,
+}
+
+impl JsonFileStore {
+    /// JSONファイルのパスから保存先を作成する
+    pub fn new(path: &str) -> Self {
+        JsonFileStore { path: path.to_string() }
+    }
+}
+
+impl DataStore for JsonFileStore {
+    fn get_name(&self) -> String {
+        format!("JSONファイル {}", self.path)
+    }
+
+    fn exists(&self) -> bool {
+        Path::new(&self.path).exists()
+    }
+
+    fn load(&self) -> Result<Vec<models::Item>> {
+        let file = File::open(&self.path).map_err(|error| KakeiboError::io(&self.path, error))?;
+        super::parse_data(&super::map_file(&file, &self.path)?)
+    }
+
+    fn load_in_range(&self, first_date: NaiveDate, last_date: NaiveDate) -> Result<Vec<models::Item>> {
+        let file = File::open(&self.path).map_err(|error| KakeiboError::io(&self.path, error))?;
+        let mmap = super::map_file(&file, &self.path)?;
+        timing::measure("期間内の読み込み", || super::parse_data_in_range(&mmap, first_date, last_date)).map_err(KakeiboError::InvalidData)
+    }
+
+    fn get_year(&self) -> Option<i32> {
+        super::get_file_year(&self.path)
+    }
+
+    fn save(&self, data: &[models::Item]) -> Result<()> {
+        let json_data = serde_json::to_string_pretty(data).expect("JSONへのシリアライズに失敗しました");
+        super::write_file(&self.path, &json_data)
+    }
+}
+
+/// メモリ上の保存先
+///
+/// 項目はJSONファイルと同じ形式のJSONとして保持し、読み込むたびにJSONファイルと同じく検証します。
+/// プロセスが終了すると内容は失われます。
+#[derive(Debug)]
+pub struct MemoryStore {
+    json: RefCell<String>,
+}
+
+impl Default for MemoryStore {
+    fn default() -> Self {
+        MemoryStore { json: RefCell::new(String::from("[]")) }
+    }
+}
+
+impl DataStore for MemoryStore {
+    fn get_name(&self) -> String {
+        String::from("メモリ")
+    }
+
+    fn exists(&self) -> bool {
+        true
+    }
+
+    fn load(&self) -> Result<Vec<models::Item>> {
+        super::parse_data(self.json.borrow().as_bytes())
+    }
+
+    fn save(&self, data: &[models::Item]) -> Result<()> {
+        *self.json.borrow_mut() = serde_json::to_string(data).expect("JSONへのシリアライズに失敗しました");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod store_test {
+    use super::*;
+
+    fn new_item(day: u32) -> models::Item {
+        models::Item::new(
+            "ランチ".to_string(),
+            models::Category::Expense(models::ExpenseCategory::Food),
+            models::Money::new(1000),
+            NaiveDate::from_ymd_opt(2023, 1, day).unwrap()
+        )
+    }
+
+    #[test]
+    fn test_memory_store() {
+        let store = MemoryStore::default();
+        assert_eq!(store.load().unwrap(), Vec::new());
+        assert_eq!(store.append(vec![new_item(1), new_item(10)]).unwrap(), vec![1, 2]);
+        assert_eq!(store.append(vec![new_item(20)]).unwrap(), vec![3]);
+
+        let first_date = NaiveDate::from_ymd_opt(2023, 1, 5).unwrap();
+        let last_date = NaiveDate::from_ymd_opt(2023, 1, 31).unwrap();
+        let data = store.load_in_range(first_date, last_date).unwrap();
+        assert_eq!(data.iter().map(|item| item.get_id()).collect::<Vec<_>>(), vec![2, 3]);
+
+        let stores: Vec<Box<dyn DataStore>> = vec![Box::new(store), Box::new(MemoryStore::default())];
+        assert_eq!(load_all(&stores).unwrap().len(), 3);
+        assert!(matches!(load_all(&stores[1..]), Err(KakeiboError::NoData)));
+    }
+}
@@ -0,0 +1,250 @@
+//! ジャーナル統合サービス
+//!
+//! このモジュールは、端末ごとのデバイスIDを管理し、複数の端末でオフラインに追記したデータファイル（ジャーナル）を
+//! 1つのデータファイルに衝突なく統合する機能を提供します。
+//!
+//! 登録した項目には、端末のデバイスIDと端末内の連番が付与されます。統合時は、同じデバイスIDと連番を持つ項目を
+//! 同一の項目とみなすため、同じジャーナルを何度統合しても項目が重複しません。
+//! デバイスIDは、JSONファイル`store/device.json`に保存されます。
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::BufReader;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::models;
+use crate::services;
+use crate::error::{KakeiboError, Result};
+
+/// 端末を表す構造体
+///
+/// - `device_id`: 端末のID（初回利用時に乱数で生成される8桁の16進数）
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct Device {
+    device_id: String,
+}
+
+impl Device {
+    /// JSONファイルから端末の情報を読み込むか、無い場合は新しいデバイスIDを生成して保存する
+    ///
+    /// #### エラー
+    ///
+    /// ファイルの内容が不正な場合は`KakeiboError::InvalidData`を、書き込みに失敗した場合は`KakeiboError::Io`を返します。
+    pub fn load_or_create(file_path: &str) -> Result<Self> {
+        let mut transaction = services::io::Transaction::new();
+        let device = Self::load_or_stage(file_path, &mut transaction)?;
+        transaction.commit()?;
+        Ok(device)
+    }
+
+    /// JSONファイルから端末の情報を読み込むか、無い場合は新しいデバイスIDを生成し、その保存をトランザクションに追加する
+    ///
+    /// 生成したデバイスIDは、トランザクションを`commit`するまで保存されません。
+    ///
+    /// #### エラー
+    ///
+    /// ファイルの内容が不正な場合は、`KakeiboError::InvalidData`を返します。
+    pub fn load_or_stage(file_path: &str, transaction: &mut services::io::Transaction) -> Result<Self> {
+        if let Ok(f) = File::open(file_path) {
+            return serde_json::from_reader(BufReader::new(f))
+                .map_err(|error| KakeiboError::InvalidData(format!("{}の読み込みに失敗しました: {}", file_path, error)));
+        }
+
+        let device = Device { device_id: format!("{:08x}", rand::thread_rng().gen::<u32>()) };
+        transaction.write_json(&device, file_path);
+        Ok(device)
+    }
+
+    /// 端末のIDを取得する
+    pub fn get_device_id(&self) -> &str {
+        &self.device_id
+    }
+
+    /// この端末で次に登録する項目の登録元を取得する
+    ///
+    /// 連番は、データ内でこの端末が付与した連番の最大値の次の値になります。
+    pub fn next_origin(&self, data: &[models::Item]) -> models::Origin {
+        let seq = data
+            .iter()
+            .filter_map(|item| item.get_origin())
+            .filter(|origin| origin.get_device_id() == self.device_id)
+            .map(|origin| origin.get_seq())
+            .max()
+            .unwrap_or(0);
+        models::Origin::new(self.device_id.clone(), seq + 1)
+    }
+}
+
+/// ジャーナルの統合結果を表す構造体
+///
+/// - `added`: 追加した項目
+/// - `skipped`: 既に統合済みのため追加しなかった項目数
+#[derive(Debug, PartialEq, Eq)]
+pub struct MergeResult {
+    pub added: Vec<models::Item>,
+    pub skipped: usize,
+}
+
+/// 他の端末のジャーナルを統合します。
+///
+/// この関数は、統合するデータファイルのパスを尋ね、まだ統合されていない項目をデータファイルに追加します。
+/// 追加する項目に締め済みの月の項目が含まれる場合は、統合しません。
+///
+/// #### 例
+///
+/// ```rust
+/// // ドキュメンテーションテストでFailになるのでコメントアウト
+/// // use kakeibo_app::services;
+/// // services::journal::run("store/data.json", "store/closed.json");
+/// ```
+///
+/// #### エラー
+///
+/// 不正な入力があった場合や、データの読み書きに失敗した場合は、`KakeiboError`を返します。
+pub fn run(file_path: &str, closed_path: &str) -> Result<()> {
+    let mut data = services::io::read_data_or_create_new_data(file_path)?;
+    let journal_path = input_journal_path()?;
+    let journal = services::io::read_data(&journal_path)?;
+
+    let result = merge(&data, journal);
+    if result.added.is_empty() {
+        println!("追加する項目はありません ({}件は統合済み)", result.skipped);
+        return Ok(());
+    }
+    let closed_months = services::close::ClosedMonths::load_or_default(closed_path)?;
+    if let Some(month) = closed_months.find_closed_month(&result.added) {
+        services::close::print_locked(month);
+        return Ok(());
+    }
+
+    let added = result.added.len();
+    data.extend(result.added);
+    services::io::assign_ids(&mut data);
+    services::io::write_to_json(&data, file_path)?;
+    println!("{}件の項目を統合しました ({}件は統合済み)", added, result.skipped);
+    Ok(())
+}
+
+/// ジャーナルの項目のうち、データにまだ無いものを取り出します。
+///
+/// 登録元（デバイスIDと連番）を持つ項目は、同じ登録元の項目がデータにあれば統合済みとみなします。
+/// 登録元を持たない古い項目は、内容がすべて同じ項目がデータにあれば統合済みとみなします。
+/// 同じ内容の項目が複数ある場合は、データにある件数を超えた分を追加します。
+/// 追加する項目のIDは未採番（`0`）に戻します。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::models::{Item, Category, ExpenseCategory, Origin, Money};
+/// use kakeibo_app::services::journal;
+/// use chrono::NaiveDate;
+///
+/// let new_item = || {
+///     let mut item = Item::new(
+///         String::from("ランチ"),
+///         Category::Expense(ExpenseCategory::Food),
+///         Money::new(1000),
+///         NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+///     );
+///     item.set_origin(Some(Origin::new(String::from("a1b2c3d4"), 1)));
+///     item
+/// };
+///
+/// let result = journal::merge(&[], vec![new_item()]);
+/// assert_eq!(result.added.len(), 1);
+/// assert_eq!(journal::merge(&result.added, vec![new_item()]).skipped, 1);
+/// ```
+pub fn merge(data: &[models::Item], journal: Vec<models::Item>) -> MergeResult {
+    let mut origins: HashSet<_> = data.iter().filter_map(|item| item.get_origin().cloned()).collect();
+    let mut unmatched: Vec<_> = data.iter().filter(|item| item.get_origin().is_none()).collect();
+    let mut added = Vec::new();
+    let mut skipped = 0;
+    for mut item in journal {
+        let merged = match item.get_origin() {
+            Some(origin) => !origins.insert(origin.clone()),
+            None => match unmatched.iter().position(|existing| existing.is_same_content(&item)) {
+                Some(index) => {
+                    unmatched.swap_remove(index);
+                    true
+                },
+                None => false,
+            },
+        };
+        if merged {
+            skipped += 1;
+        } else {
+            // IDは統合先のデータで採番し直す
+            item.set_id(0);
+            added.push(item);
+        }
+    }
+    MergeResult { added, skipped }
+}
+
+/// ユーザーに統合するデータファイルのパスを尋ね、文字列で返します。
+///
+/// #### エラー
+///
+/// 標準入力からの読み込みに失敗した場合、`KakeiboError`を返します。
+fn input_journal_path() -> Result<String> {
+    let journal_path = services::prompt::ask("統合するデータファイルのパスを入力してください")?;
+
+    Ok(journal_path.trim().to_string())
+}
+
+#[cfg(test)]
+mod journal_test {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn get_test_item(name: &str, origin: Option<(&str, u64)>) -> models::Item {
+        let mut item = models::Item::new(
+            name.to_string(),
+            models::Category::Expense(models::ExpenseCategory::Food),
+            models::Money::new(1000),
+            NaiveDate::from_ymd_opt(2023, 1, 1).unwrap()
+        );
+        item.set_origin(origin.map(|(device_id, seq)| models::Origin::new(device_id.to_string(), seq)));
+        item
+    }
+
+    #[test]
+    fn test_next_origin() {
+        let device = Device { device_id: "aaaa0001".to_string() };
+        let mut data = vec![
+            get_test_item("ランチ", Some(("aaaa0001", 1))),
+            get_test_item("ランチ", Some(("bbbb0002", 5))),
+            get_test_item("ランチ", None),
+        ];
+        assert_eq!(device.next_origin(&data), models::Origin::new("aaaa0001".to_string(), 2));
+
+        data.push(get_test_item("ランチ", Some(("aaaa0001", 3))));
+        assert_eq!(device.next_origin(&data).get_seq(), 4);
+        assert_eq!(device.next_origin(&[]).get_seq(), 1);
+    }
+
+    #[test]
+    fn test_merge() {
+        let data = vec![
+            get_test_item("ランチ", Some(("aaaa0001", 1))),
+            get_test_item("古い項目", None),
+        ];
+        let journal = vec![
+            // 別の端末で同じ内容を登録した場合は、別の項目として追加する
+            get_test_item("ランチ", Some(("bbbb0002", 1))),
+            get_test_item("ランチ", Some(("aaaa0001", 1))),
+            get_test_item("古い項目", None),
+            get_test_item("新しい項目", None),
+            get_test_item("新しい項目", None),
+        ];
+        let result = merge(&data, journal);
+
+        assert_eq!(result.added.len(), 3);
+        assert_eq!(result.added[0].get_origin().unwrap().get_device_id(), "bbbb0002");
+        assert_eq!(result.added[1].get_name(), "新しい項目");
+        assert_eq!(result.added[2].get_name(), "新しい項目");
+        assert_eq!(result.skipped, 2);
+    }
+}
@@ -0,0 +1,233 @@
+//! 複式簿記サービス
+//!
+//! このモジュールは、各項目を借方・貸方の2つの勘定科目を持つ仕訳とみなし、試算表と勘定元帳を出力する機能を提供します。
+//! 仕訳が設定されていない項目は、カテゴリから決まる既定の勘定科目（支出は「借方: 食費 / 貸方: 現金」など）で
+//! 記帳したものとみなします。登録時に勘定科目を指定するには、設定ファイルで`double_entry`を`true`にします。
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+
+use crate::config::Config;
+use crate::error::{KakeiboError, Result};
+use crate::models;
+use crate::services;
+
+/// 勘定元帳の1行を表す構造体
+///
+/// - `date`: 取引の日付
+/// - `name`: 項目の名前
+/// - `counter_account`: 相手の勘定科目
+/// - `debit`: 借方の金額
+/// - `credit`: 貸方の金額
+/// - `balance`: この行までの残高（借方 - 貸方）
+#[derive(Debug, PartialEq, Eq)]
+pub struct LedgerRow {
+    pub date: NaiveDate,
+    pub name: String,
+    pub counter_account: String,
+    pub debit: models::Money,
+    pub credit: models::Money,
+    pub balance: models::Money,
+}
+
+/// 試算表または勘定元帳を表示します。
+///
+/// この関数は、試算表と勘定元帳のどちらを表示するかを尋ね、勘定元帳の場合は対象の勘定科目を尋ねて表示します。
+///
+/// #### 例
+///
+/// ```rust
+/// // ドキュメンテーションテストでFailになるのでコメントアウト
+/// // use kakeibo_app::config::Config;
+/// // use kakeibo_app::services;
+/// // services::ledger::run("store/data.json", &Config::default());
+/// ```
+///
+/// #### エラー
+///
+/// 不正な入力があった場合、`KakeiboError`を返します。
+pub fn run(file_path: &str, config: &Config) -> Result<()> {
+    let data = services::io::read_data(file_path)?;
+    let action = services::prompt::ask("t:試算表 / l:勘定元帳")?;
+    match action.trim() {
+        "t" | "T" => print_trial_balance(&create_trial_balance(&data), config),
+        "l" | "L" => {
+            let trial_balance = create_trial_balance(&data);
+            let accounts: Vec<_> = trial_balance.keys().map(|account| account.as_str()).collect();
+            let account = services::prompt::ask(&format!("勘定科目を入力してください ({})", accounts.join(", ")))?;
+            print_ledger(account.trim(), &create_ledger(&data, account.trim()), config);
+        },
+        _ => return Err(KakeiboError::invalid_input("操作はtまたはlで入力してください")),
+    }
+    Ok(())
+}
+
+/// 勘定科目ごとに借方と貸方の合計を集計し、試算表を作成します。
+///
+/// 勘定科目をキーとし、(借方合計, 貸方合計)を値とするマップを返します。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+/// use kakeibo_app::services::ledger;
+/// use chrono::NaiveDate;
+///
+/// let data = vec![Item::new(
+///     String::from("ランチ"),
+///     Category::Expense(ExpenseCategory::Food),
+///     Money::new(1000),
+///     NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+/// )];
+/// let trial_balance = ledger::create_trial_balance(&data);
+/// assert_eq!(trial_balance["食費"], (Money::new(1000), Money::ZERO));
+/// assert_eq!(trial_balance["現金"], (Money::ZERO, Money::new(1000)));
+/// ```
+pub fn create_trial_balance(data: &[models::Item]) -> BTreeMap<String, (models::Money, models::Money)> {
+    let mut trial_balance: BTreeMap<String, (models::Money, models::Money)> = BTreeMap::new();
+    for item in data {
+        let (debit, credit) = item.get_accounts();
+        trial_balance.entry(debit.to_string()).or_default().0 += item.get_price();
+        trial_balance.entry(credit.to_string()).or_default().1 += item.get_price();
+    }
+    trial_balance
+}
+
+/// 指定された勘定科目の取引を日付順に並べ、勘定元帳を作成します。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::models::{Item, Category, IncomeCategory, Money};
+/// use kakeibo_app::services::ledger;
+/// use chrono::NaiveDate;
+///
+/// let data = vec![Item::new(
+///     String::from("給与"),
+///     Category::Income(IncomeCategory::Salary),
+///     Money::new(300000),
+///     NaiveDate::from_ymd_opt(2023, 1, 25).unwrap(),
+/// )];
+/// let rows = ledger::create_ledger(&data, "現金");
+/// assert_eq!(rows[0].counter_account, "給与");
+/// assert_eq!(rows[0].balance, Money::new(300000));
+/// ```
+pub fn create_ledger(data: &[models::Item], account: &str) -> Vec<LedgerRow> {
+    let mut items: Vec<_> = data.iter().filter(|item| {
+        let (debit, credit) = item.get_accounts();
+        debit == account || credit == account
+    }).collect();
+    items.sort_by_key(|item| item.get_date());
+
+    let mut balance = models::Money::ZERO;
+    items.into_iter().map(|item| {
+        let (debit_account, credit_account) = item.get_accounts();
+        let price = item.get_price();
+        let debit = if debit_account == account { price } else { models::Money::ZERO };
+        let credit = if credit_account == account { price } else { models::Money::ZERO };
+        balance += debit - credit;
+        LedgerRow {
+            date: item.get_date(),
+            name: item.get_name().to_string(),
+            counter_account: if debit_account == account { credit_account } else { debit_account }.to_string(),
+            debit,
+            credit,
+            balance,
+        }
+    }).collect()
+}
+
+/// 試算表を出力します。
+///
+/// 最後に借方と貸方の総合計を出力します。
+fn print_trial_balance(trial_balance: &BTreeMap<String, (models::Money, models::Money)>, config: &Config) {
+    println!("試算表");
+    for (account, (debit, credit)) in trial_balance {
+        println!(
+            "  {}: 借方 {} / 貸方 {} / 残高 {}",
+            account,
+            config.format_currency(*debit),
+            config.format_currency(*credit),
+            config.format_currency(*debit - *credit)
+        );
+    }
+    let debit_total: models::Money = trial_balance.values().map(|(debit, _)| debit).sum();
+    let credit_total: models::Money = trial_balance.values().map(|(_, credit)| credit).sum();
+    println!("  合計: 借方 {} / 貸方 {}", config.format_currency(debit_total), config.format_currency(credit_total));
+}
+
+/// 勘定元帳を出力します。
+fn print_ledger(account: &str, rows: &[LedgerRow], config: &Config) {
+    if rows.is_empty() {
+        println!("{}の取引はありません", account);
+        return;
+    }
+    println!("{}の勘定元帳", account);
+    for row in rows {
+        println!(
+            "  {} {} ({}) 借方 {} / 貸方 {} / 残高 {}",
+            row.date,
+            row.name,
+            row.counter_account,
+            config.format_currency(row.debit),
+            config.format_currency(row.credit),
+            config.format_currency(row.balance)
+        );
+    }
+}
+
+#[cfg(test)]
+mod ledger_test {
+    use super::*;
+
+    fn get_test_data() -> Vec<models::Item> {
+        let mut purchase = models::Item::new(
+            "プリンタ用紙".to_string(),
+            models::Category::Expense(models::ExpenseCategory::Other),
+            models::Money::new(2000),
+            NaiveDate::from_ymd_opt(2023, 1, 10).unwrap()
+        );
+        purchase.set_entry(Some(models::JournalEntry::new("消耗品費".to_string(), "普通預金".to_string())));
+        vec![
+            models::Item::new(
+                "給与".to_string(),
+                models::Category::Income(models::IncomeCategory::Salary),
+                models::Money::new(300000),
+                NaiveDate::from_ymd_opt(2023, 1, 25).unwrap()
+            ),
+            purchase,
+            models::Item::new(
+                "ランチ".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                models::Money::new(1000),
+                NaiveDate::from_ymd_opt(2023, 1, 5).unwrap()
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_create_trial_balance() {
+        let trial_balance = create_trial_balance(&get_test_data());
+
+        assert_eq!(trial_balance.len(), 5);
+        assert_eq!(trial_balance["現金"], (models::Money::new(300000), models::Money::new(1000)));
+        assert_eq!(trial_balance["消耗品費"], (models::Money::new(2000), models::Money::ZERO));
+        assert_eq!(trial_balance["普通預金"], (models::Money::ZERO, models::Money::new(2000)));
+        let debit_total: models::Money = trial_balance.values().map(|(debit, _)| debit).sum();
+        let credit_total: models::Money = trial_balance.values().map(|(_, credit)| credit).sum();
+        assert_eq!(debit_total, credit_total);
+    }
+
+    #[test]
+    fn test_create_ledger() {
+        let rows = create_ledger(&get_test_data(), "現金");
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].name, "ランチ");
+        assert_eq!(rows[0].counter_account, "食費");
+        assert_eq!((rows[0].debit, rows[0].credit, rows[0].balance), (models::Money::ZERO, models::Money::new(1000), models::Money::new(-1000)));
+        assert_eq!((rows[1].debit, rows[1].credit, rows[1].balance), (models::Money::new(300000), models::Money::ZERO, models::Money::new(299000)));
+        assert!(create_ledger(&get_test_data(), "売上").is_empty());
+    }
+}
@@ -0,0 +1,493 @@
+//! 一覧表示サービス
+//!
+//! このモジュールは、登録した項目を日付順に表形式で一覧表示する機能を提供します。
+//! 月・カテゴリ・品目名（部分一致）で絞り込むことができます。
+//! 並び順は「日付→金額→品目名」のように複数のキーで指定でき、すべてのキーが同じ項目はID順に並べます。
+//! 一覧には項目のIDを表示します。IDは`delete`サブコマンドで項目を指定するときに使います。
+//! また、期間内の支出を金額の大きい順に上位だけ表示することもできます（`top`サブコマンド）。
+
+use std::cmp::Ordering;
+
+use chrono::NaiveDate;
+use unicode_width::UnicodeWidthStr;
+
+use crate::config::Config;
+use crate::error::{KakeiboError, Result};
+use crate::models;
+use crate::services;
+
+/// 支出の上位を表示するときの既定の件数
+pub const DEFAULT_TOP_COUNT: usize = 10;
+
+/// 一覧の絞り込み条件を表す構造体
+///
+/// - `month`: 対象の月（その月の1日）
+/// - `category`: カテゴリ名（例: 食費）
+/// - `name`: 品目名に含まれる文字列
+///
+/// `None`の条件では絞り込みません。
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ListFilter {
+    pub month: Option<NaiveDate>,
+    pub category: Option<String>,
+    pub name: Option<String>,
+}
+
+impl ListFilter {
+    /// 絞り込み条件を作成する
+    ///
+    /// 月は`yyyy-mm`の形式で指定します。空文字列の条件は指定しなかったものとみなします。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::services::list::ListFilter;
+    /// use chrono::NaiveDate;
+    ///
+    /// let filter = ListFilter::new(Some("2023-01"), Some("食費"), None).unwrap();
+    /// assert_eq!(filter.month, NaiveDate::from_ymd_opt(2023, 1, 1));
+    /// ```
+    ///
+    /// #### エラー
+    ///
+    /// 月の形式が不正な場合は、`KakeiboError::InvalidInput`を返します。
+    pub fn new(month: Option<&str>, category: Option<&str>, name: Option<&str>) -> Result<Self> {
+        Ok(ListFilter {
+            month: non_empty(month).map(parse_month).transpose()?,
+            category: non_empty(category).map(str::to_string),
+            name: non_empty(name).map(str::to_string),
+        })
+    }
+
+    /// 項目が絞り込み条件をすべて満たすかどうかを判定する
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+    /// use kakeibo_app::services::list::ListFilter;
+    /// use chrono::NaiveDate;
+    ///
+    /// let item = Item::new(
+    ///     String::from("ランチ"),
+    ///     Category::Expense(ExpenseCategory::Food),
+    ///     Money::new(1000),
+    ///     NaiveDate::from_ymd_opt(2023, 1, 5).unwrap(),
+    /// );
+    /// assert!(ListFilter::new(Some("2023-01"), None, Some("ラン")).unwrap().matches(&item));
+    /// assert!(!ListFilter::new(None, Some("趣味"), None).unwrap().matches(&item));
+    /// ```
+    pub fn matches(&self, item: &models::Item) -> bool {
+        self.month.is_none_or(|month| month == item.get_first_day())
+            && self.category.as_ref().is_none_or(|category| item.get_item_category().get_name() == category)
+            && self.name.as_ref().is_none_or(|name| item.get_name().contains(name.as_str()))
+    }
+}
+
+/// 並び替えに使う項目を表す列挙型
+///
+/// - `Date`: 日付
+/// - `Price`: 金額
+/// - `Name`: 品目名
+/// - `Category`: カテゴリ
+/// - `Id`: ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortField {
+    Date,
+    Price,
+    Name,
+    Category,
+    Id,
+}
+
+/// 並び替えのキーを表す構造体
+///
+/// - `field`: 並び替えに使う項目
+/// - `descending`: 降順にするかどうか
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortKey {
+    pub field: SortField,
+    pub descending: bool,
+}
+
+impl SortKey {
+    /// カンマ区切りの並び替えのキーを解析する
+    ///
+    /// キーは`date`・`price`・`name`・`category`・`id`のいずれかで、先頭に`-`を付けると降順になります。
+    /// 空文字列の場合は、日付順（`date`）とします。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::services::list::{SortField, SortKey};
+    ///
+    /// let keys = SortKey::parse_keys("date,-price,name").unwrap();
+    /// assert_eq!(keys[1], SortKey { field: SortField::Price, descending: true });
+    /// assert_eq!(SortKey::parse_keys("").unwrap(), vec![SortKey { field: SortField::Date, descending: false }]);
+    /// ```
+    ///
+    /// #### エラー
+    ///
+    /// 不明なキーが含まれる場合は、`KakeiboError::InvalidInput`を返します。
+    pub fn parse_keys(text: &str) -> Result<Vec<SortKey>> {
+        let mut keys = Vec::new();
+        for key in text.split(',').map(str::trim).filter(|key| !key.is_empty()) {
+            let (descending, name) = match key.strip_prefix('-') {
+                Some(name) => (true, name),
+                None => (false, key),
+            };
+            let field = match name {
+                "date" => SortField::Date,
+                "price" => SortField::Price,
+                "name" => SortField::Name,
+                "category" => SortField::Category,
+                "id" => SortField::Id,
+                _ => return Err(KakeiboError::InvalidInput(format!(
+                    "並び替えのキー{}は不正です (date, price, name, category, idのいずれかで入力してください)",
+                    key
+                ))),
+            };
+            keys.push(SortKey { field, descending });
+        }
+        if keys.is_empty() {
+            keys.push(SortKey { field: SortField::Date, descending: false });
+        }
+        Ok(keys)
+    }
+
+    /// 2つの項目をこのキーで比較する
+    fn compare(&self, a: &models::Item, b: &models::Item) -> Ordering {
+        let ordering = match self.field {
+            SortField::Date => a.get_date().cmp(&b.get_date()),
+            SortField::Price => a.get_price().cmp(&b.get_price()),
+            SortField::Name => a.get_name().cmp(b.get_name()),
+            SortField::Category => a.get_item_category().cmp(b.get_item_category()),
+            SortField::Id => a.get_id().cmp(&b.get_id()),
+        };
+        if self.descending { ordering.reverse() } else { ordering }
+    }
+}
+
+/// `yyyy-mm`形式の文字列を月の最初の日に変換します。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::services::list;
+/// use chrono::NaiveDate;
+///
+/// assert_eq!(list::parse_month("2023-01").unwrap(), NaiveDate::from_ymd_opt(2023, 1, 1).unwrap());
+/// assert!(list::parse_month("2023/01").is_err());
+/// ```
+///
+/// #### エラー
+///
+/// 形式が不正な場合は、`KakeiboError::InvalidInput`を返します。
+pub fn parse_month(month: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(&format!("{}-01", month.trim()), "%Y-%m-%d")
+        .map_err(|_| KakeiboError::invalid_input("月はyyyy-mmの形式で入力してください"))
+}
+
+/// 前後の空白を取り除き、空文字列の場合は`None`にします。
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|value| !value.is_empty())
+}
+
+/// 絞り込み条件に一致する項目を一覧表示します。
+///
+/// 項目は`sort_keys`の順に並べ、最後に件数と収支の合計を表示します。
+/// 月で絞り込む場合は、その月の項目だけを読み込みます（`services::io::read_data_in_period`）。
+/// ただし、IDが採番されていない古いデータファイルの場合は、IDを全件の並び順で採番するため、全件を読み込みます。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// use kakeibo_app::services::list::ListFilter;
+/// use kakeibo_app::services::list::SortKey;
+/// let sort_keys = SortKey::parse_keys("date,-price").unwrap();
+/// services::list::run("store/data.json", &ListFilter::default(), &sort_keys, &Config::default()).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// データの読み込みに失敗した場合は、`KakeiboError`を返します。
+pub fn run(file_path: &str, filter: &ListFilter, sort_keys: &[SortKey], config: &Config) -> Result<()> {
+    let data = match filter.month {
+        Some(month) if services::io::data_exists(file_path) => {
+            match services::io::read_data_in_period(&[file_path], month, services::summarize::get_last_day(month)) {
+                Ok(data) if data.iter().any(|item| item.get_id() == 0) => services::io::read_data(file_path)?,
+                Err(KakeiboError::NoData) => Vec::new(),
+                result => result?,
+            }
+        },
+        _ => services::io::read_data_or_create_new_data(file_path)?,
+    };
+    let rows = get_rows(&data, filter, sort_keys);
+    if rows.is_empty() {
+        println!("条件に一致する項目はありません");
+        return Ok(());
+    }
+
+    print_table(&create_table(&rows, config));
+
+    let total: models::Money = rows.iter().map(|item| item.get_price_for_summary()).sum();
+    println!("{}件 / 合計: {}", rows.len(), config.format_currency(total));
+    Ok(())
+}
+
+/// 絞り込みの条件と並び順をユーザーに尋ね、一致する項目を一覧表示します。
+///
+/// #### 例
+///
+/// ```rust
+/// // ドキュメンテーションテストでFailになるのでコメントアウト
+/// // use kakeibo_app::config::Config;
+/// // use kakeibo_app::services;
+/// // services::list::run_interactive("store/data.json", &Config::default());
+/// ```
+///
+/// #### エラー
+///
+/// 不正な入力があった場合、`KakeiboError`を返します。
+pub fn run_interactive(file_path: &str, config: &Config) -> Result<()> {
+    let month = services::prompt::ask("月をyyyy-mmの形式で入力してください (絞り込まない場合は空行)")?;
+    let category = services::prompt::ask("カテゴリを入力してください (例: 食費, 絞り込まない場合は空行)")?;
+    let name = services::prompt::ask("品目名に含まれる文字列を入力してください (絞り込まない場合は空行)")?;
+    let sort_keys = services::prompt::ask("並び順をカンマ区切りで入力してください (例: date,-price,name, 日付順の場合は空行)")?;
+
+    let filter = ListFilter::new(Some(&month), Some(&category), Some(&name))?;
+    run(file_path, &filter, &SortKey::parse_keys(&sort_keys)?, config)
+}
+
+/// 期間内の支出を、金額の大きい順に上位`count`件だけ一覧表示します。
+///
+/// 予算を超えた原因になった大きな出費を探す場合に使います。`from`・`to`を`None`にした場合は、期間の端を区切りません。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// use chrono::NaiveDate;
+/// services::list::run_top("store/data.json", 10, NaiveDate::from_ymd_opt(2023, 1, 1), None, &Config::default()).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// データの読み込みに失敗した場合は、`KakeiboError`を返します。
+pub fn run_top(file_path: &str, count: usize, from: Option<NaiveDate>, to: Option<NaiveDate>, config: &Config) -> Result<()> {
+    let data = services::io::read_data(file_path)?;
+    let rows = get_top_expenses(&data, count, from, to);
+    if rows.is_empty() {
+        println!("期間内の支出はありません");
+        return Ok(());
+    }
+
+    print_table(&create_table(&rows, config));
+
+    let total: models::Money = rows.iter().map(|item| item.get_price()).sum();
+    println!("上位{}件の支出の合計: {}", rows.len(), config.format_currency(total));
+    Ok(())
+}
+
+/// 期間と件数をユーザーに尋ね、期間内の支出を金額の大きい順に一覧表示します。
+///
+/// #### 例
+///
+/// ```rust
+/// // ドキュメンテーションテストでFailになるのでコメントアウト
+/// // use kakeibo_app::config::Config;
+/// // use kakeibo_app::services;
+/// // services::list::run_top_interactive("store/data.json", &Config::default());
+/// ```
+///
+/// #### エラー
+///
+/// 不正な入力があった場合、`KakeiboError`を返します。
+pub fn run_top_interactive(file_path: &str, config: &Config) -> Result<()> {
+    let from = services::prompt::ask("期間の開始日をyyyy-mm-ddの形式で入力してください (区切らない場合は空行)")?;
+    let to = services::prompt::ask("期間の終了日をyyyy-mm-ddの形式で入力してください (区切らない場合は空行)")?;
+    let count = services::prompt::ask(&format!("表示する件数を入力してください (空行で{}件)", DEFAULT_TOP_COUNT))?;
+
+    let from = non_empty(Some(&from)).map(services::summarize::parse_date).transpose()?;
+    let to = non_empty(Some(&to)).map(services::summarize::parse_date).transpose()?;
+    let count = match non_empty(Some(&count)) {
+        Some(count) => count.parse().map_err(|_| KakeiboError::invalid_input("件数は数値で入力してください"))?,
+        None => DEFAULT_TOP_COUNT,
+    };
+    run_top(file_path, count, from, to, config)
+}
+
+/// 期間内（両端を含む）の支出を、金額の大きい順に上位`count`件だけ取得します。
+///
+/// 金額が同じ支出は、日付順・ID順に並べます。
+fn get_top_expenses(data: &[models::Item], count: usize, from: Option<NaiveDate>, to: Option<NaiveDate>) -> Vec<&models::Item> {
+    let mut rows: Vec<_> = data.iter()
+        .filter(|item| matches!(item.get_item_category(), models::Category::Expense(_)))
+        .filter(|item| from.is_none_or(|from| from <= item.get_date()) && to.is_none_or(|to| item.get_date() <= to))
+        .collect();
+    rows.sort_by(|a, b| {
+        b.get_price().cmp(&a.get_price())
+            .then_with(|| a.get_date().cmp(&b.get_date()))
+            .then_with(|| a.get_id().cmp(&b.get_id()))
+    });
+    rows.truncate(count);
+    rows
+}
+
+/// 絞り込み条件に一致する項目を並び替えのキーの順で取得します。
+///
+/// 前のキーが同じ項目は次のキーで比較し、すべてのキーが同じ項目はID順に並べます。
+fn get_rows<'a>(data: &'a [models::Item], filter: &ListFilter, sort_keys: &[SortKey]) -> Vec<&'a models::Item> {
+    let mut rows: Vec<_> = data.iter().filter(|item| filter.matches(item)).collect();
+    rows.sort_by(|a, b| {
+        sort_keys.iter()
+            .fold(Ordering::Equal, |ordering, key| ordering.then_with(|| key.compare(a, b)))
+            .then_with(|| a.get_id().cmp(&b.get_id()))
+    });
+    rows
+}
+
+/// 項目を表の行（ID・日付・品目名・カテゴリ・金額）に変換します。
+fn create_table(rows: &[&models::Item], config: &Config) -> Vec<[String; 5]> {
+    rows.iter().map(|item| [
+        item.get_id().to_string(),
+        item.get_date().to_string(),
+        item.get_name().to_string(),
+        config.format_category(item.get_item_category()),
+        config.format_item_price(item),
+    ]).collect()
+}
+
+/// 表を出力します。
+///
+/// IDと金額の列は右寄せ、それ以外の列は左寄せにします。列の幅は全角文字を2文字分として揃えます。
+fn print_table(table: &[[String; 5]]) {
+    let header = ["ID", "日付", "品目名", "カテゴリ", "金額"];
+    let mut widths = header.map(|column| column.width());
+    for row in table {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.width());
+        }
+    }
+
+    let format_row = |row: [&str; 5]| -> String {
+        row.iter().zip(widths).enumerate().map(|(column, (cell, width))| {
+            let padding = " ".repeat(width - cell.width());
+            if column == 0 || column == 4 {
+                format!("{}{}", padding, cell)
+            } else {
+                format!("{}{}", cell, padding)
+            }
+        }).collect::<Vec<_>>().join(" | ")
+    };
+    println!("{}", format_row(header));
+    println!("{}", widths.map(|width| "-".repeat(width)).join("-+-"));
+    for row in table {
+        println!("{}", format_row(row.each_ref().map(String::as_str)));
+    }
+}
+
+#[cfg(test)]
+mod list_test {
+    use super::*;
+
+    fn get_test_data() -> Vec<models::Item> {
+        vec![
+            models::Item::new(
+                "ランチ".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                models::Money::new(1000),
+                NaiveDate::from_ymd_opt(2023, 2, 1).unwrap()
+            ),
+            models::Item::new(
+                "給料".to_string(),
+                models::Category::Income(models::IncomeCategory::Salary),
+                models::Money::new(300000),
+                NaiveDate::from_ymd_opt(2023, 1, 25).unwrap()
+            ),
+            models::Item::new(
+                "ディナー".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                models::Money::new(5000),
+                NaiveDate::from_ymd_opt(2023, 1, 20).unwrap()
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_new() {
+        assert_eq!(ListFilter::new(None, Some(""), Some(" ")).unwrap(), ListFilter::default());
+        assert!(ListFilter::new(Some("2023/01"), None, None).is_err());
+    }
+
+    #[test]
+    fn test_get_rows() {
+        let mut test_data = get_test_data();
+        services::io::assign_ids(&mut test_data);
+
+        let date_order = SortKey::parse_keys("").unwrap();
+        let rows = get_rows(&test_data, &ListFilter::default(), &date_order);
+        assert_eq!(rows.iter().map(|item| item.get_id()).collect::<Vec<_>>(), vec![3, 2, 1]);
+
+        let filter = ListFilter::new(Some("2023-01"), Some("食費"), None).unwrap();
+        let rows = get_rows(&test_data, &filter, &date_order);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].get_name(), "ディナー");
+
+        let filter = ListFilter::new(None, None, Some("ンチ")).unwrap();
+        assert_eq!(get_rows(&test_data, &filter, &date_order)[0].get_id(), 1);
+    }
+
+    #[test]
+    fn test_get_top_expenses() {
+        let data = get_test_data();
+        let rows: Vec<_> = get_top_expenses(&data, 10, None, None).iter().map(|item| item.get_name()).collect();
+        assert_eq!(rows, vec!["ディナー", "ランチ"]);
+
+        let rows: Vec<_> = get_top_expenses(&data, 1, NaiveDate::from_ymd_opt(2023, 2, 1), None).iter().map(|item| item.get_name()).collect();
+        assert_eq!(rows, vec!["ランチ"]);
+        assert!(get_top_expenses(&data, 10, None, NaiveDate::from_ymd_opt(2023, 1, 19)).is_empty());
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        assert_eq!(SortKey::parse_keys(" -category , id").unwrap(), vec![
+            SortKey { field: SortField::Category, descending: true },
+            SortKey { field: SortField::Id, descending: false },
+        ]);
+        assert!(SortKey::parse_keys("date,amount").is_err());
+    }
+
+    #[test]
+    fn test_get_rows_with_sort_keys() {
+        let mut test_data = get_test_data();
+        test_data.push(models::Item::new(
+            "朝食".to_string(),
+            models::Category::Expense(models::ExpenseCategory::Food),
+            models::Money::new(5000),
+            NaiveDate::from_ymd_opt(2023, 1, 20).unwrap()
+        ));
+        test_data.push(models::Item::new(
+            "ランチ".to_string(),
+            models::Category::Expense(models::ExpenseCategory::Food),
+            models::Money::new(5000),
+            NaiveDate::from_ymd_opt(2023, 1, 20).unwrap()
+        ));
+        services::io::assign_ids(&mut test_data);
+        let ids = |keys: &str| -> Vec<u64> {
+            get_rows(&test_data, &ListFilter::default(), &SortKey::parse_keys(keys).unwrap())
+                .iter()
+                .map(|item| item.get_id())
+                .collect()
+        };
+
+        // 同じ日付の項目はIDの順に並ぶ
+        assert_eq!(ids("date"), vec![3, 4, 5, 2, 1]);
+        assert_eq!(ids("date,price,name"), vec![3, 5, 4, 2, 1]);
+        assert_eq!(ids("-price,name"), vec![2, 3, 5, 4, 1]);
+        assert_eq!(ids("price,-date"), vec![1, 3, 4, 5, 2]);
+    }
+}
@@ -0,0 +1,191 @@
+//! 付け替えサービス
+//!
+//! このモジュールは、カテゴリのリネーム・統合に合わせて、登録済みの項目のカテゴリを一括で付け替える機能を提供します。
+
+use crate::config::Config;
+use crate::models;
+use crate::services;
+use crate::error::{KakeiboError, Result};
+
+/// 項目のカテゴリを一括で付け替えます。
+///
+/// この関数は、付け替え元と付け替え先のカテゴリを尋ね、対象件数を表示したうえで、確認後にJSONファイルへ書き込みます。
+/// 対象の項目に締め済みの月の項目が含まれる場合は、付け替えません。
+///
+/// #### 例
+///
+/// ```rust
+/// // ドキュメンテーションテストでFailになるのでコメントアウト
+/// // use kakeibo_app::config::Config;
+/// // use kakeibo_app::services;
+/// // services::migrate::run("store/data.json", "store/closed.json", &Config::default());
+/// ```
+///
+/// #### エラー
+///
+/// 不正な入力があった場合や、データの読み書きに失敗した場合は、`KakeiboError`を返します。
+pub fn run(file_path: &str, closed_path: &str, config: &Config) -> Result<()> {
+    println!("カテゴリの付け替えを行います");
+    let mut data = services::io::read_data(file_path)?;
+
+    let from = input_category("付け替え元のカテゴリを入力してください", config)?;
+    let to = input_category("付け替え先のカテゴリを入力してください", config)?;
+    if from == to {
+        println!("付け替え元と付け替え先が同じです");
+        return Ok(());
+    }
+
+    let count = count_items(&data, &from);
+    if count == 0 {
+        println!("{}の項目はありません", config.format_category(&from));
+        return Ok(());
+    }
+    let closed_months = services::close::ClosedMonths::load_or_default(closed_path)?;
+    if let Some(month) = closed_months.find_closed_month(data.iter().filter(|item| item.get_item_category() == &from)) {
+        services::close::print_locked(month);
+        return Ok(());
+    }
+
+    println!(
+        "{}件の項目を{}から{}に付け替えます",
+        count,
+        config.format_category(&from),
+        config.format_category(&to)
+    );
+    if !input_confirmation("付け替えてよろしいですか (y/n)")? {
+        println!("付け替えを中止しました");
+        return Ok(());
+    }
+
+    migrate_category(&mut data, &from, &to);
+    services::io::write_to_json(&data, file_path)?;
+    println!("{}件の項目を付け替えました", count);
+    Ok(())
+}
+
+/// 指定されたカテゴリの項目数を数えます。
+fn count_items(data: &[models::Item], category: &models::Category) -> usize {
+    data.iter().filter(|item| item.get_item_category() == category).count()
+}
+
+/// 指定されたカテゴリの項目を別のカテゴリに付け替えます。
+///
+/// 取り込み時にカテゴリを付け直す対象とした項目は、付け替えた時点で対象から外します。
+fn migrate_category(data: &mut [models::Item], from: &models::Category, to: &models::Category) {
+    for item in data.iter_mut().filter(|item| item.get_item_category() == from) {
+        item.set_category(to.clone());
+        item.remove_extra(services::import::NEEDS_CATEGORY_KEY);
+    }
+}
+
+/// ユーザーにカテゴリを尋ね、`Category`で返します。
+///
+/// #### エラー
+///
+/// 不正な入力があった場合、`KakeiboError`を返します。
+fn input_category(message: &str, config: &Config) -> Result<models::Category> {
+    let index = services::prompt::ask(&format!("{}\n({})", message, format_category_choices(config)))?;
+    parse_category(&index)
+}
+
+/// カテゴリの選択肢を "0:💴給与(収入), 1:..." の形式の文字列にします。
+pub(crate) fn format_category_choices(config: &Config) -> String {
+    let choices: Vec<_> = models::Category::get_all().iter().enumerate().map(|(index, category)| {
+        let register_type = match category {
+            models::Category::Income(_) => "収入",
+            models::Category::Expense(_) => "支出",
+            models::Category::Transfer(_) => "振替",
+        };
+        format!("{}:{}({})", index, config.format_category(category), register_type)
+    }).collect();
+    choices.join(", ")
+}
+
+/// 選択肢の番号を解析し、`Category`で返します。
+///
+/// #### エラー
+///
+/// 番号が数値でない場合や範囲外の場合は、`KakeiboError::InvalidInput`を返します。
+pub(crate) fn parse_category(index: &str) -> Result<models::Category> {
+    let categories = models::Category::get_all();
+    let index: usize = index
+                        .trim()
+                        .parse()
+                        .map_err(|_| KakeiboError::invalid_input("カテゴリは数値で入力してください"))?;
+
+    categories
+        .get(index)
+        .cloned()
+        .ok_or_else(|| KakeiboError::InvalidInput(format!("カテゴリは0〜{}で入力してください", categories.len() - 1)))
+}
+
+/// ユーザーに付け替えてよいかを尋ね、真偽値で返します。
+///
+/// `y`以外が入力された場合は、付け替えないものとして`false`を返します。
+///
+/// #### エラー
+///
+/// 標準入力からの読み込みに失敗した場合、`KakeiboError`を返します。
+fn input_confirmation(message: &str) -> Result<bool> {
+    let answer = services::prompt::ask(message)?;
+
+    Ok(matches!(answer.trim(), "y" | "Y"))
+}
+
+#[cfg(test)]
+mod migrate_test {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn get_test_data() -> Vec<models::Item> {
+        vec![
+            models::Item::new(
+                "映画".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Other),
+                models::Money::new(2000),
+                NaiveDate::from_ymd_opt(2022, 1, 10).unwrap()
+            ),
+            models::Item::new(
+                "外食".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                models::Money::new(3000),
+                NaiveDate::from_ymd_opt(2022, 1, 15).unwrap()
+            ),
+            models::Item::new(
+                "ライブ".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Other),
+                models::Money::new(8000),
+                NaiveDate::from_ymd_opt(2022, 2, 1).unwrap()
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_count_items() {
+        let test_data = get_test_data();
+        let category = models::Category::Expense(models::ExpenseCategory::Other);
+
+        assert_eq!(count_items(&test_data, &category), 2);
+    }
+
+    #[test]
+    fn test_migrate_category() {
+        let mut test_data = get_test_data();
+        let from = models::Category::Expense(models::ExpenseCategory::Other);
+        let to = models::Category::Expense(models::ExpenseCategory::Hobby);
+        test_data[0].set_extra(services::import::NEEDS_CATEGORY_KEY.to_string(), serde_json::Value::Bool(true));
+        migrate_category(&mut test_data, &from, &to);
+
+        assert_eq!(count_items(&test_data, &from), 0);
+        assert_eq!(count_items(&test_data, &to), 2);
+        assert_eq!(test_data[1].get_item_category(), &models::Category::Expense(models::ExpenseCategory::Food));
+        assert!(!services::import::needs_category(&test_data[0]));
+    }
+
+    #[test]
+    fn test_parse_category() {
+        assert_eq!(parse_category(" 3\n").unwrap(), models::Category::Expense(models::ExpenseCategory::Food));
+        assert_eq!(parse_category("x").unwrap_err().to_string(), "カテゴリは数値で入力してください");
+        assert_eq!(parse_category("99").unwrap_err().to_string(), "カテゴリは0〜5で入力してください");
+    }
+}
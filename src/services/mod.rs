@@ -4,8 +4,80 @@
 //! - バリデーション機能
 //! - 登録サービス
 //! - 集計サービス
+//! - 分析サービス
+//! - フィルタ機能
+//! - 削除サービス
+//! - 付け替えサービス
+//! - サンプルデータ生成サービス
+//! - ピン留めサービス
+//! - 検索サービス
+//! - 締めサービス
+//! - アーカイブサービス
+//! - ジャーナル統合サービス
+//! - ダイジェストサービス
+//! - 当日サービス
+//! - 複式簿記サービス
+//! - 会計ソフト出力サービス
+//! - 一覧表示サービス
+//! - 貼り付け登録サービス
+//! - ヒートマップサービス
+//! - 年次レポートサービス
+//! - 支払先管理サービス
+//! - 定期取引サービス
+//! - 予算サービス
+//! - 監査用スナップショットサービス
+//! - 期間比較サービス
+//! - 統計サービス
+//! - フォルダ監視サービス
+//! - 相場取得サービス
+//! - エクスポートサービス
+//! - 振り返りサービス
+//! - 累計トラッカーサービス
+//! - 取り込みサービス
+//! - カレンダー機能
+//! - 棒グラフ機能
+//! - 計測機能
+//! - 対話プロンプト機能
+//! - 複数選択機能
 
 pub mod validate;
 pub mod io;
 pub mod register;
-pub mod summarize;
\ No newline at end of file
+pub mod summarize;
+pub mod analyze;
+pub mod filter;
+pub mod delete;
+pub mod migrate;
+pub mod generate;
+pub mod pin;
+pub mod search;
+pub mod close;
+pub mod archive;
+pub mod journal;
+pub mod digest;
+pub mod today;
+pub mod ledger;
+pub mod accounting;
+pub mod list;
+pub mod paste;
+pub mod heatmap;
+#[cfg(feature = "pdf")]
+pub mod report;
+pub mod payee;
+pub mod recurring;
+pub mod budget;
+pub mod snapshot;
+pub mod compare;
+pub mod stats;
+#[cfg(feature = "watch")]
+pub mod watch;
+pub mod quote;
+pub mod export;
+pub mod review;
+pub mod tracker;
+pub mod import;
+pub mod calendar;
+pub mod chart;
+pub mod timing;
+pub mod prompt;
+pub mod picker;
\ No newline at end of file
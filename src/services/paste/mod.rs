@@ -0,0 +1,182 @@
+//! 貼り付け登録サービス
+//!
+//! このモジュールは、スマートフォンのメモや表計算ソフトからコピーした表形式のテキストを貼り付け、
+//! 複数件の支出をまとめて登録する機能を提供します。
+//! 1行を1件とし、各行から「5/2 スタバ 680円」のように日付・品目名・金額を抽出します（列の区切りはタブまたは空白）。
+//!
+//! クリップボードの内容は、端末への貼り付けのほか、`pbpaste | kakeibo paste`のように標準入力から渡すこともできます。
+
+use chrono::{Local, NaiveDate};
+
+use crate::config::Config;
+use crate::error::Result;
+use crate::models;
+use crate::services;
+
+/// 貼り付けたテキストから支出をまとめて登録します。
+///
+/// この関数は、空行（または入力の終わり）までのテキストを読み取り、登録候補を一覧表示したうえで、確認後に登録します。
+/// `assume_yes`が`true`の場合は、確認せずに登録します（標準入力からテキストを渡す場合用）。
+/// 金額を抽出できなかった行は登録せず、一覧の後に表示します。
+/// 登録候補に締め済みの月の項目が含まれる場合は、登録しません。
+///
+/// #### 例
+///
+/// ```rust
+/// // ドキュメンテーションテストでFailになるのでコメントアウト
+/// // use kakeibo_app::config::Config;
+/// // use kakeibo_app::services;
+/// // services::paste::run("store/data.json", "store/closed.json", "store/device.json", false, &Config::default());
+/// ```
+///
+/// #### エラー
+///
+/// 入力の読み取りや、データの読み書きに失敗した場合は、`KakeiboError`を返します。
+pub fn run(file_path: &str, closed_path: &str, device_path: &str, assume_yes: bool, config: &Config) -> Result<()> {
+    println!("登録する内容を貼り付けてください (1行1件, 空行で終了)");
+    let mut lines = Vec::new();
+    loop {
+        let line = services::io::read_line()?;
+        if line.trim().is_empty() {
+            break;
+        }
+        lines.push(line);
+    }
+
+    let mut data = services::io::read_data_or_create_new_data(file_path)?;
+    let (candidates, skipped) = create_candidates(&lines, &data, Local::now().date_naive(), config);
+    for line in &skipped {
+        println!("金額が見つからないため登録しません: {}", line);
+    }
+    if candidates.is_empty() {
+        println!("登録できる項目はありません");
+        return Ok(());
+    }
+
+    let closed_months = services::close::ClosedMonths::load_or_default(closed_path)?;
+    if let Some(month) = closed_months.find_closed_month(&candidates) {
+        services::close::print_locked(month);
+        return Ok(());
+    }
+
+    print_candidates(&candidates, config);
+    if !assume_yes && !input_confirmation()? {
+        println!("登録を中止しました");
+        return Ok(());
+    }
+
+    let count = candidates.len();
+    let device = services::journal::Device::load_or_create(device_path)?;
+    for mut item in candidates {
+        item.set_origin(Some(device.next_origin(&data)));
+        data.push(item);
+    }
+    services::io::assign_ids(&mut data);
+    services::io::write_to_json(&data, file_path)?;
+    println!("{}件の項目を登録しました", count);
+    Ok(())
+}
+
+/// 貼り付けた各行から支出の登録候補を作成します。
+///
+/// 登録候補と、金額を抽出できなかった行の一覧を返します。
+/// 日付が無い行は`today`の日付にします。カテゴリは同名の過去の支出から引き継ぎ、無い場合は「その他」にします。
+/// 品目名が無い行は、カテゴリ名を品目名にします。金額は設定の通貨の単位で解釈します。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services::paste;
+/// use chrono::NaiveDate;
+///
+/// let today = NaiveDate::from_ymd_opt(2023, 5, 10).unwrap();
+/// let lines = ["日付\t品目\t金額", "5/2\tスタバ\t680"];
+/// let (candidates, skipped) = paste::create_candidates(&lines, &[], today, &Config::default());
+/// assert_eq!(candidates[0].get_name(), "スタバ");
+/// assert_eq!(skipped, vec!["日付\t品目\t金額"]);
+/// ```
+pub fn create_candidates<'a, S: AsRef<str>>(
+    lines: &'a [S],
+    data: &[models::Item],
+    today: NaiveDate,
+    config: &Config
+) -> (Vec<models::Item>, Vec<&'a str>) {
+    let mut candidates = Vec::new();
+    let mut skipped = Vec::new();
+    for line in lines {
+        let line = line.as_ref().trim();
+        let parsed = services::register::parse_free_text(line, today, config);
+        let Some(price) = parsed.price else {
+            skipped.push(line);
+            continue;
+        };
+
+        let category = parsed.name.as_deref()
+            .and_then(|name| services::register::get_previous_item(1, name, data))
+            .map_or(models::Category::Expense(models::ExpenseCategory::Other), |(_, item)| item.get_item_category().clone());
+        let name = parsed.name.unwrap_or_else(|| category.get_name().to_string());
+        candidates.push(models::Item::new(name, category, price, parsed.date.unwrap_or(today)));
+    }
+    (candidates, skipped)
+}
+
+/// 登録候補を出力します。
+fn print_candidates(candidates: &[models::Item], config: &Config) {
+    println!("登録候補");
+    for item in candidates {
+        println!(
+            "  {} {} {} {}",
+            item.get_date(),
+            item.get_name(),
+            config.format_category(item.get_item_category()),
+            config.format_item_price(item)
+        );
+    }
+}
+
+/// ユーザーに登録してよいかを尋ね、真偽値で返します。
+///
+/// `y`以外が入力された場合は、登録しないものとして`false`を返します。
+fn input_confirmation() -> Result<bool> {
+    let answer = services::prompt::ask("これらの項目を登録しますか (y/n)")?;
+
+    Ok(matches!(answer.trim(), "y" | "Y"))
+}
+
+#[cfg(test)]
+mod paste_test {
+    use super::*;
+
+    #[test]
+    fn test_create_candidates() {
+        let today = NaiveDate::from_ymd_opt(2023, 5, 10).unwrap();
+        let data = vec![models::Item::new(
+            "本".to_string(),
+            models::Category::Expense(models::ExpenseCategory::Hobby),
+            models::Money::new(1500),
+            NaiveDate::from_ymd_opt(2023, 4, 1).unwrap()
+        )];
+        let lines = vec![
+            "5/2 スタバ 680円".to_string(),
+            "本\t1,200".to_string(),
+            "メモ".to_string(),
+            "5/3 300円".to_string(),
+        ];
+        let (candidates, skipped) = create_candidates(&lines, &data, today, &Config::default());
+
+        assert_eq!(candidates.len(), 3);
+        assert_eq!(candidates[0].get_item_category(), &models::Category::Expense(models::ExpenseCategory::Other));
+        assert_eq!(candidates[0].get_date(), NaiveDate::from_ymd_opt(2023, 5, 2).unwrap());
+        assert_eq!(candidates[1].get_item_category(), &models::Category::Expense(models::ExpenseCategory::Hobby));
+        assert_eq!(candidates[1].get_price(), models::Money::new(1200));
+        assert_eq!(candidates[1].get_date(), today);
+        assert_eq!(candidates[2].get_name(), "その他");
+        assert_eq!(skipped, vec!["メモ"]);
+
+        // 米ドルの場合は、金額をドル単位で解釈する
+        let config = Config { currency: crate::config::Currency::USD, ..Config::default() };
+        let (candidates, _) = create_candidates(&["5/2 coffee $4.50"], &[], today, &config);
+        assert_eq!(candidates[0].get_price(), models::Money::new(450));
+    }
+}
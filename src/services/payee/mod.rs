@@ -0,0 +1,512 @@
+//! 支払先管理サービス
+//!
+//! このモジュールは、店舗・取引先などの支払先をマスタとして管理する機能を提供します。
+//! 支払先マスタは、JSONファイル`store/payees.json`に保存されます。
+//!
+//! 支払先には別名（「スタバ」に対する「スターバックス」など）を登録できます。
+//! 登録時に入力された支払先は、別名や表記ゆれ（全角・半角、大文字・小文字、空白の有無）を吸収してマスタの正式名に統一します。
+//! 表記ゆれで別々に登録された支払先は1つに統合でき、その際は家計簿データの支払先も正式名に書き換えます。
+//!
+//! #### 支払先マスタの例
+//!
+//! ```json
+//! [
+//!   { "name": "スターバックス", "aliases": ["スタバ", "Starbucks"] },
+//!   { "name": "ヨドバシカメラ" }
+//! ]
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::BufReader;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::error::{KakeiboError, Result};
+use crate::models;
+use crate::services;
+
+/// 支払先マスタの1件を表す構造体
+///
+/// - `name`: 正式名
+/// - `aliases`: 別名の一覧
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Payee {
+    name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    aliases: Vec<String>,
+}
+
+impl Payee {
+    /// 正式名を取得する
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// 別名の一覧を取得する
+    pub fn get_aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    /// 支払先の名前が、正式名または別名と表記ゆれを除いて一致するかを判定する
+    fn matches(&self, key: &str) -> bool {
+        get_key(&self.name) == key || self.aliases.iter().any(|alias| get_key(alias) == key)
+    }
+}
+
+/// 支払先マスタを表す構造体
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::services::payee::PayeeMaster;
+///
+/// let mut master = PayeeMaster::default();
+/// master.add("Amazon").unwrap();
+/// master.add("スターバックス").unwrap();
+/// master.add_alias("スターバックス", "スタバ").unwrap();
+/// assert_eq!(master.normalize("ＡＭＡＺＯＮ"), "Amazon");
+/// assert_eq!(master.normalize(" スタバ"), "スターバックス");
+/// assert_eq!(master.normalize("楽天"), "楽天");
+/// ```
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct PayeeMaster {
+    payees: Vec<Payee>,
+}
+
+impl PayeeMaster {
+    /// JSONファイルから支払先マスタを読み込む
+    ///
+    /// ファイルが存在しない場合は、支払先が無いものとして空のマスタを返します。
+    ///
+    /// #### エラー
+    ///
+    /// ファイルの内容が不正な場合は、`KakeiboError::InvalidData`を返します。
+    pub fn load_or_default(file_path: &str) -> Result<Self> {
+        match File::open(file_path) {
+            Ok(f) => {
+                let buf_reader = BufReader::new(f);
+                serde_json::from_reader(buf_reader)
+                    .map_err(|error| KakeiboError::InvalidData(format!("{}の読み込みに失敗しました: {}", file_path, error)))
+            },
+            Err(_) => Ok(PayeeMaster::default()),
+        }
+    }
+
+    /// 支払先マスタをJSONファイルに書き込む
+    ///
+    /// #### エラー
+    ///
+    /// ファイルへの書き込みに失敗した場合、`KakeiboError::Io`を返します。
+    pub fn save(&self, file_path: &str) -> Result<()> {
+        let json_data = serde_json::to_string_pretty(self).expect("JSONへのシリアライズに失敗しました");
+        services::io::write_file(file_path, &json_data)
+    }
+
+    /// 支払先の一覧を取得する
+    pub fn get_payees(&self) -> &[Payee] {
+        &self.payees
+    }
+
+    /// 正式名または別名が一致する支払先を検索する
+    ///
+    /// 全角・半角の英数字、大文字・小文字、空白の有無の違いは無視して比較します。
+    pub fn find(&self, name: &str) -> Option<&Payee> {
+        let key = get_key(name);
+        self.payees.iter().find(|payee| payee.matches(&key))
+    }
+
+    /// 入力された支払先を正式名に変換する
+    ///
+    /// マスタに無い支払先は、前後の空白を取り除いてそのまま返します。
+    pub fn normalize(&self, name: &str) -> String {
+        match self.find(name) {
+            Some(payee) => payee.name.clone(),
+            None => name.trim().to_string(),
+        }
+    }
+
+    /// 支払先をマスタに追加する
+    ///
+    /// #### エラー
+    ///
+    /// 名前が空の場合や、既に同じ名前（別名を含む）の支払先がある場合は、`KakeiboError::InvalidInput`を返します。
+    pub fn add(&mut self, name: &str) -> Result<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(KakeiboError::invalid_input("支払先の名前を入力してください"));
+        }
+        if let Some(payee) = self.find(name) {
+            return Err(KakeiboError::InvalidInput(format!("「{}」は支払先「{}」として登録済みです", name, payee.name)));
+        }
+        self.payees.push(Payee { name: name.to_string(), aliases: Vec::new() });
+        self.payees.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(())
+    }
+
+    /// 支払先に別名を追加する
+    ///
+    /// #### エラー
+    ///
+    /// 支払先がマスタに無い場合や、別名が既に他の支払先の名前（別名を含む）として登録されている場合は、`KakeiboError::InvalidInput`を返します。
+    pub fn add_alias(&mut self, name: &str, alias: &str) -> Result<()> {
+        let alias = alias.trim();
+        if alias.is_empty() {
+            return Err(KakeiboError::invalid_input("別名を入力してください"));
+        }
+        if let Some(payee) = self.find(alias) {
+            return Err(KakeiboError::InvalidInput(format!("「{}」は支払先「{}」として登録済みです", alias, payee.name)));
+        }
+        let key = get_key(name);
+        let payee = self.payees.iter_mut()
+            .find(|payee| payee.matches(&key))
+            .ok_or_else(|| KakeiboError::InvalidInput(format!("支払先「{}」はマスタにありません", name.trim())))?;
+        payee.aliases.push(alias.to_string());
+        Ok(())
+    }
+
+    /// 表記ゆれの支払先を、マスタの支払先に統合する
+    ///
+    /// `from`がマスタの支払先の場合は、その正式名と別名をすべて`into`の別名にしてマスタから削除します。
+    /// マスタに無い場合は、`from`を`into`の別名として追加します。
+    /// 家計簿データの支払先を書き換えるには、続けて`apply_to_data`を呼び出してください。
+    ///
+    /// #### エラー
+    ///
+    /// `into`がマスタに無い場合や、`from`と`into`が同じ支払先の場合は、`KakeiboError::InvalidInput`を返します。
+    pub fn merge(&mut self, from: &str, into: &str) -> Result<()> {
+        let into_name = match self.find(into) {
+            Some(payee) => payee.name.clone(),
+            None => return Err(KakeiboError::InvalidInput(format!("支払先「{}」はマスタにありません", into.trim()))),
+        };
+        let merged = match self.find(from) {
+            Some(payee) if payee.name == into_name => {
+                return Err(KakeiboError::invalid_input("統合元と統合先が同じ支払先です"));
+            },
+            Some(payee) => {
+                let payee = payee.clone();
+                self.payees.retain(|other| other.name != payee.name);
+                let mut merged = vec![payee.name];
+                merged.extend(payee.aliases);
+                merged
+            },
+            None if from.trim().is_empty() => return Err(KakeiboError::invalid_input("統合元の支払先を入力してください")),
+            None => vec![from.trim().to_string()],
+        };
+
+        let payee = self.payees.iter_mut().find(|payee| payee.name == into_name).unwrap();
+        payee.aliases.extend(merged);
+        Ok(())
+    }
+
+    /// 家計簿データの支払先をマスタの正式名に書き換え、書き換えた項目数を返す
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+    /// use kakeibo_app::services::payee::PayeeMaster;
+    /// use chrono::NaiveDate;
+    ///
+    /// let mut item = Item::new(
+    ///     String::from("コーヒー"),
+    ///     Category::Expense(ExpenseCategory::Food),
+    ///     Money::new(480),
+    ///     NaiveDate::from_ymd_opt(2023, 8, 1).unwrap(),
+    /// );
+    /// item.set_payee(Some(String::from("スタバ")));
+    /// let mut data = vec![item];
+    ///
+    /// let mut master = PayeeMaster::default();
+    /// master.add("スターバックス").unwrap();
+    /// master.merge("スタバ", "スターバックス").unwrap();
+    /// assert_eq!(master.apply_to_data(&mut data), 1);
+    /// assert_eq!(data[0].get_payee(), Some("スターバックス"));
+    /// ```
+    pub fn apply_to_data(&self, data: &mut [models::Item]) -> usize {
+        let mut count = 0;
+        for item in data {
+            let Some(payee) = item.get_payee() else {
+                continue;
+            };
+            let name = self.normalize(payee);
+            if name != payee {
+                item.set_payee(Some(name));
+                count += 1;
+            }
+        }
+        count
+    }
+}
+
+/// 支払先別の支出の累計を表す構造体
+///
+/// - `payee`: 支払先の正式名
+/// - `total`: 支出の累計額
+/// - `count`: 項目数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PayeeTotal {
+    pub payee: String,
+    pub total: models::Money,
+    pub count: usize,
+}
+
+/// 支払先別の支出の累計額を、多い順に並べて取得します。
+///
+/// 支払先は表記ゆれを吸収してマスタの正式名にまとめます。支払先の無い項目と、支出以外の項目は含みません。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+/// use kakeibo_app::services::payee::{self, PayeeMaster};
+/// use chrono::NaiveDate;
+///
+/// let mut item = Item::new(
+///     String::from("コーヒー"),
+///     Category::Expense(ExpenseCategory::Food),
+///     Money::new(480),
+///     NaiveDate::from_ymd_opt(2023, 8, 1).unwrap(),
+/// );
+/// item.set_payee(Some(String::from("スタバ")));
+/// let ranking = payee::get_ranking(&[item], &PayeeMaster::default());
+/// assert_eq!(ranking[0].payee, "スタバ");
+/// assert_eq!(ranking[0].total, Money::new(480));
+/// ```
+pub fn get_ranking(data: &[models::Item], master: &PayeeMaster) -> Vec<PayeeTotal> {
+    let mut totals: BTreeMap<String, (models::Money, usize)> = BTreeMap::new();
+    for item in data.iter().filter(|item| matches!(item.get_item_category(), models::Category::Expense(_))) {
+        let Some(payee) = item.get_payee() else {
+            continue;
+        };
+        let (total, count) = totals.entry(master.normalize(payee)).or_default();
+        *total += item.get_price();
+        *count += 1;
+    }
+
+    let mut ranking: Vec<_> = totals.into_iter()
+        .map(|(payee, (total, count))| PayeeTotal { payee, total, count })
+        .collect();
+    ranking.sort_by_key(|payee_total| Reverse(payee_total.total));
+    ranking
+}
+
+/// 支払先マスタを表示し、支払先の追加・別名の追加・統合・ランキングの表示を行います。
+///
+/// 統合した場合は、家計簿データの支払先も正式名に書き換えます。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// services::payee::run("store/data.json", "store/payees.json", &Config::default()).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// 不正な入力があった場合や、支払先マスタ・データの読み書きに失敗した場合は、`KakeiboError`を返します。
+pub fn run(file_path: &str, payees_path: &str, config: &Config) -> Result<()> {
+    let mut data = services::io::read_data_or_create_new_data(file_path)?;
+    let mut master = PayeeMaster::load_or_default(payees_path)?;
+    print_master(&master, &data);
+
+    let action = services::prompt::ask("操作を入力してください (a:支払先の追加, e:別名の追加, m:表記ゆれの統合, r:ランキング, 空行で終了)")?;
+    match action.trim() {
+        "" => return Ok(()),
+        "a" => {
+            master.add(&input_text("追加する支払先の名前を入力してください")?)?;
+            master.save(payees_path)?;
+            println!("支払先を追加しました");
+        },
+        "e" => {
+            let name = input_text("別名を追加する支払先を入力してください")?;
+            master.add_alias(&name, &input_text("別名を入力してください")?)?;
+            master.save(payees_path)?;
+            println!("別名を追加しました");
+        },
+        "m" => {
+            let from = input_text("統合元の支払先を入力してください")?;
+            let into = input_text("統合先の支払先（マスタの支払先）を入力してください")?;
+            master.merge(&from, &into)?;
+            master.save(payees_path)?;
+            let count = master.apply_to_data(&mut data);
+            if count > 0 {
+                services::io::write_to_json(&data, file_path)?;
+            }
+            println!("「{}」を「{}」に統合しました ({}件の項目を書き換えました)", from, master.normalize(&into), count);
+        },
+        "r" => print_ranking(&get_ranking(&data, &master), config),
+        _ => return Err(KakeiboError::invalid_input("操作はa, e, m, rのいずれかで入力してください")),
+    }
+    Ok(())
+}
+
+/// 支払先別の支出の累計額のランキングを表示します。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// services::payee::run_ranking("store/data.json", "store/payees.json", &Config::default()).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// 支払先マスタやデータの読み込みに失敗した場合は、`KakeiboError`を返します。
+pub fn run_ranking(file_path: &str, payees_path: &str, config: &Config) -> Result<()> {
+    let data = services::io::read_data(file_path)?;
+    let master = PayeeMaster::load_or_default(payees_path)?;
+    print_ranking(&get_ranking(&data, &master), config);
+    Ok(())
+}
+
+/// 表記ゆれの比較に使うキーを作成します。
+///
+/// 全角の英数字・記号を半角にし、空白を取り除いて小文字にします。
+fn get_key(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '！'..='～' => char::from_u32(c as u32 - 0xFEE0).unwrap(),
+            _ => c,
+        })
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// ユーザーに文字列を尋ね、前後の空白を取り除いて返します。
+fn input_text(message: &str) -> Result<String> {
+    Ok(services::prompt::ask(message)?.trim().to_string())
+}
+
+/// 支払先マスタと、マスタに無い家計簿データの支払先を出力します。
+fn print_master(master: &PayeeMaster, data: &[models::Item]) {
+    if master.payees.is_empty() {
+        println!("支払先マスタに支払先はありません");
+    } else {
+        println!("支払先マスタ");
+        for payee in &master.payees {
+            if payee.aliases.is_empty() {
+                println!("  {}", payee.name);
+            } else {
+                println!("  {} (別名: {})", payee.name, payee.aliases.join(", "));
+            }
+        }
+    }
+
+    let mut unregistered: Vec<_> = data.iter()
+        .filter_map(|item| item.get_payee())
+        .filter(|payee| master.find(payee).is_none())
+        .collect();
+    unregistered.sort();
+    unregistered.dedup();
+    if !unregistered.is_empty() {
+        println!("マスタに無い支払先: {}", unregistered.join(", "));
+    }
+}
+
+/// 支払先別の支出の累計額のランキングを出力します。
+fn print_ranking(ranking: &[PayeeTotal], config: &Config) {
+    if ranking.is_empty() {
+        println!("支払先が登録された支出はありません");
+        return;
+    }
+    println!("支払先別の支出ランキング");
+    for (rank, payee_total) in ranking.iter().enumerate() {
+        println!(
+            "  {}. {} {} ({}件)",
+            rank + 1,
+            payee_total.payee,
+            config.format_currency(payee_total.total),
+            payee_total.count
+        );
+    }
+}
+
+#[cfg(test)]
+mod payee_test {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn get_test_master() -> PayeeMaster {
+        let mut master = PayeeMaster::default();
+        master.add("スターバックス").unwrap();
+        master.add("Amazon").unwrap();
+        master.add_alias("スターバックス", "スタバ").unwrap();
+        master
+    }
+
+    fn get_test_item(payee: Option<&str>, price: i64) -> models::Item {
+        let mut item = models::Item::new(
+            "テスト".to_string(),
+            models::Category::Expense(models::ExpenseCategory::Food),
+            models::Money::new(price),
+            NaiveDate::from_ymd_opt(2023, 1, 1).unwrap()
+        );
+        item.set_payee(payee.map(str::to_string));
+        item
+    }
+
+    #[test]
+    fn test_normalize() {
+        let master = get_test_master();
+        assert_eq!(master.normalize("ａｍａｚｏｎ"), "Amazon");
+        assert_eq!(master.normalize("スタ バ"), "スターバックス");
+        assert_eq!(master.normalize(" 楽天 "), "楽天");
+    }
+
+    #[test]
+    fn test_add() {
+        let mut master = get_test_master();
+        assert!(master.add("AMAZON").is_err());
+        assert!(master.add("スタバ").is_err());
+        assert!(master.add(" ").is_err());
+        assert!(master.add_alias("楽天", "楽天市場").is_err());
+        assert!(master.add_alias("Amazon", "スタバ").is_err());
+        assert_eq!(master.get_payees()[0].get_name(), "Amazon");
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut master = get_test_master();
+        master.add("スターバックスコーヒー").unwrap();
+        master.merge("スターバックスコーヒー", "スタバ").unwrap();
+        master.merge("Starbucks", "スターバックス").unwrap();
+
+        assert_eq!(master.get_payees().len(), 2);
+        assert_eq!(master.find("スターバックスコーヒー").unwrap().get_aliases(), ["スタバ", "スターバックスコーヒー", "Starbucks"]);
+        assert!(master.merge("スタバ", "スターバックス").is_err());
+        assert!(master.merge("楽天", "楽天市場").is_err());
+    }
+
+    #[test]
+    fn test_get_ranking() {
+        let mut data = vec![
+            get_test_item(Some("スタバ"), 500),
+            get_test_item(Some("amazon"), 3000),
+            get_test_item(Some("スターバックス"), 700),
+            get_test_item(Some("楽天"), 1000),
+            get_test_item(None, 10000),
+        ];
+        data[3].set_category(models::Category::Income(models::IncomeCategory::Other));
+
+        let ranking = get_ranking(&data, &get_test_master());
+        assert_eq!(ranking, vec![
+            PayeeTotal { payee: "Amazon".to_string(), total: models::Money::new(3000), count: 1 },
+            PayeeTotal { payee: "スターバックス".to_string(), total: models::Money::new(1200), count: 2 },
+        ]);
+    }
+
+    #[test]
+    fn test_apply_to_data() {
+        let mut data = vec![get_test_item(Some("スタバ"), 500), get_test_item(Some("楽天"), 1000), get_test_item(None, 100)];
+        assert_eq!(get_test_master().apply_to_data(&mut data), 1);
+        assert_eq!(data[0].get_payee(), Some("スターバックス"));
+        assert_eq!(data[1].get_payee(), Some("楽天"));
+    }
+}
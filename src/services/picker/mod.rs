@@ -0,0 +1,180 @@
+//! 複数選択機能
+//!
+//! このモジュールは、検索結果などの一覧から、操作の対象にする項目を複数選ぶピッカーを提供します。
+//!
+//! 端末から実行した場合は、↑↓（`k`・`j`）で移動し、スペースキーで選択・解除、`a`ですべての選択・解除、Enterで確定、Escか`q`で中止します。
+//! 端末以外（パイプやスクリプト）から実行した場合や、プロンプトのタイムアウトを設定した場合、`terminal`機能を無効にしてビルドした場合は、
+//! 選択を切り替える番号（`1 3 5`・`2-4`など）を1行ずつ入力する方式になります。
+
+use crate::error::{KakeiboError, Result};
+use crate::services;
+
+/// 一覧から複数の要素を選び、選んだ要素のインデックスを昇順で返します。
+///
+/// 最初はすべての要素を選んだ状態で表示します。中止した場合や、何も選ばずに確定した場合は空の一覧を返します。
+///
+/// #### 例
+///
+/// ```rust
+/// // ドキュメンテーションテストでFailになるのでコメントアウト
+/// // use kakeibo_app::services::picker;
+/// // let labels = vec![String::from("2023-01-01 ランチ"), String::from("2023-01-02 本")];
+/// // let indices = picker::pick("対象の項目を選んでください", &labels).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// 標準入力からの読み込みや端末の操作に失敗した場合は、`KakeiboError`を返します。
+pub fn pick(message: &str, labels: &[String]) -> Result<Vec<usize>> {
+    if labels.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut selected = vec![true; labels.len()];
+    let confirmed = match () {
+        #[cfg(feature = "terminal")]
+        _ if can_use_keys(labels.len()) => pick_with_keys(message, labels, &mut selected)?,
+        _ => pick_with_lines(message, labels, &mut selected)?,
+    };
+    if !confirmed {
+        return Ok(Vec::new());
+    }
+    Ok(selected.iter().enumerate().filter(|(_, selected)| **selected).map(|(index, _)| index).collect())
+}
+
+/// キー操作で選ぶピッカーを使えるかどうかを返します。
+///
+/// 標準入力・標準出力が端末で、一覧が端末の高さに収まり、プロンプトのタイムアウトが設定されていない場合に使えます。
+#[cfg(feature = "terminal")]
+fn can_use_keys(count: usize) -> bool {
+    use std::io::IsTerminal;
+
+    let fits = crossterm::terminal::size().is_ok_and(|(_, height)| count + 2 <= height as usize);
+    std::io::stdin().is_terminal() && std::io::stdout().is_terminal() && fits && !services::prompt::has_timeout()
+}
+
+/// キー操作で選びます。確定した場合は`true`、中止した場合は`false`を返します。
+#[cfg(feature = "terminal")]
+fn pick_with_keys(message: &str, labels: &[String], selected: &mut [bool]) -> Result<bool> {
+    use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+    use crossterm::terminal;
+
+    println!("{} (↑↓:移動, スペース:選択・解除, a:すべて, Enter:確定, Esc:中止)", message);
+    terminal::enable_raw_mode().map_err(KakeiboError::Stdin)?;
+    let mut cursor = 0;
+    let result = loop {
+        if let Err(error) = draw(labels, selected, cursor) {
+            break Err(error);
+        }
+        let key = match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => key,
+            Ok(_) => continue,
+            Err(error) => break Err(error),
+        };
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => cursor = cursor.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => cursor = (cursor + 1).min(labels.len() - 1),
+            KeyCode::Char(' ') => selected[cursor] = !selected[cursor],
+            KeyCode::Char('a') => toggle_all(selected),
+            KeyCode::Enter => break Ok(true),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break Ok(false),
+            KeyCode::Esc | KeyCode::Char('q') => break Ok(false),
+            _ => {},
+        }
+        if let Err(error) = crossterm::execute!(std::io::stdout(), crossterm::cursor::MoveUp(labels.len() as u16)) {
+            break Err(error);
+        }
+    };
+    terminal::disable_raw_mode().map_err(KakeiboError::Stdin)?;
+    result.map_err(KakeiboError::Stdin)
+}
+
+/// 一覧を描画します。カーソルのある行には`>`を付けます。
+///
+/// 端末がrawモードのため、改行は`\r\n`で出力します。
+#[cfg(feature = "terminal")]
+fn draw(labels: &[String], selected: &[bool], cursor: usize) -> std::io::Result<()> {
+    use std::io::Write;
+    use crossterm::terminal::{Clear, ClearType};
+
+    let mut stdout = std::io::stdout();
+    crossterm::queue!(stdout, Clear(ClearType::FromCursorDown))?;
+    for (index, label) in labels.iter().enumerate() {
+        let pointer = if index == cursor { ">" } else { " " };
+        write!(stdout, "{} {}\r\n", pointer, format_line(index, label, selected[index]))?;
+    }
+    stdout.flush()
+}
+
+/// 選択を切り替える番号を1行ずつ入力して選びます。空行で確定し、`q`で中止します。
+///
+/// 番号が不正な場合は、メッセージを表示して入力し直してもらいます。
+fn pick_with_lines(message: &str, labels: &[String], selected: &mut [bool]) -> Result<bool> {
+    loop {
+        for (index, label) in labels.iter().enumerate() {
+            println!("  {}", format_line(index, label, selected[index]));
+        }
+        let input = services::prompt::ask(&format!("{} (選択を切り替える番号: 1 3 5・2-4など, a:すべて, 空行で確定, q:中止)", message))?;
+        match input.trim() {
+            "" => return Ok(true),
+            "q" | "Q" => return Ok(false),
+            input => {
+                if let Err(error) = toggle(selected, input) {
+                    println!("{}", error);
+                }
+            },
+        }
+    }
+}
+
+/// 一覧の1行を "[x] 1: ラベル" の形式の文字列にします。
+fn format_line(index: usize, label: &str, selected: bool) -> String {
+    format!("[{}] {}: {}", if selected { "x" } else { " " }, index + 1, label)
+}
+
+/// すべて選んでいる場合はすべて解除し、それ以外の場合はすべて選びます。
+fn toggle_all(selected: &mut [bool]) {
+    let all = selected.iter().all(|selected| *selected);
+    selected.fill(!all);
+}
+
+/// 入力された番号（1始まり）の選択を切り替えます。
+///
+/// 番号は空白かカンマで区切り、`2-4`のように範囲でも指定できます。`a`はすべての選択・解除です。
+fn toggle(selected: &mut [bool], input: &str) -> Result<()> {
+    let count = selected.len();
+    let invalid = |token: &str| KakeiboError::InvalidInput(format!("番号{}が不正です (1〜{}で入力してください)", token, count));
+    for token in input.split([' ', ',']).filter(|token| !token.is_empty()) {
+        if token == "a" || token == "A" {
+            toggle_all(selected);
+            continue;
+        }
+        let (first, last) = token.split_once('-').unwrap_or((token, token));
+        let (first, last): (usize, usize) = match (first.parse(), last.parse()) {
+            (Ok(first), Ok(last)) if 1 <= first && first <= last && last <= selected.len() => (first, last),
+            _ => return Err(invalid(token)),
+        };
+        selected[first - 1..last].iter_mut().for_each(|selected| *selected = !*selected);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod picker_test {
+    use super::*;
+
+    #[test]
+    fn test_toggle() {
+        let mut selected = vec![true; 5];
+        toggle(&mut selected, "1 3,5").unwrap();
+        assert_eq!(selected, vec![false, true, false, true, false]);
+        toggle(&mut selected, "2-4").unwrap();
+        assert_eq!(selected, vec![false, false, true, false, false]);
+        toggle(&mut selected, "a").unwrap();
+        assert_eq!(selected, vec![true; 5]);
+
+        assert!(toggle(&mut selected, "6").is_err());
+        assert!(toggle(&mut selected, "3-2").is_err());
+        assert_eq!(format_line(0, "ランチ", true), "[x] 1: ランチ");
+    }
+}
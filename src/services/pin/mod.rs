@@ -0,0 +1,194 @@
+//! ピン留めサービス
+//!
+//! このモジュールは、大きな買い物など頻繁に参照したい項目をピン留めし、一覧で確認する機能を提供します。
+
+use crate::config::Config;
+use crate::models;
+use crate::services;
+use crate::services::filter::Filter;
+use crate::error::{KakeiboError, Result};
+
+/// ピン留めした項目の一覧を表示し、項目のピン留め・解除を行います。
+///
+/// この関数は、ピン留めした項目を日付順に表示したうえで、操作を尋ねます。
+/// ピン留め・解除を選んだ場合は、条件式に一致した項目と件数を表示し、確認後にJSONファイルへ書き込みます。
+///
+/// #### 例
+///
+/// ```rust
+/// // ドキュメンテーションテストでFailになるのでコメントアウト
+/// // use kakeibo_app::config::Config;
+/// // use kakeibo_app::services;
+/// // services::pin::run("store/data.json", &Config::default());
+/// ```
+///
+/// #### エラー
+///
+/// 不正な入力があった場合や、データの読み書きに失敗した場合は、`KakeiboError`を返します。
+pub fn run(file_path: &str, config: &Config) -> Result<()> {
+    let mut data = services::io::read_data(file_path)?;
+    print_pinned_items(&data, config);
+
+    let pinned = match input_action()? {
+        Some(pinned) => pinned,
+        None => return Ok(()),
+    };
+    let filter = input_filter(config)?;
+    let targets: Vec<_> = data.iter().filter(|item| filter.matches(item) && item.is_pinned() != pinned).collect();
+    if targets.is_empty() {
+        println!("対象の項目はありません");
+        return Ok(());
+    }
+
+    print_items(&targets, config);
+    let action = if pinned { "ピン留め" } else { "ピン留め解除" };
+    println!("{}件の項目が対象です", targets.len());
+    if !input_confirmation(&format!("これらの項目を{}しますか (y/n)", action))? {
+        println!("{}を中止しました", action);
+        return Ok(());
+    }
+
+    let count = set_pinned(&mut data, &filter, pinned);
+    services::io::write_to_json(&data, file_path)?;
+    println!("{}件の項目を{}しました", count, action);
+    Ok(())
+}
+
+/// 条件に一致する項目のピン留めを設定し、変更した件数を返します。
+fn set_pinned(data: &mut [models::Item], filter: &Filter, pinned: bool) -> usize {
+    let mut count = 0;
+    for item in data.iter_mut().filter(|item| filter.matches(item) && item.is_pinned() != pinned) {
+        item.set_pinned(pinned);
+        count += 1;
+    }
+    count
+}
+
+/// ピン留めした項目を日付順に取得します。
+fn get_pinned_items(data: &[models::Item]) -> Vec<&models::Item> {
+    let mut pinned: Vec<_> = data.iter().filter(|item| item.is_pinned()).collect();
+    pinned.sort_by_key(|item| item.get_date());
+    pinned
+}
+
+/// ピン留めした項目の一覧を出力します。
+fn print_pinned_items(data: &[models::Item], config: &Config) {
+    let pinned = get_pinned_items(data);
+    if pinned.is_empty() {
+        println!("ピン留めした項目はありません");
+    } else {
+        println!("ピン留めした項目");
+        print_items(&pinned, config);
+    }
+}
+
+/// ユーザーに操作を尋ね、ピン留めする場合は`Some(true)`、解除する場合は`Some(false)`を返します。
+///
+/// 空行が入力された場合は、何もせずに終了するものとして`None`を返します。
+///
+/// #### エラー
+///
+/// 不正な入力があった場合、`KakeiboError`を返します。
+fn input_action() -> Result<Option<bool>> {
+    let action = services::prompt::ask("p:ピン留め / u:ピン留め解除 / 空行:終了")?;
+
+    match action.trim() {
+        "p" | "P" => Ok(Some(true)),
+        "u" | "U" => Ok(Some(false)),
+        "" => Ok(None),
+        _ => Err(KakeiboError::invalid_input("操作はp, uのいずれかで入力してください")),
+    }
+}
+
+/// ユーザーに対象の条件を尋ね、`Filter`で返します。
+///
+/// #### エラー
+///
+/// 不正な入力があった場合、`KakeiboError`を返します。
+fn input_filter(config: &Config) -> Result<Filter> {
+    let filter = services::prompt::ask("対象の条件を入力してください (例: name=冷蔵庫 and date=2023-01-01)")?;
+
+    Filter::parse(filter.trim(), config)
+}
+
+/// ユーザーに実行してよいかを尋ね、真偽値で返します。
+///
+/// `y`以外が入力された場合は、実行しないものとして`false`を返します。
+///
+/// #### エラー
+///
+/// 標準入力からの読み込みに失敗した場合、`KakeiboError`を返します。
+fn input_confirmation(message: &str) -> Result<bool> {
+    let answer = services::prompt::ask(message)?;
+
+    Ok(matches!(answer.trim(), "y" | "Y"))
+}
+
+/// 項目を出力します。
+fn print_items(items: &[&models::Item], config: &Config) {
+    for item in items {
+        println!(
+            "  {} {} {} {}",
+            item.get_date(),
+            item.get_name(),
+            config.format_category(item.get_item_category()),
+            config.format_item_price(item)
+        );
+    }
+}
+
+#[cfg(test)]
+mod pin_test {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn get_test_data() -> Vec<models::Item> {
+        vec![
+            models::Item::new(
+                "冷蔵庫".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Other),
+                models::Money::new(150000),
+                NaiveDate::from_ymd_opt(2022, 3, 1).unwrap()
+            ),
+            models::Item::new(
+                "ランチ".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                models::Money::new(1000),
+                NaiveDate::from_ymd_opt(2022, 1, 15).unwrap()
+            ),
+            models::Item::new(
+                "テレビ".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Other),
+                models::Money::new(80000),
+                NaiveDate::from_ymd_opt(2022, 2, 1).unwrap()
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_set_pinned() {
+        let mut test_data = get_test_data();
+
+        assert_eq!(set_pinned(&mut test_data, &Filter::parse("price>=50000", &Config::default()).unwrap(), true), 2);
+        assert!(test_data[0].is_pinned());
+        assert!(!test_data[1].is_pinned());
+        assert!(test_data[2].is_pinned());
+
+        // 既にピン留めされている項目は数えない
+        assert_eq!(set_pinned(&mut test_data, &Filter::parse("category=その他", &Config::default()).unwrap(), true), 0);
+        assert_eq!(set_pinned(&mut test_data, &Filter::parse("name=テレビ", &Config::default()).unwrap(), false), 1);
+        assert!(!test_data[2].is_pinned());
+    }
+
+    #[test]
+    fn test_get_pinned_items() {
+        let mut test_data = get_test_data();
+        test_data[0].set_pinned(true);
+        test_data[2].set_pinned(true);
+        let pinned = get_pinned_items(&test_data);
+
+        assert_eq!(pinned.len(), 2);
+        assert_eq!(pinned[0].get_name(), "テレビ");
+        assert_eq!(pinned[1].get_name(), "冷蔵庫");
+    }
+}
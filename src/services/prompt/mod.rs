@@ -0,0 +1,187 @@
+//! 対話プロンプト
+//!
+//! このモジュールは、対話メニューで入力を尋ねるプロンプトの既定値とタイムアウトを扱う機能を提供します。
+//! 設定はプロセス全体で共有し、起動時に`configure`で一度だけ与えます。
+//!
+//! - 既定値: プロンプトの文言（括弧書きより前の部分）ごとに設定し、空行を入力したときに採用します。
+//! - タイムアウト: 指定した秒数のあいだ入力が無い場合は入力を打ち切ってエラーにし、スクリプトやデーモンからの実行が入力待ちで止まらないようにします。
+//!   一度タイムアウトした後の入力は、待たずにすぐエラーになります。
+//!
+//! #### 例
+//!
+//! ```rust
+//! use kakeibo_app::services::prompt::PromptSettings;
+//!
+//! let settings = PromptSettings::default();
+//! assert_eq!(settings.timeout_secs, None);
+//! ```
+
+use std::collections::BTreeMap;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{KakeiboError, Result};
+
+/// プロンプトの設定を表す構造体
+///
+/// - `timeout_secs`: 入力を待つ秒数（`None`の場合は入力があるまで待つ）
+/// - `defaults`: プロンプトの文言ごとの既定値（例: `"口座を入力してください": "1"`）
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct PromptSettings {
+    pub timeout_secs: Option<u64>,
+    pub defaults: BTreeMap<String, String>,
+}
+
+impl PromptSettings {
+    /// プロンプトの既定値を取得する
+    ///
+    /// プロンプトの文言の1行目のうち、括弧書き（` (`）より前の部分で既定値を探します。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use std::collections::BTreeMap;
+    /// use kakeibo_app::services::prompt::PromptSettings;
+    ///
+    /// let settings = PromptSettings {
+    ///     defaults: BTreeMap::from([(String::from("口座を入力してください"), String::from("1"))]),
+    ///     ..PromptSettings::default()
+    /// };
+    /// assert_eq!(settings.get_default("口座を入力してください (0:現金, 1:銀行, 2:クレジットカード)"), Some("1"));
+    /// assert_eq!(settings.get_default("金額を入力してください"), None);
+    /// ```
+    pub fn get_default(&self, message: &str) -> Option<&str> {
+        let line = message.lines().next().unwrap_or("");
+        let key = line.split(" (").next().unwrap_or(line).trim();
+        self.defaults.get(key).map(String::as_str)
+    }
+}
+
+/// プロセス全体のプロンプトの設定
+static SETTINGS: OnceLock<PromptSettings> = OnceLock::new();
+
+/// タイムアウト付きで読み込むときに、標準入力の各行を受け取る受信側
+static LINES: OnceLock<Mutex<Receiver<io::Result<String>>>> = OnceLock::new();
+
+/// 入力待ちがタイムアウトして、入力を打ち切ったかどうか
+static TIMED_OUT: AtomicBool = AtomicBool::new(false);
+
+/// プロンプトの設定を与えます。
+///
+/// 2回目以降の呼び出しは無視します。
+pub fn configure(settings: PromptSettings) {
+    let _ = SETTINGS.set(settings);
+}
+
+/// 入力待ちのタイムアウトが設定されているかどうかを返します。
+pub fn has_timeout() -> bool {
+    get_settings().timeout_secs.is_some()
+}
+
+/// プロンプトの設定を取得します。設定が与えられていない場合は既定値を返します。
+fn get_settings() -> &'static PromptSettings {
+    SETTINGS.get_or_init(PromptSettings::default)
+}
+
+/// メッセージを表示し、標準入力から1行読み込みます。
+///
+/// メッセージに既定値が設定されている場合は、メッセージに続けて既定値を表示し、空行が入力されたときは既定値を返します。
+/// 入力の終わり（EOF）の場合は、既定値があっても空文字列を返します。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::services::prompt;
+///
+/// // ドキュメンテーションテストでFailになるのでコメントアウト
+/// // let name = prompt::ask("品目名を入力してください").unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// 標準入力からの読み込みに失敗した場合や、入力待ちがタイムアウトした場合はエラーを返します。
+pub fn ask(message: &str) -> Result<String> {
+    let default = get_settings().get_default(message);
+    match default {
+        Some(default) => println!("{} [既定値: {}]", message, default),
+        None => println!("{}", message),
+    }
+
+    let line = read_line()?;
+    match default {
+        Some(default) if !line.is_empty() && line.trim().is_empty() => Ok(format!("{}\n", default)),
+        _ => Ok(line),
+    }
+}
+
+/// 標準入力から1行読み込みます。
+///
+/// 読み込んだ行は、末尾の改行を含めてそのまま返します。入力の終わり（EOF）の場合は空文字列を返します。
+///
+/// #### エラー
+///
+/// 標準入力からの読み込みに失敗した場合はエラーを返します。
+/// タイムアウトが設定されていて時間内に入力が無かった場合や、既にタイムアウトしている場合は、
+/// 種類が`io::ErrorKind::TimedOut`の`KakeiboError::Stdin`を返します。
+pub fn read_line() -> Result<String> {
+    let Some(timeout_secs) = get_settings().timeout_secs else {
+        let mut line = String::new();
+        io::stdin().read_line(&mut line).map_err(KakeiboError::Stdin)?;
+        return Ok(line);
+    };
+    if TIMED_OUT.load(Ordering::Relaxed) {
+        return Err(get_timeout_error(timeout_secs));
+    }
+
+    let lines = LINES.get_or_init(|| Mutex::new(spawn_reader())).lock().unwrap_or_else(|error| error.into_inner());
+    match lines.recv_timeout(Duration::from_secs(timeout_secs)) {
+        Ok(line) => line.map_err(KakeiboError::Stdin),
+        Err(RecvTimeoutError::Timeout) => {
+            TIMED_OUT.store(true, Ordering::Relaxed);
+            Err(get_timeout_error(timeout_secs))
+        },
+        Err(RecvTimeoutError::Disconnected) => Ok(String::new()),
+    }
+}
+
+/// 入力待ちがタイムアウトしたことを表すエラーを作成します。
+fn get_timeout_error(timeout_secs: u64) -> KakeiboError {
+    let message = format!("{}秒間入力が無かったため、入力を打ち切りました", timeout_secs);
+    KakeiboError::Stdin(io::Error::new(io::ErrorKind::TimedOut, message))
+}
+
+/// 標準入力を1行ずつ読み込んで送るスレッドを起動し、受信側を返します。
+fn spawn_reader() -> Receiver<io::Result<String>> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || loop {
+        let mut line = String::new();
+        let result = io::stdin().read_line(&mut line).map(|_| line);
+        let finished = !matches!(&result, Ok(line) if !line.is_empty());
+        if sender.send(result).is_err() || finished {
+            break;
+        }
+    });
+    receiver
+}
+
+#[cfg(test)]
+mod prompt_test {
+    use super::*;
+
+    #[test]
+    fn test_get_default() {
+        let settings: PromptSettings = serde_json::from_str(r#"{ "defaults": { "日付を入力してください": "2023-01-01" } }"#).unwrap();
+        assert_eq!(settings.timeout_secs, None);
+        assert_eq!(settings.get_default("日付を入力してください"), Some("2023-01-01"));
+        assert_eq!(settings.get_default("日付を入力してください (空行で今日)"), Some("2023-01-01"));
+        assert_eq!(settings.get_default("日付を入力してください\n(0:今日, 1:昨日)"), Some("2023-01-01"));
+        assert_eq!(settings.get_default("日付"), None);
+    }
+}
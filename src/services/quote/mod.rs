@@ -0,0 +1,347 @@
+//! 相場取得サービス
+//!
+//! このモジュールは、為替レートや株価などの相場（1単位あたりの既定通貨での価格）を取得する機能を提供します。
+//! 相場の取得先は`QuoteProvider`トレイトで抽象化しており、設定の`quote_sources`に並べた順に問い合わせ、最初に取得できた相場を使います。
+//!
+//! | 取得先 | 内容 |
+//! | --- | --- |
+//! | `file` | 相場ファイル（JSON）から読み込む。オフライン環境でも使える |
+//! | `http` | HTTPのAPIから取得する。URLの`{symbol}`・`{date}`を銘柄と日付に置き換え、応答のJSONからJSON Pointerで値を取り出す |
+//!
+//! HTTPの取得先の後に相場ファイルを並べておくと、ネットワークにつながらない場合も相場ファイルの値で動作します。
+//! 設定が無い場合は、`store/quotes.json`の相場ファイルだけを使います。
+//!
+//! #### 設定の例
+//!
+//! ```json
+//! {
+//!   "quote_sources": [
+//!     { "type": "http", "url": "https://api.example.com/{date}?base={symbol}", "pointer": "/rates/JPY" },
+//!     { "type": "file", "path": "store/quotes.json" }
+//!   ]
+//! }
+//! ```
+//!
+//! #### 相場ファイルの例
+//!
+//! 銘柄ごとに、日付と相場を記述します。指定した日の相場が無い場合は、それより前の最も新しい相場を使います。
+//!
+//! ```json
+//! {
+//!   "USD": { "2024-01-04": 144.5, "2024-02-01": 146.9 },
+//!   "7203.T": { "2024-01-04": 2500.0 }
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::BufReader;
+#[cfg(feature = "http")]
+use std::time::Duration;
+
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "http")]
+use serde_json::Value;
+
+use crate::config::Config;
+use crate::error::{KakeiboError, Result};
+use crate::models;
+use crate::services;
+
+/// 相場ファイルの既定の保存先
+pub const DEFAULT_QUOTES_PATH: &str = "store/quotes.json";
+
+/// HTTPの取得先からの応答を待つ時間
+#[cfg(feature = "http")]
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 銘柄ごとの、日付をキーとする相場の表
+pub type QuoteTable = BTreeMap<String, BTreeMap<NaiveDate, f64>>;
+
+/// 相場を表す構造体
+///
+/// - `symbol`: 銘柄（例: USD、7203.T）
+/// - `date`: 相場の日付（指定した日の相場が無い場合は、それより前の日付）
+/// - `price`: 1単位あたりの既定通貨での価格
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quote {
+    pub symbol: String,
+    pub date: NaiveDate,
+    pub price: f64,
+}
+
+/// 相場の取得先を表すトレイト
+///
+/// 新しい取得先を追加する場合は、このトレイトを実装し、`QuoteSource`に設定の記述方法を追加します。
+pub trait QuoteProvider {
+    /// 取得先の名前を取得する（表示用）
+    fn get_name(&self) -> String;
+
+    /// 指定した日の銘柄の相場を取得する
+    ///
+    /// #### エラー
+    ///
+    /// 相場を取得できない場合は、`KakeiboError`を返します。
+    fn get_quote(&self, symbol: &str, date: NaiveDate) -> Result<Quote>;
+}
+
+/// 設定に記述する相場の取得先を表す列挙型
+///
+/// - `File`: 相場ファイル（`path`: ファイルのパス）
+/// - `Http`: HTTPのAPI（`url`: `{symbol}`・`{date}`を含むURL、`pointer`: 応答のJSONから相場を取り出すJSON Pointer）
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum QuoteSource {
+    File { path: String },
+    Http { url: String, pointer: String },
+}
+
+impl QuoteSource {
+    /// 設定の記述から相場の取得先を作成する
+    pub fn create_provider(&self) -> Box<dyn QuoteProvider> {
+        match self {
+            QuoteSource::File { path } => Box::new(FileProvider { path: path.clone() }),
+            QuoteSource::Http { url, pointer } => Box::new(HttpProvider { url: url.clone(), pointer: pointer.clone() }),
+        }
+    }
+}
+
+/// 相場ファイルから相場を読み込む取得先
+pub struct FileProvider {
+    path: String,
+}
+
+impl QuoteProvider for FileProvider {
+    fn get_name(&self) -> String {
+        format!("相場ファイル {}", self.path)
+    }
+
+    fn get_quote(&self, symbol: &str, date: NaiveDate) -> Result<Quote> {
+        let file = File::open(&self.path).map_err(|error| KakeiboError::io(&self.path, error))?;
+        let table: QuoteTable = serde_json::from_reader(BufReader::new(file))
+            .map_err(|error| KakeiboError::InvalidData(format!("{}の形式が不正です: {}", self.path, error)))?;
+        find_quote(&table, symbol, date)
+            .ok_or_else(|| KakeiboError::InvalidInput(format!("{}に{}の{}以前の相場がありません", self.path, symbol, date)))
+    }
+}
+
+/// HTTPのAPIから相場を取得する取得先
+///
+/// `http`機能を無効にしてビルドした場合は、常にエラーを返します。
+pub struct HttpProvider {
+    url: String,
+    #[cfg_attr(not(feature = "http"), allow(dead_code))]
+    pointer: String,
+}
+
+impl QuoteProvider for HttpProvider {
+    fn get_name(&self) -> String {
+        format!("HTTP {}", self.url)
+    }
+
+    #[cfg(feature = "http")]
+    fn get_quote(&self, symbol: &str, date: NaiveDate) -> Result<Quote> {
+        let url = create_url(&self.url, symbol, date);
+        let error = |message: String| KakeiboError::InvalidInput(format!("{}から相場を取得できません: {}", url, message));
+        let json: Value = ureq::get(&url)
+            .config()
+            .timeout_global(Some(HTTP_TIMEOUT))
+            .build()
+            .call()
+            .map_err(|e| error(e.to_string()))?
+            .body_mut()
+            .read_json()
+            .map_err(|e| error(e.to_string()))?;
+        let price = json.pointer(&self.pointer)
+            .and_then(Value::as_f64)
+            .ok_or_else(|| error(format!("{}に数値がありません", self.pointer)))?;
+        Ok(Quote { symbol: symbol.to_string(), date, price })
+    }
+
+    #[cfg(not(feature = "http"))]
+    fn get_quote(&self, _symbol: &str, _date: NaiveDate) -> Result<Quote> {
+        Err(KakeiboError::InvalidInput(format!("{}から相場を取得できません: http機能を有効にしてビルドしてください", self.url)))
+    }
+}
+
+/// URLの`{symbol}`と`{date}`（yyyy-mm-dd）を、銘柄と日付に置き換えます。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::services::quote;
+/// use chrono::NaiveDate;
+///
+/// let url = quote::create_url("https://api.example.com/{date}?base={symbol}", "USD", NaiveDate::from_ymd_opt(2024, 1, 4).unwrap());
+/// assert_eq!(url, "https://api.example.com/2024-01-04?base=USD");
+/// ```
+pub fn create_url(template: &str, symbol: &str, date: NaiveDate) -> String {
+    template.replace("{symbol}", symbol).replace("{date}", &date.to_string())
+}
+
+/// 相場の表から、指定した日以前で最も新しい銘柄の相場を探します。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::services::quote::{self, QuoteTable};
+/// use chrono::NaiveDate;
+///
+/// let date = |day| NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+/// let table: QuoteTable = [(String::from("USD"), [(date(4), 144.5), (date(10), 145.2)].into())].into();
+/// assert_eq!(quote::find_quote(&table, "USD", date(9)).unwrap().date, date(4));
+/// assert!(quote::find_quote(&table, "USD", date(3)).is_none());
+/// assert!(quote::find_quote(&table, "EUR", date(9)).is_none());
+/// ```
+pub fn find_quote(table: &QuoteTable, symbol: &str, date: NaiveDate) -> Option<Quote> {
+    let (quote_date, price) = table.get(symbol)?.range(..=date).next_back()?;
+    Some(Quote { symbol: symbol.to_string(), date: *quote_date, price: *price })
+}
+
+/// 取得先に順に問い合わせ、最初に取得できた相場と、その取得先の名前を返します。
+///
+/// 取得先が1つも無い場合や、すべての取得先で取得できなかった場合は、各取得先のエラーをまとめた`KakeiboError::InvalidInput`を返します。
+pub fn get_quote(providers: &[Box<dyn QuoteProvider>], symbol: &str, date: NaiveDate) -> Result<(Quote, String)> {
+    let mut errors = Vec::new();
+    for provider in providers {
+        match provider.get_quote(symbol, date) {
+            Ok(quote) => return Ok((quote, provider.get_name())),
+            Err(error) => errors.push(format!("{}: {}", provider.get_name(), error)),
+        }
+    }
+    if errors.is_empty() {
+        errors.push(String::from("相場の取得先が設定されていません"));
+    }
+    Err(KakeiboError::InvalidInput(format!("{}の相場を取得できません ({})", symbol, errors.join(" / "))))
+}
+
+/// 数量に相場を掛けて、既定通貨の金額に換算します。
+///
+/// 通貨の最小単位未満は四捨五入します。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::config::{Config, Currency};
+/// use kakeibo_app::models::Money;
+/// use kakeibo_app::services::quote::{self, Quote};
+/// use chrono::NaiveDate;
+///
+/// let quote = Quote { symbol: String::from("USD"), date: NaiveDate::from_ymd_opt(2024, 1, 4).unwrap(), price: 144.55 };
+/// assert_eq!(quote::convert(&quote, 10.0, &Config::default()), Money::new(1446));
+/// let config = Config { currency: Currency::EUR, ..Config::default() };
+/// assert_eq!(quote::convert(&quote, 10.0, &config), Money::new(144550));
+/// ```
+pub fn convert(quote: &Quote, amount: f64, config: &Config) -> models::Money {
+    let unit = 10_f64.powi(config.currency.get_minor_digits() as i32);
+    models::Money::new((amount * quote.price * unit).round() as i64)
+}
+
+/// 銘柄の相場を取得して表示します。
+///
+/// 日付（yyyy-mm-dd）を省略した場合は、今日の相場を取得します。数量を指定した場合は、既定通貨に換算した金額も表示します。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// services::quote::run("USD", Some("2024-01-04"), Some(100.0), &Config::default()).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// 日付の形式が不正な場合や、相場を取得できない場合は、`KakeiboError::InvalidInput`を返します。
+pub fn run(symbol: &str, date: Option<&str>, amount: Option<f64>, config: &Config) -> Result<()> {
+    let date = match date {
+        Some(date) => services::summarize::parse_date(date)?,
+        None => Local::now().date_naive(),
+    };
+    let providers: Vec<_> = config.quote_sources.iter().map(QuoteSource::create_provider).collect();
+    let (quote, provider_name) = get_quote(&providers, symbol, date)?;
+
+    println!(
+        "{}の相場: {} ({}時点, 取得先: {})",
+        quote.symbol,
+        config.currency.attach_unit(&quote.price.to_string()),
+        quote.date,
+        provider_name
+    );
+    if let Some(amount) = amount {
+        println!("{} {} = {}", amount, quote.symbol, config.format_currency(convert(&quote, amount, config)));
+    }
+    Ok(())
+}
+
+/// 銘柄・日付・数量を尋ね、相場を取得して表示します。
+///
+/// #### 例
+///
+/// ```rust
+/// // ドキュメンテーションテストでFailになるのでコメントアウト
+/// // use kakeibo_app::config::Config;
+/// // use kakeibo_app::services;
+/// // services::quote::run_interactive(&Config::default());
+/// ```
+///
+/// #### エラー
+///
+/// 不正な入力があった場合や、相場を取得できない場合は、`KakeiboError`を返します。
+pub fn run_interactive(config: &Config) -> Result<()> {
+    let symbol = services::prompt::ask("銘柄を入力してください (例: USD)")?;
+    let date = services::prompt::ask("日付をyyyy-mm-ddの形式で入力してください (空行で今日)")?;
+    let amount = services::prompt::ask("換算する数量を入力してください (空行で換算しない)")?;
+    let amount = match amount.trim() {
+        "" => None,
+        amount => Some(amount.parse::<f64>().map_err(|_| KakeiboError::invalid_input("数量は数値で入力してください"))?),
+    };
+
+    let date = Some(date.trim()).filter(|date| !date.is_empty());
+    run(symbol.trim(), date, amount, config)
+}
+
+#[cfg(test)]
+mod quote_test {
+    use super::*;
+
+    /// 常に同じ相場を返すか、常に失敗するテスト用の取得先
+    struct TestProvider {
+        price: Option<f64>,
+    }
+
+    impl QuoteProvider for TestProvider {
+        fn get_name(&self) -> String {
+            format!("テスト {:?}", self.price)
+        }
+
+        fn get_quote(&self, symbol: &str, date: NaiveDate) -> Result<Quote> {
+            let price = self.price.ok_or_else(|| KakeiboError::invalid_input("オフラインです"))?;
+            Ok(Quote { symbol: symbol.to_string(), date, price })
+        }
+    }
+
+    #[test]
+    fn test_get_quote() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 4).unwrap();
+        let providers: Vec<Box<dyn QuoteProvider>> = vec![
+            Box::new(TestProvider { price: None }),
+            Box::new(TestProvider { price: Some(144.5) }),
+        ];
+        let (quote, name) = get_quote(&providers, "USD", date).unwrap();
+        assert_eq!(quote.price, 144.5);
+        assert_eq!(name, "テスト Some(144.5)");
+
+        let error = get_quote(&providers[..1], "USD", date).unwrap_err();
+        assert_eq!(error.to_string(), "USDの相場を取得できません (テスト None: オフラインです)");
+        assert!(get_quote(&[], "USD", date).is_err());
+    }
+
+    #[test]
+    fn test_quote_source() {
+        let json = r#"[{ "type": "http", "url": "https://api.example.com/{symbol}", "pointer": "/rate" }, { "type": "file", "path": "quotes.json" }]"#;
+        let sources: Vec<QuoteSource> = serde_json::from_str(json).unwrap();
+        assert_eq!(sources[1], QuoteSource::File { path: "quotes.json".to_string() });
+        assert_eq!(sources[0].create_provider().get_name(), "HTTP https://api.example.com/{symbol}");
+    }
+}
@@ -0,0 +1,762 @@
+//! 定期取引サービス
+//!
+//! このモジュールは、家賃や給料のように毎月決まった日に発生する取引をルールとして管理し、
+//! 期日を迎えた分を家計簿データに登録する機能を提供します。
+//! ルールは、JSONファイル`store/recurring.json`に保存されます。
+//!
+//! ルールから登録した項目には、追加属性`recurring`に対象の月（`2023-08`の形式）を記録します。
+//! 同じ品目名・同じ月の項目が既にある場合は登録しないため、何度実行しても二重に登録されません。
+//!
+//! 口座維持手数料や預金の利息は、JSONファイル`store/accounts.json`の口座の設定から、定期取引と同時に計上します。
+//!
+//! #### ルールの例
+//!
+//! ```json
+//! [
+//!   {
+//!     "name": "家賃",
+//!     "category": {"Expense": "Other"},
+//!     "price": 80000,
+//!     "day": 27,
+//!     "account": "Bank",
+//!     "adjustment": "previous",
+//!     "start": "2023-01-01"
+//!   }
+//! ]
+//! ```
+//!
+//! #### 口座の設定の例
+//!
+//! ```json
+//! [
+//!   { "account": "Bank", "fee": 220, "fee_day": 25, "interest_rate": 0.1, "interest_months": [2, 8], "start": "2023-01-01" }
+//! ]
+//! ```
+
+use std::fs::File;
+use std::io::BufReader;
+use std::str::FromStr;
+
+use chrono::{Datelike, Local, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config::Config;
+use crate::error::{KakeiboError, Result};
+use crate::models;
+use crate::services;
+use crate::services::calendar::BusinessDayRule;
+
+/// 定期取引から登録した項目に、対象の月を記録する追加属性のキー
+const RECURRING_KEY: &str = "recurring";
+
+/// 定期取引のルールを表す構造体
+///
+/// - `name`: 品目名
+/// - `category`: カテゴリ
+/// - `price`: 金額
+/// - `day`: 毎月の日にち（1〜31。月末を超える場合は月末日）
+/// - `account`: 口座（振替の場合は振替元の口座）
+/// - `adjustment`: 休日に当たる場合の調整ルール（`None`の場合は調整しない）
+/// - `start`: 適用を開始する日
+/// - `end`: 適用を終了する日（`None`の場合は無期限）
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RecurringRule {
+    pub name: String,
+    pub category: models::Category,
+    pub price: models::Money,
+    pub day: u32,
+    #[serde(default)]
+    pub account: models::Account,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adjustment: Option<BusinessDayRule>,
+    pub start: NaiveDate,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<NaiveDate>,
+}
+
+impl RecurringRule {
+    /// 指定された月の期日を取得する
+    ///
+    /// 日にちが月末を超える場合は月末日にし、休日の調整ルールがあれば営業日に調整します。
+    /// 適用期間外の月の場合は`None`を返します。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Category, ExpenseCategory, Money};
+    /// use kakeibo_app::services::calendar::BusinessDayRule;
+    /// use kakeibo_app::services::recurring::RecurringRule;
+    /// use chrono::NaiveDate;
+    ///
+    /// let rule = RecurringRule {
+    ///     name: String::from("家賃"),
+    ///     category: Category::Expense(ExpenseCategory::Other),
+    ///     price: Money::new(80000),
+    ///     day: 31,
+    ///     account: Default::default(),
+    ///     adjustment: Some(BusinessDayRule::Previous),
+    ///     start: NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+    ///     end: None,
+    /// };
+    /// // 2023/9/30は土曜日
+    /// let month = NaiveDate::from_ymd_opt(2023, 9, 1).unwrap();
+    /// assert_eq!(rule.get_due_date(month), NaiveDate::from_ymd_opt(2023, 9, 29));
+    /// ```
+    pub fn get_due_date(&self, month: NaiveDate) -> Option<NaiveDate> {
+        let last_day = services::summarize::get_last_day(month).day();
+        let date = month.with_day(self.day.clamp(1, last_day))?;
+        if date < self.start || self.end.is_some_and(|end| end < date) {
+            return None;
+        }
+        Some(self.adjustment.map_or(date, |rule| rule.apply(date)))
+    }
+
+    /// 指定された月の分として登録する項目を作成する
+    fn to_item(&self, month: NaiveDate, date: NaiveDate) -> models::Item {
+        let mut item = models::Item::new(self.name.clone(), self.category.clone(), self.price, date);
+        item.set_account(self.account.clone());
+        item.set_extra(RECURRING_KEY.to_string(), Value::String(format_month(month)));
+        item
+    }
+
+    /// 指定された月の分が、既にデータに登録されているかどうかを判定する
+    fn is_posted<'a>(&self, month: NaiveDate, data: impl IntoIterator<Item = &'a models::Item>) -> bool {
+        let month = Value::String(format_month(month));
+        data.into_iter().any(|item| item.get_name() == self.name && item.get_extra().get(RECURRING_KEY) == Some(&month))
+    }
+}
+
+/// 定期取引のルールの一覧を表す構造体
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct RecurringRules {
+    rules: Vec<RecurringRule>,
+}
+
+impl RecurringRules {
+    /// JSONファイルからルールの一覧を読み込む
+    ///
+    /// ファイルが存在しない場合は、ルールが無いものとして空の一覧を返します。
+    ///
+    /// #### エラー
+    ///
+    /// ファイルの内容が不正な場合は、`KakeiboError::InvalidData`を返します。
+    pub fn load_or_default(file_path: &str) -> Result<Self> {
+        match File::open(file_path) {
+            Ok(f) => {
+                let buf_reader = BufReader::new(f);
+                serde_json::from_reader(buf_reader)
+                    .map_err(|error| KakeiboError::InvalidData(format!("{}の読み込みに失敗しました: {}", file_path, error)))
+            },
+            Err(_) => Ok(RecurringRules::default()),
+        }
+    }
+
+    /// ルールの一覧をJSONファイルに書き込む
+    ///
+    /// #### エラー
+    ///
+    /// ファイルへの書き込みに失敗した場合、`KakeiboError::Io`を返します。
+    pub fn save(&self, file_path: &str) -> Result<()> {
+        let json_data = serde_json::to_string_pretty(self).expect("JSONへのシリアライズに失敗しました");
+        services::io::write_file(file_path, &json_data)
+    }
+
+    /// ルールの一覧を取得する
+    pub fn get_rules(&self) -> &[RecurringRule] {
+        &self.rules
+    }
+
+    /// ルールを追加する
+    pub fn add(&mut self, rule: RecurringRule) {
+        self.rules.push(rule);
+    }
+
+    /// 指定された番号（0始まり）のルールを削除し、削除したルールを返す
+    ///
+    /// 番号が範囲外の場合は`None`を返します。
+    pub fn remove(&mut self, index: usize) -> Option<RecurringRule> {
+        (index < self.rules.len()).then(|| self.rules.remove(index))
+    }
+
+    /// 指定された日までに期日を迎え、まだ登録されていない項目を作成する
+    ///
+    /// 各ルールの適用開始日の月から`today`の月までを対象にし、期日が`today`以前の分を日付順に返します。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Category, IncomeCategory, Money};
+    /// use kakeibo_app::services::recurring::{RecurringRule, RecurringRules};
+    /// use chrono::NaiveDate;
+    ///
+    /// let mut rules = RecurringRules::default();
+    /// rules.add(RecurringRule {
+    ///     name: String::from("給料"),
+    ///     category: Category::Income(IncomeCategory::Salary),
+    ///     price: Money::new(300000),
+    ///     day: 25,
+    ///     account: Default::default(),
+    ///     adjustment: None,
+    ///     start: NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+    ///     end: None,
+    /// });
+    /// let items = rules.create_due_items(&[], NaiveDate::from_ymd_opt(2023, 3, 10).unwrap());
+    /// assert_eq!(items.len(), 2);
+    /// assert!(rules.create_due_items(&items, NaiveDate::from_ymd_opt(2023, 3, 10).unwrap()).is_empty());
+    /// ```
+    pub fn create_due_items(&self, data: &[models::Item], today: NaiveDate) -> Vec<models::Item> {
+        let mut items = Vec::new();
+        for rule in &self.rules {
+            let mut month = get_first_day(rule.start);
+            while month <= today {
+                if let Some(date) = rule.get_due_date(month) {
+                    if date <= today && !rule.is_posted(month, data) {
+                        items.push(rule.to_item(month, date));
+                    }
+                }
+                month = month.checked_add_months(Months::new(1)).unwrap();
+            }
+        }
+        items.sort_by_key(|item| item.get_date());
+        items
+    }
+}
+
+/// 口座の利息・手数料の設定を表す構造体
+///
+/// - `account`: 対象の口座
+/// - `fee`: 毎月の口座維持手数料（`None`の場合は計上しない）
+/// - `fee_day`: 手数料が引き落とされる日にち（1〜31。月末を超える場合は月末日）
+/// - `interest_rate`: 税引後の年利（%）（`None`の場合は計上しない）
+/// - `interest_months`: 利息が付く月（例: 2月と8月の年2回なら`[2, 8]`）
+/// - `interest_day`: 利息が付く日にち（1〜31。月末を超える場合は月末日）
+/// - `start`: 計上を開始する日
+///
+/// 手数料と利息は、休日に当たる場合は翌営業日に計上します。
+/// 利息の額は、利息が付く日の前日までの口座残高に、年利を年間の利息の回数で割った利率を掛けて計算します（最小単位未満は切り捨て）。
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AccountSetting {
+    pub account: models::Account,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee: Option<models::Money>,
+    #[serde(default = "get_default_day")]
+    pub fee_day: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interest_rate: Option<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub interest_months: Vec<u32>,
+    #[serde(default = "get_default_day")]
+    pub interest_day: u32,
+    pub start: NaiveDate,
+}
+
+impl AccountSetting {
+    /// 口座維持手数料を計上するルールを作成する
+    fn get_fee_rule(&self) -> Option<RecurringRule> {
+        self.fee.map(|fee| RecurringRule {
+            name: format!("{}の口座維持手数料", self.account.get_name()),
+            category: models::Category::Expense(models::ExpenseCategory::Other),
+            price: fee,
+            day: self.fee_day,
+            account: self.account.clone(),
+            adjustment: Some(BusinessDayRule::Next),
+            start: self.start,
+            end: None,
+        })
+    }
+
+    /// 利息を計上するルールを作成する（金額は計上する日の残高から計算するため0）
+    fn get_interest_rule(&self) -> RecurringRule {
+        RecurringRule {
+            name: format!("{}の利息", self.account.get_name()),
+            category: models::Category::Income(models::IncomeCategory::Other),
+            price: models::Money::ZERO,
+            day: self.interest_day,
+            account: self.account.clone(),
+            adjustment: Some(BusinessDayRule::Next),
+            start: self.start,
+            end: None,
+        }
+    }
+
+    /// 指定された日までに利息が付き、まだ登録されていない利息の項目を作成する
+    ///
+    /// 残高が無い場合や、利息が最小単位に満たない場合は作成しません。
+    fn create_interest_items(&self, data: &[&models::Item], today: NaiveDate) -> Vec<models::Item> {
+        let Some(rate) = self.interest_rate.filter(|_| !self.interest_months.is_empty()) else {
+            return Vec::new();
+        };
+        let mut rule = self.get_interest_rule();
+        let mut items = Vec::new();
+        let mut month = get_first_day(self.start);
+        while month <= today {
+            let date = rule.get_due_date(month).filter(|date| *date <= today);
+            if let Some(date) = date.filter(|_| self.interest_months.contains(&month.month())) {
+                let balance: models::Money = data.iter()
+                    .filter(|item| item.get_date() < date)
+                    .flat_map(|item| item.get_account_changes())
+                    .filter(|(account, _)| *account == self.account)
+                    .map(|(_, price)| price)
+                    .sum();
+                let interest = balance.get_minor_units() as f64 * rate / 100.0 / self.interest_months.len() as f64;
+                rule.price = models::Money::new(interest.floor() as i64);
+                if rule.price > models::Money::ZERO && !rule.is_posted(month, data.iter().copied()) {
+                    items.push(rule.to_item(month, date));
+                }
+            }
+            month = month.checked_add_months(Months::new(1)).unwrap();
+        }
+        items
+    }
+}
+
+/// 口座の利息・手数料の設定の一覧を表す構造体
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(transparent)]
+pub struct AccountSettings {
+    settings: Vec<AccountSetting>,
+}
+
+impl AccountSettings {
+    /// JSONファイルから口座の設定の一覧を読み込む
+    ///
+    /// ファイルが存在しない場合は、設定が無いものとして空の一覧を返します。
+    ///
+    /// #### エラー
+    ///
+    /// ファイルの内容が不正な場合は、`KakeiboError::InvalidData`を返します。
+    pub fn load_or_default(file_path: &str) -> Result<Self> {
+        match File::open(file_path) {
+            Ok(f) => {
+                let buf_reader = BufReader::new(f);
+                serde_json::from_reader(buf_reader)
+                    .map_err(|error| KakeiboError::InvalidData(format!("{}の読み込みに失敗しました: {}", file_path, error)))
+            },
+            Err(_) => Ok(AccountSettings::default()),
+        }
+    }
+
+    /// 口座の設定を追加する
+    pub fn add(&mut self, setting: AccountSetting) {
+        self.settings.push(setting);
+    }
+
+    /// 指定された日までに期日を迎え、まだ登録されていない手数料と利息の項目を作成する
+    ///
+    /// 利息の計算には、先に計上する手数料も含めた残高を使います。項目は日付順に返します。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Account, Category, IncomeCategory, Item, Money};
+    /// use kakeibo_app::services::recurring::{AccountSetting, AccountSettings};
+    /// use chrono::NaiveDate;
+    ///
+    /// let mut deposit = Item::new(
+    ///     String::from("給料"),
+    ///     Category::Income(IncomeCategory::Salary),
+    ///     Money::new(1000000),
+    ///     NaiveDate::from_ymd_opt(2023, 1, 25).unwrap(),
+    /// );
+    /// deposit.set_account(Account::Bank);
+    ///
+    /// let mut settings = AccountSettings::default();
+    /// settings.add(AccountSetting {
+    ///     account: Account::Bank,
+    ///     fee: None,
+    ///     fee_day: 31,
+    ///     interest_rate: Some(0.2),
+    ///     interest_months: vec![2, 8],
+    ///     interest_day: 20,
+    ///     start: NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+    /// });
+    /// let items = settings.create_due_items(&[deposit], NaiveDate::from_ymd_opt(2023, 3, 1).unwrap());
+    /// assert_eq!(items[0].get_name(), "銀行の利息");
+    /// assert_eq!(items[0].get_price(), Money::new(1000));
+    /// ```
+    pub fn create_due_items(&self, data: &[models::Item], today: NaiveDate) -> Vec<models::Item> {
+        let fee_rules = RecurringRules { rules: self.settings.iter().filter_map(AccountSetting::get_fee_rule).collect() };
+        let mut items = fee_rules.create_due_items(data, today);
+        let data_with_fees: Vec<_> = data.iter().chain(&items).collect();
+        let interests: Vec<_> = self.settings.iter()
+            .flat_map(|setting| setting.create_interest_items(&data_with_fees, today))
+            .collect();
+        items.extend(interests);
+        items.sort_by_key(|item| item.get_date());
+        items
+    }
+}
+
+/// 定期取引で読み書きするファイルの保存先を表す構造体
+///
+/// - `file_path`: 家計簿データのJSONファイル
+/// - `recurring_path`: 定期取引のルールのJSONファイル
+/// - `accounts_path`: 口座の利息・手数料の設定のJSONファイル
+/// - `closed_path`: 締め情報のJSONファイル
+/// - `device_path`: デバイス情報のJSONファイル
+pub struct RecurringPaths<'a> {
+    pub file_path: &'a str,
+    pub recurring_path: &'a str,
+    pub accounts_path: &'a str,
+    pub closed_path: &'a str,
+    pub device_path: &'a str,
+}
+
+/// 定期取引のルールを表示し、ルールの追加・削除と、期日を迎えた分の登録を行います。
+///
+/// #### 例
+///
+/// ```rust
+/// // ドキュメンテーションテストでFailになるのでコメントアウト
+/// // use kakeibo_app::config::Config;
+/// // use kakeibo_app::services::recurring::{self, RecurringPaths};
+/// // let paths = RecurringPaths {
+/// //     file_path: "store/data.json",
+/// //     recurring_path: "store/recurring.json",
+/// //     accounts_path: "store/accounts.json",
+/// //     closed_path: "store/closed.json",
+/// //     device_path: "store/device.json",
+/// // };
+/// // recurring::run(&paths, &Config::default());
+/// ```
+///
+/// #### エラー
+///
+/// 不正な入力があった場合や、データの読み書きに失敗した場合は、`KakeiboError`を返します。
+pub fn run(paths: &RecurringPaths, config: &Config) -> Result<()> {
+    let mut rules = RecurringRules::load_or_default(paths.recurring_path)?;
+    print_rules(&rules, config);
+
+    let action = services::prompt::ask("操作を入力してください (a:ルールの追加, d:ルールの削除, p:期日を迎えた分の登録, 空行で終了)")?;
+    match action.trim() {
+        "" => Ok(()),
+        "a" => {
+            rules.add(input_rule(config)?);
+            rules.save(paths.recurring_path)?;
+            println!("ルールを追加しました");
+            Ok(())
+        },
+        "d" => {
+            let index: usize = services::prompt::ask("削除するルールの番号を入力してください")?
+                .trim()
+                .parse()
+                .map_err(|_| KakeiboError::invalid_input("ルールの番号は数値で入力してください"))?;
+            let rule = index.checked_sub(1)
+                .and_then(|index| rules.remove(index))
+                .ok_or_else(|| KakeiboError::InvalidInput(format!("{}番のルールはありません", index)))?;
+            rules.save(paths.recurring_path)?;
+            println!("ルール「{}」を削除しました", rule.name);
+            Ok(())
+        },
+        "p" => apply(paths, &rules, config),
+        _ => Err(KakeiboError::invalid_input("操作はa, d, pのいずれかで入力してください")),
+    }
+}
+
+/// 期日を迎えた定期取引と、口座の利息・手数料を家計簿データに登録します。
+///
+/// 既に登録済みの月の分は登録しません。締め済みの月の分は登録せず、件数を表示します。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services::recurring::{self, RecurringPaths};
+///
+/// let paths = RecurringPaths {
+///     file_path: "store/data.json",
+///     recurring_path: "store/recurring.json",
+///     accounts_path: "store/accounts.json",
+///     closed_path: "store/closed.json",
+///     device_path: "store/device.json",
+/// };
+/// recurring::run_apply(&paths, &Config::default()).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// ルールやデータの読み書きに失敗した場合は、`KakeiboError`を返します。
+pub fn run_apply(paths: &RecurringPaths, config: &Config) -> Result<()> {
+    let rules = RecurringRules::load_or_default(paths.recurring_path)?;
+    apply(paths, &rules, config)
+}
+
+/// 期日を迎えた定期取引と口座の利息・手数料を登録し、登録した項目を出力します。
+///
+/// 家計簿データと、初めて登録する場合に作成する端末の情報は、1つのトランザクションでまとめて保存します。
+fn apply(paths: &RecurringPaths, rules: &RecurringRules, config: &Config) -> Result<()> {
+    let mut transaction = services::io::Transaction::new();
+    let count = stage_due_items(paths, rules, config, &mut transaction)?;
+    if count == 0 {
+        return Ok(());
+    }
+    transaction.commit()?;
+    println!("{}件の定期取引を登録しました", count);
+    Ok(())
+}
+
+/// 期日を迎えた定期取引と口座の利息・手数料を家計簿データに加え、その保存をトランザクションに追加します。
+///
+/// 登録する項目を出力し、その件数を返します。トランザクションを`commit`するまで、どのファイルも変更しません。
+/// 予算の更新など、他の変更と合わせて1つのトランザクションで保存する場合に使います。
+///
+/// #### エラー
+///
+/// ルールや口座の設定、データの読み込みに失敗した場合は、`KakeiboError`を返します。
+pub fn stage_due_items(
+    paths: &RecurringPaths,
+    rules: &RecurringRules,
+    config: &Config,
+    transaction: &mut services::io::Transaction,
+) -> Result<usize> {
+    let settings = AccountSettings::load_or_default(paths.accounts_path)?;
+    if rules.rules.is_empty() && settings.settings.is_empty() {
+        println!("定期取引のルールがありません ({}にルールを追加してください)", paths.recurring_path);
+        return Ok(0);
+    }
+
+    let mut data = services::io::read_data_or_create_new_data(paths.file_path)?;
+    let closed_months = services::close::ClosedMonths::load_or_default(paths.closed_path)?;
+    let today = Local::now().date_naive();
+    let mut candidates = rules.create_due_items(&data, today);
+    candidates.extend(settings.create_due_items(&data, today));
+    candidates.sort_by_key(|item| item.get_date());
+    let (items, locked): (Vec<_>, Vec<_>) = candidates
+        .into_iter()
+        .partition(|item| !closed_months.is_closed(item.get_date()));
+    if !locked.is_empty() {
+        println!("締め済みの月の{}件は登録しません", locked.len());
+    }
+    if items.is_empty() {
+        println!("登録する定期取引はありません");
+        return Ok(0);
+    }
+
+    let count = items.len();
+    let device = services::journal::Device::load_or_stage(paths.device_path, transaction)?;
+    for mut item in items {
+        println!(
+            "  {} {} {} {}",
+            item.get_date(),
+            item.get_name(),
+            config.format_category(item.get_item_category()),
+            config.format_item_price(&item)
+        );
+        item.set_origin(Some(device.next_origin(&data)));
+        data.push(item);
+    }
+    services::io::assign_ids(&mut data);
+    transaction.write_to_json(&data, paths.file_path);
+    Ok(count)
+}
+
+/// ユーザーにルールの内容を尋ね、ルールを作成します。
+///
+/// #### エラー
+///
+/// 不正な入力があった場合、`KakeiboError`を返します。
+fn input_rule(config: &Config) -> Result<RecurringRule> {
+    let name = input_text("品目名を入力してください")?;
+    if name.is_empty() {
+        return Err(KakeiboError::invalid_input("品目名を入力してください"));
+    }
+
+    let register_type = input_number("登録種別を入力してください (0:収入, 1:支出, 2:振替)")?;
+    services::validate::InputValidator::validate_register_type(register_type)?;
+    let categories: Vec<_> = (0..=2)
+        .filter_map(|category_type| models::Item::get_category(register_type, category_type).map(|category| (category_type, category)))
+        .map(|(category_type, category)| format!("{}:{}", category_type, config.format_category(&category)))
+        .collect();
+    let category_type = input_number(&format!("カテゴリを入力してください ({})", categories.join(", ")))?;
+    services::validate::InputValidator::validate_category_type(register_type, category_type)?;
+    let category = models::Item::get_category(register_type, category_type)
+        .ok_or_else(|| KakeiboError::invalid_input("カテゴリ種別が不正です"))?;
+
+    let price = config.parse_price(&input_text("金額を入力してください")?)?;
+    let day = input_number("毎月の日にちを入力してください (1〜31, 月末を超える場合は月末日)")?;
+    if !(1..=31).contains(&day) {
+        return Err(KakeiboError::InvalidInput(format!("日にち{}は不正です (1〜31で入力してください)", day)));
+    }
+    let account_type = input_number("口座を入力してください (0:現金, 1:銀行, 2:クレジットカード)")?;
+    services::validate::InputValidator::validate_account_type(account_type)?;
+    let account = models::Account::get_account(account_type)
+        .ok_or_else(|| KakeiboError::invalid_input("口座種別が不正です"))?;
+
+    let adjustment = match input_text("休日に当たる場合の調整を入力してください (p:前営業日, n:翌営業日, 空行:調整しない)")?.as_str() {
+        "" => None,
+        "p" => Some(BusinessDayRule::Previous),
+        "n" => Some(BusinessDayRule::Next),
+        _ => return Err(KakeiboError::invalid_input("休日の調整はp, nまたは空行で入力してください")),
+    };
+    let start = match input_text("適用を開始する日をyyyy-mm-ddの形式で入力してください (空行で今日)")?.as_str() {
+        "" => Local::now().date_naive(),
+        start => NaiveDate::from_str(start)
+            .map_err(|_| KakeiboError::invalid_input("日付はyyyy-mm-ddの形式で入力してください"))?,
+    };
+
+    Ok(RecurringRule {
+        name,
+        category,
+        price,
+        day: day as u32,
+        account,
+        adjustment,
+        start,
+        end: None,
+    })
+}
+
+/// ユーザーに文字列を尋ね、前後の空白を取り除いて返します。
+fn input_text(message: &str) -> Result<String> {
+    Ok(services::prompt::ask(message)?.trim().to_string())
+}
+
+/// ユーザーに数値を尋ねます。
+///
+/// #### エラー
+///
+/// 数値以外が入力された場合、`KakeiboError::InvalidInput`を返します。
+fn input_number(message: &str) -> Result<u8> {
+    input_text(message)?
+        .parse()
+        .map_err(|_| KakeiboError::invalid_input("数値で入力してください"))
+}
+
+/// ルールの一覧を出力します。
+fn print_rules(rules: &RecurringRules, config: &Config) {
+    if rules.rules.is_empty() {
+        println!("定期取引のルールはありません");
+        return;
+    }
+    println!("定期取引のルール");
+    for (index, rule) in rules.rules.iter().enumerate() {
+        let adjustment = match rule.adjustment {
+            Some(BusinessDayRule::Previous) => " (休日は前営業日)",
+            Some(BusinessDayRule::Next) => " (休日は翌営業日)",
+            None => "",
+        };
+        println!(
+            "  {}. {} {} {} 毎月{}日{} {}から",
+            index + 1,
+            rule.name,
+            config.format_category(&rule.category),
+            config.format_currency(rule.price),
+            rule.day,
+            adjustment,
+            rule.start
+        );
+    }
+}
+
+/// 手数料・利息の日にちの既定値（月末日）を取得します。
+fn get_default_day() -> u32 {
+    31
+}
+
+/// 日付の月をyyyy-mmの形式でフォーマットします。
+fn format_month(month: NaiveDate) -> String {
+    month.format("%Y-%m").to_string()
+}
+
+/// 日付の月の最初の日を取得します。
+fn get_first_day(date: NaiveDate) -> NaiveDate {
+    NaiveDate::from_ymd_opt(date.year(), date.month(), 1).unwrap()
+}
+
+#[cfg(test)]
+mod recurring_test {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn get_test_rule() -> RecurringRule {
+        RecurringRule {
+            name: "家賃".to_string(),
+            category: models::Category::Expense(models::ExpenseCategory::Other),
+            price: models::Money::new(80000),
+            day: 27,
+            account: models::Account::Bank,
+            adjustment: Some(BusinessDayRule::Previous),
+            start: date(2023, 4, 1),
+            end: Some(date(2023, 8, 31)),
+        }
+    }
+
+    #[test]
+    fn test_get_due_date() {
+        let mut rule = get_test_rule();
+        // 2023/5/27は土曜日
+        assert_eq!(rule.get_due_date(date(2023, 5, 1)), Some(date(2023, 5, 26)));
+        assert_eq!(rule.get_due_date(date(2023, 6, 1)), Some(date(2023, 6, 27)));
+        assert_eq!(rule.get_due_date(date(2023, 3, 1)), None);
+        assert_eq!(rule.get_due_date(date(2023, 9, 1)), None);
+
+        rule.day = 31;
+        rule.adjustment = None;
+        assert_eq!(rule.get_due_date(date(2023, 6, 1)), Some(date(2023, 6, 30)));
+    }
+
+    #[test]
+    fn test_create_due_items() {
+        let mut rules = RecurringRules::default();
+        rules.add(get_test_rule());
+
+        let items = rules.create_due_items(&[], date(2023, 6, 26));
+        assert_eq!(items.iter().map(|item| item.get_date()).collect::<Vec<_>>(), vec![date(2023, 4, 27), date(2023, 5, 26)]);
+        assert_eq!(items[0].get_account(), &models::Account::Bank);
+        assert_eq!(items[0].get_extra().get(RECURRING_KEY), Some(&Value::String("2023-04".to_string())));
+
+        // 登録済みの月は作成しない
+        let items = rules.create_due_items(&items[..1], date(2023, 12, 31));
+        assert_eq!(items.iter().map(|item| item.get_date()).collect::<Vec<_>>(), vec![
+            date(2023, 5, 26), date(2023, 6, 27), date(2023, 7, 27), date(2023, 8, 25),
+        ]);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut rules = RecurringRules::default();
+        rules.add(get_test_rule());
+        assert!(rules.remove(1).is_none());
+        assert_eq!(rules.remove(0).map(|rule| rule.name), Some("家賃".to_string()));
+        assert!(rules.get_rules().is_empty());
+    }
+
+    #[test]
+    fn test_create_due_items_for_account_settings() {
+        let mut deposit = models::Item::new(
+            "給料".to_string(),
+            models::Category::Income(models::IncomeCategory::Salary),
+            models::Money::new(1000000),
+            date(2023, 1, 10)
+        );
+        deposit.set_account(models::Account::Bank);
+        let mut settings = AccountSettings::default();
+        settings.add(AccountSetting {
+            account: models::Account::Bank,
+            fee: Some(models::Money::new(220)),
+            fee_day: 25,
+            interest_rate: Some(0.1),
+            interest_months: vec![2],
+            interest_day: 20,
+            start: date(2023, 1, 1),
+        });
+
+        // 2023/2/25は土曜日のため、手数料は翌営業日の2/27に計上する
+        let mut data = vec![deposit];
+        let items = settings.create_due_items(&data, date(2023, 3, 1));
+        let summary: Vec<_> = items.iter().map(|item| (item.get_date(), item.get_name(), item.get_price())).collect();
+        assert_eq!(summary, vec![
+            (date(2023, 1, 25), "銀行の口座維持手数料", models::Money::new(220)),
+            (date(2023, 2, 20), "銀行の利息", models::Money::new(999)),
+            (date(2023, 2, 27), "銀行の口座維持手数料", models::Money::new(220)),
+        ]);
+
+        data.extend(items);
+        assert!(settings.create_due_items(&data, date(2023, 3, 1)).is_empty());
+    }
+}
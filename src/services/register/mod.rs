@@ -1,13 +1,25 @@
 //! 登録サービス
 //!
 //! このモジュールは、データをJOSNファイル`store/data.json`に登録する機能を提供します。
+//!
+//! 対話入力を伴わない登録には、`RegisterRequest`と`save`関数を使います。
+//! CLIの対話入力もこの構造体に入力内容を集めてから保存するため、TUIやWebなど他のフロントエンドからも同じ検証・保存処理を利用できます。
+//! データの読み書きは保存先（`services::io::store::DataStore`）を通して行うため、`save_to`関数ではJSONファイル以外の保存先にも登録できます。
 
-use std::io;
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
 use std::str::FromStr;
-use chrono::NaiveDate;
+use chrono::{Datelike, Local, NaiveDate};
+use serde_json::Value;
 
+use crate::config::Config;
+use crate::error::{KakeiboError, Result};
 use crate::models;
 use crate::services;
+use crate::services::io::store::DataStore;
+
+/// カテゴリの使用頻度を数える対象とする、直近の項目数
+const RECENT_ITEM_COUNT: usize = 100;
 
 /// 家計簿アプリの登録機能を提供します。
 ///
@@ -18,133 +30,1100 @@ use crate::services;
 /// ```rust
 /// // ドキュメンテーションテストでFailになるのでコメントアウト
 /// // use kakeibo_app::services;
-/// // services::register::run("store/data.json");
+/// // services::register::run("store/data.json", "store/closed.json", "store/device.json", "store/payees.json", &Config::default());
 /// ```
 ///
 /// #### 詳細
 /// 
 /// この関数は以下の手順で動作します。
-/// 1. ユーザーに登録種別（収入または支出）を尋ねる。
-/// 2. ユーザーに品目名を尋ねる。
-/// 3. ユーザーにカテゴリ種別を尋ねる。
-/// 4. ユーザーに金額を尋ねる。
-/// 5. ユーザーに日付を尋ねる。
-/// 6. 入力された情報をもとに、`Item`インスタンスを作成する。
-/// 7. JSONファイルから既存のデータを読み込む。
-/// 8. 新しい`Item`インスタンスをデータに追加する。
-/// 9. 更新されたデータをJSONファイルに書き込む。
-///
-/// #### 注意
-/// 
-/// この関数は、ユーザーからの入力が正しい形式であることを前提としています。
-/// 不正な入力があった場合、プログラムはパニックになります。
-pub fn run(file_path: &str) {
+/// 1. JSONファイルから既存のデータを読み込む。
+/// 2. ユーザーに登録種別（収入・支出・口座間の振替）を尋ねる。
+/// 3. ユーザーに品目名を尋ねる。
+/// 4. 同じ登録種別で同名の過去の項目がある場合は、直近の項目のカテゴリと金額を表示し、流用するかを尋ねる。流用する場合は5と6を省略する。
+/// 5. ユーザーにカテゴリ種別（振替の場合は振替先の口座）を尋ねる。カテゴリは最近よく使うものから順に表示する。
+/// 6. 振替以外の場合は、ユーザーにサブカテゴリ（外食・自炊など）を尋ねる（省略可）。同じカテゴリで過去に使ったサブカテゴリを表示する。
+/// 7. ユーザーに金額を尋ねる。
+/// 8. ユーザーに日付を尋ねる。
+/// 9. ユーザーに口座（現金・銀行・クレジットカード）を尋ねる（省略時は設定の`default_accounts`のカテゴリの口座、設定が無い場合は現金）。振替の場合は振替元の口座になる。
+/// 10. 支出の場合は、ユーザーに固定費かどうかを尋ねる。
+/// 11. 支出の場合は、ユーザーに支出の分類（必要・浪費・投資）を尋ねる（省略可）。
+/// 12. ユーザーにイベント名を尋ねる（省略可）。
+/// 13. ユーザーに支払先を尋ねる（省略可）。支払先マスタの別名や表記ゆれは正式名に統一する。
+/// 14. ユーザーに参照番号（領収書番号・注文番号など）を尋ねる（省略可）。
+/// 15. ユーザーに追加属性をkey=valueの形式で尋ねる（省略可）。
+/// 16. 複式モードの場合は、ユーザーに借方・貸方の勘定科目を尋ねる（省略時はカテゴリと口座から決まる勘定科目）。
+/// 17. 入力内容を表示し、保存・修正・破棄のいずれかを尋ねる。修正の場合や、保存しようとした日付の月が締め済みの場合は、17に戻る。
+/// 18. 入力された情報をもとに、`Item`インスタンスを作成する。
+/// 19. 新しい`Item`インスタンスに、この端末のデバイスIDと連番を付与してデータに追加する。
+/// 20. 更新されたデータを保存先（JSONファイルなど）に書き込む。
+///
+/// #### エラー
+/// 
+/// 不正な入力があった場合や、支払先マスタ・データの読み書きに失敗した場合は、`KakeiboError`を返します。
+pub fn run(file_path: &str, closed_path: &str, device_path: &str, payees_path: &str, config: &Config) -> Result<()> {
     println!("収支の登録を行います");
-    let register_type = input_register_type();
-    let name = input_name();
-    let category_type = input_category_type(register_type);
-    let price = input_price();
-    let date = input_date();
-    let category = models::Item::get_category(register_type, category_type);
+    let closed_months = services::close::ClosedMonths::load_or_default(closed_path)?;
+    let payees = services::payee::PayeeMaster::load_or_default(payees_path)?;
+    let store = services::io::store::open_store(file_path);
+    let data = services::io::store::load_or_create(store.as_ref())?;
+    let register_type = input_register_type()?;
+    let name = input_name()?;
+    let (category_type, subcategory, price) = match input_previous(register_type, &name, &data, config)? {
+        Some(previous) => previous,
+        None => {
+            let category_type = input_category_type(register_type, &data, config)?;
+            let subcategory = if register_type == 2 { None } else { input_subcategory(register_type, category_type, &data)? };
+            (category_type, subcategory, input_price(config)?)
+        },
+    };
+    let mut request = RegisterRequest::new(register_type, category_type, name, price, input_date()?);
+    request.subcategory = subcategory;
+    request.account = input_account(&request.get_default_account(config))?;
+    request.fixed = register_type == 1 && input_fixed()?;
+    request.judgment = if register_type == 1 { input_judgment()? } else { None };
+    request.event = input_event()?;
+    request.payee = input_payee(&payees)?;
+    request.reference = input_reference()?;
+    request.extra = input_extra()?;
+    if config.double_entry {
+        request.entry = input_entry(request.register_type, request.category_type, &request.account)?;
+    }
+    confirm_and_save(request, data, &closed_months, &payees, store.as_ref(), device_path, config)
+}
+
+/// 自由文から日付・金額・品目名を抽出して登録します。
+///
+/// この関数は、「5/2 スタバ 680円」のような自由文を受け付け、抽出した内容を支出の登録候補として確認画面に表示します。
+/// 抽出できなかった品目名と金額はユーザーに尋ね、日付が無い場合は今日の日付にします。
+/// カテゴリは、同名の過去の支出があればそのカテゴリ・サブカテゴリ・口座・支払先を使い、無ければユーザーに尋ねます（口座は設定のカテゴリの既定の口座か、現金）。
+///
+/// #### 例
+///
+/// ```rust
+/// // ドキュメンテーションテストでFailになるのでコメントアウト
+/// // use kakeibo_app::services;
+/// // services::register::run_smart("store/data.json", "store/closed.json", "store/device.json", "store/payees.json", &Config::default());
+/// ```
+///
+/// #### エラー
+///
+/// 不正な入力があった場合や、支払先マスタ・データの読み書きに失敗した場合は、`KakeiboError`を返します。
+pub fn run_smart(file_path: &str, closed_path: &str, device_path: &str, payees_path: &str, config: &Config) -> Result<()> {
+    println!("自由文から支出を登録します");
+    let closed_months = services::close::ClosedMonths::load_or_default(closed_path)?;
+    let payees = services::payee::PayeeMaster::load_or_default(payees_path)?;
+    let store = services::io::store::open_store(file_path);
+    let data = services::io::store::load_or_create(store.as_ref())?;
+    let text = services::prompt::ask("支出を入力してください (例: 5/2 スタバ 680円)")?;
+    let parsed = parse_free_text(&text, Local::now().date_naive(), config);
+
+    let name = match parsed.name {
+        Some(name) => name,
+        None => input_name()?,
+    };
+    let (category_type, subcategory, account, payee) = match get_previous_item(1, &name, &data) {
+        Some((category_type, previous)) => (
+            category_type,
+            previous.get_subcategory().map(str::to_string),
+            previous.get_account().clone(),
+            previous.get_payee().map(|payee| payees.normalize(payee)),
+        ),
+        None => {
+            let category_type = input_category_type(1, &data, config)?;
+            let account = models::Item::get_category(1, category_type)
+                .and_then(|category| config.get_default_account(&category))
+                .unwrap_or_default();
+            (category_type, None, account, None)
+        },
+    };
+    let price = match parsed.price {
+        Some(price) => price,
+        None => input_price(config)?,
+    };
+    let date = parsed.date.unwrap_or_else(|| Local::now().date_naive());
+    let mut request = RegisterRequest::new(1, category_type, name, price, date);
+    request.subcategory = subcategory;
+    request.account = account;
+    request.payee = payee;
+    confirm_and_save(request, data, &closed_months, &payees, store.as_ref(), device_path, config)
+}
+
+/// 自由文から抽出した登録候補を表す構造体
+///
+/// 抽出できなかった値は`None`になります。
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParsedText {
+    pub date: Option<NaiveDate>,
+    pub price: Option<models::Money>,
+    pub name: Option<String>,
+}
+
+/// 自由文を空白で区切り、日付・金額・品目名を抽出します。
+///
+/// - 日付: `5/2`、`5月2日`、`2023/5/2`、`2023-05-02`の形式。年が無い場合は、`today`以前で最も近い日付にします。
+/// - 金額: `680円`、`¥680`、`1,280円`、`$12.50`の形式、または数字のみの語。設定の通貨の単位で解釈します（米ドルなら`12.5`は12ドル50セント）。
+/// - 品目名: 日付と金額以外の語を空白でつないだもの。
+///
+/// 全角の数字・記号は半角とみなします。日付や金額が複数ある場合は、最初のものを使います。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::models::Money;
+/// use kakeibo_app::services::register;
+/// use chrono::NaiveDate;
+///
+/// let today = NaiveDate::from_ymd_opt(2023, 5, 10).unwrap();
+/// let parsed = register::parse_free_text("5/2 スタバ 680円", today, &Config::default());
+/// assert_eq!(parsed.date, NaiveDate::from_ymd_opt(2023, 5, 2));
+/// assert_eq!(parsed.price, Some(Money::new(680)));
+/// assert_eq!(parsed.name.as_deref(), Some("スタバ"));
+/// ```
+pub fn parse_free_text(text: &str, today: NaiveDate, config: &Config) -> ParsedText {
+    let text = to_half_width(text);
+    let mut parsed = ParsedText { date: None, price: None, name: None };
+    let mut words = Vec::new();
+    for word in text.split_whitespace() {
+        if parsed.date.is_none() {
+            if let Some(date) = parse_date_word(word, today) {
+                parsed.date = Some(date);
+                continue;
+            }
+        }
+        if parsed.price.is_none() {
+            if let Some(price) = parse_price_word(word, config) {
+                parsed.price = Some(price);
+                continue;
+            }
+        }
+        words.push(word);
+    }
+    if !words.is_empty() {
+        parsed.name = Some(words.join(" "));
+    }
+    parsed
+}
+
+/// 全角の数字と日付・金額に使う記号を半角に変換します。
+fn to_half_width(text: &str) -> String {
+    text.chars().map(|c| match c {
+        '０'..='９' => char::from_u32(c as u32 - '０' as u32 + '0' as u32).unwrap(),
+        '／' => '/',
+        '－' => '-',
+        '，' => ',',
+        '￥' => '¥',
+        _ => c,
+    }).collect()
+}
+
+/// 語を日付として解釈します。
+///
+/// 年が無い場合は、`today`の年の日付とし、それが`today`より後であれば前年の日付にします。
+fn parse_date_word(word: &str, today: NaiveDate) -> Option<NaiveDate> {
+    if let Ok(date) = NaiveDate::parse_from_str(word, "%Y-%m-%d") {
+        return Some(date);
+    }
+    let word = word.replace('月', "/").replace('日', "");
+    let parts: Vec<u32> = word.split('/').map(|part| part.parse().ok()).collect::<Option<_>>()?;
+    match parts[..] {
+        [year, month, day] => NaiveDate::from_ymd_opt(year as i32, month, day),
+        [month, day] => {
+            let date = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+            if date > today {
+                NaiveDate::from_ymd_opt(today.year() - 1, month, day)
+            } else {
+                Some(date)
+            }
+        },
+        _ => None,
+    }
+}
+
+/// 語を金額として解釈します。
+///
+/// 通貨の記号（`¥`・`$`・`€`）や`円`を取り除いてから、設定の通貨の金額に変換します。
+fn parse_price_word(word: &str, config: &Config) -> Option<models::Money> {
+    let price = word.strip_prefix(['¥', '$', '€']).unwrap_or(word);
+    let price = price.strip_suffix('円').unwrap_or(price);
+    if price.is_empty() || !price.chars().all(|c| c.is_ascii_digit() || c == ',' || c == '.') {
+        return None;
+    }
+    config.parse_price(price).ok()
+}
+
+/// 入力内容の確認画面を表示し、保存を選ばれた場合はデータに追加して保存先に書き込みます。
+///
+/// #### エラー
+///
+/// 不正な入力があった場合や、データの書き込みに失敗した場合は、`KakeiboError`を返します。
+fn confirm_and_save(
+    mut request: RegisterRequest,
+    mut data: Vec<models::Item>,
+    closed_months: &services::close::ClosedMonths,
+    payees: &services::payee::PayeeMaster,
+    store: &dyn DataStore,
+    device_path: &str,
+    config: &Config
+) -> Result<()> {
+    loop {
+        print_confirmation(&request, config)?;
+        match input_action()? {
+            'y' if closed_months.is_closed(request.date) => services::close::print_locked(request.date),
+            'y' => break,
+            'e' => request.edit(input_edit_target()?, &data, payees, config)?,
+            _ => {
+                println!("登録を破棄しました");
+                return Ok(());
+            }
+        }
+    }
 
-    let item = models::Item::new(name, category, price, date);
-    println!("登録情報: {:?}", item);
+    add_to_data(&request, &mut data, device_path)?;
+    store.save(&data)?;
+    println!("項目の登録が完了しました");
+    Ok(())
+}
 
-    let mut data = services::io::read_data_or_create_new_data(file_path);
+/// 登録内容を検証し、JSONファイルに保存します。
+///
+/// この関数は対話入力や画面への出力を行わないため、CLI以外のフロントエンドからも利用できます。
+/// 新しい項目には、この端末のデバイスIDと連番、および項目IDを付与します。保存した項目のIDを返します。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::models::Money;
+/// use kakeibo_app::services::register::{self, RegisterRequest};
+/// use chrono::NaiveDate;
+///
+/// let mut request = RegisterRequest::new(1, 0, String::from("ランチ"), Money::new(1000), NaiveDate::from_ymd_opt(2023, 1, 5).unwrap());
+/// request.event = Some(String::from("出張"));
+/// let id = register::save(&request, "store/data.json", "store/closed.json", "store/device.json").unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// 登録内容が不正な場合や、日付の月が締め済みの場合は、`KakeiboError::InvalidInput`を返します。
+/// データの読み書きに失敗した場合も、`KakeiboError`を返します。
+pub fn save(request: &RegisterRequest, file_path: &str, closed_path: &str, device_path: &str) -> Result<u64> {
+    save_to(request, services::io::store::open_store(file_path).as_ref(), closed_path, device_path)
+}
+
+/// 登録内容を検証し、指定された保存先に保存します。
+///
+/// `save`と同じ処理を、データファイルのパスの代わりに保存先（`DataStore`）を指定して行います。
+/// 保存先が存在しない場合は、新しく作成します。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::models::Money;
+/// use kakeibo_app::services::io::store::{DataStore, MemoryStore};
+/// use kakeibo_app::services::register::{self, RegisterRequest};
+/// use chrono::NaiveDate;
+///
+/// let store = MemoryStore::default();
+/// let request = RegisterRequest::new(1, 0, String::from("ランチ"), Money::new(1000), NaiveDate::from_ymd_opt(2023, 1, 5).unwrap());
+/// let id = register::save_to(&request, &store, "store/closed.json", "store/device.json").unwrap();
+/// assert_eq!(store.load().unwrap().len(), 1);
+/// ```
+///
+/// #### エラー
+///
+/// `save`と同じです。
+pub fn save_to(request: &RegisterRequest, store: &dyn DataStore, closed_path: &str, device_path: &str) -> Result<u64> {
+    request.validate()?;
+    let closed_months = services::close::ClosedMonths::load_or_default(closed_path)?;
+    if closed_months.is_closed(request.date) {
+        return Err(KakeiboError::InvalidInput(format!(
+            "{}は締め済みのため登録できません",
+            services::summarize::format_date(request.date)
+        )));
+    }
+
+    let mut data = if store.exists() { store.load()? } else { Vec::new() };
+    let id = add_to_data(request, &mut data, device_path)?;
+    store.save(&data)?;
+    Ok(id)
+}
+
+/// 登録内容から作成した項目に、デバイスIDと連番、項目IDを付与してデータに追加し、項目IDを返します。
+fn add_to_data(request: &RegisterRequest, data: &mut Vec<models::Item>, device_path: &str) -> Result<u64> {
+    let mut item = request.to_item()?;
+    let device = services::journal::Device::load_or_create(device_path)?;
+    item.set_origin(Some(device.next_origin(data)));
     data.push(item);
-    services::io::write_to_json(&data, file_path);
+    services::io::assign_ids(data);
+    Ok(data.last().map_or(0, |item| item.get_id()))
 }
 
-/// ユーザーに登録種別（収入または支出）を尋ね、数値で返します。
+/// 登録内容を表す構造体
 ///
-/// #### 注意
+/// - `register_type`: 登録種別（0:収入, 1:支出, 2:振替）
+/// - `category_type`: カテゴリ種別（0〜2。振替の場合は振替先の口座種別）
+/// - `subcategory`: サブカテゴリ名（外食・自炊など）
+/// - `name`: 品目名
+/// - `price`: 金額
+/// - `date`: 日付
+/// - `account`: 口座（振替の場合は振替元の口座）
+/// - `fixed`: 固定費かどうか（支出のみ）
+/// - `judgment`: 支出の分類（必要・浪費・投資。支出のみ）
+/// - `event`: イベント名
+/// - `payee`: 支払先
+/// - `reference`: 参照番号（領収書番号・注文番号など）
+/// - `extra`: 追加属性（キーと値の組）
+/// - `entry`: 仕訳（`None`の場合はカテゴリから決まる勘定科目）
+///
+/// `Item`を作成する前の値を保持するため、CLIの確認画面ではこの構造体の値を修正してから保存します。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisterRequest {
+    pub register_type: u8,
+    pub category_type: u8,
+    pub subcategory: Option<String>,
+    pub name: String,
+    pub price: models::Money,
+    pub date: NaiveDate,
+    pub account: models::Account,
+    pub fixed: bool,
+    pub judgment: Option<models::Judgment>,
+    pub event: Option<String>,
+    pub payee: Option<String>,
+    pub reference: Option<String>,
+    pub extra: Vec<(String, String)>,
+    pub entry: Option<models::JournalEntry>,
+}
+
+impl RegisterRequest {
+    /// 必須の値から登録内容を作成します。
+    ///
+    /// サブカテゴリ・固定費・分類・イベント名・支払先・参照番号・追加属性・仕訳は未設定、口座は現金になります。必要に応じて各フィールドに設定してください。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::Money;
+    /// use kakeibo_app::services::register::RegisterRequest;
+    /// use chrono::NaiveDate;
+    ///
+    /// let request = RegisterRequest::new(1, 0, String::from("ランチ"), Money::new(1000), NaiveDate::from_ymd_opt(2023, 1, 5).unwrap());
+    /// assert!(!request.fixed);
+    /// assert!(request.event.is_none());
+    /// ```
+    pub fn new(register_type: u8, category_type: u8, name: String, price: models::Money, date: NaiveDate) -> Self {
+        RegisterRequest {
+            register_type,
+            category_type,
+            subcategory: None,
+            name,
+            price,
+            date,
+            account: models::Account::Cash,
+            fixed: false,
+            judgment: None,
+            event: None,
+            payee: None,
+            reference: None,
+            extra: Vec::new(),
+            entry: None,
+        }
+    }
+
+    /// 登録内容を検証します。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::Money;
+    /// use kakeibo_app::services::register::RegisterRequest;
+    /// use chrono::NaiveDate;
+    ///
+    /// let date = NaiveDate::from_ymd_opt(2023, 1, 5).unwrap();
+    /// assert!(RegisterRequest::new(1, 0, String::from("ランチ"), Money::new(1000), date).validate().is_ok());
+    /// assert!(RegisterRequest::new(1, 3, String::from("ランチ"), Money::new(1000), date).validate().is_err());
+    /// ```
+    ///
+    /// #### エラー
+    ///
+    /// 登録種別・カテゴリ種別が範囲外の場合は、`KakeiboError::Validation`を返します。
+    /// 品目名が空の場合、支出以外に固定費や分類が設定されている場合、振替元と振替先の口座が同じ場合は、`KakeiboError::InvalidInput`を返します。
+    pub fn validate(&self) -> Result<()> {
+        services::validate::InputValidator::validate_register_type(self.register_type)?;
+        services::validate::InputValidator::validate_category_type(self.register_type, self.category_type)?;
+        if self.name.trim().is_empty() {
+            return Err(KakeiboError::invalid_input("品目名を入力してください"));
+        }
+        if self.register_type != 1 && self.fixed {
+            return Err(KakeiboError::invalid_input("支出以外には固定費を設定できません"));
+        }
+        if self.register_type != 1 && self.judgment.is_some() {
+            return Err(KakeiboError::invalid_input("支出以外には分類を設定できません"));
+        }
+        if models::Item::get_category(self.register_type, self.category_type) == Some(models::Category::Transfer(self.account.clone())) {
+            return Err(KakeiboError::invalid_input("振替元と振替先の口座が同じです"));
+        }
+        Ok(())
+    }
+
+    /// 登録内容を検証し、`Item`インスタンスを作成します。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Category, ExpenseCategory, Money};
+    /// use kakeibo_app::services::register::RegisterRequest;
+    /// use chrono::NaiveDate;
+    ///
+    /// let date = NaiveDate::from_ymd_opt(2023, 1, 5).unwrap();
+    /// let item = RegisterRequest::new(1, 0, String::from("ランチ"), Money::new(1000), date).to_item().unwrap();
+    /// assert_eq!(item.get_item_category(), &Category::Expense(ExpenseCategory::Food));
+    /// assert!(RegisterRequest::new(1, 3, String::from("ランチ"), Money::new(1000), date).to_item().is_err());
+    /// ```
+    ///
+    /// #### エラー
+    ///
+    /// 登録内容が不正な場合は、`validate`と同じエラーを返します。
+    pub fn to_item(&self) -> Result<models::Item> {
+        self.validate()?;
+        let category = models::Item::get_category(self.register_type, self.category_type)
+            .ok_or_else(|| KakeiboError::invalid_input("カテゴリ種別が不正です"))?;
+        let mut item = models::Item::new(self.name.clone(), category, self.price, self.date);
+        item.set_subcategory(self.subcategory.clone());
+        item.set_account(self.account.clone());
+        item.set_fixed(self.fixed);
+        item.set_judgment(self.judgment);
+        item.set_event(self.event.clone());
+        item.set_payee(self.payee.clone());
+        item.set_reference(self.reference.clone());
+        item.set_entry(self.entry.clone());
+        for (key, value) in &self.extra {
+            item.set_extra(key.clone(), Value::String(value.clone()));
+        }
+        Ok(item)
+    }
+
+    /// カテゴリの既定の口座を取得します。
+    ///
+    /// 設定の`default_accounts`にカテゴリの口座が無い場合は、現金を返します。
+    fn get_default_account(&self, config: &Config) -> models::Account {
+        models::Item::get_category(self.register_type, self.category_type)
+            .and_then(|category| config.get_default_account(&category))
+            .unwrap_or_default()
+    }
+
+    /// 指定された項目をユーザーに入力し直してもらいます。
+    ///
+    /// 収支の区分（カテゴリ）を修正した場合は、登録種別・カテゴリ種別・サブカテゴリを尋ね直し、支出以外になった場合は固定費と分類を解除します。
+    fn edit(&mut self, edit_target: u8, data: &[models::Item], payees: &services::payee::PayeeMaster, config: &Config) -> Result<()> {
+        match edit_target {
+            0 => self.name = input_name()?,
+            1 => {
+                self.register_type = input_register_type()?;
+                self.category_type = input_category_type(self.register_type, data, config)?;
+                self.subcategory = input_subcategory(self.register_type, self.category_type, data)?;
+                if self.register_type != 1 {
+                    self.fixed = false;
+                    self.judgment = None;
+                }
+            },
+            2 => self.price = input_price(config)?,
+            3 => self.date = input_date()?,
+            4 => {
+                if self.register_type != 1 {
+                    println!("支出以外には固定費を設定できません");
+                } else {
+                    self.fixed = input_fixed()?;
+                }
+            },
+            5 => self.event = input_event()?,
+            6 => self.reference = input_reference()?,
+            7 => self.extra = input_extra()?,
+            8 => {
+                if config.double_entry {
+                    self.entry = input_entry(self.register_type, self.category_type, &self.account)?;
+                } else {
+                    println!("複式モードではないため勘定科目は設定できません");
+                }
+            },
+            9 => self.subcategory = input_subcategory(self.register_type, self.category_type, data)?,
+            10 => self.account = input_account(&self.get_default_account(config))?,
+            11 => self.payee = input_payee(payees)?,
+            _ => {
+                if self.register_type != 1 {
+                    println!("支出以外には分類を設定できません");
+                } else {
+                    self.judgment = input_judgment()?;
+                }
+            },
+        }
+        Ok(())
+    }
+}
+
+/// ユーザーに登録種別（収入・支出・振替）を尋ね、数値で返します。
+///
+/// #### エラー
 /// 
-/// この関数は、ユーザーからの入力が正しい形式であることを前提としています。
-/// 不正な入力があった場合、プログラムはパニックになります。
-fn input_register_type() -> u8 {
-    println!("登録種別を入力してください (0:収入, 1:支出)");
-    let mut register_type = String::new();
-    io::stdin().read_line(&mut register_type).expect("登録種別の入力に失敗しました");
+/// 不正な入力があった場合、`KakeiboError`を返します。
+fn input_register_type() -> Result<u8> {
+    let register_type = services::prompt::ask("登録種別を入力してください (0:収入, 1:支出, 2:振替)")?;
     let register_type: u8 = register_type
                                 .trim()
                                 .parse()
-                                .expect("登録種別は数値で入力してください");
+                                .map_err(|_| KakeiboError::invalid_input("登録種別は数値で入力してください"))?;
 
     // バリデーション
-    services::validate::InputValidator::validate_register_type(register_type);
+    services::validate::InputValidator::validate_register_type(register_type)?;
 
-    register_type
+    Ok(register_type)
 }
 
 /// ユーザーに品目名を尋ね、文字列で返します。
 ///
-/// #### 注意
+/// #### エラー
 /// 
-/// この関数は、ユーザーからの入力が正しい形式であることを前提としています。
-/// 不正な入力があった場合、プログラムはパニックになります。
-fn input_name() -> String {
-    println!("品目名を入力してください");
-    let mut name = String::new();
-    io::stdin().read_line(&mut name).expect("品目名の入力に失敗しました");
+/// 不正な入力があった場合、`KakeiboError`を返します。
+fn input_name() -> Result<String> {
+    let name = services::prompt::ask("品目名を入力してください")?;
 
-    name.trim().to_string()
+    Ok(name.trim().to_string())
 }
 
-/// ユーザーにカテゴリ種別を尋ね、数値で返します。
+/// 同名の過去の項目を表示し、カテゴリ種別・サブカテゴリ・金額を流用するかをユーザーに尋ねます。
 ///
-/// #### 注意
+/// 同じ登録種別で同名の項目が無い場合や、流用しない場合は`None`を返します。
+///
+/// #### エラー
 /// 
-/// この関数は、ユーザーからの入力が正しい形式であることを前提としています。
-/// 不正な入力があった場合、プログラムはパニックになります。
-fn input_category_type(register_type: u8) ->  u8 {
-    println!("カテゴリを入力してください");
-    if register_type == 0 {
-        println!("(0:給与, 1:ボーナス, 2:その他)");
-    } else {
-        println!("(0:食費, 1:趣味, 2:その他)");
+/// 不正な入力があった場合、`KakeiboError`を返します。
+fn input_previous(
+    register_type: u8,
+    name: &str,
+    data: &[models::Item],
+    config: &Config
+) -> Result<Option<(u8, Option<String>, models::Money)>> {
+    let Some((category_type, previous)) = get_previous_item(register_type, name, data) else {
+        return Ok(None);
+    };
+    println!(
+        "前回の「{}」: {} {} {}",
+        name,
+        previous.get_date(),
+        config.format_category(previous.get_item_category()),
+        config.format_currency(previous.get_price())
+    );
+    let answer = services::prompt::ask("前回と同じカテゴリと金額を使いますか (y/n)")?;
+    match answer.trim() {
+        "y" | "Y" => Ok(Some((category_type, previous.get_subcategory().map(str::to_string), previous.get_price()))),
+        "n" | "N" | "" => Ok(None),
+        _ => Err(KakeiboError::invalid_input("yまたはnで入力してください")),
     }
+}
 
-    let mut category_type = String::new();
-    io::stdin().read_line(&mut category_type).expect("カテゴリ種別の入力に失敗しました");
+/// 同じ登録種別で同名の項目のうち、日付が最も新しいものをカテゴリ種別とともに返します。
+///
+/// 日付が同じ項目が複数ある場合は、後に登録した項目を返します。
+pub(crate) fn get_previous_item<'a>(register_type: u8, name: &str, data: &'a [models::Item]) -> Option<(u8, &'a models::Item)> {
+    data.iter()
+        .filter(|item| item.get_name() == name)
+        .filter_map(|item| {
+            (0..=2)
+                .find(|category_type| models::Item::get_category(register_type, *category_type).as_ref() == Some(item.get_item_category()))
+                .map(|category_type| (category_type, item))
+        })
+        .max_by_key(|(_, item)| item.get_date())
+}
+
+/// ユーザーにカテゴリ種別を尋ね、数値で返します。
+///
+/// カテゴリは最近よく使うものから順に表示します。入力する数値は並び順によらず同じです。
+///
+/// #### エラー
+/// 
+/// 不正な入力があった場合、`KakeiboError`を返します。
+fn input_category_type(register_type: u8, data: &[models::Item], config: &Config) -> Result<u8> {
+    let message = if register_type == 2 { "振替先の口座を入力してください" } else { "カテゴリを入力してください" };
+    let choices: Vec<_> = get_category_order(register_type, data).into_iter().filter_map(|category_type| {
+        let category = models::Item::get_category(register_type, category_type)?;
+        Some(format!("{}:{}", category_type, config.format_category(&category)))
+    }).collect();
+
+    let category_type = services::prompt::ask(&format!("{}\n({})", message, choices.join(", ")))?;
     let category_type: u8 = category_type
                                 .trim()
                                 .parse()
-                                .expect("カテゴリ種別は数値で入力してください");
+                                .map_err(|_| KakeiboError::invalid_input("カテゴリ種別は数値で入力してください"))?;
 
     // バリデーション
-    services::validate::InputValidator::validate_category_type(register_type, category_type);
+    services::validate::InputValidator::validate_category_type(register_type, category_type)?;
+
+    Ok(category_type)
+}
 
-    category_type
+/// ユーザーにサブカテゴリを尋ね、文字列で返します。
+///
+/// 同じカテゴリで過去に使ったサブカテゴリがある場合は、入力例として表示します。
+/// 空行が入力された場合は、カテゴリを細分しないものとして`None`を返します。
+fn input_subcategory(register_type: u8, category_type: u8, data: &[models::Item]) -> Result<Option<String>> {
+    let subcategories = models::Item::get_category(register_type, category_type)
+        .map(|category| get_subcategories(&category, data))
+        .unwrap_or_default();
+    let message = if subcategories.is_empty() {
+        "サブカテゴリを入力してください (例: 外食, 無い場合は空行)".to_string()
+    } else {
+        format!("サブカテゴリを入力してください (これまでの入力: {}, 無い場合は空行)", subcategories.join(", "))
+    };
+    let subcategory = services::prompt::ask(&message)?;
+
+    let subcategory = subcategory.trim();
+    if subcategory.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(subcategory.to_string()))
+    }
+}
+
+/// 指定されたカテゴリで使われているサブカテゴリを、重複を除いて名前順で返します。
+fn get_subcategories<'a>(category: &models::Category, data: &'a [models::Item]) -> Vec<&'a str> {
+    let subcategories: BTreeSet<_> = data.iter()
+        .filter(|item| item.get_item_category() == category)
+        .filter_map(|item| item.get_subcategory())
+        .collect();
+    subcategories.into_iter().collect()
+}
+
+/// カテゴリ種別を最近の使用頻度の高い順に並べて返します。
+///
+/// 直近`RECENT_ITEM_COUNT`件の項目のうち、同じ登録種別の項目でカテゴリごとの件数を数えます。
+/// 件数が同じカテゴリは、カテゴリ種別の順に並べます。
+fn get_category_order(register_type: u8, data: &[models::Item]) -> Vec<u8> {
+    let recent = &data[data.len().saturating_sub(RECENT_ITEM_COUNT)..];
+    let mut order: Vec<u8> = (0..=2).collect();
+    order.sort_by_key(|category_type| {
+        let category = models::Item::get_category(register_type, *category_type);
+        Reverse(recent.iter().filter(|item| category.as_ref() == Some(item.get_item_category())).count())
+    });
+    order
 }
 
-/// ユーザーに金額を尋ね、数値で返します。
+/// ユーザーに金額を尋ね、設定の通貨の金額で返します。
 ///
-/// #### 注意
+/// #### エラー
 /// 
-/// この関数は、ユーザーからの入力が正しい形式であることを前提としています。
-/// 不正な入力があった場合、プログラムはパニックになります。
-fn input_price() -> u32 {
-    println!("金額を入力してください");
-    let mut price = String::new();
-    io::stdin().read_line(&mut price).expect("金額の入力に失敗しました");
+/// 不正な入力があった場合、`KakeiboError`を返します。
+fn input_price(config: &Config) -> Result<models::Money> {
+    let price = services::prompt::ask("金額を入力してください")?;
 
-    price.trim().parse().expect("金額は数値で入力してください")
+    config.parse_price(&price)
 }
 
 /// ユーザーに日付を尋ね、`NaiveDate`オブジェクトで返します。
 ///
-/// #### 注意
+/// #### エラー
 /// 
-/// この関数は、ユーザーからの入力が正しい形式であることを前提としています。
-/// 不正な入力があった場合、プログラムはパニックになります。
-fn input_date() -> NaiveDate {
-    println!("日付を入力してください");
-    let mut date = String::new();
-    io::stdin().read_line(&mut date).expect("日付の入力に失敗しました");
-    NaiveDate::from_str(&date).expect("日付はyyyy-mm-ddの形式で入力してください")
+/// 不正な入力があった場合、`KakeiboError`を返します。
+fn input_date() -> Result<NaiveDate> {
+    let date = services::prompt::ask("日付を入力してください")?;
+    NaiveDate::from_str(&date).map_err(|_| KakeiboError::invalid_input("日付はyyyy-mm-ddの形式で入力してください"))
+}
+
+/// ユーザーに口座を尋ね、`Account`で返します。
+///
+/// 空行が入力された場合は、`default`の口座とします。
+///
+/// #### エラー
+/// 
+/// 不正な入力があった場合、`KakeiboError`を返します。
+fn input_account(default: &models::Account) -> Result<models::Account> {
+    let account_type = services::prompt::ask(&format!("口座を入力してください (0:現金, 1:銀行, 2:クレジットカード, 空行で{})", default.get_name()))?;
+    let account_type = account_type.trim();
+    if account_type.is_empty() {
+        return Ok(default.clone());
+    }
+    let account_type: u8 = account_type
+                            .parse()
+                            .map_err(|_| KakeiboError::invalid_input("口座種別は数値で入力してください"))?;
+
+    // バリデーション
+    services::validate::InputValidator::validate_account_type(account_type)?;
+
+    models::Account::get_account(account_type).ok_or_else(|| KakeiboError::invalid_input("口座種別が不正です"))
+}
+
+/// ユーザーに固定費かどうかを尋ね、真偽値で返します。
+///
+/// #### エラー
+/// 
+/// 不正な入力があった場合、`KakeiboError`を返します。
+fn input_fixed() -> Result<bool> {
+    let fixed = services::prompt::ask("固定費ですか (y/n)")?;
+    match fixed.trim() {
+        "y" | "Y" => Ok(true),
+        "n" | "N" | "" => Ok(false),
+        _ => Err(KakeiboError::invalid_input("固定費かどうかはyまたはnで入力してください")),
+    }
+}
+
+/// ユーザーに支出の分類（必要・浪費・投資）を尋ねます。
+///
+/// 空行が入力された場合は、未分類として`None`を返します。
+///
+/// #### エラー
+/// 
+/// 不正な入力があった場合、`KakeiboError`を返します。
+fn input_judgment() -> Result<Option<models::Judgment>> {
+    let judgment_type = services::prompt::ask("支出の分類を入力してください (0:必要, 1:浪費, 2:投資, 空行で未分類)")?;
+    let judgment_type = judgment_type.trim();
+    if judgment_type.is_empty() {
+        return Ok(None);
+    }
+    let judgment_type: u8 = judgment_type
+                            .parse()
+                            .map_err(|_| KakeiboError::invalid_input("分類は数値で入力してください"))?;
+
+    // バリデーション
+    services::validate::InputValidator::validate_judgment_type(judgment_type)?;
+
+    models::Judgment::get_judgment(judgment_type)
+        .map(Some)
+        .ok_or_else(|| KakeiboError::invalid_input("分類種別が不正です"))
+}
+
+/// ユーザーにイベント名を尋ね、文字列で返します。
+///
+/// 空行が入力された場合は、イベントに属さないものとして`None`を返します。
+fn input_event() -> Result<Option<String>> {
+    let event = services::prompt::ask("イベント名を入力してください (無い場合は空行)")?;
+
+    let event = event.trim();
+    if event.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(event.to_string()))
+    }
+}
+
+/// ユーザーに支払先を尋ね、文字列で返します。
+///
+/// 入力された支払先は、支払先マスタの別名や表記ゆれを吸収して正式名に変換します。マスタに無い支払先はそのまま返します。
+/// 空行が入力された場合は、支払先が無いものとして`None`を返します。
+fn input_payee(payees: &services::payee::PayeeMaster) -> Result<Option<String>> {
+    let payee = services::prompt::ask("支払先を入力してください (無い場合は空行)")?;
+
+    let payee = payee.trim();
+    if payee.is_empty() {
+        return Ok(None);
+    }
+    let name = payees.normalize(payee);
+    if name != payee {
+        println!("支払先を「{}」として登録します", name);
+    }
+    Ok(Some(name))
+}
+
+/// ユーザーに参照番号（領収書番号・注文番号など）を尋ね、文字列で返します。
+///
+/// 空行が入力された場合は、参照番号が無いものとして`None`を返します。
+fn input_reference() -> Result<Option<String>> {
+    let reference = services::prompt::ask("参照番号（領収書番号・注文番号など）を入力してください (無い場合は空行)")?;
+
+    let reference = reference.trim();
+    if reference.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(reference.to_string()))
+    }
+}
+
+/// ユーザーに追加属性をkey=valueの形式で尋ね、キーと値の組の一覧で返します。
+///
+/// 空行が入力されるまで繰り返し尋ねます。
+///
+/// #### エラー
+/// 
+/// 不正な入力があった場合、`KakeiboError`を返します。
+fn input_extra() -> Result<Vec<(String, String)>> {
+    let mut extra = Vec::new();
+    loop {
+        let attribute = services::prompt::ask("追加属性をkey=valueの形式で入力してください (例: shop_code=S001, 空行で終了)")?;
+        let attribute = attribute.trim();
+        if attribute.is_empty() {
+            return Ok(extra);
+        }
+
+        let (key, value) = attribute
+            .split_once('=')
+            .ok_or_else(|| KakeiboError::invalid_input("追加属性はkey=valueの形式で入力してください"))?;
+        extra.push((key.trim().to_string(), value.trim().to_string()));
+    }
+}
+
+/// ユーザーに借方と貸方の勘定科目を尋ね、仕訳で返します。
+///
+/// 空行が入力された勘定科目は、カテゴリと口座から決まる既定の勘定科目にします。
+/// 両方とも空行の場合は、既定の仕訳を使うものとして`None`を返します。
+///
+/// #### エラー
+/// 
+/// 不正な入力があった場合、`KakeiboError`を返します。
+fn input_entry(register_type: u8, category_type: u8, account: &models::Account) -> Result<Option<models::JournalEntry>> {
+    let category = models::Item::get_category(register_type, category_type)
+        .ok_or_else(|| KakeiboError::invalid_input("カテゴリ種別が不正です"))?;
+    let (default_debit, default_credit) = category.get_default_accounts_for(account);
+    let debit = services::prompt::ask(&format!("借方の勘定科目を入力してください (空行で{})", default_debit))?;
+    let credit = services::prompt::ask(&format!("貸方の勘定科目を入力してください (空行で{})", default_credit))?;
+
+    let (debit, credit) = (debit.trim(), credit.trim());
+    if debit.is_empty() && credit.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(models::JournalEntry::new(
+        if debit.is_empty() { default_debit } else { debit }.to_string(),
+        if credit.is_empty() { default_credit } else { credit }.to_string(),
+    )))
+}
+
+/// 入力内容を確認画面として出力します。
+///
+/// #### エラー
+///
+/// 登録内容が不正な場合は、`KakeiboError`を返します。
+fn print_confirmation(request: &RegisterRequest, config: &Config) -> Result<()> {
+    let item = request.to_item()?;
+    println!("登録内容を確認してください");
+    println!("  品目名: {}", item.get_name());
+    println!("  カテゴリ: {}", config.format_category(item.get_item_category()));
+    println!("  サブカテゴリ: {}", item.get_subcategory().unwrap_or("なし"));
+    println!("  金額: {}", config.format_item_price(&item));
+    println!("  日付: {}", item.get_date());
+    println!("  口座: {}", item.get_account().get_name());
+    println!("  固定費: {}", if item.is_fixed() { "はい" } else { "いいえ" });
+    println!("  分類: {}", item.get_judgment().map_or("なし", |judgment| judgment.get_name()));
+    println!("  イベント: {}", item.get_event().unwrap_or("なし"));
+    println!("  支払先: {}", item.get_payee().unwrap_or("なし"));
+    println!("  参照番号: {}", item.get_reference().unwrap_or("なし"));
+    for (key, value) in item.get_extra() {
+        println!("  {}: {}", key, value);
+    }
+    if config.double_entry {
+        let (debit, credit) = item.get_accounts();
+        println!("  仕訳: 借方 {} / 貸方 {}", debit, credit);
+    }
+    Ok(())
+}
+
+/// ユーザーに保存・修正・破棄のいずれかを尋ね、文字で返します。
+///
+/// #### エラー
+/// 
+/// 不正な入力があった場合、`KakeiboError`を返します。
+fn input_action() -> Result<char> {
+    let action = services::prompt::ask("y:保存 / e:修正 / n:破棄")?;
+
+    match action.trim() {
+        "y" | "Y" => Ok('y'),
+        "e" | "E" => Ok('e'),
+        "n" | "N" => Ok('n'),
+        _ => Err(KakeiboError::invalid_input("操作はy, e, nのいずれかで入力してください")),
+    }
+}
+
+/// ユーザーに修正する項目を尋ね、数値で返します。
+///
+/// #### エラー
+/// 
+/// 不正な入力があった場合、`KakeiboError`を返します。
+fn input_edit_target() -> Result<u8> {
+    let edit_target = services::prompt::ask("修正する項目を入力してください (0:品目名, 1:カテゴリ, 2:金額, 3:日付, 4:固定費, 5:イベント, 6:参照番号, 7:追加属性, 8:勘定科目, 9:サブカテゴリ, 10:口座, 11:支払先, 12:分類)")?;
+    let edit_target: u8 = edit_target
+                            .trim()
+                            .parse()
+                            .map_err(|_| KakeiboError::invalid_input("修正する項目は数値で入力してください"))?;
+
+    // バリデーション
+    services::validate::InputValidator::validate_edit_target(edit_target)?;
+
+    Ok(edit_target)
 }
 
 #[cfg(test)]
 mod register_test {
-    // use super::*;
+    use super::*;
+
+    fn get_test_item(category: models::Category) -> models::Item {
+        models::Item::new(
+            "テスト".to_string(),
+            category,
+            models::Money::new(1000),
+            NaiveDate::from_ymd_opt(2023, 1, 1).unwrap()
+        )
+    }
+
+    #[test]
+    fn test_get_category_order() {
+        let test_data = vec![
+            get_test_item(models::Category::Expense(models::ExpenseCategory::Hobby)),
+            get_test_item(models::Category::Expense(models::ExpenseCategory::Other)),
+            get_test_item(models::Category::Expense(models::ExpenseCategory::Other)),
+            get_test_item(models::Category::Income(models::IncomeCategory::Bonus)),
+        ];
+
+        assert_eq!(get_category_order(1, &test_data), vec![2, 1, 0]);
+        assert_eq!(get_category_order(0, &test_data), vec![1, 0, 2]);
+        assert_eq!(get_category_order(1, &[]), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_get_previous_item() {
+        let mut test_data = vec![
+            get_test_item(models::Category::Expense(models::ExpenseCategory::Other)),
+            get_test_item(models::Category::Expense(models::ExpenseCategory::Hobby)),
+            get_test_item(models::Category::Income(models::IncomeCategory::Other)),
+            models::Item::new(
+                "テスト".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                models::Money::new(2000),
+                NaiveDate::from_ymd_opt(2022, 12, 1).unwrap()
+            ),
+        ];
+
+        let (category_type, item) = get_previous_item(1, "テスト", &test_data).unwrap();
+        assert_eq!(category_type, 1);
+        assert_eq!(item.get_price(), models::Money::new(1000));
+        assert_eq!(get_previous_item(0, "テスト", &test_data).unwrap().0, 2);
+        assert!(get_previous_item(1, "別の品目", &test_data).is_none());
+
+        // 日付が同じ場合は、後に登録した項目を返す
+        test_data.push(get_test_item(models::Category::Expense(models::ExpenseCategory::Other)));
+        assert_eq!(get_previous_item(1, "テスト", &test_data).unwrap().0, 2);
+    }
+
+    #[test]
+    fn test_get_subcategories() {
+        let mut test_data = vec![
+            get_test_item(models::Category::Expense(models::ExpenseCategory::Food)),
+            get_test_item(models::Category::Expense(models::ExpenseCategory::Food)),
+            get_test_item(models::Category::Expense(models::ExpenseCategory::Food)),
+            get_test_item(models::Category::Expense(models::ExpenseCategory::Hobby)),
+        ];
+        test_data[0].set_subcategory(Some("自炊".to_string()));
+        test_data[1].set_subcategory(Some("外食".to_string()));
+        test_data[2].set_subcategory(Some("自炊".to_string()));
+        test_data[3].set_subcategory(Some("本".to_string()));
+
+        let food = models::Category::Expense(models::ExpenseCategory::Food);
+        assert_eq!(get_subcategories(&food, &test_data), vec!["外食", "自炊"]);
+        assert!(get_subcategories(&food, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_get_category_order_for_recent_items() {
+        let mut test_data: Vec<_> = (0..RECENT_ITEM_COUNT)
+            .map(|_| get_test_item(models::Category::Expense(models::ExpenseCategory::Hobby)))
+            .collect();
+        test_data.extend((0..RECENT_ITEM_COUNT).map(|_| get_test_item(models::Category::Expense(models::ExpenseCategory::Food))));
+
+        // 古い項目は数えない
+        assert_eq!(get_category_order(1, &test_data), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_validate_request() {
+        let date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        assert!(RegisterRequest::new(0, 2, "給料".to_string(), models::Money::new(1000), date).validate().is_ok());
+        assert!(RegisterRequest::new(2, 0, "給料".to_string(), models::Money::new(1000), date).validate().is_err());
+        assert!(RegisterRequest::new(1, 0, " ".to_string(), models::Money::new(1000), date).validate().is_err());
+
+        let mut request = RegisterRequest::new(0, 0, "給料".to_string(), models::Money::new(1000), date);
+        request.fixed = true;
+        assert!(request.validate().is_err());
+
+        let mut request = RegisterRequest::new(0, 0, "給料".to_string(), models::Money::new(1000), date);
+        request.judgment = Some(models::Judgment::Need);
+        assert!(request.validate().is_err());
+
+        // 振替元と振替先が同じ振替は登録できない
+        let mut request = RegisterRequest::new(2, 1, "ATM".to_string(), models::Money::new(10000), date);
+        assert!(request.validate().is_ok());
+        request.account = models::Account::Bank;
+        assert!(request.validate().is_err());
+    }
+
+    #[test]
+    fn test_to_item() {
+        let mut request = RegisterRequest::new(1, 1, "本".to_string(), models::Money::new(1500), NaiveDate::from_ymd_opt(2023, 2, 1).unwrap());
+        request.fixed = true;
+        request.judgment = Some(models::Judgment::Investment);
+        request.extra.push(("shop".to_string(), "書店".to_string()));
+        let item = request.to_item().unwrap();
+
+        assert_eq!(item.get_item_category(), &models::Category::Expense(models::ExpenseCategory::Hobby));
+        assert_eq!(item.get_price(), models::Money::new(1500));
+        assert!(item.is_fixed());
+        assert_eq!(item.get_judgment(), Some(models::Judgment::Investment));
+        assert_eq!(item.get_extra()["shop"], Value::String("書店".to_string()));
+        assert_eq!(item.get_id(), 0);
+
+        // 範囲外のカテゴリ種別や、振替元と振替先が同じ振替はエラーになる
+        let date = NaiveDate::from_ymd_opt(2023, 2, 1).unwrap();
+        assert!(RegisterRequest::new(1, 3, "本".to_string(), models::Money::new(1500), date).to_item().is_err());
+        assert!(RegisterRequest::new(3, 0, "本".to_string(), models::Money::new(1500), date).to_item().is_err());
+        let mut request = RegisterRequest::new(2, 0, "ATM".to_string(), models::Money::new(10000), date);
+        assert!(request.to_item().is_err());
+        request.account = models::Account::Bank;
+        assert_eq!(request.to_item().unwrap().get_item_category(), &models::Category::Transfer(models::Account::Cash));
+    }
+
+    #[test]
+    fn test_parse_free_text() {
+        let today = NaiveDate::from_ymd_opt(2023, 5, 10).unwrap();
+
+        let parsed = parse_free_text("5/2 スタバ 680円", today, &Config::default());
+        assert_eq!(parsed, ParsedText {
+            date: NaiveDate::from_ymd_opt(2023, 5, 2),
+            price: Some(models::Money::new(680)),
+            name: Some("スタバ".to_string()),
+        });
+
+        let parsed = parse_free_text("ドラッグストア　シャンプー ￥１，２８０ ５月１２日", today, &Config::default());
+        assert_eq!(parsed.date, NaiveDate::from_ymd_opt(2022, 5, 12));
+        assert_eq!(parsed.price, Some(models::Money::new(1280)));
+        assert_eq!(parsed.name.as_deref(), Some("ドラッグストア シャンプー"));
+
+        let parsed = parse_free_text("2023-04-01 ランチ", today, &Config::default());
+        assert_eq!(parsed.date, NaiveDate::from_ymd_opt(2023, 4, 1));
+        assert_eq!(parsed.price, None);
+
+        assert_eq!(parse_free_text("", today, &Config::default()), ParsedText { date: None, price: None, name: None });
+    }
+
+    #[test]
+    fn test_parse_date_word() {
+        let today = NaiveDate::from_ymd_opt(2023, 1, 5).unwrap();
+
+        assert_eq!(parse_date_word("12/31", today), NaiveDate::from_ymd_opt(2022, 12, 31));
+        assert_eq!(parse_date_word("1/5", today), NaiveDate::from_ymd_opt(2023, 1, 5));
+        assert_eq!(parse_date_word("2022/2/3", today), NaiveDate::from_ymd_opt(2022, 2, 3));
+        assert_eq!(parse_date_word("2/30", today), None);
+        assert_eq!(parse_date_word("スタバ", today), None);
+    }
+
+    #[test]
+    fn test_parse_price_word() {
+        assert_eq!(parse_price_word("680円", &Config::default()), Some(models::Money::new(680)));
+        assert_eq!(parse_price_word("¥1,280", &Config::default()), Some(models::Money::new(1280)));
+        assert_eq!(parse_price_word("500", &Config::default()), Some(models::Money::new(500)));
+        assert_eq!(parse_price_word("円", &Config::default()), None);
+        assert_eq!(parse_price_word("7-11", &Config::default()), None);
+        assert_eq!(parse_price_word("12.5", &Config::default()), None);
+
+        // 米ドルの場合は、ドル単位の入力をセント単位の金額にする
+        let config = Config { currency: crate::config::Currency::USD, ..Config::default() };
+        assert_eq!(parse_price_word("$12.50", &config), Some(models::Money::new(1250)));
+        assert_eq!(parse_price_word("1,280", &config), Some(models::Money::new(128000)));
+        assert_eq!(parse_price_word("0.005", &config), None);
+        let parsed = parse_free_text("5/2 coffee $4.5", NaiveDate::from_ymd_opt(2023, 5, 10).unwrap(), &config);
+        assert_eq!(parsed.price, Some(models::Money::new(450)));
+        assert_eq!(parsed.name.as_deref(), Some("coffee"));
+    }
 }
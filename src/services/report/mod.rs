@@ -0,0 +1,392 @@
+//! 年次レポートサービス
+//!
+//! このモジュールは、1年分の総括をまとめたPDFファイル（年次レポート）を作成する機能を提供します。
+//!
+//! レポートには、以下の内容を1ページにまとめます。
+//! - 年間の収入・支出・収支と、前年からの増減
+//! - カテゴリ別の内訳（金額・割合の横棒グラフ）と前年実績との比較
+//! - 月別の収入・支出の棒グラフ
+//!
+//! 日本語の文字は、PDFビューアに標準で備わる日本語フォント（平成角ゴシック）で表示するため、フォントは埋め込みません。
+//! カテゴリの絵文字は表示できないため、レポートではカテゴリ名だけを表示します。
+
+use std::fs;
+
+use chrono::Datelike;
+use pdf_writer::types::{CidFontType, FontFlags, SystemInfo};
+use pdf_writer::{Content, Name, Pdf, Rect, Ref, Str};
+
+use crate::config::Config;
+use crate::error::{KakeiboError, Result};
+use crate::models;
+use crate::services;
+
+/// ページの幅（A4縦）
+const PAGE_WIDTH: f32 = 595.0;
+
+/// ページの高さ（A4縦）
+const PAGE_HEIGHT: f32 = 842.0;
+
+/// 左右の余白
+const MARGIN: f32 = 50.0;
+
+/// 日本語フォントの名前
+const FONT_NAME: &[u8] = b"HeiseiKakuGo-W5";
+
+/// コンテンツから参照するフォントのリソース名
+const FONT_RESOURCE: &[u8] = b"F1";
+
+/// 収入のグラフの色
+const INCOME_COLOR: (f32, f32, f32) = (0.25, 0.6, 0.35);
+
+/// 支出のグラフの色
+const EXPENSE_COLOR: (f32, f32, f32) = (0.85, 0.35, 0.3);
+
+/// 年次レポートの内容を表す構造体
+///
+/// - `year`: 対象の年
+/// - `income` / `expense`: 年間の収入・支出の合計
+/// - `previous_income` / `previous_expense`: 前年の収入・支出の合計
+/// - `categories`: カテゴリごとの(カテゴリ, 今年の合計, 前年の合計)
+/// - `monthly`: 月ごとの(収入, 支出)。添字0が1月
+#[derive(Debug, PartialEq, Eq)]
+pub struct YearlyReport {
+    pub year: i32,
+    pub income: models::Money,
+    pub expense: models::Money,
+    pub previous_income: models::Money,
+    pub previous_expense: models::Money,
+    pub categories: Vec<(models::Category, models::Money, models::Money)>,
+    pub monthly: [(models::Money, models::Money); 12],
+}
+
+impl YearlyReport {
+    /// 指定された年の年次レポートの内容を集計する
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+    /// use kakeibo_app::services::report::YearlyReport;
+    /// use chrono::NaiveDate;
+    ///
+    /// let data = vec![Item::new(
+    ///     String::from("ランチ"),
+    ///     Category::Expense(ExpenseCategory::Food),
+    ///     Money::new(1000),
+    ///     NaiveDate::from_ymd_opt(2023, 3, 1).unwrap(),
+    /// )];
+    /// let report = YearlyReport::new(&data, 2023);
+    /// assert_eq!(report.expense, Money::new(1000));
+    /// assert_eq!(report.monthly[2], (Money::ZERO, Money::new(1000)));
+    /// ```
+    pub fn new(data: &[models::Item], year: i32) -> Self {
+        let mut categories: Vec<_> = models::Category::get_all().into_iter().map(|category| (category, models::Money::ZERO, models::Money::ZERO)).collect();
+        let mut monthly = [(models::Money::ZERO, models::Money::ZERO); 12];
+        for item in data {
+            let date = item.get_date();
+            let price = item.get_price();
+            let Some(entry) = categories.iter_mut().find(|(category, _, _)| category == item.get_item_category()) else {
+                continue;
+            };
+            if date.year() == year {
+                entry.1 += price;
+                let month = &mut monthly[date.month0() as usize];
+                match item.get_item_category() {
+                    models::Category::Income(_) => month.0 += price,
+                    models::Category::Expense(_) => month.1 += price,
+                    models::Category::Transfer(_) => {},
+                }
+            } else if date.year() == year - 1 {
+                entry.2 += price;
+            }
+        }
+
+        let sum = |is_income: bool, column: fn(&(models::Category, models::Money, models::Money)) -> models::Money| -> models::Money {
+            categories.iter()
+                .filter(|entry| matches!(entry.0, models::Category::Income(_)) == is_income)
+                .map(column)
+                .sum()
+        };
+        YearlyReport {
+            year,
+            income: sum(true, |entry| entry.1),
+            expense: sum(false, |entry| entry.1),
+            previous_income: sum(true, |entry| entry.2),
+            previous_expense: sum(false, |entry| entry.2),
+            categories,
+            monthly,
+        }
+    }
+}
+
+/// 年次レポートのPDFファイルを作成します。
+///
+/// この関数は、対象の年と保存先を尋ねます。年を省略した場合は、データの中で最も新しい年を対象にします。
+///
+/// #### 例
+///
+/// ```rust
+/// // ドキュメンテーションテストでFailになるのでコメントアウト
+/// // use kakeibo_app::config::Config;
+/// // use kakeibo_app::services;
+/// // services::report::run("store/data.json", &Config::default());
+/// ```
+///
+/// #### エラー
+///
+/// 不正な入力があった場合や、データの読み書きに失敗した場合は、`KakeiboError`を返します。
+pub fn run(file_path: &str, config: &Config) -> Result<()> {
+    let year = services::prompt::ask("対象の年を入力してください (空行で最新の年)")?;
+    let year = match year.trim() {
+        "" => None,
+        year => Some(year.parse().map_err(|_| KakeiboError::invalid_input("年は数値で入力してください"))?),
+    };
+    let output_path = services::prompt::ask("保存先を入力してください (空行でkakeibo_<年>_report.pdf)")?;
+    let output_path = Some(output_path.trim()).filter(|output_path| !output_path.is_empty());
+    run_with_options(file_path, year, output_path, config)
+}
+
+/// 指定された年の年次レポートのPDFファイルを作成します。
+///
+/// `year`が`None`の場合は、データの中で最も新しい年を対象にします。
+/// `output_path`が`None`の場合は、`kakeibo_<年>_report.pdf`に保存します。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// services::report::run_with_options("store/data.json", Some(2023), None, &Config::default()).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// データの読み込みやPDFファイルの書き込みに失敗した場合は、`KakeiboError`を返します。
+pub fn run_with_options(file_path: &str, year: Option<i32>, output_path: Option<&str>, config: &Config) -> Result<()> {
+    let data = services::io::read_data(file_path)?;
+    let year = year.unwrap_or_else(|| data.iter().map(|item| item.get_date().year()).max().unwrap());
+    let output_path = output_path.map_or_else(|| format!("kakeibo_{}_report.pdf", year), str::to_string);
+
+    let report = YearlyReport::new(&data, year);
+    fs::write(&output_path, render_pdf(&report, config)).map_err(|error| KakeiboError::io(&output_path, error))?;
+    println!("{}年の年次レポートを{}に出力しました", year, output_path);
+    Ok(())
+}
+
+/// 年次レポートをPDFにします。
+fn render_pdf(report: &YearlyReport, config: &Config) -> Vec<u8> {
+    let catalog_id = Ref::new(1);
+    let page_tree_id = Ref::new(2);
+    let page_id = Ref::new(3);
+    let content_id = Ref::new(4);
+    let font_id = Ref::new(5);
+    let cid_font_id = Ref::new(6);
+    let descriptor_id = Ref::new(7);
+
+    let mut pdf = Pdf::new();
+    pdf.catalog(catalog_id).pages(page_tree_id);
+    pdf.pages(page_tree_id).kids([page_id]).count(1);
+    let mut page = pdf.page(page_id);
+    page.parent(page_tree_id)
+        .media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT))
+        .contents(content_id);
+    page.resources().fonts().pair(Name(FONT_RESOURCE), font_id);
+    drop(page);
+
+    // 埋め込まない日本語フォント（Adobe-Japan1の文字集合をUTF-16で参照する）
+    pdf.type0_font(font_id)
+        .base_font(Name(FONT_NAME))
+        .encoding_predefined(Name(b"UniJIS-UTF16-H"))
+        .descendant_font(cid_font_id);
+    let mut cid_font = pdf.cid_font(cid_font_id);
+    cid_font.subtype(CidFontType::Type0)
+        .base_font(Name(FONT_NAME))
+        .system_info(SystemInfo { registry: Str(b"Adobe"), ordering: Str(b"Japan1"), supplement: 5 })
+        .font_descriptor(descriptor_id)
+        .default_width(1000.0);
+    // 半角英数字（CID 1〜95）は全角の半分の幅にする
+    cid_font.widths().same(1, 95, 500.0);
+    drop(cid_font);
+    pdf.font_descriptor(descriptor_id)
+        .name(Name(FONT_NAME))
+        .flags(FontFlags::SYMBOLIC)
+        .bbox(Rect::new(-92.0, -250.0, 1010.0, 922.0))
+        .italic_angle(0.0)
+        .ascent(880.0)
+        .descent(-120.0)
+        .cap_height(700.0)
+        .stem_v(80.0);
+
+    let content = render_content(report, config);
+    pdf.stream(content_id, &content);
+    pdf.finish()
+}
+
+/// 年次レポートのページの内容（テキストとグラフ）を描画します。
+fn render_content(report: &YearlyReport, config: &Config) -> Vec<u8> {
+    let mut content = Content::new();
+    let mut y = PAGE_HEIGHT - 70.0;
+    show_text(&mut content, MARGIN, y, 20.0, &format!("{}年 年間レポート", report.year));
+
+    // 年間の収支
+    y -= 40.0;
+    show_text(&mut content, MARGIN, y, 14.0, "年間の収支");
+    let balance = report.income - report.expense;
+    let previous_balance = report.previous_income - report.previous_expense;
+    for (label, amount, previous) in [
+        ("収入", report.income, report.previous_income),
+        ("支出", report.expense, report.previous_expense),
+        ("収支", balance, previous_balance),
+    ] {
+        y -= 20.0;
+        show_text(&mut content, MARGIN + 10.0, y, 11.0, label);
+        show_text(&mut content, MARGIN + 60.0, y, 11.0, &config.format_currency(amount));
+        show_text(&mut content, MARGIN + 200.0, y, 11.0, &format!("前年比 {}", format_difference(amount, previous, config)));
+    }
+
+    // カテゴリ別の内訳と前年実績との比較
+    y -= 40.0;
+    show_text(&mut content, MARGIN, y, 14.0, "カテゴリ別の内訳");
+    y -= 20.0;
+    for (x, header) in [(MARGIN + 10.0, "カテゴリ"), (MARGIN + 110.0, "今年"), (MARGIN + 200.0, "割合"), (MARGIN + 340.0, "前年実績との差")] {
+        show_text(&mut content, x, y, 9.0, header);
+    }
+    for (category, amount, previous) in &report.categories {
+        let (total, color) = match category {
+            models::Category::Income(_) => (report.income, INCOME_COLOR),
+            models::Category::Expense(_) => (report.expense, EXPENSE_COLOR),
+            models::Category::Transfer(_) => continue,
+        };
+        let ratio = if total == models::Money::ZERO { 0.0 } else { amount.get_minor_units() as f32 / total.get_minor_units() as f32 };
+        let kind = if matches!(category, models::Category::Income(_)) { "収入" } else { "支出" };
+        y -= 18.0;
+        show_text(&mut content, MARGIN + 10.0, y, 11.0, &format!("{}({})", category.get_name(), kind));
+        show_text(&mut content, MARGIN + 110.0, y, 11.0, &config.format_currency(*amount));
+        fill_rect(&mut content, MARGIN + 200.0, y - 1.0, 100.0 * ratio, 9.0, color);
+        show_text(&mut content, MARGIN + 305.0, y, 9.0, &format!("{:.0}%", ratio * 100.0));
+        show_text(&mut content, MARGIN + 340.0, y, 11.0, &format_difference(*amount, *previous, config));
+    }
+
+    // 月別の収支のグラフ
+    y -= 40.0;
+    show_text(&mut content, MARGIN, y, 14.0, "月別の収支");
+    show_text(&mut content, MARGIN + 120.0, y, 9.0, "■収入");
+    show_text(&mut content, MARGIN + 170.0, y, 9.0, "■支出");
+    fill_rect(&mut content, MARGIN + 120.0, y - 1.0, 9.0, 9.0, INCOME_COLOR);
+    fill_rect(&mut content, MARGIN + 170.0, y - 1.0, 9.0, 9.0, EXPENSE_COLOR);
+    let chart_height = (y - 80.0).min(220.0);
+    let baseline = y - 20.0 - chart_height;
+    let max_amount = report.monthly.iter().map(|(income, expense)| *income.max(expense)).max().unwrap_or_default().max(models::Money::new(1));
+    let column_width = (PAGE_WIDTH - MARGIN * 2.0) / 12.0;
+    for (month, (income, expense)) in report.monthly.iter().enumerate() {
+        let x = MARGIN + column_width * month as f32;
+        let bar_width = column_width * 0.35;
+        let scale = |amount: models::Money| chart_height * amount.get_minor_units() as f32 / max_amount.get_minor_units() as f32;
+        fill_rect(&mut content, x + column_width * 0.1, baseline, bar_width, scale(*income), INCOME_COLOR);
+        fill_rect(&mut content, x + column_width * 0.1 + bar_width, baseline, bar_width, scale(*expense), EXPENSE_COLOR);
+        show_text(&mut content, x + column_width * 0.25, baseline - 14.0, 9.0, &format!("{}月", month + 1));
+    }
+    content.set_stroke_rgb(0.5, 0.5, 0.5);
+    content.move_to(MARGIN, baseline).line_to(PAGE_WIDTH - MARGIN, baseline).stroke();
+    show_text(&mut content, MARGIN, baseline + chart_height + 4.0, 8.0, &format!("最大 {}", config.format_currency(max_amount)));
+
+    content.finish()
+}
+
+/// 前年からの増減を、符号付きの金額と割合の文字列にします。
+///
+/// 前年の金額が0の場合は、割合を省略します。
+fn format_difference(amount: models::Money, previous: models::Money, config: &Config) -> String {
+    let difference = amount - previous;
+    let sign = if difference > models::Money::ZERO { "+" } else { "" };
+    if previous == models::Money::ZERO {
+        format!("{}{}", sign, config.format_currency(difference))
+    } else {
+        format!("{}{} ({}{:.1}%)", sign, config.format_currency(difference), sign, difference.get_minor_units() as f64 * 100.0 / previous.abs().get_minor_units() as f64)
+    }
+}
+
+/// 指定された位置に文字列を描画します。
+fn show_text(content: &mut Content, x: f32, y: f32, size: f32, text: &str) {
+    content.begin_text();
+    content.set_font(Name(FONT_RESOURCE), size);
+    content.next_line(x, y);
+    content.show(Str(&encode_utf16be(text)));
+    content.end_text();
+}
+
+/// 指定された色で長方形を塗りつぶします。
+fn fill_rect(content: &mut Content, x: f32, y: f32, width: f32, height: f32, (r, g, b): (f32, f32, f32)) {
+    content.set_fill_rgb(r, g, b);
+    content.rect(x, y, width, height);
+    content.fill_nonzero();
+    content.set_fill_rgb(0.0, 0.0, 0.0);
+}
+
+/// 文字列をUTF-16BEのバイト列にします。
+fn encode_utf16be(text: &str) -> Vec<u8> {
+    text.encode_utf16().flat_map(|unit| unit.to_be_bytes()).collect()
+}
+
+#[cfg(test)]
+mod report_test {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn get_test_data() -> Vec<models::Item> {
+        vec![
+            models::Item::new(
+                "給料".to_string(),
+                models::Category::Income(models::IncomeCategory::Salary),
+                models::Money::new(300000),
+                NaiveDate::from_ymd_opt(2023, 1, 25).unwrap()
+            ),
+            models::Item::new(
+                "ランチ".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                models::Money::new(1000),
+                NaiveDate::from_ymd_opt(2023, 12, 1).unwrap()
+            ),
+            models::Item::new(
+                "ランチ".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                models::Money::new(800),
+                NaiveDate::from_ymd_opt(2022, 12, 1).unwrap()
+            ),
+            models::Item::new(
+                "本".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Hobby),
+                models::Money::new(1500),
+                NaiveDate::from_ymd_opt(2021, 5, 1).unwrap()
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_new() {
+        let report = YearlyReport::new(&get_test_data(), 2023);
+
+        assert_eq!((report.income, report.expense), (models::Money::new(300000), models::Money::new(1000)));
+        assert_eq!((report.previous_income, report.previous_expense), (models::Money::ZERO, models::Money::new(800)));
+        assert_eq!(report.categories.len(), 6);
+        assert_eq!(report.categories[3], (models::Category::Expense(models::ExpenseCategory::Food), models::Money::new(1000), models::Money::new(800)));
+        assert_eq!(report.categories[4], (models::Category::Expense(models::ExpenseCategory::Hobby), models::Money::ZERO, models::Money::ZERO));
+        assert_eq!(report.monthly[0], (models::Money::new(300000), models::Money::ZERO));
+        assert_eq!(report.monthly[11], (models::Money::ZERO, models::Money::new(1000)));
+    }
+
+    #[test]
+    fn test_format_difference() {
+        let config = Config::default();
+        assert_eq!(format_difference(models::Money::new(1000), models::Money::new(800), &config), "+200円 (+25.0%)");
+        assert_eq!(format_difference(models::Money::new(600), models::Money::new(800), &config), "-200円 (-25.0%)");
+        assert_eq!(format_difference(models::Money::new(1000), models::Money::ZERO, &config), "+1000円");
+    }
+
+    #[test]
+    fn test_render_pdf() {
+        let pdf = render_pdf(&YearlyReport::new(&get_test_data(), 2023), &Config::default());
+        assert!(pdf.starts_with(b"%PDF-"));
+    }
+}
@@ -0,0 +1,191 @@
+//! 振り返りサービス
+//!
+//! このモジュールは、支出に付けた分類（必要・浪費・投資）をもとに、月ごとの支出の内訳と比率を表示する機能を提供します。
+//! 分類の無い支出は「未分類」として数えます。口座間の振替と収入は対象外です。
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+
+use crate::config::Config;
+use crate::error::Result;
+use crate::models;
+use crate::services;
+
+/// 1か月分の分類別の支出合計を表す構造体
+///
+/// - `month`: 対象の月の最初の日
+/// - `totals`: 分類ごとの支出合計（必要・浪費・投資の順）
+/// - `unjudged`: 分類の無い支出の合計
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonthlyReview {
+    pub month: NaiveDate,
+    pub totals: [models::Money; 3],
+    pub unjudged: models::Money,
+}
+
+impl MonthlyReview {
+    /// 月の支出の合計を取得する
+    pub fn get_total(&self) -> models::Money {
+        self.totals.iter().copied().sum::<models::Money>() + self.unjudged
+    }
+
+    /// 支出の合計に対する金額の比率（%）を取得する
+    ///
+    /// 支出が無い月は0.0を返します。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::Money;
+    /// use kakeibo_app::services::review::MonthlyReview;
+    /// use chrono::NaiveDate;
+    ///
+    /// let review = MonthlyReview {
+    ///     month: NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+    ///     totals: [Money::new(6000), Money::new(3000), Money::new(1000)],
+    ///     unjudged: Money::ZERO,
+    /// };
+    /// assert_eq!(review.get_ratio(Money::new(3000)), 30.0);
+    /// ```
+    pub fn get_ratio(&self, price: models::Money) -> f64 {
+        let total = self.get_total();
+        if total == models::Money::ZERO {
+            return 0.0;
+        }
+        price.get_minor_units() as f64 / total.get_minor_units() as f64 * 100.0
+    }
+}
+
+/// 家計簿データから、月ごとの分類別の支出合計を作成します。
+///
+/// 支出のある月だけを、古い月から順に返します。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::models::{Item, Category, ExpenseCategory, Judgment, Money};
+/// use kakeibo_app::services::review;
+/// use chrono::NaiveDate;
+///
+/// let mut item = Item::new(String::from("ランチ"), Category::Expense(ExpenseCategory::Food), Money::new(1000), NaiveDate::from_ymd_opt(2023, 1, 5).unwrap());
+/// item.set_judgment(Some(Judgment::Waste));
+/// let reviews = review::create_reviews(&[item]);
+/// assert_eq!(reviews[0].totals, [Money::ZERO, Money::new(1000), Money::ZERO]);
+/// ```
+pub fn create_reviews(data: &[models::Item]) -> Vec<MonthlyReview> {
+    let mut reviews: BTreeMap<NaiveDate, MonthlyReview> = BTreeMap::new();
+    for item in data {
+        if !matches!(item.get_item_category(), models::Category::Expense(_)) {
+            continue;
+        }
+        let month = item.get_first_day();
+        let review = reviews.entry(month).or_insert(MonthlyReview {
+            month,
+            totals: [models::Money::ZERO; 3],
+            unjudged: models::Money::ZERO,
+        });
+        match item.get_judgment() {
+            Some(judgment) => review.totals[judgment as usize] += item.get_price(),
+            None => review.unjudged += item.get_price(),
+        }
+    }
+    reviews.into_values().collect()
+}
+
+/// 月ごとの支出を、分類（必要・浪費・投資・未分類）別の金額と比率で表示します。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// services::review::run("store/data.json", &Config::default()).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// データの読み込みに失敗した場合は、`KakeiboError`を返します。
+pub fn run(file_path: &str, config: &Config) -> Result<()> {
+    let data = services::io::read_data(file_path)?;
+    let reviews = create_reviews(&data);
+    if reviews.is_empty() {
+        println!("支出の登録がありません");
+        return Ok(());
+    }
+
+    for review in &reviews {
+        println!(
+            "{}: 支出 {}",
+            services::summarize::format_date(review.month),
+            config.format_currency(review.get_total())
+        );
+        let totals = models::Judgment::get_all().map(|judgment| (judgment.get_name(), review.totals[judgment as usize]));
+        for (name, price) in totals.into_iter().chain([("未分類", review.unjudged)]) {
+            println!("  {}: {} ({:.1}%)", name, config.format_currency(price), review.get_ratio(price));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod review_test {
+    use super::*;
+
+    #[test]
+    fn test_create_reviews() {
+        let mut need = models::Item::new(
+            "食材".to_string(),
+            models::Category::Expense(models::ExpenseCategory::Food),
+            models::Money::new(80000),
+            NaiveDate::from_ymd_opt(2023, 1, 25).unwrap()
+        );
+        need.set_judgment(Some(models::Judgment::Need));
+        let mut investment = models::Item::new(
+            "本".to_string(),
+            models::Category::Expense(models::ExpenseCategory::Hobby),
+            models::Money::new(2000),
+            NaiveDate::from_ymd_opt(2023, 3, 1).unwrap()
+        );
+        investment.set_judgment(Some(models::Judgment::Investment));
+        let data = vec![
+            need,
+            models::Item::new(
+                "外食".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                models::Money::new(3000),
+                NaiveDate::from_ymd_opt(2023, 1, 15).unwrap()
+            ),
+            models::Item::new(
+                "給料".to_string(),
+                models::Category::Income(models::IncomeCategory::Salary),
+                models::Money::new(300000),
+                NaiveDate::from_ymd_opt(2023, 2, 25).unwrap()
+            ),
+            investment,
+        ];
+
+        assert_eq!(create_reviews(&data), vec![
+            MonthlyReview {
+                month: NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+                totals: [models::Money::new(80000), models::Money::ZERO, models::Money::ZERO],
+                unjudged: models::Money::new(3000),
+            },
+            MonthlyReview {
+                month: NaiveDate::from_ymd_opt(2023, 3, 1).unwrap(),
+                totals: [models::Money::ZERO, models::Money::ZERO, models::Money::new(2000)],
+                unjudged: models::Money::ZERO,
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_get_ratio() {
+        let review = MonthlyReview {
+            month: NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+            totals: [models::Money::ZERO; 3],
+            unjudged: models::Money::ZERO,
+        };
+        assert_eq!(review.get_ratio(models::Money::ZERO), 0.0);
+    }
+}
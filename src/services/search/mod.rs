@@ -0,0 +1,228 @@
+//! 検索サービス
+//!
+//! このモジュールは、条件式に一致する項目を一覧表示する機能を提供します。
+//! 参照番号（領収書番号・注文番号など）で検索すれば、返品時に元の項目と照合できます。
+//! 検索結果からは、複数選択のピッカーで選んだ項目をまとめて削除したり、カテゴリやタグ（イベント名）を変更したりできます。
+
+use crate::config::Config;
+use crate::models;
+use crate::services;
+use crate::services::filter::Filter;
+use crate::error::{KakeiboError, Result};
+
+/// 検索結果から選んだ項目への操作を表す列挙型
+#[derive(Debug, Clone, PartialEq)]
+enum SearchAction {
+    Delete,
+    SetCategory(models::Category),
+    SetTag(Option<String>),
+}
+
+/// 条件に一致する項目を検索して表示します。
+///
+/// この関数は、ユーザーから条件式を受け付け、一致した項目と件数を表示します。
+/// 続けて操作を選んだ場合は、検索結果からピッカーで選んだ項目を削除するか、カテゴリ・タグ（イベント名）を変更します。
+/// 選んだ項目に締め済みの月の項目が含まれる場合は、変更しません。
+///
+/// #### 例
+///
+/// ```rust
+/// // ドキュメンテーションテストでFailになるのでコメントアウト
+/// // use kakeibo_app::config::Config;
+/// // use kakeibo_app::services;
+/// // services::search::run("store/data.json", "store/closed.json", &Config::default());
+/// ```
+///
+/// #### エラー
+///
+/// 不正な入力があった場合や、データの読み書きに失敗した場合は、`KakeiboError`を返します。
+pub fn run(file_path: &str, closed_path: &str, config: &Config) -> Result<()> {
+    let mut data = services::io::read_data(file_path)?;
+    let filter = input_filter(config)?;
+
+    let items: Vec<_> = data.iter().filter(|item| filter.matches(item)).collect();
+    if items.is_empty() {
+        println!("条件に一致する項目はありません");
+        return Ok(());
+    }
+
+    for item in &items {
+        println!("  {}", format_item(item, config));
+    }
+    println!("{}件の項目が条件に一致しました", items.len());
+
+    let Some(kind) = input_action_kind()? else {
+        return Ok(());
+    };
+    let labels: Vec<_> = items.iter().map(|item| format_item(item, config)).collect();
+    let ids: Vec<u64> = services::picker::pick("操作する項目を選んでください", &labels)?
+        .into_iter()
+        .map(|index| items[index].get_id())
+        .collect();
+    if ids.is_empty() {
+        println!("項目が選ばれなかったため、中止しました");
+        return Ok(());
+    }
+    let closed_months = services::close::ClosedMonths::load_or_default(closed_path)?;
+    if let Some(month) = closed_months.find_closed_month(data.iter().filter(|item| ids.contains(&item.get_id()))) {
+        services::close::print_locked(month);
+        return Ok(());
+    }
+
+    let action = match kind {
+        'd' => {
+            println!("{}件の項目を選びました", ids.len());
+            let answer = services::prompt::ask("これらの項目を削除しますか (y/n)")?;
+            if !matches!(answer.trim(), "y" | "Y") {
+                println!("削除を中止しました");
+                return Ok(());
+            }
+            SearchAction::Delete
+        },
+        'c' => {
+            let Some(category) = input_new_category(config)? else {
+                println!("変更を中止しました");
+                return Ok(());
+            };
+            SearchAction::SetCategory(category)
+        },
+        _ => {
+            let tag = services::prompt::ask("付けるタグ（イベント名）を入力してください (空行でタグを外す)")?;
+            SearchAction::SetTag(Some(tag.trim().to_string()).filter(|tag| !tag.is_empty()))
+        },
+    };
+    let count = apply_action(&mut data, &ids, &action);
+    services::io::write_to_json(&data, file_path)?;
+    println!("{}件の項目を{}しました", count, if action == SearchAction::Delete { "削除" } else { "変更" });
+    Ok(())
+}
+
+/// 選んだIDの項目に操作を適用し、対象にした件数を返します。
+fn apply_action(data: &mut Vec<models::Item>, ids: &[u64], action: &SearchAction) -> usize {
+    let count = data.iter().filter(|item| ids.contains(&item.get_id())).count();
+    match action {
+        SearchAction::Delete => data.retain(|item| !ids.contains(&item.get_id())),
+        SearchAction::SetCategory(category) => {
+            for item in data.iter_mut().filter(|item| ids.contains(&item.get_id())) {
+                item.set_category(category.clone());
+                item.remove_extra(services::import::NEEDS_CATEGORY_KEY);
+            }
+        },
+        SearchAction::SetTag(tag) => {
+            data.iter_mut().filter(|item| ids.contains(&item.get_id())).for_each(|item| item.set_event(tag.clone()));
+        },
+    }
+    count
+}
+
+/// ユーザーに検索結果への操作を尋ね、`d`（削除）・`c`（カテゴリの変更）・`t`（タグの変更）のいずれかで返します。
+///
+/// 空行が入力された場合は、何もせずに終了するものとして`None`を返します。
+///
+/// #### エラー
+///
+/// 不正な入力があった場合、`KakeiboError`を返します。
+fn input_action_kind() -> Result<Option<char>> {
+    let action = services::prompt::ask("選んだ項目への操作を入力してください (d:削除, c:カテゴリの変更, t:タグ（イベント名）の変更, 空行で終了)")?;
+    match action.trim() {
+        "" => Ok(None),
+        "d" | "D" => Ok(Some('d')),
+        "c" | "C" => Ok(Some('c')),
+        "t" | "T" => Ok(Some('t')),
+        _ => Err(KakeiboError::invalid_input("操作はd, c, tのいずれかで入力してください")),
+    }
+}
+
+/// ユーザーに変更後のカテゴリを尋ね、`Category`で返します。
+///
+/// カテゴリが不正な場合は、メッセージを表示して入力し直してもらいます。空行が入力された場合は、変更を中止するものとして`None`を返します。
+///
+/// #### エラー
+///
+/// 入力の読み込みに失敗した場合（入力の打ち切りを含む）は、`KakeiboError`を返します。
+fn input_new_category(config: &Config) -> Result<Option<models::Category>> {
+    let choices = services::migrate::format_category_choices(config);
+    loop {
+        let input = services::prompt::ask(&format!("変更後のカテゴリを入力してください (空行で中止)\n({})", choices))?;
+        if input.trim().is_empty() {
+            return Ok(None);
+        }
+        match services::migrate::parse_category(&input) {
+            Ok(category) => return Ok(Some(category)),
+            Err(error) => println!("{}", error),
+        }
+    }
+}
+
+/// 項目を "日付 品目名 カテゴリ 金額 (参照番号)" の形式の文字列にします。
+///
+/// 参照番号が無い項目は、参照番号を省略します。
+fn format_item(item: &models::Item, config: &Config) -> String {
+    let line = format!(
+        "{} {} {} {}",
+        item.get_date(),
+        item.get_name(),
+        config.format_category(item.get_item_category()),
+        config.format_item_price(item)
+    );
+    match item.get_reference() {
+        Some(reference) => format!("{} ({})", line, reference),
+        None => line,
+    }
+}
+
+/// ユーザーに検索条件を尋ね、`Filter`で返します。
+///
+/// #### エラー
+///
+/// 不正な入力があった場合、`KakeiboError`を返します。
+fn input_filter(config: &Config) -> Result<Filter> {
+    let filter = services::prompt::ask("検索条件を入力してください (例: reference=249-1234567-1234567)")?;
+
+    Filter::parse(filter.trim(), config)
+}
+
+#[cfg(test)]
+mod search_test {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_format_item() {
+        let config = Config::default();
+        let mut item = models::Item::new(
+            "本".to_string(),
+            models::Category::Expense(models::ExpenseCategory::Hobby),
+            models::Money::new(1500),
+            NaiveDate::from_ymd_opt(2023, 8, 1).unwrap()
+        );
+        assert_eq!(format_item(&item, &config), "2023-08-01 本 🎮趣味 -1500円");
+
+        item.set_reference(Some("249-1234567-1234567".to_string()));
+        assert_eq!(format_item(&item, &config), "2023-08-01 本 🎮趣味 -1500円 (249-1234567-1234567)");
+    }
+
+    #[test]
+    fn test_apply_action() {
+        let mut data: Vec<_> = (1..=3).map(|id| {
+            let mut item = models::Item::new(
+                "ランチ".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                models::Money::new(1000),
+                NaiveDate::from_ymd_opt(2023, 8, id).unwrap()
+            );
+            item.set_id(id as u64);
+            item
+        }).collect();
+
+        assert_eq!(apply_action(&mut data, &[1, 3], &SearchAction::SetTag(Some("旅行".to_string()))), 2);
+        assert_eq!(data.iter().map(|item| item.get_event()).collect::<Vec<_>>(), vec![Some("旅行"), None, Some("旅行")]);
+
+        let hobby = models::Category::Expense(models::ExpenseCategory::Hobby);
+        assert_eq!(apply_action(&mut data, &[2], &SearchAction::SetCategory(hobby.clone())), 1);
+        assert_eq!(data[1].get_item_category(), &hobby);
+
+        assert_eq!(apply_action(&mut data, &[1, 2], &SearchAction::Delete), 2);
+        assert_eq!(data.iter().map(|item| item.get_id()).collect::<Vec<_>>(), vec![3]);
+    }
+}
@@ -0,0 +1,286 @@
+//! 監査用スナップショットサービス
+//!
+//! このモジュールは、指定した日までの家計簿データを、改ざんを検出できる読み取り専用のJSONファイル（スナップショット）に書き出す機能を提供します。
+//! スナップショットには、基準日・作成日時・項目と、それらから計算したSHA-256のハッシュ値を記録します。
+//! 書き出したファイルは読み取り専用になり、後から内容が変更されていないかをハッシュ値で検証できます。
+//!
+//! #### スナップショットの例
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "as_of": "2023-12-31",
+//!   "created_at": "2024-01-05T10:00:00+09:00",
+//!   "data": [
+//!     { "name": "ランチ", "category": { "Expense": "Food" }, "price": 1000, "date": "2023-01-15" }
+//!   ],
+//!   "sha256": "5f2b..."
+//! }
+//! ```
+
+use std::fs::{self, File};
+use std::io::BufReader;
+
+use chrono::{DateTime, FixedOffset, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::{KakeiboError, Result};
+use crate::models;
+use crate::services;
+
+/// スナップショット形式のバージョン
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// スナップショットの内容のうち、ハッシュ値の計算対象となる部分を表す構造体
+///
+/// - `version`: スナップショット形式のバージョン
+/// - `as_of`: 基準日（この日までの項目を含む）
+/// - `created_at`: 作成日時
+/// - `data`: 家計簿データ
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+struct SnapshotBody {
+    version: u32,
+    as_of: NaiveDate,
+    created_at: DateTime<FixedOffset>,
+    data: Vec<models::Item>,
+}
+
+/// 監査用スナップショットを表す構造体
+///
+/// - `sha256`: スナップショットの内容（`version`・`as_of`・`created_at`・`data`）から計算したSHA-256のハッシュ値
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    #[serde(flatten)]
+    body: SnapshotBody,
+    sha256: String,
+}
+
+impl Snapshot {
+    /// 基準日までの項目からスナップショットを作成する
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+    /// use kakeibo_app::services::snapshot::Snapshot;
+    /// use chrono::{Local, NaiveDate};
+    ///
+    /// let data = vec![
+    ///     Item::new(String::from("ランチ"), Category::Expense(ExpenseCategory::Food), Money::new(1000), NaiveDate::from_ymd_opt(2023, 12, 31).unwrap()),
+    ///     Item::new(String::from("ランチ"), Category::Expense(ExpenseCategory::Food), Money::new(1000), NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()),
+    /// ];
+    /// let snapshot = Snapshot::new(data, NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(), Local::now().fixed_offset());
+    /// assert_eq!(snapshot.get_data().len(), 1);
+    /// assert!(snapshot.verify());
+    /// ```
+    pub fn new(data: Vec<models::Item>, as_of: NaiveDate, created_at: DateTime<FixedOffset>) -> Self {
+        let data = data.into_iter().filter(|item| item.get_date() <= as_of).collect();
+        let body = SnapshotBody { version: SNAPSHOT_VERSION, as_of, created_at, data };
+        let sha256 = body.get_hash();
+        Snapshot { body, sha256 }
+    }
+
+    /// 基準日を取得する
+    pub fn get_as_of(&self) -> NaiveDate {
+        self.body.as_of
+    }
+
+    /// 作成日時を取得する
+    pub fn get_created_at(&self) -> DateTime<FixedOffset> {
+        self.body.created_at
+    }
+
+    /// 家計簿データを取得する
+    pub fn get_data(&self) -> &[models::Item] {
+        &self.body.data
+    }
+
+    /// 記録されたハッシュ値を取得する
+    pub fn get_hash(&self) -> &str {
+        &self.sha256
+    }
+
+    /// 内容から計算したハッシュ値が、記録されたハッシュ値と一致するかを検証する
+    pub fn verify(&self) -> bool {
+        self.body.get_hash() == self.sha256
+    }
+}
+
+impl SnapshotBody {
+    /// 内容をJSONにシリアライズし、SHA-256のハッシュ値を16進数の文字列で計算する
+    fn get_hash(&self) -> String {
+        let json = serde_json::to_vec(self).expect("JSONへのシリアライズに失敗しました");
+        Sha256::digest(json).iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+}
+
+/// 基準日までの家計簿データを、読み取り専用のスナップショットとして書き出します。
+///
+/// 基準日（yyyy-mm-dd）を省略した場合は今日までの項目を、保存先を省略した場合は`kakeibo_snapshot_<基準日>.json`に書き出します。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::services;
+/// services::snapshot::run_export("store/data.json", Some("2023-12-31"), None).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// 基準日の形式が不正な場合は`KakeiboError::InvalidInput`を返します。
+/// データの読み込みや、スナップショットの書き込みに失敗した場合は、`KakeiboError`を返します。
+pub fn run_export(file_path: &str, as_of: Option<&str>, output_path: Option<&str>) -> Result<()> {
+    let as_of = match as_of {
+        Some(as_of) => parse_as_of(as_of)?,
+        None => Local::now().date_naive(),
+    };
+    match output_path {
+        Some(output_path) => export(file_path, as_of, output_path),
+        None => export(file_path, as_of, &get_default_path(as_of)),
+    }
+}
+
+/// 基準日までの家計簿データをスナップショットとして書き出し、ファイルを読み取り専用にします。
+fn export(file_path: &str, as_of: NaiveDate, output_path: &str) -> Result<()> {
+    let data = services::io::read_data(file_path)?;
+    let snapshot = Snapshot::new(data, as_of, Local::now().fixed_offset());
+
+    let json_data = serde_json::to_string_pretty(&snapshot).expect("JSONへのシリアライズに失敗しました");
+    services::io::write_file(output_path, &json_data)?;
+    let mut permissions = fs::metadata(output_path).map_err(|error| KakeiboError::io(output_path, error))?.permissions();
+    permissions.set_readonly(true);
+    fs::set_permissions(output_path, permissions).map_err(|error| KakeiboError::io(output_path, error))?;
+
+    println!(
+        "{}までの{}件の項目を{}に書き出しました (SHA-256: {})",
+        snapshot.get_as_of(),
+        snapshot.get_data().len(),
+        output_path,
+        snapshot.get_hash()
+    );
+    Ok(())
+}
+
+/// スナップショットの内容が書き出した時点から変更されていないかを検証します。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::services;
+/// services::snapshot::run_verify("kakeibo_snapshot_2023-12-31.json").unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// スナップショットの読み込みに失敗した場合は`KakeiboError::Io`を、形式が不正な場合は`KakeiboError::InvalidData`を返します。
+/// ハッシュ値が一致しない場合は、`KakeiboError::InvalidData`を返します。
+pub fn run_verify(snapshot_path: &str) -> Result<()> {
+    let file = File::open(snapshot_path).map_err(|error| KakeiboError::io(snapshot_path, error))?;
+    let snapshot: Snapshot = serde_json::from_reader(BufReader::new(file))
+        .map_err(|error| KakeiboError::InvalidData(format!("{}の形式が不正です: {}", snapshot_path, error)))?;
+    if !snapshot.verify() {
+        return Err(KakeiboError::InvalidData(format!(
+            "{}の内容はハッシュ値と一致しません。書き出した後に変更された可能性があります",
+            snapshot_path
+        )));
+    }
+    println!(
+        "{}は改ざんされていません (基準日: {}, 作成日時: {}, {}件)",
+        snapshot_path,
+        snapshot.get_as_of(),
+        snapshot.get_created_at(),
+        snapshot.get_data().len()
+    );
+    Ok(())
+}
+
+/// スナップショットの書き出しまたは検証を行います。
+///
+/// この関数は、書き出しか検証かを尋ね、書き出しの場合は基準日と保存先を、検証の場合はファイルのパスを受け付けて処理します。
+///
+/// #### 例
+///
+/// ```rust
+/// // ドキュメンテーションテストでFailになるのでコメントアウト
+/// // use kakeibo_app::services;
+/// // services::snapshot::run("store/data.json");
+/// ```
+///
+/// #### エラー
+///
+/// 不正な入力があった場合や、ファイルの読み書きに失敗した場合は、`KakeiboError`を返します。
+pub fn run(file_path: &str) -> Result<()> {
+    let action = services::prompt::ask("e:書き出し / v:検証")?;
+    match action.trim() {
+        "e" | "E" => {
+            let as_of = services::prompt::ask("基準日をyyyy-mm-ddの形式で入力してください (空行で今日)")?;
+            let as_of = match as_of.trim() {
+                "" => Local::now().date_naive(),
+                as_of => parse_as_of(as_of)?,
+            };
+            let default_path = get_default_path(as_of);
+            let output_path = services::prompt::ask(&format!("保存先を入力してください (空行で{})", default_path))?;
+            match output_path.trim() {
+                "" => export(file_path, as_of, &default_path),
+                output_path => export(file_path, as_of, output_path),
+            }
+        },
+        "v" | "V" => {
+            run_verify(services::prompt::ask("検証するスナップショットのパスを入力してください")?.trim())
+        },
+        _ => Err(KakeiboError::invalid_input("操作はe, vのいずれかで入力してください")),
+    }
+}
+
+/// 基準日をyyyy-mm-ddの形式で解析します。
+fn parse_as_of(as_of: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(as_of, "%Y-%m-%d")
+        .map_err(|_| KakeiboError::invalid_input("基準日はyyyy-mm-ddの形式で入力してください"))
+}
+
+/// 基準日からスナップショットの既定の保存先を作成します。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::services::snapshot;
+/// use chrono::NaiveDate;
+///
+/// assert_eq!(snapshot::get_default_path(NaiveDate::from_ymd_opt(2023, 12, 31).unwrap()), "kakeibo_snapshot_2023-12-31.json");
+/// ```
+pub fn get_default_path(as_of: NaiveDate) -> String {
+    format!("kakeibo_snapshot_{}.json", as_of)
+}
+
+#[cfg(test)]
+mod snapshot_test {
+    use super::*;
+
+    fn get_test_snapshot() -> Snapshot {
+        let data = vec![models::Item::new(
+            "ランチ".to_string(),
+            models::Category::Expense(models::ExpenseCategory::Food),
+            models::Money::new(1000),
+            NaiveDate::from_ymd_opt(2023, 1, 15).unwrap()
+        )];
+        let created_at = DateTime::parse_from_rfc3339("2024-01-05T10:00:00+09:00").unwrap();
+        Snapshot::new(data, NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(), created_at)
+    }
+
+    #[test]
+    fn test_verify() {
+        let snapshot = get_test_snapshot();
+        let json = serde_json::to_string_pretty(&snapshot).unwrap();
+        let snapshot: Snapshot = serde_json::from_str(&json).unwrap();
+        assert!(snapshot.verify());
+        assert_eq!(snapshot.get_hash().len(), 64);
+    }
+
+    #[test]
+    fn test_verify_for_tampered_snapshot() {
+        let json = serde_json::to_string(&get_test_snapshot()).unwrap();
+        let tampered: Snapshot = serde_json::from_str(&json.replace("\"price\":1000", "\"price\":100")).unwrap();
+        assert!(!tampered.verify());
+    }
+}
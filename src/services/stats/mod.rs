@@ -0,0 +1,208 @@
+//! 統計サービス
+//!
+//! このモジュールは、家計簿全体の月ごとの支出・収入から、平均・中央値・最小・最大・標準偏差を計算する機能を提供します。
+//! 統計は、最初の項目の月から最後の項目の月までのすべての月を対象にします（登録の無い月は0円として数えます）。
+
+use std::collections::BTreeMap;
+
+use chrono::{Months, NaiveDate};
+
+use crate::config::Config;
+use crate::error::Result;
+use crate::models;
+use crate::services;
+
+/// 月ごとの金額の統計を表す構造体
+///
+/// - `average`: 平均（通貨の最小単位未満は切り捨て）
+/// - `median`: 中央値（月数が偶数の場合は中央の2つの平均、通貨の最小単位未満は切り捨て）
+/// - `min`: 最小
+/// - `max`: 最大
+/// - `std_dev`: 標準偏差（母標準偏差、通貨の最小単位未満は切り捨て）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Statistics {
+    pub average: models::Money,
+    pub median: models::Money,
+    pub min: models::Money,
+    pub max: models::Money,
+    pub std_dev: models::Money,
+}
+
+impl Statistics {
+    /// 月ごとの金額から統計を計算する
+    ///
+    /// 金額が1つも無い場合は`None`を返します。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::models::Money;
+    /// use kakeibo_app::services::stats::Statistics;
+    ///
+    /// let values = [Money::new(1000), Money::new(3000), Money::new(2000), Money::new(6000)];
+    /// let statistics = Statistics::from_values(&values).unwrap();
+    /// assert_eq!(statistics.average, Money::new(3000));
+    /// assert_eq!(statistics.median, Money::new(2500));
+    /// assert_eq!(statistics.std_dev, Money::new(1870));
+    /// assert!(Statistics::from_values(&[]).is_none());
+    /// ```
+    pub fn from_values(values: &[models::Money]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        let mut sorted = values.to_vec();
+        sorted.sort();
+        let count = sorted.len() as i64;
+        let total: models::Money = sorted.iter().copied().sum();
+        let average = total.get_minor_units() as f64 / count as f64;
+        let middle = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            models::Money::new((sorted[middle - 1].get_minor_units() + sorted[middle].get_minor_units()) / 2)
+        } else {
+            sorted[middle]
+        };
+        let variance = sorted.iter()
+            .map(|value| (value.get_minor_units() as f64 - average).powi(2))
+            .sum::<f64>() / count as f64;
+
+        Some(Statistics {
+            average: models::Money::new(average.floor() as i64),
+            median,
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+            std_dev: models::Money::new(variance.sqrt().floor() as i64),
+        })
+    }
+}
+
+/// 家計簿データから月ごとの支出合計と収入合計を取得します。
+///
+/// この関数は、最初の項目の月から最後の項目の月までのすべての月について、月の最初の日をキーとし、
+/// (支出合計, 収入合計)を値とするマップを返します。登録の無い月は0円になります。口座間の振替は含みません。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+/// use kakeibo_app::services::stats;
+/// use chrono::NaiveDate;
+///
+/// let data = vec![
+///     Item::new(String::from("ランチ"), Category::Expense(ExpenseCategory::Food), Money::new(1000), NaiveDate::from_ymd_opt(2023, 1, 5).unwrap()),
+///     Item::new(String::from("ランチ"), Category::Expense(ExpenseCategory::Food), Money::new(1500), NaiveDate::from_ymd_opt(2023, 3, 5).unwrap()),
+/// ];
+/// let totals = stats::get_monthly_totals(&data);
+/// assert_eq!(totals.len(), 3);
+/// assert_eq!(totals[&NaiveDate::from_ymd_opt(2023, 2, 1).unwrap()], (Money::ZERO, Money::ZERO));
+/// ```
+pub fn get_monthly_totals(data: &[models::Item]) -> BTreeMap<NaiveDate, (models::Money, models::Money)> {
+    let mut totals: BTreeMap<NaiveDate, (models::Money, models::Money)> = BTreeMap::new();
+    let (Some(first), Some(last)) = (
+        data.iter().map(|item| item.get_first_day()).min(),
+        data.iter().map(|item| item.get_first_day()).max(),
+    ) else {
+        return totals;
+    };
+    let mut month = first;
+    while month <= last {
+        totals.insert(month, (models::Money::ZERO, models::Money::ZERO));
+        month = month.checked_add_months(Months::new(1)).unwrap();
+    }
+
+    for item in data {
+        let (expense, income) = totals.get_mut(&item.get_first_day()).unwrap();
+        match item.get_item_category() {
+            models::Category::Expense(_) => *expense += item.get_price(),
+            models::Category::Income(_) => *income += item.get_price(),
+            models::Category::Transfer(_) => {},
+        }
+    }
+    totals
+}
+
+/// 月ごとの支出と収入の統計を表示します。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// services::stats::run("store/data.json", &Config::default()).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// データの読み込みに失敗した場合は、`KakeiboError`を返します。
+pub fn run(file_path: &str, config: &Config) -> Result<()> {
+    let data = services::io::read_data(file_path)?;
+    let totals = get_monthly_totals(&data);
+    let (first, last) = (totals.keys().next().unwrap(), totals.keys().next_back().unwrap());
+    println!(
+        "{}〜{}の{}か月分の統計",
+        services::summarize::format_date(*first),
+        services::summarize::format_date(*last),
+        totals.len()
+    );
+
+    let expenses: Vec<_> = totals.values().map(|(expense, _)| *expense).collect();
+    let incomes: Vec<_> = totals.values().map(|(_, income)| *income).collect();
+    for (label, values) in [("月の支出", expenses), ("月の収入", incomes)] {
+        let statistics = Statistics::from_values(&values).unwrap();
+        println!(
+            "{}: 平均 {} / 中央値 {} / 最小 {} / 最大 {} / 標準偏差 {}",
+            label,
+            config.format_currency(statistics.average),
+            config.format_currency(statistics.median),
+            config.format_currency(statistics.min),
+            config.format_currency(statistics.max),
+            config.format_currency(statistics.std_dev)
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod stats_test {
+    use super::*;
+
+    #[test]
+    fn test_from_values() {
+        let values = [models::Money::new(5000), models::Money::new(1000), models::Money::new(3000)];
+        assert_eq!(Statistics::from_values(&values), Some(Statistics {
+            average: models::Money::new(3000),
+            median: models::Money::new(3000),
+            min: models::Money::new(1000),
+            max: models::Money::new(5000),
+            std_dev: models::Money::new(1632),
+        }));
+    }
+
+    #[test]
+    fn test_get_monthly_totals() {
+        let data = vec![
+            models::Item::new(
+                "給料".to_string(),
+                models::Category::Income(models::IncomeCategory::Salary),
+                models::Money::new(300000),
+                NaiveDate::from_ymd_opt(2022, 12, 25).unwrap()
+            ),
+            models::Item::new(
+                "引き出し".to_string(),
+                models::Category::Transfer(models::Account::Cash),
+                models::Money::new(10000),
+                NaiveDate::from_ymd_opt(2022, 12, 26).unwrap()
+            ),
+            models::Item::new(
+                "外食".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                models::Money::new(3000),
+                NaiveDate::from_ymd_opt(2023, 1, 15).unwrap()
+            ),
+        ];
+        let totals: Vec<_> = get_monthly_totals(&data).into_values().collect();
+        assert_eq!(totals, vec![
+            (models::Money::ZERO, models::Money::new(300000)),
+            (models::Money::new(3000), models::Money::ZERO),
+        ]);
+    }
+}
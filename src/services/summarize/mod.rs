@@ -1,55 +1,685 @@
 //! 集計サービス
 //!
 //! このモジュールは、登録済みのデータから各月ごとに集計を行う機能を提供します。
+//!
+//! 集計そのものは`summarize`関数が`SummaryReport`として返し、画面への表示はその結果をもとに行います。
+//! CLI以外のフロントエンドからは、`summarize`関数を直接呼び出して集計結果を利用できます。
+//! データは保存先（`services::io::store::DataStore`）を通して読み込むため、`run_with_stores`ではJSONファイル以外の保存先も集計できます。
 
 use std::collections::{BTreeSet, BTreeMap};
 
-use chrono::{Datelike, NaiveDate};
+use chrono::{Datelike, Days, IsoWeek, Months, NaiveDate, Weekday};
+use serde::Serialize;
 
-use crate::{models, services};
+use crate::{config::Config, models, services};
+use crate::error::{KakeiboError, Result};
+use crate::services::io::store::DataStore;
+use crate::services::timing;
 
 /// 家計簿の集計を実行する。
 ///
 /// この関数は、指定されたファイルパスから家計簿データを読み込み、各月ごとの収支の集計結果を表示します。
+/// 複数のファイルパスを指定した場合は、すべてのファイルのデータをまとめて集計します。
+/// 各月の収支の行の後には、カテゴリ別の小計（`食費: -23000, 趣味: -12000, 給与: +300000`の形式）を続けて表示します。
+/// 集計結果の表示後、年月を入力するとその月の明細とカテゴリ別の内訳を表示します（ドリルダウン）。
 ///
 /// #### 例
 ///
-/// ```rust
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
 /// use kakeibo_app::services;
-/// services::summarize::run("store/data.json");
+/// services::summarize::run(&["store/2022.json", "store/2023.json"], &Config::default()).unwrap();
 /// ```
-pub fn run(file_path: &str) {
+pub fn run(file_paths: &[&str], config: &Config) -> Result<()> {
     println!("家計簿の集計を行います");
-    let data = services::io::read_data_or_panic(file_path);
+    let stores = services::io::store::open_stores(file_paths);
+    check_data_exists(&stores)?;
+    let data = timing::measure("読み込み合計", || services::io::store::load_all(&stores))?;
+    let options = input_options()?;
+
+    print_summary(&data, &options, config);
+    drill_down(&data, &options, config)
+}
+
+/// 表示オプションを指定して家計簿の集計を実行する。
+///
+/// この関数は、`run`と同じ集計結果を表示しますが、表示オプションを尋ねずドリルダウンも行いません。
+/// スクリプトなどから対話入力なしで集計する場合に使用します。
+/// `options.month`で月を指定した場合は、その月の項目だけを読み込むため、データが多くてもすぐに表示できます。
+/// `options.from`・`options.to`で期間を指定した場合も同様に、期間内の項目だけを読み込んで集計します。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// use kakeibo_app::services::summarize::SummarizeOptions;
+///
+/// let options = SummarizeOptions { show_cumulative: true, ..SummarizeOptions::default() };
+/// services::summarize::run_with_options(&["store/data.json"], &options, &Config::default()).unwrap();
+/// ```
+pub fn run_with_options(file_paths: &[&str], options: &SummarizeOptions, config: &Config) -> Result<()> {
+    run_with_stores(&services::io::store::open_stores(file_paths), options, config)
+}
+
+/// 保存先を指定し、表示オプションを指定して家計簿の集計を実行する。
+///
+/// この関数は、`run_with_options`と同じ集計を、データファイルのパスの代わりに保存先（`DataStore`）から読み込んで行います。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+/// use kakeibo_app::services;
+/// use kakeibo_app::services::io::store::{DataStore, MemoryStore};
+/// use kakeibo_app::services::summarize::SummarizeOptions;
+/// use chrono::NaiveDate;
+///
+/// let store = MemoryStore::default();
+/// let item = Item::new(
+///     String::from("ランチ"),
+///     Category::Expense(ExpenseCategory::Food),
+///     Money::new(1000),
+///     NaiveDate::from_ymd_opt(2023, 1, 5).unwrap(),
+/// );
+/// store.append(vec![item]).unwrap();
+/// let stores: Vec<Box<dyn DataStore>> = vec![Box::new(store)];
+/// services::summarize::run_with_stores(&stores, &SummarizeOptions::default(), &Config::default()).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// 期間の指定が不正な場合や、データの読み込みに失敗した場合は、`KakeiboError`を返します。
+pub fn run_with_stores(stores: &[Box<dyn DataStore>], options: &SummarizeOptions, config: &Config) -> Result<()> {
+    if let (Some(from), Some(to)) = (options.from, options.to) {
+        if from > to {
+            return Err(KakeiboError::invalid_input("期間の開始日は終了日以前の日付を指定してください"));
+        }
+    }
+    check_data_exists(stores)?;
+    let period = match (options.month, options.from, options.to) {
+        (Some(month), _, _) => Some((month, get_last_day(month))),
+        (None, None, None) => None,
+        (None, from, to) => Some((from.unwrap_or(NaiveDate::MIN), to.unwrap_or(NaiveDate::MAX))),
+    };
+    let data = timing::measure("読み込み合計", || match period {
+        Some((first_date, last_date)) => services::io::store::load_in_period(stores, first_date, last_date),
+        None => services::io::store::load_all(stores),
+    });
+    let data = match data {
+        // データはあるが期間内に項目が無い場合は、登録を促さずにその旨だけを表示する
+        Err(KakeiboError::NoData) if period.is_some() => {
+            println!("指定した期間の項目はありません");
+            return Ok(());
+        },
+        data => data?,
+    };
+    print_summary(&data, options, config);
+    Ok(())
+}
+
+/// 集計対象の保存先が1つも存在しない場合は、まだ何も登録していないものとして`KakeiboError::NoData`を返します。
+///
+/// 一部の保存先だけが存在しない場合は、パスの誤りとして読み込み時に`KakeiboError::Io`になります。
+fn check_data_exists(stores: &[Box<dyn DataStore>]) -> Result<()> {
+    if stores.iter().any(|store| store.exists()) {
+        Ok(())
+    } else {
+        Err(KakeiboError::NoData)
+    }
+}
+
+/// 各月ごとの収支を集計して表示する。
+///
+/// 出力形式にCSV・TSVを指定した場合は、集計結果の表だけを区切り文字で区切って出力する。
+fn print_summary(data: &[models::Item], options: &SummarizeOptions, config: &Config) {
+    let report = timing::measure("集計", || summarize(data, options));
+    if timing::is_enabled() {
+        println!("[計測] 件数: {}件", report.item_count);
+    }
+
+    let separator = match options.format {
+        SummaryFormat::Table => None,
+        SummaryFormat::Csv => Some(','),
+        SummaryFormat::Tsv => Some('\t'),
+    };
+    if let Some(separator) = separator {
+        let rows = services::export::create_summary_rows(&report, options.show_categories, config);
+        println!("{}", services::io::format_delimited(&rows, separator));
+        return;
+    }
+
+    timing::measure("表示", || {
+        if options.show_real {
+            match config.get_base_year() {
+                Some(year) => println!("実質値は{}年の物価指数で換算しています", year),
+                None => println!("物価指数が設定されていないため、実質値は表示しません"),
+            }
+        }
+        if options.chart {
+            print_chart(&get_chart_bars(&report, data, options), config);
+        } else if options.weekly {
+            print_weekly_table(&summarize_weeks(data, options), options, config);
+        } else {
+            print_table(&report, options, config);
+        }
+        print_account_balances(&report, config);
+    });
+}
 
-    let target_dates: BTreeSet<NaiveDate> = get_target_dates(&data);
-    let mut result_table: BTreeMap<NaiveDate, i32> = BTreeMap::new();
+/// 1か月分の集計結果を表す構造体
+///
+/// - `month`: 対象の月（その月の1日）
+/// - `income`: 収入（0以上）
+/// - `expense`: 支出（支出のため0以下）
+/// - `balance`: 収支（収入 - 支出）
+/// - `cumulative`: 最初の月からこの月までの累積収支
+/// - `fixed`: 固定費（支出のため0以下）
+/// - `variable`: 変動費（支出のため0以下）
+/// - `categories`: カテゴリ別の内訳
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MonthlySummary {
+    pub month: NaiveDate,
+    pub income: models::Money,
+    pub expense: models::Money,
+    pub balance: models::Money,
+    pub cumulative: models::Money,
+    pub fixed: models::Money,
+    pub variable: models::Money,
+    pub categories: Vec<CategoryTotal>,
+}
+
+/// 1週間分の集計結果を表す構造体
+///
+/// - `week`: 対象のISO週（月曜日始まり）
+/// - `first_day`: 週の最初の日（月曜日）
+/// - `income`: 収入（0以上）
+/// - `expense`: 支出（支出のため0以下）
+/// - `balance`: 収支（収入 - 支出）
+/// - `cumulative`: 最初の週からこの週までの累積収支
+/// - `fixed`: 固定費（支出のため0以下）
+/// - `variable`: 変動費（支出のため0以下）
+/// - `categories`: カテゴリ別の内訳
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeeklySummary {
+    pub week: IsoWeek,
+    pub first_day: NaiveDate,
+    pub income: models::Money,
+    pub expense: models::Money,
+    pub balance: models::Money,
+    pub cumulative: models::Money,
+    pub fixed: models::Money,
+    pub variable: models::Money,
+    pub categories: Vec<CategoryTotal>,
+}
+
+/// カテゴリ別の集計結果を表す構造体
+///
+/// - `category`: カテゴリ
+/// - `subcategory`: サブカテゴリ（サブカテゴリをカテゴリにまとめて集計した場合や、サブカテゴリの無い項目は`None`）
+/// - `total`: 収支の合計（支出は負の値）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CategoryTotal {
+    pub category: models::Category,
+    pub subcategory: Option<String>,
+    pub total: models::Money,
+}
+
+/// 集計結果を表す構造体
+///
+/// - `months`: 月ごとの集計結果（古い月から順）
+/// - `accounts`: 口座別の残高（口座の順）
+/// - `item_count`: 集計した項目数
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub struct SummaryReport {
+    pub months: Vec<MonthlySummary>,
+    pub accounts: Vec<AccountBalance>,
+    pub item_count: usize,
+}
+
+/// 口座別の残高を表す構造体
+///
+/// - `account`: 口座
+/// - `balance`: 全期間の収支の合計
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AccountBalance {
+    pub account: models::Account,
+    pub balance: models::Money,
+}
+
+/// 家計簿データを月ごとに集計し、集計結果を返す。
+///
+/// この関数は画面への表示やファイルの読み書きを行わないため、CLI以外のフロントエンドからも利用できます。
+/// `options`のうち集計結果に影響するのは`fill_empty_months`・`detail`・`month`・`from`・`to`だけで、累積収支・固定費と変動費・カテゴリ別の内訳は常に集計します。
+/// `month`を指定した場合は、その月の項目だけを集計します（累積収支と口座別の残高も、その月の分だけになります）。
+/// `from`・`to`を指定した場合も同様に、期間内（両端を含む）の項目だけを集計します。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::models::{Item, Category, ExpenseCategory, IncomeCategory, Money};
+/// use kakeibo_app::services::summarize::{self, SummarizeOptions};
+/// use chrono::NaiveDate;
+///
+/// let data = vec![
+///     Item::new(String::from("給与"), Category::Income(IncomeCategory::Salary), Money::new(300000), NaiveDate::from_ymd_opt(2023, 1, 25).unwrap()),
+///     Item::new(String::from("ランチ"), Category::Expense(ExpenseCategory::Food), Money::new(1000), NaiveDate::from_ymd_opt(2023, 3, 1).unwrap()),
+/// ];
+/// let report = summarize::summarize(&data, &SummarizeOptions { fill_empty_months: true, ..SummarizeOptions::default() });
+/// assert_eq!(report.months.len(), 3);
+/// assert_eq!(report.months[1].balance, Money::ZERO);
+/// assert_eq!(report.months[2].cumulative, Money::new(299000));
+/// ```
+pub fn summarize(data: &[models::Item], options: &SummarizeOptions) -> SummaryReport {
+    let mut target_dates: BTreeSet<NaiveDate> = get_target_dates(data, options);
+    if let Some(month) = options.month {
+        target_dates.retain(|date| *date == month);
+    } else if options.fill_empty_months {
+        target_dates = fill_empty_months_between(&target_dates);
+    }
+    let mut result_table: BTreeMap<NaiveDate, models::Money> = BTreeMap::new();
+    let mut flow_table: BTreeMap<NaiveDate, (models::Money, models::Money)> = BTreeMap::new();
+    let mut cost_table: BTreeMap<NaiveDate, (models::Money, models::Money)> = BTreeMap::new();
+    let mut category_table: BTreeMap<NaiveDate, Vec<CategoryTotal>> = BTreeMap::new();
 
     for date in target_dates {
-        let filterd_data = get_filtered_data(&data, date);
+        let filterd_data = get_filtered_data(data, date, options);
         let sum = summarize_data(&filterd_data);
         result_table.insert(date, sum);
-    } 
+        flow_table.insert(date, summarize_income_expense(&filterd_data));
+        cost_table.insert(date, summarize_fixed_costs(&filterd_data));
+        category_table.insert(date, get_category_breakdown(&filterd_data, options.detail));
+    }
 
-    print_table(result_table);
+    let cumulative_table = get_cumulative_table(&result_table);
+    let months = result_table.into_iter().map(|(month, balance)| {
+        let (income, expense) = flow_table[&month];
+        let (fixed, variable) = cost_table[&month];
+        let categories = category_table.remove(&month).unwrap_or_default();
+        MonthlySummary { month, income, expense, balance, cumulative: cumulative_table[&month], fixed, variable, categories }
+    }).collect();
+    let target_data: Vec<_> = data.iter()
+        .filter(|item| options.month.is_none_or(|month| item.get_first_day() == month) && options.is_in_range(item.get_date()))
+        .collect();
+    SummaryReport { months, accounts: get_account_balances(&target_data), item_count: target_data.len() }
+}
+
+/// 家計簿データをISO週ごとに集計し、集計結果を返す。
+///
+/// 月ではなく週単位で予算を管理する場合に使います。週は、ISO 8601に従って月曜日から日曜日までとします（設定の`week_start`は使いません）。
+/// `options`の`month`・`from`・`to`・`detail`は`summarize`と同じように扱い、`fill_empty_months`を指定した場合は登録の無い週も0円として含めます。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+/// use kakeibo_app::services::summarize::{self, SummarizeOptions};
+/// use chrono::{Datelike, NaiveDate};
+///
+/// let data = vec![
+///     Item::new(String::from("ランチ"), Category::Expense(ExpenseCategory::Food), Money::new(1000), NaiveDate::from_ymd_opt(2023, 1, 1).unwrap()),
+///     Item::new(String::from("ランチ"), Category::Expense(ExpenseCategory::Food), Money::new(1200), NaiveDate::from_ymd_opt(2023, 1, 2).unwrap()),
+/// ];
+/// let weeks = summarize::summarize_weeks(&data, &SummarizeOptions::default());
+/// assert_eq!(weeks.len(), 2);
+/// assert_eq!((weeks[0].week.year(), weeks[0].week.week()), (2022, 52));
+/// assert_eq!(weeks[1].first_day, NaiveDate::from_ymd_opt(2023, 1, 2).unwrap());
+/// assert_eq!(weeks[1].cumulative, Money::new(-2200));
+/// ```
+pub fn summarize_weeks(data: &[models::Item], options: &SummarizeOptions) -> Vec<WeeklySummary> {
+    let mut week_table: BTreeMap<IsoWeek, Vec<&models::Item>> = BTreeMap::new();
+    for item in data.iter().filter(|item| options.month.is_none_or(|month| item.get_first_day() == month) && options.is_in_range(item.get_date())) {
+        week_table.entry(item.get_date().iso_week()).or_default().push(item);
+    }
+    if options.fill_empty_months {
+        if let (Some((first, _)), Some((last, _))) = (week_table.first_key_value(), week_table.last_key_value()) {
+            let (first, last) = (get_week_first_day(*first), get_week_first_day(*last));
+            for day in first.iter_days().step_by(7).take_while(|day| *day <= last) {
+                week_table.entry(day.iso_week()).or_default();
+            }
+        }
+    }
+
+    let mut cumulative = models::Money::ZERO;
+    week_table.into_iter().map(|(week, items)| {
+        let balance = summarize_data(&items);
+        cumulative += balance;
+        let (income, expense) = summarize_income_expense(&items);
+        let (fixed, variable) = summarize_fixed_costs(&items);
+        WeeklySummary {
+            week,
+            first_day: get_week_first_day(week),
+            income,
+            expense,
+            balance,
+            cumulative,
+            fixed,
+            variable,
+            categories: get_category_breakdown(&items, options.detail),
+        }
+    }).collect()
+}
+
+/// ISO週の最初の日（月曜日）を取得する。
+fn get_week_first_day(week: IsoWeek) -> NaiveDate {
+    NaiveDate::from_isoywd_opt(week.year(), week.week(), Weekday::Mon).unwrap()
+}
+
+/// 残高の推移の1行を表す構造体
+///
+/// - `item`: 項目
+/// - `balance`: この項目を反映した後の残高
+#[derive(Debug, PartialEq, Eq)]
+pub struct RunningBalanceRow<'a> {
+    pub item: &'a models::Item,
+    pub balance: models::Money,
+}
+
+/// 家計簿データを日付順に並べ、各項目を反映した後の残高を計算する。
+///
+/// この関数は、開始残高（家計簿を付け始める前の残高）に収入を足し、支出を引いた残高を項目ごとに返します。
+/// 同じ日付の項目はID順に並べます。口座間の振替は全体の残高を変えないため含みません。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::models::{Item, Category, ExpenseCategory, IncomeCategory, Money};
+/// use kakeibo_app::services::summarize;
+/// use chrono::NaiveDate;
+///
+/// let data = vec![
+///     Item::new(String::from("ランチ"), Category::Expense(ExpenseCategory::Food), Money::new(1000), NaiveDate::from_ymd_opt(2023, 1, 26).unwrap()),
+///     Item::new(String::from("給与"), Category::Income(IncomeCategory::Salary), Money::new(300000), NaiveDate::from_ymd_opt(2023, 1, 25).unwrap()),
+/// ];
+/// let rows = summarize::get_running_balance(&data, Money::new(50000));
+/// assert_eq!(rows[0].item.get_name(), "給与");
+/// assert_eq!(rows[0].balance, Money::new(350000));
+/// assert_eq!(rows[1].balance, Money::new(349000));
+/// ```
+pub fn get_running_balance(data: &[models::Item], opening_balance: models::Money) -> Vec<RunningBalanceRow<'_>> {
+    let mut items: Vec<_> = data.iter()
+        .filter(|item| !matches!(item.get_item_category(), models::Category::Transfer(_)))
+        .collect();
+    items.sort_by_key(|item| (item.get_date(), item.get_id()));
+
+    let mut balance = opening_balance;
+    items.into_iter().map(|item| {
+        balance += item.get_price_for_summary();
+        RunningBalanceRow { item, balance }
+    }).collect()
+}
+
+/// 残高の推移を表示する。
+///
+/// この関数は、指定されたファイルパスから家計簿データを読み込み、すべての項目を日付順に並べて、各項目の後の残高を表示します。
+/// 月ごとの収支ではなく、残高がどのように推移してきたかを確認する場合に使います。
+/// 開始残高は、設定の`opening_balance`です。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// services::summarize::run_running_balance(&["store/data.json"], &Config::default()).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// データの読み込みに失敗した場合は、`KakeiboError`を返します。
+pub fn run_running_balance(file_paths: &[&str], config: &Config) -> Result<()> {
+    let stores = services::io::store::open_stores(file_paths);
+    check_data_exists(&stores)?;
+    let data = services::io::store::load_all(&stores)?;
+
+    println!("開始残高: {}", config.format_currency(config.opening_balance));
+    for row in get_running_balance(&data, config.opening_balance) {
+        println!(
+            "{} {} {} (残高: {})",
+            row.item.get_date(),
+            row.item.get_name(),
+            format_price(row.item.get_price_for_summary(), config),
+            config.format_currency(row.balance)
+        );
+    }
+    Ok(())
+}
+
+/// 家計簿データを口座別に集計する。
+///
+/// この関数は、全期間の収支を口座ごとに合計し、項目のある口座だけを口座の順に並べて返します。
+/// 口座間の振替は、振替元の口座から振替先の口座に金額を移します。
+fn get_account_balances(data: &[&models::Item]) -> Vec<AccountBalance> {
+    let mut balances: BTreeMap<models::Account, models::Money> = BTreeMap::new();
+    for item in data {
+        for (account, change) in item.get_account_changes() {
+            *balances.entry(account).or_default() += change;
+        }
+    }
+    balances.into_iter().map(|(account, balance)| AccountBalance { account, balance }).collect()
+}
+
+/// イベント単位の集計を実行する。
+///
+/// この関数は、指定されたファイルパスから家計簿データを読み込み、イベントごとの総費用と期間を表示します。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// services::summarize::run_by_event("store/data.json", &Config::default()).unwrap();
+/// ```
+pub fn run_by_event(file_path: &str, config: &Config) -> Result<()> {
+    println!("イベント別の集計を行います");
+    let data = services::io::read_data(file_path)?;
+
+    let event_table = summarize_events(&data);
+    if event_table.is_empty() {
+        println!("イベントが登録された項目はありません");
+        return Ok(());
+    }
+
+    for (event, summary) in event_table {
+        println!(
+            "{} 合計{} ({}〜{}, {}件)",
+            event,
+            config.format_currency(summary.total_expense),
+            summary.first_date,
+            summary.last_date,
+            summary.count
+        );
+    }
+    Ok(())
+}
+
+/// イベント単位の集計結果を表す構造体
+///
+/// - `total_expense`: 支出の合計
+/// - `first_date`: イベントの最初の日付
+/// - `last_date`: イベントの最後の日付
+/// - `count`: 項目数
+#[derive(Debug, PartialEq, Eq)]
+struct EventSummary {
+    total_expense: models::Money,
+    first_date: NaiveDate,
+    last_date: NaiveDate,
+    count: usize,
+}
+
+/// 家計簿データをイベント単位で集計する。
+///
+/// この関数は、イベント名が設定された項目をイベントごとにまとめ、支出合計・期間・項目数をイベント名をキーとするマップとして返します。
+fn summarize_events(data: &[models::Item]) -> BTreeMap<String, EventSummary> {
+    let mut event_table: BTreeMap<String, EventSummary> = BTreeMap::new();
+    for item in data {
+        let Some(event) = item.get_event() else {
+            continue;
+        };
+        let expense = match item.get_item_category() {
+            models::Category::Expense(_) => item.get_price(),
+            models::Category::Income(_) | models::Category::Transfer(_) => models::Money::ZERO,
+        };
+
+        let summary = event_table.entry(event.to_string()).or_insert(EventSummary {
+            total_expense: models::Money::ZERO,
+            first_date: item.get_date(),
+            last_date: item.get_date(),
+            count: 0,
+        });
+        summary.total_expense += expense;
+        summary.first_date = summary.first_date.min(item.get_date());
+        summary.last_date = summary.last_date.max(item.get_date());
+        summary.count += 1;
+    }
+    event_table
+}
+
+/// 集計の表示オプションを表す構造体
+///
+/// - `fill_empty_months`: 登録の無い月も0円として表示するかどうか
+/// - `show_cumulative`: 累積収支を表示するかどうか
+/// - `show_fixed_costs`: 固定費・変動費の内訳を表示するかどうか
+/// - `show_categories`: 月ごとのカテゴリ別の内訳を表示するかどうか
+/// - `detail`: カテゴリ別の内訳をサブカテゴリに分けるかどうか（`false`の場合はサブカテゴリをカテゴリにまとめる）
+/// - `month`: 集計する月（その月の1日）。`None`の場合は全期間を集計する
+/// - `from`: 集計する期間の開始日（その日を含む）。`None`の場合は最初の項目から集計する
+/// - `to`: 集計する期間の終了日（その日を含む）。`None`の場合は最後の項目まで集計する
+/// - `weekly`: 月ごとではなくISO週ごとに集計するかどうか
+/// - `show_real`: 収支に加えて、設定の物価指数で換算した実質値を表示するかどうか
+/// - `chart`: 表の代わりに、収入・支出・収支を横棒グラフで表示するかどうか
+/// - `format`: 集計結果の出力形式（表・CSV・TSV）
+#[derive(Debug, Default)]
+pub struct SummarizeOptions {
+    pub fill_empty_months: bool,
+    pub show_cumulative: bool,
+    pub show_fixed_costs: bool,
+    pub show_categories: bool,
+    pub detail: bool,
+    pub month: Option<NaiveDate>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub weekly: bool,
+    pub show_real: bool,
+    pub chart: bool,
+    pub format: SummaryFormat,
+}
+
+/// 集計結果の出力形式を表す列挙型
+///
+/// - `Table`: 画面表示用の表（カテゴリ別の小計や口座別の残高も表示する）
+/// - `Csv`: 月・収入・支出・収支をカンマ区切りで出力する
+/// - `Tsv`: 月・収入・支出・収支をタブ区切りで出力する（表計算ソフトにそのまま貼り付けられる）
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SummaryFormat {
+    #[default]
+    Table,
+    Csv,
+    Tsv,
+}
+
+impl SummaryFormat {
+    /// 形式の名前（`table`・`csv`・`tsv`）から出力形式を取得する
+    ///
+    /// 大文字・小文字は区別しません。
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::services::summarize::SummaryFormat;
+    ///
+    /// assert_eq!(SummaryFormat::parse("TSV").unwrap(), SummaryFormat::Tsv);
+    /// assert!(SummaryFormat::parse("xlsx").is_err());
+    /// ```
+    ///
+    /// #### エラー
+    ///
+    /// 対応していない形式の場合は、`KakeiboError::InvalidInput`を返します。
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "table" => Ok(SummaryFormat::Table),
+            "csv" => Ok(SummaryFormat::Csv),
+            "tsv" => Ok(SummaryFormat::Tsv),
+            _ => Err(KakeiboError::InvalidInput(format!("形式{}には対応していません (table・csv・tsvで指定してください)", name))),
+        }
+    }
+}
+
+impl SummarizeOptions {
+    /// 日付が集計する期間（`from`〜`to`）に含まれるかどうかを判定する
+    ///
+    /// #### 例
+    ///
+    /// ```rust
+    /// use kakeibo_app::services::summarize::SummarizeOptions;
+    /// use chrono::NaiveDate;
+    ///
+    /// let options = SummarizeOptions { from: NaiveDate::from_ymd_opt(2023, 1, 15), ..SummarizeOptions::default() };
+    /// assert!(options.is_in_range(NaiveDate::from_ymd_opt(2023, 1, 15).unwrap()));
+    /// assert!(!options.is_in_range(NaiveDate::from_ymd_opt(2023, 1, 14).unwrap()));
+    /// ```
+    pub fn is_in_range(&self, date: NaiveDate) -> bool {
+        self.from.is_none_or(|from| from <= date) && self.to.is_none_or(|to| date <= to)
+    }
+}
+
+/// ユーザーに集計の表示オプションを尋ね、`SummarizeOptions`で返す。
+///
+/// カテゴリ別の小計は常に表示します。
+///
+/// #### エラー
+///
+/// 不正な入力があった場合、`KakeiboError`を返します。
+fn input_options() -> Result<SummarizeOptions> {
+    Ok(SummarizeOptions {
+        fill_empty_months: input_yes_no("登録の無い月も0円として表示しますか (y/n)")?,
+        show_cumulative: input_yes_no("累積収支も表示しますか (y/n)")?,
+        show_fixed_costs: input_yes_no("固定費・変動費の内訳も表示しますか (y/n)")?,
+        show_categories: true,
+        detail: input_yes_no("カテゴリ別の小計をサブカテゴリに分けて表示しますか (y/n)")?,
+        month: None,
+        from: None,
+        to: None,
+        weekly: false,
+        show_real: false,
+        chart: false,
+        format: SummaryFormat::Table,
+    })
 }
 
 /// 家計簿データから対象の年月の集合を取得する。
 ///
-/// この関数は、家計簿データから各項目の年月を取得し、重複を除去した集合を返します。
-fn get_target_dates(data: &Vec<models::Item>) -> BTreeSet<NaiveDate> {
-    let target_dates: BTreeSet<_> = data.iter().map(|item| {
+/// この関数は、家計簿データのうち集計する期間内の各項目の年月を取得し、重複を除去した集合を返します。
+fn get_target_dates(data: &[models::Item], options: &SummarizeOptions) -> BTreeSet<NaiveDate> {
+    let target_dates: BTreeSet<_> = data.iter().filter(|item| options.is_in_range(item.get_date())).map(|item| {
         item.get_first_day()
     }).collect();
     target_dates
 }
 
+/// 年月の集合を、最初の月から最後の月まで連続する集合に補完する。
+///
+/// この関数は、登録の無い月を含め、期間内のすべての月の最初の日を集合として返します。
+fn fill_empty_months_between(target_dates: &BTreeSet<NaiveDate>) -> BTreeSet<NaiveDate> {
+    let (Some(first), Some(last)) = (target_dates.first(), target_dates.last()) else {
+        return BTreeSet::new();
+    };
+
+    let mut filled_dates = BTreeSet::new();
+    let mut date = *first;
+    while date <= *last {
+        filled_dates.insert(date);
+        date = date.checked_add_months(Months::new(1)).unwrap();
+    }
+    filled_dates
+}
+
 /// 家計簿データから指定された年月のデータを抽出する。
 ///
-/// この関数は、家計簿データから指定された年月に一致する項目を抽出し、ベクタとして返します。
-fn get_filtered_data(data: &Vec<models::Item>, first_date: NaiveDate) -> Vec<&models::Item> {
+/// この関数は、家計簿データから指定された年月に一致し、集計する期間内の項目を抽出し、ベクタとして返します。
+fn get_filtered_data<'a>(data: &'a [models::Item], first_date: NaiveDate, options: &SummarizeOptions) -> Vec<&'a models::Item> {
     let filtered_data: Vec<_> = data.iter().filter(|item| {
-        (item.get_year() == first_date.year()) && (item.get_month() == first_date.month())
+        (item.get_year() == first_date.year()) && (item.get_month() == first_date.month()) && options.is_in_range(item.get_date())
     }).collect();
     filtered_data
 }
@@ -57,40 +687,347 @@ fn get_filtered_data(data: &Vec<models::Item>, first_date: NaiveDate) -> Vec<&mo
 /// 家計簿データの金額を集計する。
 ///
 /// この関数は、家計簿データの金額を合計し、集計結果を返します。
-fn summarize_data(data: &Vec<&models::Item>) -> i32 {
-    let mut sum = 0;
+/// 口座間の振替は収入でも支出でもないため、合計に含めません。
+fn summarize_data(data: &Vec<&models::Item>) -> models::Money {
+    let mut sum = models::Money::ZERO;
     for item in data {
+        if let models::Category::Transfer(_) = item.get_item_category() {
+            continue;
+        }
         sum += item.get_price_for_summary();
     }
     sum
 }
 
+/// 家計簿データの金額を収入と支出に分けて集計する。
+///
+/// この関数は、収入項目と支出項目の金額をそれぞれ合計し、`(収入, 支出)`の組として返します（支出は0以下）。口座間の振替は含みません。
+fn summarize_income_expense(data: &[&models::Item]) -> (models::Money, models::Money) {
+    let mut income = models::Money::ZERO;
+    let mut expense = models::Money::ZERO;
+    for item in data {
+        match item.get_item_category() {
+            models::Category::Income(_) => income += item.get_price_for_summary(),
+            models::Category::Expense(_) => expense += item.get_price_for_summary(),
+            models::Category::Transfer(_) => {},
+        }
+    }
+    (income, expense)
+}
+
+/// 家計簿データの支出を固定費と変動費に分けて集計する。
+///
+/// この関数は、支出項目の金額を固定費と変動費に分けて合計し、`(固定費, 変動費)`の組として返します。収入項目は含みません。
+fn summarize_fixed_costs(data: &[&models::Item]) -> (models::Money, models::Money) {
+    let mut fixed = models::Money::ZERO;
+    let mut variable = models::Money::ZERO;
+    for item in data {
+        if let models::Category::Expense(_) = item.get_item_category() {
+            if item.is_fixed() {
+                fixed += item.get_price_for_summary();
+            } else {
+                variable += item.get_price_for_summary();
+            }
+        }
+    }
+    (fixed, variable)
+}
+
 /// 日付を "年/月" の形式でフォーマットする。
 ///
 /// この関数は、指定された日付を "年/月" の形式でフォーマットし、文字列として返します。
-fn format_date(date: NaiveDate) -> String {
+pub(crate) fn format_date(date: NaiveDate) -> String {
     format!("{}/{}", date.year(), date.month())
 }
 
+/// 月の最後の日を取得する。
+///
+/// この関数は、月の最初の日を受け取り、同じ月の最後の日を返します。
+pub(crate) fn get_last_day(first_date: NaiveDate) -> NaiveDate {
+    first_date.checked_add_months(Months::new(1)).unwrap().pred_opt().unwrap()
+}
+
 /// 金額を符号付きでフォーマットする。
 ///
-/// この関数は、指定された金額を符号付きでフォーマットし、文字列として返します。正の金額にはプラス記号が付きます。
-fn format_price(price: i32) -> String {
-    if price > 0 {
-        format!("+{}", price)
+/// この関数は、指定された金額を設定の通貨・桁区切りに従って符号付きでフォーマットし、文字列として返します。正の金額にはプラス記号が付きます。
+fn format_price(price: models::Money, config: &Config) -> String {
+    if price > models::Money::ZERO {
+        format!("+{}", config.format_currency(price))
     } else {
-        format!("{}", price)
+        config.format_currency(price)
     }
 }
 
 /// 集計結果を表形式で出力する。
 ///
-/// この関数は、集計結果を "年/月 の収支は +/-金額 円でした" の形式で出力します。
-fn print_table(result_table: BTreeMap<NaiveDate, i32>) {
-    for result in result_table {
-        let date = format_date(result.0);
-        let price = format_price(result.1);
-        println!("{}の収支は{}円でした", date, price);
+/// この関数は、集計結果を "年/月 の収支は +/-金額 でした" の形式で出力します。
+/// オプションに応じて、累積収支と固定費・変動費の内訳を併記し、カテゴリ別の小計を次の行に続けて出力します。
+fn print_table(report: &SummaryReport, options: &SummarizeOptions, config: &Config) {
+    for summary in &report.months {
+        let line = format_summary_line(
+            &format_date(summary.month),
+            summary.month.year(),
+            summary.balance,
+            summary.cumulative,
+            (summary.fixed, summary.variable),
+            options,
+            config
+        );
+        println!("{}", line);
+        if options.show_categories && !summary.categories.is_empty() {
+            println!("  {}", format_category_subtotals(&summary.categories, config));
+        }
+    }
+}
+
+/// 各週の集計結果を表形式で出力する。
+///
+/// 週は "2023-W05 (1/30〜2/5)" の形式で表示します。
+fn print_weekly_table(weeks: &[WeeklySummary], options: &SummarizeOptions, config: &Config) {
+    for summary in weeks {
+        let last_day = summary.first_day + Days::new(6);
+        let label = format!(
+            "{}-W{:02} ({}/{}〜{}/{})",
+            summary.week.year(),
+            summary.week.week(),
+            summary.first_day.month(),
+            summary.first_day.day(),
+            last_day.month(),
+            last_day.day()
+        );
+        println!("{}", format_summary_line(
+            &label,
+            summary.first_day.year(),
+            summary.balance,
+            summary.cumulative,
+            (summary.fixed, summary.variable),
+            options,
+            config
+        ));
+        if options.show_categories && !summary.categories.is_empty() {
+            println!("  {}", format_category_subtotals(&summary.categories, config));
+        }
+    }
+}
+
+/// 月または週ごとに、収入・支出・収支の3本の棒を作成する。
+///
+/// 見出しは、月ごとの場合は "年/月"、週ごとの場合は "2023-W05" の形式で、各月（週）の最初の棒にだけ付けます。
+fn get_chart_bars(report: &SummaryReport, data: &[models::Item], options: &SummarizeOptions) -> Vec<services::chart::Bar> {
+    let rows: Vec<(String, models::Money, models::Money, models::Money)> = if options.weekly {
+        summarize_weeks(data, options).into_iter().map(|summary| {
+            let label = format!("{}-W{:02}", summary.week.year(), summary.week.week());
+            (label, summary.income, summary.expense, summary.balance)
+        }).collect()
+    } else {
+        report.months.iter().map(|summary| (format_date(summary.month), summary.income, summary.expense, summary.balance)).collect()
+    };
+
+    rows.into_iter().flat_map(|(label, income, expense, balance)| [
+        services::chart::Bar { label, kind: "収入", value: income, symbol: '+' },
+        services::chart::Bar { label: String::new(), kind: "支出", value: expense, symbol: '-' },
+        services::chart::Bar { label: String::new(), kind: "収支", value: balance, symbol: '#' },
+    ]).collect()
+}
+
+/// 集計結果を横棒グラフで出力する。
+///
+/// グラフの幅は端末の幅に合わせます。
+fn print_chart(bars: &[services::chart::Bar], config: &Config) {
+    for line in services::chart::render(bars, services::chart::get_terminal_width(), config) {
+        println!("{}", line);
+    }
+}
+
+/// 月または週の収支の行をフォーマットする。
+///
+/// この関数は、表示オプションに従って実質値（`year`年の物価指数で換算）・累積収支・固定費と変動費（`(固定費, 変動費)`の組）を収支の後に続けます。
+/// 実質値は、その年の物価指数が設定されている場合だけ表示します。
+fn format_summary_line(
+    label: &str,
+    year: i32,
+    balance: models::Money,
+    cumulative: models::Money,
+    (fixed, variable): (models::Money, models::Money),
+    options: &SummarizeOptions,
+    config: &Config
+) -> String {
+    let mut line = format!("{}の収支は{}でした", label, format_price(balance, config));
+    if let Some(real) = config.to_real_value(balance, year).filter(|_| options.show_real) {
+        line += &format!(" (実質: {})", format_price(real, config));
+    }
+    if options.show_cumulative {
+        line += &format!(" (累計: {})", format_price(cumulative, config));
+    }
+    if options.show_fixed_costs {
+        line += &format!(" (固定費: {}, 変動費: {})", format_price(fixed, config), format_price(variable, config));
+    }
+    line
+}
+
+/// カテゴリ別の小計を1行にフォーマットする。
+///
+/// この関数は、カテゴリ別の集計結果を "カテゴリ: +/-金額" の形式でカンマ区切りにつなげた文字列を返します。
+fn format_category_subtotals(breakdown: &[CategoryTotal], config: &Config) -> String {
+    breakdown.iter()
+        .map(|total| format!("{}: {}", format_category_label(total, config), format_price(total.total, config)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// カテゴリ別の集計結果のラベルをフォーマットする。
+///
+/// サブカテゴリがある場合は "カテゴリ/サブカテゴリ" の形式にします。
+fn format_category_label(total: &CategoryTotal, config: &Config) -> String {
+    let mut label = config.format_category(&total.category);
+    if let Some(subcategory) = &total.subcategory {
+        label += &format!("/{}", subcategory);
+    }
+    label
+}
+
+/// 口座別の残高と全体の残高を出力する。
+///
+/// この関数は、複数の口座に項目がある場合だけ、"口座: +/-金額" の形式で1行ずつ出力し、最後に全体の残高を出力します。
+fn print_account_balances(report: &SummaryReport, config: &Config) {
+    if report.accounts.len() < 2 {
+        return;
+    }
+    println!("口座別の残高");
+    for balance in &report.accounts {
+        println!("  {}: {}", balance.account.get_name(), format_price(balance.balance, config));
+    }
+    let total = report.accounts.iter().map(|balance| balance.balance).sum();
+    println!("  全体: {}", format_price(total, config));
+}
+
+/// 月次の集計結果から累積収支を計算する。
+///
+/// この関数は、各月の収支を古い月から順に積み上げた累計値を、月をキーとするマップとして返します。
+fn get_cumulative_table(result_table: &BTreeMap<NaiveDate, models::Money>) -> BTreeMap<NaiveDate, models::Money> {
+    let mut cumulative = models::Money::ZERO;
+    result_table.iter().map(|(date, sum)| {
+        cumulative += *sum;
+        (*date, cumulative)
+    }).collect()
+}
+
+/// ユーザーにy/nで尋ね、真偽値で返す。
+///
+/// 空行は`n`として扱います。
+///
+/// #### エラー
+///
+/// 不正な入力があった場合、`KakeiboError`を返します。
+fn input_yes_no(message: &str) -> Result<bool> {
+    let answer = services::prompt::ask(message)?;
+    match answer.trim() {
+        "y" | "Y" => Ok(true),
+        "n" | "N" | "" => Ok(false),
+        _ => Err(KakeiboError::invalid_input("yまたはnで入力してください")),
+    }
+}
+
+/// 家計簿データをカテゴリ別に集計する。
+///
+/// この関数は、家計簿データの金額をカテゴリごとに合計し、カテゴリ順に並べて返します。
+/// `detail`が`true`の場合はサブカテゴリごとに分けて合計し、`false`の場合はサブカテゴリをカテゴリにまとめます。
+/// 口座間の振替は収支ではないため、内訳に含めません。
+fn get_category_breakdown(data: &[&models::Item], detail: bool) -> Vec<CategoryTotal> {
+    let mut breakdown: BTreeMap<(models::Category, Option<String>), models::Money> = BTreeMap::new();
+    for item in data.iter().filter(|item| !matches!(item.get_item_category(), models::Category::Transfer(_))) {
+        let subcategory = if detail { item.get_subcategory().map(str::to_string) } else { None };
+        *breakdown.entry((item.get_item_category().clone(), subcategory)).or_default() += item.get_price_for_summary();
+    }
+    breakdown.into_iter().map(|((category, subcategory), total)| CategoryTotal { category, subcategory, total }).collect()
+}
+
+/// yyyy-mm-dd形式の文字列を日付に変換する。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::services::summarize;
+/// use chrono::NaiveDate;
+///
+/// assert_eq!(summarize::parse_date("2023-01-15").unwrap(), NaiveDate::from_ymd_opt(2023, 1, 15).unwrap());
+/// assert!(summarize::parse_date("2023/01/15").is_err());
+/// ```
+///
+/// #### エラー
+///
+/// 形式が不正な場合は、`KakeiboError::InvalidInput`を返します。
+pub fn parse_date(date: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map_err(|_| KakeiboError::invalid_input("日付はyyyy-mm-ddの形式で入力してください"))
+}
+
+/// "年/月" 形式の文字列を月の最初の日に変換する。
+///
+/// この関数は、`format_date`の出力と同じ "年/月" 形式の文字列を解析します。解析できない場合は`None`を返します。
+pub(crate) fn parse_year_month(input: &str) -> Option<NaiveDate> {
+    let (year, month) = input.split_once('/')?;
+    NaiveDate::from_ymd_opt(year.trim().parse().ok()?, month.trim().parse().ok()?, 1)
+}
+
+/// 集計結果から月を選んで明細とカテゴリ別の内訳を表示する。
+///
+/// この関数は、ユーザーが入力した年月の明細一覧とカテゴリ別の内訳を表示します。空行が入力されるまで繰り返します。
+fn drill_down(data: &[models::Item], options: &SummarizeOptions, config: &Config) -> Result<()> {
+    loop {
+        let input = services::prompt::ask("明細を表示する年月を入力してください (例: 2022/1, 空行で終了)")?;
+        let input = input.trim();
+        if input.is_empty() {
+            return Ok(());
+        }
+
+        let first_date = match parse_year_month(input) {
+            Some(date) => date,
+            None => {
+                println!("年月はyyyy/mの形式で入力してください");
+                continue;
+            }
+        };
+
+        let filtered_data = get_filtered_data(data, first_date, options);
+        if filtered_data.is_empty() {
+            println!("{}の明細はありません", format_date(first_date));
+            continue;
+        }
+
+        print_details(&filtered_data, first_date, config);
+        print_category_breakdown(&get_category_breakdown(&filtered_data, options.detail), config);
+    }
+}
+
+/// 明細一覧を出力する。
+///
+/// この関数は、指定された項目を "日付 品目名 カテゴリ 金額" の形式で1行ずつ出力します。
+/// ピン留めされた項目は📌を付けて先頭に出力します。
+fn print_details(data: &[&models::Item], first_date: NaiveDate, config: &Config) {
+    println!("{}の明細", format_date(first_date));
+    let pinned = data.iter().filter(|item| item.is_pinned());
+    let unpinned = data.iter().filter(|item| !item.is_pinned());
+    for item in pinned.chain(unpinned) {
+        println!(
+            "{} {} {} {} {}",
+            if item.is_pinned() { "📌" } else { "  " },
+            item.get_date(),
+            item.get_name(),
+            config.format_category(item.get_item_category()),
+            format_price(item.get_price_for_summary(), config)
+        );
+    }
+}
+
+/// カテゴリ別の内訳を出力する。
+///
+/// この関数は、カテゴリ別の集計結果を "カテゴリ: +/-金額" の形式で1行ずつ出力します。
+/// サブカテゴリに分けて集計した項目は "カテゴリ/サブカテゴリ" として出力します。
+fn print_category_breakdown(breakdown: &[CategoryTotal], config: &Config) {
+    println!("カテゴリ別の内訳");
+    for total in breakdown {
+        println!("  {}: {}", format_category_label(total, config), format_price(total.total, config));
     }
 }
 
@@ -103,31 +1040,31 @@ mod summarize_test {
             super::models::Item::new(
                 "新年会".to_string(),
                 models::Category::Expense(models::ExpenseCategory::Food),
-                5000,
+                models::Money::new(5000),
                 NaiveDate::from_ymd_opt(2022, 1, 10).unwrap()
             ),
             super::models::Item::new(
                 "給料".to_string(),
                 models::Category::Income(models::IncomeCategory::Salary),
-                300000,
+                models::Money::new(300000),
                 NaiveDate::from_ymd_opt(2022, 1, 20).unwrap()
             ),
             super::models::Item::new(
                 "旅行".to_string(),
                 models::Category::Expense(models::ExpenseCategory::Hobby),
-                100000,
+                models::Money::new(100000),
                 NaiveDate::from_ymd_opt(2022, 1, 30).unwrap()
             ),
             super::models::Item::new(
                 "外食".to_string(),
                 models::Category::Expense(models::ExpenseCategory::Food),
-                3000,
+                models::Money::new(3000),
                 NaiveDate::from_ymd_opt(2022, 2, 15).unwrap()
             ),
             super::models::Item::new(
                 "歓迎会".to_string(),
                 models::Category::Expense(models::ExpenseCategory::Other),
-                10000,
+                models::Money::new(10000),
                 NaiveDate::from_ymd_opt(2022, 4, 15).unwrap()
             ),
         ]
@@ -141,7 +1078,28 @@ mod summarize_test {
         expected.insert(NaiveDate::from_ymd_opt(2022, 2, 1).unwrap());
         expected.insert(NaiveDate::from_ymd_opt(2022, 4, 1).unwrap());
 
-        assert_eq!(get_target_dates(&test_data), expected);
+        assert_eq!(get_target_dates(&test_data, &SummarizeOptions::default()), expected);
+
+        // 期間を指定した場合は、期間内の項目がある月だけを対象にする
+        let options = SummarizeOptions {
+            from: NaiveDate::from_ymd_opt(2022, 1, 31),
+            to: NaiveDate::from_ymd_opt(2022, 4, 14),
+            ..SummarizeOptions::default()
+        };
+        assert_eq!(get_target_dates(&test_data, &options), BTreeSet::from([NaiveDate::from_ymd_opt(2022, 2, 1).unwrap()]));
+    }
+
+    #[test]
+    fn test_fill_empty_months_between() {
+        let test_data = get_test_data();
+        let mut expected = BTreeSet::new();
+        expected.insert(NaiveDate::from_ymd_opt(2022, 1, 1).unwrap());
+        expected.insert(NaiveDate::from_ymd_opt(2022, 2, 1).unwrap());
+        expected.insert(NaiveDate::from_ymd_opt(2022, 3, 1).unwrap());
+        expected.insert(NaiveDate::from_ymd_opt(2022, 4, 1).unwrap());
+
+        assert_eq!(fill_empty_months_between(&get_target_dates(&test_data, &SummarizeOptions::default())), expected);
+        assert_eq!(fill_empty_months_between(&BTreeSet::new()), BTreeSet::new());
     }
 
     #[test]
@@ -150,18 +1108,197 @@ mod summarize_test {
         let first_date = NaiveDate::from_ymd_opt(2022, 4, 20).unwrap();
         let expected = vec![&test_data[4]];
 
-        assert_eq!(get_filtered_data(&test_data, first_date), expected);
+        assert_eq!(get_filtered_data(&test_data, first_date, &SummarizeOptions::default()), expected);
+
+        // 期間を指定した場合は、期間外の項目を除く
+        let first_date = NaiveDate::from_ymd_opt(2022, 1, 1).unwrap();
+        let options = SummarizeOptions { from: NaiveDate::from_ymd_opt(2022, 1, 15), ..SummarizeOptions::default() };
+        assert_eq!(get_filtered_data(&test_data, first_date, &options), vec![&test_data[1], &test_data[2]]);
     }
 
     #[test]
     fn test_summarize_data() {
         let data = get_test_data();
         let test_data = vec![&data[0], &data[1], &data[2]];
-        let expected: i32 = data[0..=2].iter().map(|item| item.get_price_for_summary()).sum();
+        let expected: models::Money = data[0..=2].iter().map(|item| item.get_price_for_summary()).sum();
 
         assert_eq!(summarize_data(&test_data), expected);
     }
 
+    #[test]
+    fn test_summarize_fixed_costs() {
+        let mut data = get_test_data();
+        data[0].set_fixed(true);
+        let test_data = vec![&data[0], &data[1], &data[2]];
+
+        assert_eq!(summarize_fixed_costs(&test_data), (models::Money::new(-5000), models::Money::new(-100000)));
+    }
+
+    #[test]
+    fn test_summarize_events() {
+        let mut data = get_test_data();
+        data[0].set_event(Some("新年".to_string()));
+        data[1].set_event(Some("新年".to_string()));
+        data[3].set_event(Some("新年".to_string()));
+        let mut expected = BTreeMap::new();
+        expected.insert("新年".to_string(), EventSummary {
+            total_expense: models::Money::new(8000),
+            first_date: NaiveDate::from_ymd_opt(2022, 1, 10).unwrap(),
+            last_date: NaiveDate::from_ymd_opt(2022, 2, 15).unwrap(),
+            count: 3,
+        });
+
+        assert_eq!(summarize_events(&data), expected);
+    }
+
+    #[test]
+    fn test_summarize() {
+        let mut data = get_test_data();
+        data[0].set_fixed(true);
+
+        let report = summarize(&data, &SummarizeOptions::default());
+        assert_eq!(report.item_count, 5);
+        assert_eq!(report.months.len(), 3);
+        assert_eq!(report.months[0], MonthlySummary {
+            month: NaiveDate::from_ymd_opt(2022, 1, 1).unwrap(),
+            income: models::Money::new(300000),
+            expense: models::Money::new(-105000),
+            balance: models::Money::new(195000),
+            cumulative: models::Money::new(195000),
+            fixed: models::Money::new(-5000),
+            variable: models::Money::new(-100000),
+            categories: get_category_breakdown(&[&data[0], &data[1], &data[2]], false),
+        });
+        assert_eq!(report.months[2].cumulative, models::Money::new(182000));
+        assert_eq!(report.accounts, vec![AccountBalance { account: models::Account::Cash, balance: models::Money::new(182000) }]);
+
+        let options = SummarizeOptions { fill_empty_months: true, ..SummarizeOptions::default() };
+        let report = summarize(&data, &options);
+        assert_eq!(report.months.len(), 4);
+        assert_eq!((report.months[2].balance, report.months[2].cumulative), (models::Money::ZERO, models::Money::new(192000)));
+        assert_eq!(summarize(&[], &options), SummaryReport::default());
+
+        // 月を指定した場合は、その月だけを集計する
+        let options = SummarizeOptions { month: NaiveDate::from_ymd_opt(2022, 2, 1), ..SummarizeOptions::default() };
+        let report = summarize(&data, &options);
+        assert_eq!(report.months.len(), 1);
+        assert_eq!((report.months[0].balance, report.months[0].cumulative), (models::Money::new(-3000), models::Money::new(-3000)));
+        assert_eq!(report.item_count, 1);
+    }
+
+    #[test]
+    fn test_summarize_weeks() {
+        let data = get_test_data();
+        let weeks = summarize_weeks(&data, &SummarizeOptions::default());
+        let summary: Vec<_> = weeks.iter().map(|week| (week.week.week(), week.first_day, week.balance)).collect();
+        assert_eq!(summary, vec![
+            (2, NaiveDate::from_ymd_opt(2022, 1, 10).unwrap(), models::Money::new(-5000)),
+            (3, NaiveDate::from_ymd_opt(2022, 1, 17).unwrap(), models::Money::new(300000)),
+            (4, NaiveDate::from_ymd_opt(2022, 1, 24).unwrap(), models::Money::new(-100000)),
+            (7, NaiveDate::from_ymd_opt(2022, 2, 14).unwrap(), models::Money::new(-3000)),
+            (15, NaiveDate::from_ymd_opt(2022, 4, 11).unwrap(), models::Money::new(-10000)),
+        ]);
+        assert_eq!(weeks[4].cumulative, models::Money::new(182000));
+
+        // 登録の無い週も含める場合は、最初の週から最後の週までのすべての週を集計する
+        let options = SummarizeOptions { fill_empty_months: true, ..SummarizeOptions::default() };
+        let weeks = summarize_weeks(&data, &options);
+        assert_eq!(weeks.len(), 14);
+        assert_eq!((weeks[3].week.week(), weeks[3].balance, weeks[3].cumulative), (5, models::Money::ZERO, models::Money::new(195000)));
+    }
+
+    #[test]
+    fn test_format_summary_line() {
+        let config = Config { price_index: [(2022, 100.0), (2024, 110.0)].into(), ..Config::default() };
+        let options = SummarizeOptions { show_real: true, show_cumulative: true, ..SummarizeOptions::default() };
+        let (balance, cumulative, costs) = (models::Money::new(-10000), models::Money::new(5000), (models::Money::ZERO, models::Money::ZERO));
+        assert_eq!(format_summary_line("2022/1", 2022, balance, cumulative, costs, &options, &config), "2022/1の収支は-10000円でした (実質: -11000円) (累計: +5000円)");
+        assert_eq!(format_summary_line("2023/1", 2023, balance, cumulative, costs, &options, &config), "2023/1の収支は-10000円でした (累計: +5000円)");
+    }
+
+    #[test]
+    fn test_get_chart_bars() {
+        let data = get_test_data();
+        let options = SummarizeOptions { chart: true, ..SummarizeOptions::default() };
+        let bars = get_chart_bars(&summarize(&data, &options), &data, &options);
+        assert_eq!(bars.len(), 9);
+        let first_month: Vec<_> = bars[..3].iter().map(|bar| (bar.label.as_str(), bar.kind, bar.value)).collect();
+        assert_eq!(first_month, vec![
+            ("2022/1", "収入", models::Money::new(300000)),
+            ("", "支出", models::Money::new(-105000)),
+            ("", "収支", models::Money::new(195000)),
+        ]);
+
+        let options = SummarizeOptions { chart: true, weekly: true, ..SummarizeOptions::default() };
+        let bars = get_chart_bars(&summarize(&data, &options), &data, &options);
+        assert_eq!(bars.len(), 15);
+        assert_eq!((bars[0].label.as_str(), bars[1].value), ("2022-W02", models::Money::new(-5000)));
+    }
+
+    #[test]
+    fn test_get_running_balance() {
+        let mut data = get_test_data();
+        data.reverse();
+        data.push(models::Item::new(
+            "引き出し".to_string(),
+            models::Category::Transfer(models::Account::Cash),
+            models::Money::new(20000),
+            NaiveDate::from_ymd_opt(2022, 1, 25).unwrap()
+        ));
+
+        let rows = get_running_balance(&data, models::Money::new(10000));
+        let balances: Vec<_> = rows.iter().map(|row| (row.item.get_name(), row.balance)).collect();
+        assert_eq!(balances, vec![
+            ("新年会", models::Money::new(5000)),
+            ("給料", models::Money::new(305000)),
+            ("旅行", models::Money::new(205000)),
+            ("外食", models::Money::new(202000)),
+            ("歓迎会", models::Money::new(192000)),
+        ]);
+    }
+
+    #[test]
+    fn test_get_account_balances() {
+        let mut data = get_test_data();
+        data[1].set_account(models::Account::Bank);
+        data[2].set_account(models::Account::CreditCard);
+
+        assert_eq!(get_account_balances(&data.iter().collect::<Vec<_>>()), vec![
+            AccountBalance { account: models::Account::Cash, balance: models::Money::new(-18000) },
+            AccountBalance { account: models::Account::Bank, balance: models::Money::new(300000) },
+            AccountBalance { account: models::Account::CreditCard, balance: models::Money::new(-100000) },
+        ]);
+        assert!(get_account_balances(&[]).is_empty());
+
+        // 振替は振替元から振替先に金額を移す
+        let mut transfer = models::Item::new(
+            "ATM".to_string(),
+            models::Category::Transfer(models::Account::Cash),
+            models::Money::new(20000),
+            NaiveDate::from_ymd_opt(2022, 1, 25).unwrap()
+        );
+        transfer.set_account(models::Account::Bank);
+        data.push(transfer);
+        let balances = get_account_balances(&data.iter().collect::<Vec<_>>());
+        assert_eq!(balances[0], AccountBalance { account: models::Account::Cash, balance: models::Money::new(2000) });
+        assert_eq!(balances[1], AccountBalance { account: models::Account::Bank, balance: models::Money::new(280000) });
+        assert_eq!(summarize_data(&data.iter().collect()), models::Money::new(182000));
+    }
+
+    #[test]
+    fn test_get_cumulative_table() {
+        let mut result_table = BTreeMap::new();
+        result_table.insert(NaiveDate::from_ymd_opt(2022, 1, 1).unwrap(), models::Money::new(1000));
+        result_table.insert(NaiveDate::from_ymd_opt(2022, 2, 1).unwrap(), models::Money::new(-3000));
+        result_table.insert(NaiveDate::from_ymd_opt(2022, 3, 1).unwrap(), models::Money::new(5000));
+        let mut expected = BTreeMap::new();
+        expected.insert(NaiveDate::from_ymd_opt(2022, 1, 1).unwrap(), models::Money::new(1000));
+        expected.insert(NaiveDate::from_ymd_opt(2022, 2, 1).unwrap(), models::Money::new(-2000));
+        expected.insert(NaiveDate::from_ymd_opt(2022, 3, 1).unwrap(), models::Money::new(3000));
+
+        assert_eq!(get_cumulative_table(&result_table), expected);
+    }
+
     #[test]
     fn test_format_date() {
         let date = NaiveDate::from_ymd_opt(2022, 4, 20).unwrap();
@@ -172,7 +1309,58 @@ mod summarize_test {
 
     #[test]
     fn test_format_price() {
-        assert_eq!(format_price(1000), "+1000");
-        assert_eq!(format_price(-1000), "-1000");
+        let config = Config::default();
+        assert_eq!(format_price(models::Money::new(1000), &config), "+1000円");
+        assert_eq!(format_price(models::Money::new(-1000), &config), "-1000円");
+
+        let config = Config { thousands_separator: Some(','), ..Config::default() };
+        assert_eq!(format_price(models::Money::new(300000), &config), "+300,000円");
+    }
+
+    #[test]
+    fn test_get_category_breakdown() {
+        let mut data = get_test_data();
+        data[0].set_subcategory(Some("外食".to_string()));
+        let test_data = vec![&data[0], &data[1], &data[2], &data[3]];
+        let total = |category, subcategory: Option<&str>, total| CategoryTotal {
+            category,
+            subcategory: subcategory.map(str::to_string),
+            total,
+        };
+        let food = models::Category::Expense(models::ExpenseCategory::Food);
+
+        assert_eq!(get_category_breakdown(&test_data, false), vec![
+            total(models::Category::Income(models::IncomeCategory::Salary), None, models::Money::new(300000)),
+            total(food.clone(), None, models::Money::new(-8000)),
+            total(models::Category::Expense(models::ExpenseCategory::Hobby), None, models::Money::new(-100000)),
+        ]);
+        assert_eq!(get_category_breakdown(&test_data, true)[1..3], [
+            total(food.clone(), None, models::Money::new(-3000)),
+            total(food, Some("外食"), models::Money::new(-5000)),
+        ]);
+    }
+
+    #[test]
+    fn test_format_category_subtotals() {
+        let config = Config { thousands_separator: Some(','), show_category_emoji: false, ..Config::default() };
+        let breakdown = vec![
+            CategoryTotal { category: models::Category::Expense(models::ExpenseCategory::Food), subcategory: None, total: models::Money::new(-23000) },
+            CategoryTotal { category: models::Category::Expense(models::ExpenseCategory::Hobby), subcategory: Some("映画".to_string()), total: models::Money::new(-12000) },
+            CategoryTotal { category: models::Category::Income(models::IncomeCategory::Salary), subcategory: None, total: models::Money::new(300000) },
+        ];
+        assert_eq!(format_category_subtotals(&breakdown, &config), "食費: -23,000円, 趣味/映画: -12,000円, 給与: +300,000円");
+    }
+
+    #[test]
+    fn test_get_last_day() {
+        assert_eq!(get_last_day(NaiveDate::from_ymd_opt(2024, 2, 1).unwrap()), NaiveDate::from_ymd_opt(2024, 2, 29).unwrap());
+        assert_eq!(get_last_day(NaiveDate::from_ymd_opt(2023, 12, 1).unwrap()), NaiveDate::from_ymd_opt(2023, 12, 31).unwrap());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_year_month() {
+        assert_eq!(parse_year_month("2022/4"), NaiveDate::from_ymd_opt(2022, 4, 1));
+        assert_eq!(parse_year_month("2022/13"), None);
+        assert_eq!(parse_year_month("2022-4"), None);
+    }
+}
@@ -0,0 +1,47 @@
+//! 計測機能
+//!
+//! このモジュールは、`--timing`オプション指定時に読み込みや集計の各段階の処理時間を計測して表示する機能を提供します。
+//! 計測はプロセス全体で有効・無効を切り替え、無効の場合は何も表示しません。
+//!
+//! #### 例
+//!
+//! ```rust
+//! use kakeibo_app::services::timing;
+//!
+//! timing::enable();
+//! let sum = timing::measure("集計", || (1..=100).sum::<i32>());
+//! assert_eq!(sum, 5050);
+//! ```
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+/// 計測が有効かどうか
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// 計測を有効にします。
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// 計測が有効かどうかを返します。
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// 処理を実行し、計測が有効な場合は処理時間を "[計測] ラベル: 時間ms" の形式で表示します。
+///
+/// #### 引数
+///
+/// - `label`: 処理の名前
+/// - `f`: 計測する処理
+pub fn measure<T, F: FnOnce() -> T>(label: &str, f: F) -> T {
+    if !is_enabled() {
+        return f();
+    }
+
+    let start = Instant::now();
+    let result = f();
+    println!("[計測] {}: {:.1}ms", label, start.elapsed().as_secs_f64() * 1000.0);
+    result
+}
@@ -0,0 +1,79 @@
+//! 当日サービス
+//!
+//! このモジュールは、今日の支出合計と明細をすぐに確認する機能を提供します。
+
+use chrono::{Local, NaiveDate};
+
+use crate::config::Config;
+use crate::models;
+use crate::services;
+use crate::error::Result;
+
+/// 今日の支出合計と明細を表示します。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// services::today::run("store/data.json", &Config::default()).unwrap();
+/// ```
+pub fn run(file_path: &str, config: &Config) -> Result<()> {
+    let data = services::io::read_data_or_create_new_data(file_path)?;
+    let today = Local::now().date_naive();
+    let expenses = get_expenses_on(&data, today);
+    let total: models::Money = expenses.iter().map(|item| item.get_price()).sum();
+
+    println!("{}の支出: {}", today, config.format_currency(total));
+    for item in expenses {
+        println!(
+            "  {} {} {}",
+            item.get_name(),
+            config.format_category(item.get_item_category()),
+            config.format_currency(item.get_price())
+        );
+    }
+    Ok(())
+}
+
+/// 指定された日の支出項目を取得します。
+fn get_expenses_on(data: &[models::Item], date: NaiveDate) -> Vec<&models::Item> {
+    data.iter()
+        .filter(|item| item.get_date() == date)
+        .filter(|item| matches!(item.get_item_category(), models::Category::Expense(_)))
+        .collect()
+}
+
+#[cfg(test)]
+mod today_test {
+    use super::*;
+
+    #[test]
+    fn test_get_expenses_on() {
+        let date = NaiveDate::from_ymd_opt(2023, 1, 25).unwrap();
+        let test_data = vec![
+            models::Item::new(
+                "ランチ".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                models::Money::new(1000),
+                date
+            ),
+            models::Item::new(
+                "給料".to_string(),
+                models::Category::Income(models::IncomeCategory::Salary),
+                models::Money::new(300000),
+                date
+            ),
+            models::Item::new(
+                "映画".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Hobby),
+                models::Money::new(2000),
+                NaiveDate::from_ymd_opt(2023, 1, 24).unwrap()
+            ),
+        ];
+        let expenses = get_expenses_on(&test_data, date);
+
+        assert_eq!(expenses.len(), 1);
+        assert_eq!(expenses[0].get_name(), "ランチ");
+    }
+}
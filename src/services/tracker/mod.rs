@@ -0,0 +1,271 @@
+//! 累計トラッカーサービス
+//!
+//! このモジュールは、子ども費・ペット費のようなライフイベントにかかった支出を、開始日から積み上げて表示する機能を提供します。
+//! 項目のイベント名をタグとみなし、タグごとに開始日を設定します。開始日以降の、そのタグの支出をすべて合計します。
+//! トラッカーは、JSONファイル`store/trackers.json`に保存されます。
+//!
+//! #### トラッカーの例
+//!
+//! ```json
+//! { "子ども": "2020-04-01", "ペット": "2022-10-15" }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::BufReader;
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::error::{KakeiboError, Result};
+use crate::models;
+use crate::services;
+
+/// タグごとの累計の開始日を表す構造体
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::services::tracker::Trackers;
+/// use chrono::NaiveDate;
+///
+/// let mut trackers = Trackers::default();
+/// trackers.set_start(String::from("子ども"), Some(NaiveDate::from_ymd_opt(2020, 4, 1).unwrap()));
+/// assert_eq!(trackers.get_start("子ども"), NaiveDate::from_ymd_opt(2020, 4, 1));
+/// assert_eq!(trackers.get_start("ペット"), None);
+/// ```
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct Trackers {
+    starts: BTreeMap<String, NaiveDate>,
+}
+
+impl Trackers {
+    /// JSONファイルからトラッカーを読み込む
+    ///
+    /// ファイルが存在しない場合は、トラッカーが無いものとして空のトラッカーを返します。
+    ///
+    /// #### エラー
+    ///
+    /// ファイルの内容が不正な場合は、`KakeiboError::InvalidData`を返します。
+    pub fn load_or_default(file_path: &str) -> Result<Self> {
+        match File::open(file_path) {
+            Ok(f) => {
+                let buf_reader = BufReader::new(f);
+                serde_json::from_reader(buf_reader)
+                    .map_err(|error| KakeiboError::InvalidData(format!("{}の読み込みに失敗しました: {}", file_path, error)))
+            },
+            Err(_) => Ok(Trackers::default()),
+        }
+    }
+
+    /// トラッカーをJSONファイルに書き込む
+    ///
+    /// #### エラー
+    ///
+    /// ファイルへの書き込みに失敗した場合、`KakeiboError::Io`を返します。
+    pub fn save(&self, file_path: &str) -> Result<()> {
+        let json_data = serde_json::to_string_pretty(self).expect("JSONへのシリアライズに失敗しました");
+        services::io::write_file(file_path, &json_data)
+    }
+
+    /// タグの累計の開始日を取得する
+    pub fn get_start(&self, tag: &str) -> Option<NaiveDate> {
+        self.starts.get(tag).copied()
+    }
+
+    /// タグの累計の開始日を設定する
+    ///
+    /// `None`を指定した場合は、そのタグのトラッカーを削除します。
+    pub fn set_start(&mut self, tag: String, start: Option<NaiveDate>) {
+        match start {
+            Some(start) => self.starts.insert(tag, start),
+            None => self.starts.remove(&tag),
+        };
+    }
+}
+
+/// 1つのタグの累計を表す構造体
+///
+/// - `tag`: タグ（イベント名）
+/// - `start`: 累計の開始日
+/// - `years`: 年ごとの支出合計（古い年から順。支出の無い年も0円として含む）
+/// - `count`: 項目数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackerReport {
+    pub tag: String,
+    pub start: NaiveDate,
+    pub years: Vec<(i32, models::Money)>,
+    pub count: usize,
+}
+
+impl TrackerReport {
+    /// 開始日からの累計額を取得する
+    pub fn get_total(&self) -> models::Money {
+        self.years.iter().map(|(_, total)| *total).sum()
+    }
+}
+
+/// 家計簿データから、トラッカーのタグごとの累計を作成します。
+///
+/// 開始日から`today`までの、タグをイベント名に持つ支出を年ごとに合計します。タグの順に並べて返します。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::models::{Item, Category, ExpenseCategory, Money};
+/// use kakeibo_app::services::tracker::{self, Trackers};
+/// use chrono::NaiveDate;
+///
+/// let mut item = Item::new(String::from("おむつ"), Category::Expense(ExpenseCategory::Other), Money::new(3000), NaiveDate::from_ymd_opt(2021, 5, 1).unwrap());
+/// item.set_event(Some(String::from("子ども")));
+/// let mut trackers = Trackers::default();
+/// trackers.set_start(String::from("子ども"), NaiveDate::from_ymd_opt(2020, 4, 1));
+///
+/// let reports = tracker::create_reports(&[item], &trackers, NaiveDate::from_ymd_opt(2022, 1, 1).unwrap());
+/// assert_eq!(reports[0].years, vec![(2020, Money::ZERO), (2021, Money::new(3000)), (2022, Money::ZERO)]);
+/// assert_eq!(reports[0].get_total(), Money::new(3000));
+/// ```
+pub fn create_reports(data: &[models::Item], trackers: &Trackers, today: NaiveDate) -> Vec<TrackerReport> {
+    trackers.starts.iter().map(|(tag, start)| {
+        let mut years: BTreeMap<i32, models::Money> = (start.year()..=today.year().max(start.year()))
+            .map(|year| (year, models::Money::ZERO))
+            .collect();
+        let mut count = 0;
+        for item in data {
+            if item.get_event() != Some(tag.as_str()) || item.get_date() < *start || item.get_date() > today {
+                continue;
+            }
+            if let models::Category::Expense(_) = item.get_item_category() {
+                *years.entry(item.get_date().year()).or_default() += item.get_price();
+                count += 1;
+            }
+        }
+        TrackerReport { tag: tag.clone(), start: *start, years: years.into_iter().collect(), count }
+    }).collect()
+}
+
+/// トラッカーのタグごとに、開始日からの累計額と年ごとの積み上げを表示します。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// services::tracker::run_report("store/data.json", "store/trackers.json", &Config::default()).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// トラッカーやデータの読み込みに失敗した場合は、`KakeiboError`を返します。
+pub fn run_report(file_path: &str, trackers_path: &str, config: &Config) -> Result<()> {
+    let trackers = Trackers::load_or_default(trackers_path)?;
+    if trackers.starts.is_empty() {
+        println!("累計トラッカーが設定されていません");
+        return Ok(());
+    }
+    let data = services::io::read_data(file_path)?;
+    print_reports(&create_reports(&data, &trackers, chrono::Local::now().date_naive()), config);
+    Ok(())
+}
+
+/// 累計を表示し、タグの累計の開始日を設定します。
+///
+/// #### 例
+///
+/// ```rust
+/// // ドキュメンテーションテストでFailになるのでコメントアウト
+/// // use kakeibo_app::config::Config;
+/// // use kakeibo_app::services;
+/// // services::tracker::run("store/data.json", "store/trackers.json", &Config::default());
+/// ```
+///
+/// #### エラー
+///
+/// 不正な入力があった場合や、トラッカーの読み書きに失敗した場合は、`KakeiboError`を返します。
+pub fn run(file_path: &str, trackers_path: &str, config: &Config) -> Result<()> {
+    let mut trackers = Trackers::load_or_default(trackers_path)?;
+    if trackers.starts.is_empty() {
+        println!("累計トラッカーは設定されていません");
+    } else {
+        let data = services::io::read_data_or_create_new_data(file_path)?;
+        print_reports(&create_reports(&data, &trackers, chrono::Local::now().date_naive()), config);
+    }
+
+    let tag = services::prompt::ask("累計するタグ（イベント名）を入力してください (空行で終了)")?;
+    let tag = tag.trim();
+    if tag.is_empty() {
+        return Ok(());
+    }
+
+    let start = services::prompt::ask("累計の開始日を入力してください (yyyy-mm-dd, 空行でトラッカーを削除)")?;
+    let start = match start.trim() {
+        "" => None,
+        start => Some(services::summarize::parse_date(start)?),
+    };
+    trackers.set_start(tag.to_string(), start);
+    trackers.save(trackers_path)?;
+    println!("累計トラッカーを保存しました");
+    Ok(())
+}
+
+/// タグごとの累計を出力します。
+fn print_reports(reports: &[TrackerReport], config: &Config) {
+    for report in reports {
+        println!(
+            "{} ({}〜): 累計 {} ({}件)",
+            report.tag,
+            report.start,
+            config.format_currency(report.get_total()),
+            report.count
+        );
+        let mut cumulative = models::Money::ZERO;
+        for (year, total) in &report.years {
+            cumulative += *total;
+            println!("  {}年 {} (累計 {})", year, config.format_currency(*total), config.format_currency(cumulative));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tracker_test {
+    use super::*;
+
+    fn get_test_item(category: models::Category, price: i64, date: NaiveDate, event: Option<&str>) -> models::Item {
+        let mut item = models::Item::new("テスト".to_string(), category, models::Money::new(price), date);
+        item.set_event(event.map(str::to_string));
+        item
+    }
+
+    #[test]
+    fn test_create_reports() {
+        let expense = models::Category::Expense(models::ExpenseCategory::Other);
+        let data = vec![
+            get_test_item(expense.clone(), 1000, NaiveDate::from_ymd_opt(2020, 3, 31).unwrap(), Some("子ども")),
+            get_test_item(expense.clone(), 2000, NaiveDate::from_ymd_opt(2020, 4, 1).unwrap(), Some("子ども")),
+            get_test_item(expense.clone(), 3000, NaiveDate::from_ymd_opt(2021, 6, 1).unwrap(), Some("子ども")),
+            get_test_item(expense.clone(), 4000, NaiveDate::from_ymd_opt(2021, 6, 1).unwrap(), Some("ペット")),
+            get_test_item(expense, 5000, NaiveDate::from_ymd_opt(2021, 7, 1).unwrap(), None),
+            get_test_item(models::Category::Income(models::IncomeCategory::Other), 10000, NaiveDate::from_ymd_opt(2021, 8, 1).unwrap(), Some("子ども")),
+        ];
+        let mut trackers = Trackers::default();
+        trackers.set_start("子ども".to_string(), NaiveDate::from_ymd_opt(2020, 4, 1));
+
+        let reports = create_reports(&data, &trackers, NaiveDate::from_ymd_opt(2021, 12, 31).unwrap());
+        assert_eq!(reports, vec![TrackerReport {
+            tag: "子ども".to_string(),
+            start: NaiveDate::from_ymd_opt(2020, 4, 1).unwrap(),
+            years: vec![(2020, models::Money::new(2000)), (2021, models::Money::new(3000))],
+            count: 2,
+        }]);
+    }
+
+    #[test]
+    fn test_set_start() {
+        let mut trackers = Trackers::default();
+        trackers.set_start("ペット".to_string(), NaiveDate::from_ymd_opt(2022, 10, 15));
+        trackers.set_start("ペット".to_string(), None);
+        assert_eq!(trackers, Trackers::default());
+    }
+}
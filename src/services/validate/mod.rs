@@ -12,10 +12,80 @@
 //! let register_type = 1;
 //! let category_type = 2;
 //!
-//! InputValidator::validate_service_type(service_type);
-//! InputValidator::validate_register_type(register_type);
-//! InputValidator::validate_category_type(register_type, category_type);
+//! assert!(InputValidator::validate_service_type(service_type).is_ok());
+//! assert!(InputValidator::validate_register_type(register_type).is_ok());
+//! assert!(InputValidator::validate_category_type(register_type, category_type).is_ok());
 //! ```
+//!
+//! 不正な入力値の場合は、どの入力項目のどの値が、どの範囲から外れていたかを`ValidationError`として返します。
+
+use std::fmt;
+use std::ops::RangeInclusive;
+
+/// 入力値の検証エラーを表す構造体
+///
+/// - `field`: 検証した入力項目の名前（例: 登録種別）
+/// - `value`: 不正だった入力値
+/// - `allowed`: 許容される入力値の範囲
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::services::validate::InputValidator;
+///
+/// let error = InputValidator::validate_register_type(3).unwrap_err();
+/// assert_eq!(error.get_field(), "登録種別");
+/// assert_eq!(error.get_value(), 3);
+/// assert_eq!(error.get_allowed(), &(0..=2));
+/// assert_eq!(error.to_string(), "登録種別の入力値3が不正です (0〜2で入力してください)");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    field: &'static str,
+    value: u8,
+    allowed: RangeInclusive<u8>,
+}
+
+impl ValidationError {
+    /// 入力項目の名前を取得する
+    pub fn get_field(&self) -> &'static str {
+        self.field
+    }
+
+    /// 不正だった入力値を取得する
+    pub fn get_value(&self) -> u8 {
+        self.value
+    }
+
+    /// 許容される入力値の範囲を取得する
+    pub fn get_allowed(&self) -> &RangeInclusive<u8> {
+        &self.allowed
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}の入力値{}が不正です ({}〜{}で入力してください)",
+            self.field,
+            self.value,
+            self.allowed.start(),
+            self.allowed.end()
+        )
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// 入力値が許容範囲に含まれるかを検証します。
+fn check_range(field: &'static str, value: u8, allowed: RangeInclusive<u8>) -> Result<(), ValidationError> {
+    if allowed.contains(&value) {
+        Ok(())
+    } else {
+        Err(ValidationError { field, value, allowed })
+    }
+}
 
 /// 入力値のバリデーションを行う構造体
 pub struct InputValidator {}
@@ -23,9 +93,9 @@ pub struct InputValidator {}
 impl InputValidator {
     /// サービスタイプの入力値を検証します。
     ///
-    /// #### パニック
+    /// #### エラー
     /// 
-    /// サービスタイプが0または1以外の場合、パニックになります。
+    /// サービスタイプが0〜36以外の場合、`ValidationError`を返します。
     ///
     /// #### 例
     /// 
@@ -33,20 +103,17 @@ impl InputValidator {
     /// use kakeibo_app::services::validate::InputValidator;
     /// 
     /// let service_type = 0;
-    /// InputValidator::validate_service_type(service_type);
+    /// assert!(InputValidator::validate_service_type(service_type).is_ok());
     /// ```
-    pub fn validate_service_type(service_type: u8) {
-        match service_type {
-            0 | 1 => {},
-            _ => panic!("入力値が不正です")
-        }
+    pub fn validate_service_type(service_type: u8) -> Result<(), ValidationError> {
+        check_range("サービス種別", service_type, 0..=36)
     }
 
     /// 登録種別の入力値を検証します。
     ///
-    /// #### パニック
+    /// #### エラー
     /// 
-    /// 登録種別が0または1以外の場合、パニックになります。
+    /// 登録種別が0、1または2以外の場合、`ValidationError`を返します。
     ///
     /// #### 例
     /// 
@@ -54,20 +121,17 @@ impl InputValidator {
     /// use kakeibo_app::services::validate::InputValidator;
     /// 
     /// let register_type = 1;
-    /// InputValidator::validate_register_type(register_type);
+    /// assert!(InputValidator::validate_register_type(register_type).is_ok());
     /// ```
-    pub fn validate_register_type(register_type: u8) {
-        match register_type {
-            0 | 1 => {},
-            _ => panic!("登録種別の入力値が不正です")
-        }
+    pub fn validate_register_type(register_type: u8) -> Result<(), ValidationError> {
+        check_range("登録種別", register_type, 0..=2)
     }
 
     /// カテゴリタイプの入力値を検証します。
     ///
-    /// #### パニック
+    /// #### エラー
     /// 
-    /// カテゴリタイプが0、1または2以外の場合、パニックになります。
+    /// カテゴリタイプが0、1または2以外の場合、`ValidationError`を返します。
     ///
     /// #### 例
     /// 
@@ -76,20 +140,65 @@ impl InputValidator {
     /// 
     /// let register_type = 1;
     /// let category_type = 2;
-    /// InputValidator::validate_category_type(register_type, category_type);
+    /// assert!(InputValidator::validate_category_type(register_type, category_type).is_ok());
+    /// ```
+    pub fn validate_category_type(_register_type: u8, category_type: u8) -> Result<(), ValidationError> {
+        // 収入・支出ともにカテゴリ種別は0〜2の3種類（振替は振替先の口座種別で、同じく0〜2）
+        check_range("カテゴリ種別", category_type, 0..=2)
+    }
+
+    /// 口座種別の入力値を検証します。
+    ///
+    /// #### エラー
+    /// 
+    /// 口座種別が0、1または2以外の場合、`ValidationError`を返します。
+    ///
+    /// #### 例
+    /// 
+    /// ```rust
+    /// use kakeibo_app::services::validate::InputValidator;
+    /// 
+    /// let account_type = 2;
+    /// assert!(InputValidator::validate_account_type(account_type).is_ok());
     /// ```
-    pub fn validate_category_type(register_type: u8, category_type: u8) {
-        if register_type == 0 {
-            match category_type {
-                0 | 1 | 2 => {},
-                _ => panic!("カテゴリ入力値が不正です")
-            }
-        } else {
-            match category_type {
-                0 | 1 | 2 => {},
-                _ => panic!("カテゴリ入力値が不正です")
-            }
-        }
+    pub fn validate_account_type(account_type: u8) -> Result<(), ValidationError> {
+        check_range("口座種別", account_type, 0..=2)
+    }
+
+    /// 支出の分類種別の入力値を検証します。
+    ///
+    /// #### エラー
+    /// 
+    /// 分類種別が0、1または2以外の場合、`ValidationError`を返します。
+    ///
+    /// #### 例
+    /// 
+    /// ```rust
+    /// use kakeibo_app::services::validate::InputValidator;
+    /// 
+    /// let judgment_type = 1;
+    /// assert!(InputValidator::validate_judgment_type(judgment_type).is_ok());
+    /// ```
+    pub fn validate_judgment_type(judgment_type: u8) -> Result<(), ValidationError> {
+        check_range("分類種別", judgment_type, 0..=2)
+    }
+
+    /// 登録内容の確認画面で修正する項目の入力値を検証します。
+    ///
+    /// #### エラー
+    /// 
+    /// 修正する項目が0〜12以外の場合、`ValidationError`を返します。
+    ///
+    /// #### 例
+    /// 
+    /// ```rust
+    /// use kakeibo_app::services::validate::InputValidator;
+    /// 
+    /// let edit_target = 3;
+    /// assert!(InputValidator::validate_edit_target(edit_target).is_ok());
+    /// ```
+    pub fn validate_edit_target(edit_target: u8) -> Result<(), ValidationError> {
+        check_range("修正する項目", edit_target, 0..=12)
     }
 }
 
@@ -99,41 +208,104 @@ mod validate_test {
 
     #[test]
     fn test_validate_service_type_for_ok() {
-        InputValidator::validate_service_type(0);
-        InputValidator::validate_service_type(1);
+        assert!(InputValidator::validate_service_type(0).is_ok());
+        assert!(InputValidator::validate_service_type(1).is_ok());
+        assert!(InputValidator::validate_service_type(2).is_ok());
+        assert!(InputValidator::validate_service_type(3).is_ok());
+        assert!(InputValidator::validate_service_type(4).is_ok());
+        assert!(InputValidator::validate_service_type(5).is_ok());
+        assert!(InputValidator::validate_service_type(6).is_ok());
+        assert!(InputValidator::validate_service_type(7).is_ok());
+        assert!(InputValidator::validate_service_type(8).is_ok());
+        assert!(InputValidator::validate_service_type(9).is_ok());
+        assert!(InputValidator::validate_service_type(10).is_ok());
+        assert!(InputValidator::validate_service_type(11).is_ok());
+        assert!(InputValidator::validate_service_type(12).is_ok());
+        assert!(InputValidator::validate_service_type(13).is_ok());
+        assert!(InputValidator::validate_service_type(14).is_ok());
+        assert!(InputValidator::validate_service_type(15).is_ok());
+        assert!(InputValidator::validate_service_type(16).is_ok());
+        assert!(InputValidator::validate_service_type(17).is_ok());
+        assert!(InputValidator::validate_service_type(18).is_ok());
+        assert!(InputValidator::validate_service_type(19).is_ok());
+        assert!(InputValidator::validate_service_type(20).is_ok());
+        assert!(InputValidator::validate_service_type(21).is_ok());
+        assert!(InputValidator::validate_service_type(22).is_ok());
+        assert!(InputValidator::validate_service_type(23).is_ok());
+        assert!(InputValidator::validate_service_type(24).is_ok());
+        assert!(InputValidator::validate_service_type(25).is_ok());
+        assert!(InputValidator::validate_service_type(26).is_ok());
+        assert!(InputValidator::validate_service_type(27).is_ok());
+        assert!(InputValidator::validate_service_type(28).is_ok());
+        assert!(InputValidator::validate_service_type(29).is_ok());
+        assert!(InputValidator::validate_service_type(30).is_ok());
+        assert!(InputValidator::validate_service_type(31).is_ok());
+        assert!(InputValidator::validate_service_type(32).is_ok());
+        assert!(InputValidator::validate_service_type(33).is_ok());
+        assert!(InputValidator::validate_service_type(34).is_ok());
+        assert!(InputValidator::validate_service_type(35).is_ok());
+        assert!(InputValidator::validate_service_type(36).is_ok());
     }
 
     #[test]
-    #[should_panic(expected="入力値が不正です")]
     fn test_validate_service_type_for_ng() {
-        InputValidator::validate_service_type(2);
+        let error = InputValidator::validate_service_type(37).unwrap_err();
+        assert_eq!(error.to_string(), "サービス種別の入力値37が不正です (0〜36で入力してください)");
     }
 
     #[test]
     fn test_validate_register_type_for_ok() {
-        InputValidator::validate_register_type(0);
-        InputValidator::validate_register_type(1);
+        assert!(InputValidator::validate_register_type(0).is_ok());
+        assert!(InputValidator::validate_register_type(1).is_ok());
+        assert!(InputValidator::validate_register_type(2).is_ok());
     }
 
     #[test]
-    #[should_panic(expected="登録種別の入力値が不正です")]
     fn test_validate_register_type_for_ng() {
-        InputValidator::validate_register_type(2);
+        let error = InputValidator::validate_register_type(3).unwrap_err();
+        assert_eq!(error, ValidationError { field: "登録種別", value: 3, allowed: 0..=2 });
     }
 
     #[test]
     fn test_validate_category_type_for_ok() {
-        InputValidator::validate_category_type(0, 1);
-        InputValidator::validate_category_type(0, 1);
-        InputValidator::validate_category_type(0, 2);
-        InputValidator::validate_category_type(1, 1);
-        InputValidator::validate_category_type(1, 1);
-        InputValidator::validate_category_type(1, 2);
+        assert!(InputValidator::validate_category_type(0, 1).is_ok());
+        assert!(InputValidator::validate_category_type(0, 1).is_ok());
+        assert!(InputValidator::validate_category_type(0, 2).is_ok());
+        assert!(InputValidator::validate_category_type(1, 1).is_ok());
+        assert!(InputValidator::validate_category_type(1, 1).is_ok());
+        assert!(InputValidator::validate_category_type(1, 2).is_ok());
     }
 
     #[test]
-    #[should_panic(expected="カテゴリ入力値が不正です")]
     fn test_validate_category_type_for_ng() {
-        InputValidator::validate_category_type(0, 3);
+        let error = InputValidator::validate_category_type(0, 3).unwrap_err();
+        assert_eq!(error.get_value(), 3);
+        assert_eq!(error.get_allowed(), &(0..=2));
+    }
+
+    #[test]
+    fn test_validate_account_type() {
+        assert!(InputValidator::validate_account_type(0).is_ok());
+        assert!(InputValidator::validate_account_type(2).is_ok());
+        assert_eq!(InputValidator::validate_account_type(3).unwrap_err().get_field(), "口座種別");
+    }
+
+    #[test]
+    fn test_validate_judgment_type() {
+        assert!(InputValidator::validate_judgment_type(0).is_ok());
+        assert!(InputValidator::validate_judgment_type(2).is_ok());
+        assert_eq!(InputValidator::validate_judgment_type(3).unwrap_err().get_field(), "分類種別");
+    }
+
+    #[test]
+    fn test_validate_edit_target_for_ok() {
+        assert!(InputValidator::validate_edit_target(0).is_ok());
+        assert!(InputValidator::validate_edit_target(12).is_ok());
+    }
+
+    #[test]
+    fn test_validate_edit_target_for_ng() {
+        let error = InputValidator::validate_edit_target(13).unwrap_err();
+        assert_eq!(error.to_string(), "修正する項目の入力値13が不正です (0〜12で入力してください)");
     }
 }
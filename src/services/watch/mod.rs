@@ -0,0 +1,219 @@
+//! フォルダ監視サービス
+//!
+//! このモジュールは、指定したフォルダを監視し、置かれたCSV・JSONファイルを自動で家計簿データに取り込む機能を提供します。
+//! ダウンロードした明細をフォルダに置くだけで、その内容が登録されます。
+//!
+//! - CSV: 1行を1件の支出とし、各列から日付・品目名・金額を抽出します（貼り付け登録と同じ規則。見出し行など金額の無い行は、その内容を表示して取り込みません）。
+//! - JSON: 家計簿データと同じ形式（項目の配列）のファイルを取り込みます。
+//!
+//! 取り込みの済んだファイルは、フォルダ内の`imported`フォルダに移動します。
+//! 既に登録されている項目（日付・品目名・カテゴリ・金額が同じ項目）は重複して取り込みません。
+//! 締め済みの月の項目を含むファイルは取り込まず、フォルダに残します。
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use chrono::{Local, NaiveDate};
+use notify::{EventKind, RecursiveMode, Watcher};
+
+use crate::config::Config;
+use crate::error::{KakeiboError, Result};
+use crate::models;
+use crate::services;
+
+/// 取り込みの済んだファイルを移動するフォルダの名前
+pub const IMPORTED_DIR: &str = "imported";
+
+/// ファイルの書き込みが終わったとみなすまでの待ち時間
+///
+/// この時間、ファイルへの変更が無ければ書き込みが終わったものとして取り込みます。
+const SETTLE_TIME: Duration = Duration::from_secs(1);
+
+/// フォルダを監視し、置かれたCSV・JSONファイルを取り込み続けます。
+///
+/// この関数は、監視を始める前に既にフォルダにあるファイルを取り込み、その後はCtrl+Cで終了するまで監視を続けます。
+/// 1つのファイルの取り込みに失敗した場合は、メッセージを表示して監視を続けます。
+///
+/// #### 例
+///
+/// ```rust,no_run
+/// use kakeibo_app::config::Config;
+/// use kakeibo_app::services;
+/// services::watch::run("inbox", "store/data.json", "store/closed.json", "store/device.json", &Config::default()).unwrap();
+/// ```
+///
+/// #### エラー
+///
+/// 指定したパスがフォルダでない場合は`KakeiboError::InvalidInput`を、フォルダを監視できない場合は`KakeiboError::Io`を返します。
+pub fn run(dir: &str, file_path: &str, closed_path: &str, device_path: &str, config: &Config) -> Result<()> {
+    if !Path::new(dir).is_dir() {
+        return Err(KakeiboError::InvalidInput(format!("{}はフォルダではありません", dir)));
+    }
+
+    let entries = fs::read_dir(dir).map_err(|error| KakeiboError::io(dir, error))?;
+    let mut pending: BTreeSet<PathBuf> = entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect();
+    import_pending(&mut pending, file_path, closed_path, device_path, config);
+
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)
+        .map_err(|error| KakeiboError::io(dir, io::Error::other(error)))?;
+    watcher.watch(Path::new(dir), RecursiveMode::NonRecursive)
+        .map_err(|error| KakeiboError::io(dir, io::Error::other(error)))?;
+    println!("{}を監視しています。CSV・JSONファイルを置くと取り込みます (Ctrl+Cで終了)", dir);
+
+    loop {
+        match receiver.recv_timeout(SETTLE_TIME) {
+            Ok(Ok(event)) if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) => pending.extend(event.paths),
+            Ok(Ok(_)) => {},
+            Ok(Err(error)) => eprintln!("エラー: {}の監視中にエラーが発生しました: {}", dir, error),
+            Err(mpsc::RecvTimeoutError::Timeout) => import_pending(&mut pending, file_path, closed_path, device_path, config),
+            Err(mpsc::RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+    }
+}
+
+/// 取り込み待ちのファイルのうち、CSV・JSONファイルを取り込みます。
+///
+/// 取り込みに失敗したファイルは、メッセージを表示してフォルダに残します（書き込みの途中だった場合は、次の変更時に再度取り込みます）。
+fn import_pending(pending: &mut BTreeSet<PathBuf>, file_path: &str, closed_path: &str, device_path: &str, config: &Config) {
+    for path in std::mem::take(pending) {
+        if !path.is_file() || get_file_kind(&path).is_none() {
+            continue;
+        }
+        if let Err(error) = import_file(&path, file_path, closed_path, device_path, config) {
+            eprintln!("エラー: {}", error);
+        }
+    }
+}
+
+/// 取り込むファイルの種類を表す列挙型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    Csv,
+    Json,
+}
+
+/// ファイルの拡張子から、取り込むファイルの種類を判定します。
+///
+/// 拡張子の大文字・小文字は区別しません。CSV・JSON以外のファイルは`None`になります。
+///
+/// #### 例
+///
+/// ```rust
+/// use kakeibo_app::services::watch::{self, FileKind};
+/// use std::path::Path;
+///
+/// assert_eq!(watch::get_file_kind(Path::new("inbox/meisai.CSV")), Some(FileKind::Csv));
+/// assert_eq!(watch::get_file_kind(Path::new("inbox/data.json")), Some(FileKind::Json));
+/// assert_eq!(watch::get_file_kind(Path::new("inbox/meisai.csv.part")), None);
+/// ```
+pub fn get_file_kind(path: &Path) -> Option<FileKind> {
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    match extension.as_str() {
+        "csv" => Some(FileKind::Csv),
+        "json" => Some(FileKind::Json),
+        _ => None,
+    }
+}
+
+/// 1つのファイルを家計簿データに取り込み、取り込んだファイルを`imported`フォルダに移動します。
+fn import_file(path: &Path, file_path: &str, closed_path: &str, device_path: &str, config: &Config) -> Result<()> {
+    let display_path = path.to_string_lossy().to_string();
+    let mut data = services::io::read_data_or_create_new_data(file_path)?;
+    let candidates = match get_file_kind(path) {
+        Some(FileKind::Csv) => {
+            let text = fs::read_to_string(path).map_err(|error| KakeiboError::io(&display_path, error))?;
+            let lines: Vec<String> = text.lines().map(|line| services::import::split_csv_line(line).join("\t")).collect();
+            let (candidates, skipped) = services::paste::create_candidates(&lines, &data, Local::now().date_naive(), config);
+            for line in &skipped {
+                println!("{}: 金額が見つからないため取り込みません: {}", display_path, line);
+            }
+            candidates
+        },
+        _ => services::io::read_data(&display_path)?,
+    };
+    let (items, duplicated) = services::import::remove_duplicates(candidates, &data);
+
+    let closed_months = services::close::ClosedMonths::load_or_default(closed_path)?;
+    if let Some(month) = closed_months.find_closed_month(&items) {
+        println!("{}は取り込みません", display_path);
+        services::close::print_locked(month);
+        return Ok(());
+    }
+
+    let count = items.len();
+    if count > 0 {
+        let device = services::journal::Device::load_or_create(device_path)?;
+        for mut item in items {
+            item.set_id(0);
+            if item.get_origin().is_none() {
+                item.set_origin(Some(device.next_origin(&data)));
+            }
+            print_imported(&item, config);
+            data.push(item);
+        }
+        services::io::assign_ids(&mut data);
+        services::io::write_to_json(&data, file_path)?;
+    }
+    let moved_path = move_to_imported(path, Local::now().date_naive())?;
+    println!(
+        "{}から{}件の項目を取り込みました (重複{}件は取り込みません, 移動先: {})",
+        display_path,
+        count,
+        duplicated,
+        moved_path.to_string_lossy()
+    );
+    Ok(())
+}
+
+/// 取り込んだ項目を出力します。
+fn print_imported(item: &models::Item, config: &Config) {
+    println!(
+        "  {} {} {} {}",
+        item.get_date(),
+        item.get_name(),
+        config.format_category(item.get_item_category()),
+        config.format_item_price(item)
+    );
+}
+
+/// 取り込んだファイルを、同じフォルダ内の`imported`フォルダに移動します。
+///
+/// 同じ名前のファイルを上書きしないよう、移動先のファイル名の先頭に取り込んだ日付を付けます。
+fn move_to_imported(path: &Path, today: NaiveDate) -> Result<PathBuf> {
+    let destination = get_imported_path(path, today);
+    let imported_dir = destination.parent().unwrap_or(Path::new(IMPORTED_DIR));
+    fs::create_dir_all(imported_dir).map_err(|error| KakeiboError::io(&imported_dir.to_string_lossy(), error))?;
+    fs::rename(path, &destination).map_err(|error| KakeiboError::io(&path.to_string_lossy(), error))?;
+    Ok(destination)
+}
+
+/// 取り込んだファイルの移動先のパスを作成します。
+fn get_imported_path(path: &Path, today: NaiveDate) -> PathBuf {
+    let file_name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    let mut destination = path.with_file_name(IMPORTED_DIR).join(format!("{}_{}", today, file_name));
+    let mut count = 1;
+    while destination.exists() {
+        count += 1;
+        destination = path.with_file_name(IMPORTED_DIR).join(format!("{}_{}_{}", today, count, file_name));
+    }
+    destination
+}
+
+#[cfg(test)]
+mod watch_test {
+    use super::*;
+
+    #[test]
+    fn test_get_imported_path() {
+        let today = NaiveDate::from_ymd_opt(2023, 5, 2).unwrap();
+        assert_eq!(
+            get_imported_path(Path::new("no_such_inbox/meisai.csv"), today),
+            Path::new("no_such_inbox").join(IMPORTED_DIR).join("2023-05-02_meisai.csv")
+        );
+    }
+}